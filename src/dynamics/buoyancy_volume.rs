@@ -0,0 +1,165 @@
+use crate::dynamics::{RigidBodyForces, RigidBodyMassProps, RigidBodyVelocity};
+use crate::geometry::shape_volume_and_mass_properties;
+use crate::math::{Isometry, Point, Real, Vector};
+#[cfg(feature = "dim2")]
+use num::Zero;
+use parry::shape::Shape;
+
+/// A body of fluid applying buoyancy and drag forces to the rigid-bodies submerged into it.
+///
+/// The fluid fills the whole half-space below the horizontal plane `y = plane`. Buoyancy
+/// volumes can be added to the `PhysicsPipeline::buoyancy_volumes` in order to be applied
+/// automatically at each timestep, and `plane` may be modified between two timesteps for
+/// simulating waves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct BuoyancyVolume {
+    /// The height of the fluid surface along the `y` axis.
+    pub plane: Real,
+    /// The density of the fluid.
+    pub fluid_density: Real,
+    /// Damping factor slowing down the translational motion of the submerged rigid-bodies.
+    ///
+    /// This is multiplied by the submerged fraction of each rigid-body.
+    pub linear_drag: Real,
+    /// Damping factor slowing down the angular motion of the submerged rigid-bodies.
+    ///
+    /// This is multiplied by the submerged fraction of each rigid-body.
+    pub angular_drag: Real,
+}
+
+impl BuoyancyVolume {
+    /// Creates a new buoyancy volume.
+    pub fn new(plane: Real, fluid_density: Real, linear_drag: Real, angular_drag: Real) -> Self {
+        Self {
+            plane,
+            fluid_density,
+            linear_drag,
+            angular_drag,
+        }
+    }
+
+    /// Estimates the volume and the centroid of the part of `shape` below the fluid surface.
+    ///
+    /// Returns the submerged volume, the total volume of the shape, and the submerged centroid.
+    /// The submerged fraction of the shape is approximated by the submerged fraction of its AABB.
+    /// Shapes without volume, like segments or triangle meshes in 2D, are never submerged.
+    pub fn submerged_volume(
+        &self,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+    ) -> (Real, Real, Point<Real>) {
+        let aabb = shape.compute_aabb(position);
+        let mprops = shape_volume_and_mass_properties(shape, 1.0);
+        let volume = crate::utils::inv(mprops.inv_mass);
+
+        // NOTE: the center of mass of shapes without volume may not be finite.
+        if volume <= 0.0 || !volume.is_finite() {
+            return (0.0, 0.0, position.translation.vector.into());
+        }
+
+        let mut centroid = position * mprops.local_com;
+
+        if aabb.mins.y >= self.plane {
+            return (0.0, volume, centroid);
+        }
+
+        if aabb.maxs.y <= self.plane {
+            return (volume, volume, centroid);
+        }
+
+        let height = aabb.maxs.y - aabb.mins.y;
+        let fraction = (self.plane - aabb.mins.y) / height;
+        centroid.y = (aabb.mins.y + self.plane) / 2.0;
+
+        (volume * fraction, volume, centroid)
+    }
+
+    /// Adds to `forces` the buoyancy and drag forces applied by this fluid to a rigid-body.
+    ///
+    /// Here `submerged_volume` and `submerged_centroid` describe the submerged part of the
+    /// rigid-body, and `submerged_fraction` is the ratio between this volume and the total
+    /// volume of the rigid-body.
+    pub fn apply_forces(
+        &self,
+        gravity: &Vector<Real>,
+        submerged_volume: Real,
+        submerged_centroid: Point<Real>,
+        submerged_fraction: Real,
+        forces: &mut RigidBodyForces,
+        vels: &RigidBodyVelocity,
+        mprops: &RigidBodyMassProps,
+    ) {
+        if submerged_volume <= 0.0 {
+            return;
+        }
+
+        let buoyancy = -gravity * (self.fluid_density * submerged_volume);
+        forces.apply_force_at_point(mprops, buoyancy, submerged_centroid);
+        forces.force -=
+            vels.linvel * (self.linear_drag * submerged_fraction * mprops.effective_mass());
+
+        if !mprops.effective_world_inv_inertia_sqrt.is_zero() {
+            let angular_drag = self.angular_drag * submerged_fraction;
+            forces.torque -= mprops.effective_angular_inertia() * (vels.angvel * angular_drag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BuoyancyVolume;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn shapes_without_volume_are_never_submerged() {
+        let fluid = BuoyancyVolume::new(0.0, 1.0, 0.0, 0.0);
+        let segment = ColliderBuilder::segment(Point::origin(), Point::from(Vector::x())).build();
+        let position = Isometry::from(Vector::y() * -1.0);
+        let (submerged_volume, volume, centroid) =
+            fluid.submerged_volume(segment.shape(), &position);
+
+        assert_eq!((submerged_volume, volume), (0.0, 0.0));
+        assert!(centroid.coords.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn boxes_float_at_their_draft() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world
+            .pipeline
+            .buoyancy_volumes
+            .push(BuoyancyVolume::new(0.0, 1.0, 10.0, 2.0));
+
+        // A box half as dense as the fluid sinks to half of its height.
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 2.0)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world
+            .colliders
+            .insert_with_parent(shape.density(0.5).build(), body, &mut world.bodies);
+        // A segment, without volume, attached to the box doesn't prevent it from floating.
+        world.colliders.insert_with_parent(
+            ColliderBuilder::segment(Point::origin(), Point::from(Vector::x())).build(),
+            body,
+            &mut world.bodies,
+        );
+
+        for _ in 0..600 {
+            world.step();
+        }
+
+        let translation = world.bodies[body].translation();
+        assert!(translation.y.abs() < 1.0e-3);
+        assert!(world.bodies[body].linvel().norm() < 1.0e-2);
+    }
+}
@@ -1,6 +1,6 @@
 use super::TOIEntry;
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
-use crate::dynamics::{IslandManager, RigidBodyColliders, RigidBodyForces};
+use crate::dynamics::{CcdMode, IslandManager, RigidBodyColliders, RigidBodyForces};
 use crate::dynamics::{
     RigidBodyCcd, RigidBodyHandle, RigidBodyMassProps, RigidBodyPosition, RigidBodyVelocity,
 };
@@ -114,7 +114,9 @@ impl CCDSolver {
 
             if ccd.ccd_enabled {
                 let forces = if include_forces { Some(forces) } else { None };
-                let moving_fast = ccd.is_moving_fast(dt, vels, forces);
+                // The speculative contacts are handled by the narrow-phase instead.
+                let moving_fast =
+                    ccd.ccd_mode == CcdMode::Toi && ccd.is_moving_fast(dt, vels, forces);
 
                 bodies.map_mut_internal(handle.0, |ccd| {
                     ccd.ccd_active = moving_fast;
@@ -624,3 +626,109 @@ impl CCDSolver {
         PredictedImpacts::Impacts(frozen)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, CompoundShape, SharedShape};
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn ccd_prevents_compound_shapes_tunneling() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.01, 50.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.01, 50.0, 50.0);
+        world
+            .colliders
+            .insert(wall.translation(Vector::x() * 10.0).build());
+
+        // Two compounds thrown at a thin wall, moving by more than 8 meters per timestep: a
+        // parry compound, and a compound shape with a disabled part.
+        let parts = vec![
+            (Isometry::from(Vector::y()), SharedShape::ball(0.1)),
+            (Isometry::from(-Vector::y()), SharedShape::ball(0.1)),
+        ];
+        let mut compound_shape = CompoundShape::new(parts.clone());
+        compound_shape.set_part_enabled(1, false);
+        let shapes = [
+            SharedShape::compound(parts),
+            SharedShape::new(compound_shape),
+        ];
+        let mut handles = vec![];
+
+        for (k, shape) in shapes.iter().enumerate() {
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * (k as Real * 10.0))
+                    .linvel(Vector::x() * 500.0)
+                    .ccd_enabled(true)
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::new(shape.clone()).build(),
+                body,
+                &mut world.bodies,
+            );
+            handles.push(body);
+        }
+
+        for _ in 0..10 {
+            world.step();
+        }
+
+        for handle in handles {
+            let x = world.bodies[handle].translation().x;
+            assert!(x > 9.5 && x < 10.0);
+        }
+    }
+
+    #[test]
+    fn speculative_ccd_prevents_tunneling() {
+        use crate::dynamics::CcdMode;
+
+        let mut world = TestWorld::new(Vector::zeros());
+
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.01, 50.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.01, 50.0, 50.0);
+        world
+            .colliders
+            .insert(wall.translation(Vector::x() * 10.0).build());
+
+        // Two balls moving by more than 8 meters per timestep toward a thin wall, with
+        // speculative CCD and without CCD.
+        let mut handles = vec![];
+        for k in 0..2 {
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * (k as Real * 10.0))
+                    .linvel(Vector::x() * 500.0)
+                    .ccd_enabled(k == 0)
+                    .ccd_mode(CcdMode::Speculative)
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.1).build(),
+                body,
+                &mut world.bodies,
+            );
+            handles.push(body);
+        }
+
+        for _ in 0..10 {
+            world.step();
+
+            // The TOI-based CCD isn't used for the speculative contacts.
+            assert!(!world.bodies[handles[0]].is_ccd_active());
+        }
+
+        let speculative = world.bodies[handles[0]].translation().x;
+        assert!(speculative > 9.8 && speculative < 10.0);
+        assert!(world.bodies[handles[1]].translation().x > 10.0);
+    }
+}
@@ -0,0 +1,404 @@
+#[cfg(feature = "dim3")]
+use crate::math::UnitVector;
+use crate::math::{Point, Real, Vector, DEFAULT_EPSILON, DIM};
+
+/// A field of accelerations applied to the dynamic rigid-bodies located inside of it.
+///
+/// Force fields can be added to the `PhysicsPipeline::force_fields` in order to be
+/// applied automatically at each timestep.
+pub trait ForceField: Send + Sync {
+    /// The acceleration applied to a rigid-body with its center-of-mass at `position`
+    /// and moving with the linear velocity `velocity`.
+    fn acceleration_at(&self, position: &Point<Real>, velocity: &Vector<Real>) -> Vector<Real>;
+
+    /// Advances the time of time-dependent force fields.
+    ///
+    /// This is called by the `PhysicsPipeline` after the forces of each (sub-)step are applied.
+    fn advance_time(&mut self, _dt: Real) {}
+}
+
+/// A force field attracting (or repelling) rigid-bodies towards a single point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RadialForceField {
+    /// The point the rigid-bodies are attracted to.
+    pub center: Point<Real>,
+    /// The acceleration at a distance of 1 from the center.
+    ///
+    /// A positive value attracts the rigid-bodies while a negative value repels them.
+    pub magnitude: Real,
+    /// The exponent of the distance dividing the acceleration.
+    ///
+    /// Set this to `2.0` for a gravity well, or to `0.0` for an acceleration independent
+    /// from the distance to the center.
+    pub falloff_exponent: Real,
+}
+
+impl RadialForceField {
+    /// Creates a new radial force field.
+    pub fn new(center: Point<Real>, magnitude: Real, falloff_exponent: Real) -> Self {
+        Self {
+            center,
+            magnitude,
+            falloff_exponent,
+        }
+    }
+}
+
+impl ForceField for RadialForceField {
+    fn acceleration_at(&self, position: &Point<Real>, _velocity: &Vector<Real>) -> Vector<Real> {
+        let dir = self.center - position;
+        let dist = dir.norm();
+
+        if dist <= DEFAULT_EPSILON {
+            return Vector::zeros();
+        }
+
+        dir * (self.magnitude / (dist * dist.powf(self.falloff_exponent)))
+    }
+}
+
+/// A force field dragging rigid-bodies along the velocity of a, possibly turbulent, wind.
+///
+/// The acceleration is the difference between the wind velocity and the velocity of the
+/// rigid-body, so the rigid-bodies will progressively reach the wind velocity.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct WindField {
+    /// The direction of the wind. Should be normalized.
+    pub direction: Vector<Real>,
+    /// The speed of the wind along its direction.
+    pub speed: Real,
+    /// The magnitude of the position-dependent perturbations of the wind velocity.
+    pub turbulence: Real,
+}
+
+impl WindField {
+    /// Creates a new wind field.
+    pub fn new(direction: Vector<Real>, speed: Real, turbulence: Real) -> Self {
+        Self {
+            direction,
+            speed,
+            turbulence,
+        }
+    }
+
+    /// The wind velocity at the given point.
+    pub fn velocity_at(&self, position: &Point<Real>) -> Vector<Real> {
+        let mut velocity = self.direction * self.speed;
+
+        if self.turbulence != 0.0 {
+            // A smooth deterministic noise, so that the same scene always gets the same wind.
+            let p = position.coords;
+            velocity += Vector::from_fn(|i, _| {
+                let noise = (0..DIM)
+                    .map(|j| (p[j] * (1.0 + 0.37 * (i + j) as Real) + i as Real).sin())
+                    .sum::<Real>();
+                noise * self.turbulence / DIM as Real
+            });
+        }
+
+        velocity
+    }
+}
+
+impl ForceField for WindField {
+    fn acceleration_at(&self, position: &Point<Real>, velocity: &Vector<Real>) -> Vector<Real> {
+        self.velocity_at(position) - velocity
+    }
+}
+
+/// A force field dragging rigid-bodies along a wind with spatially and temporally
+/// coherent turbulences.
+///
+/// The wind velocity is `base_velocity` plus an offset given, for each axis, by a 4D gradient
+/// noise evaluated at the position and the current time of the field. The acceleration is the
+/// difference between the wind velocity and the velocity of the rigid-body, like for the
+/// [`WindField`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TurbulentWindField {
+    /// The wind velocity, without turbulences.
+    pub base_velocity: Vector<Real>,
+    /// The magnitude of the turbulent offset added to the base velocity, along each axis.
+    pub turbulence_scale: Real,
+    /// The spatial and temporal frequency of the turbulences.
+    ///
+    /// The turbulences vary over distances (and durations) around `1.0 / turbulence_frequency`.
+    pub turbulence_frequency: Real,
+    /// The seed of the noise, so that different fields can have different turbulences.
+    pub seed: u64,
+    time: Real,
+}
+
+impl TurbulentWindField {
+    /// Creates a new turbulent wind field.
+    pub fn new(
+        base_velocity: Vector<Real>,
+        turbulence_scale: Real,
+        turbulence_frequency: Real,
+        seed: u64,
+    ) -> Self {
+        Self {
+            base_velocity,
+            turbulence_scale,
+            turbulence_frequency,
+            seed,
+            time: 0.0,
+        }
+    }
+
+    /// The time elapsed since the creation of this field.
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
+    /// The wind velocity at the given point, at the current time of this field.
+    pub fn velocity_at(&self, position: &Point<Real>) -> Vector<Real> {
+        let mut velocity = self.base_velocity;
+
+        if self.turbulence_scale != 0.0 {
+            let freq = self.turbulence_frequency;
+            let mut p = [0.0; 4];
+            for i in 0..DIM {
+                p[i] = position[i] * freq;
+            }
+            p[3] = self.time * freq;
+
+            for i in 0..DIM {
+                let seed = self
+                    .seed
+                    .wrapping_add((i as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+                velocity[i] += gradient_noise4(seed, p) * self.turbulence_scale;
+            }
+        }
+
+        velocity
+    }
+}
+
+impl ForceField for TurbulentWindField {
+    fn acceleration_at(&self, position: &Point<Real>, velocity: &Vector<Real>) -> Vector<Real> {
+        self.velocity_at(position) - velocity
+    }
+
+    fn advance_time(&mut self, dt: Real) {
+        self.time += dt;
+    }
+}
+
+/// Mixes the coordinates of a lattice point into a pseudo-random number.
+fn hash_lattice_point(seed: u64, cell: [i64; 4]) -> u64 {
+    let mut h = seed;
+    for c in cell {
+        h ^= c as u64;
+        h = h.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h ^= h >> 31;
+    }
+    h ^= h >> 29;
+    h = h.wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 32)
+}
+
+/// Perlin's 4D gradient noise, with values roughly in `[-1, 1]`.
+fn gradient_noise4(seed: u64, p: [Real; 4]) -> Real {
+    let cell = p.map(|e| e.floor());
+    let frac = [
+        p[0] - cell[0],
+        p[1] - cell[1],
+        p[2] - cell[2],
+        p[3] - cell[3],
+    ];
+    let fade = frac.map(|t| t * t * t * (t * (t * 6.0 - 15.0) + 10.0));
+    let cell = cell.map(|e| e as i64);
+    let mut result = 0.0;
+
+    for corner in 0..16 {
+        let mut weight = 1.0;
+        let mut corner_cell = cell;
+        let mut offset = frac;
+
+        for k in 0..4 {
+            if corner & (1 << k) != 0 {
+                weight *= fade[k];
+                corner_cell[k] += 1;
+                offset[k] -= 1.0;
+            } else {
+                weight *= 1.0 - fade[k];
+            }
+        }
+
+        // The gradients are the 32 vectors with one zero coordinate and the other
+        // coordinates equal to ±1.
+        let h = hash_lattice_point(seed, corner_cell);
+        let zero_axis = (h & 3) as usize;
+        let mut dot = 0.0;
+        let mut sign_bit = 2;
+
+        for (k, offset) in offset.iter().enumerate() {
+            if k != zero_axis {
+                if h & (1 << sign_bit) != 0 {
+                    dot -= offset;
+                } else {
+                    dot += offset;
+                }
+                sign_bit += 1;
+            }
+        }
+
+        result += weight * dot;
+    }
+
+    result
+}
+
+/// A force field dragging rigid-bodies into a rotational motion around an axis.
+///
+/// The acceleration is the difference between the swirl velocity and the velocity of the
+/// rigid-body in the plane orthogonal to the axis, so the motion of the rigid-bodies
+/// along the axis isn't affected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct VortexField {
+    /// The rotation axis of the vortex.
+    #[cfg(feature = "dim3")]
+    pub axis: UnitVector<Real>,
+    /// A point on the rotation axis of the vortex.
+    pub center: Point<Real>,
+    /// The angular speed of the swirl around the axis.
+    pub angular_speed: Real,
+}
+
+impl VortexField {
+    /// Creates a new vortex field rotating around `center`.
+    #[cfg(feature = "dim2")]
+    pub fn new(center: Point<Real>, angular_speed: Real) -> Self {
+        Self {
+            center,
+            angular_speed,
+        }
+    }
+
+    /// Creates a new vortex field rotating around the line passing through `center`
+    /// with the direction `axis`.
+    #[cfg(feature = "dim3")]
+    pub fn new(axis: UnitVector<Real>, center: Point<Real>, angular_speed: Real) -> Self {
+        Self {
+            axis,
+            center,
+            angular_speed,
+        }
+    }
+}
+
+impl ForceField for VortexField {
+    #[cfg(feature = "dim2")]
+    fn acceleration_at(&self, position: &Point<Real>, velocity: &Vector<Real>) -> Vector<Real> {
+        let dpos = position - self.center;
+        Vector::new(-dpos.y, dpos.x) * self.angular_speed - velocity
+    }
+
+    #[cfg(feature = "dim3")]
+    fn acceleration_at(&self, position: &Point<Real>, velocity: &Vector<Real>) -> Vector<Real> {
+        let dpos = position - self.center;
+        let swirl_vel = self.axis.cross(&dpos) * self.angular_speed;
+        let planar_vel = velocity - *self.axis * self.axis.dot(velocity);
+        swirl_vel - planar_vel
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ForceField, RadialForceField, TurbulentWindField, VortexField, WindField};
+    use crate::math::{Point, Real, Vector};
+
+    #[test]
+    fn radial_force_fields_attract_towards_their_center() {
+        let center = Point::from(Vector::y());
+        let well = RadialForceField::new(center, 8.0, 2.0);
+
+        // The acceleration decreases with the square of the distance to the center.
+        let acceleration = well.acceleration_at(&Point::from(Vector::y() * 3.0), &Vector::x());
+        assert!((acceleration + Vector::y() * 2.0).norm() < 1.0e-6);
+        assert_eq!(
+            well.acceleration_at(&center, &Vector::zeros()),
+            Vector::zeros()
+        );
+
+        // A negative magnitude repels the rigid-bodies, independently from the distance.
+        let blast = RadialForceField::new(center, -3.0, 0.0);
+        for dist in [0.5, 4.0] {
+            let position = center + Vector::x() * dist;
+            let acceleration = blast.acceleration_at(&position, &Vector::zeros());
+            assert!((acceleration - Vector::x() * 3.0).norm() < 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn wind_fields_drag_bodies_to_the_wind_velocity() {
+        let wind = WindField::new(Vector::x(), 5.0, 0.0);
+        let position = Point::from(Vector::repeat(2.0));
+        let acceleration = wind.acceleration_at(&position, &(Vector::x() * 2.0 + Vector::y()));
+        assert!((acceleration - (Vector::x() * 3.0 - Vector::y())).norm() < 1.0e-6);
+        assert_eq!(
+            wind.acceleration_at(&position, &(Vector::x() * 5.0)),
+            Vector::zeros()
+        );
+
+        // Turbulences perturb the wind velocity by a bounded amount.
+        let gusty = WindField::new(Vector::x(), 5.0, 1.0);
+        assert_ne!(gusty.velocity_at(&position), wind.velocity_at(&position));
+        for i in 0..100 {
+            let p = Point::from(Vector::repeat(i as Real * 0.37));
+            assert!((gusty.velocity_at(&p) - wind.velocity_at(&p)).amax() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn vortex_fields_swirl_around_their_axis() {
+        let center = Point::from(Vector::x());
+        #[cfg(feature = "dim2")]
+        let vortex = VortexField::new(center, 2.0);
+        #[cfg(feature = "dim3")]
+        let vortex = VortexField::new(Vector::z_axis(), center, 2.0);
+
+        // A point at a distance of 1 from the axis swirls at a speed of 2.
+        let position = center + Vector::x();
+        let acceleration = vortex.acceleration_at(&position, &Vector::zeros());
+        assert!((acceleration - Vector::y() * 2.0).norm() < 1.0e-6);
+        let acceleration = vortex.acceleration_at(&position, &(Vector::y() * 2.0));
+        assert!(acceleration.norm() < 1.0e-6);
+
+        // The motion along the axis isn't affected.
+        #[cfg(feature = "dim3")]
+        {
+            let velocity = Vector::y() * 2.0 + Vector::z() * 3.0;
+            let acceleration = vortex.acceleration_at(&position, &velocity);
+            assert!(acceleration.norm() < 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn turbulent_wind_field_is_coherent() {
+        let mut field = TurbulentWindField::new(Vector::x() * 2.0, 0.5, 1.0, 42);
+        let p = Point::from(Vector::repeat(0.3));
+        let v0 = field.velocity_at(&p);
+
+        // Nearby points get similar velocities, which differ from the base velocity.
+        let nearby = Point::from(Vector::repeat(0.301));
+        assert!((field.velocity_at(&nearby) - v0).norm() < 1.0e-2);
+        assert!((v0 - field.base_velocity).norm() > 1.0e-3);
+
+        field.advance_time(0.5);
+        assert_eq!(field.time(), 0.5);
+        assert!((field.velocity_at(&p) - v0).norm() > 1.0e-3);
+
+        // The turbulences stay bounded and deterministic.
+        let other = TurbulentWindField::new(Vector::x() * 2.0, 0.5, 1.0, 42);
+        assert_eq!(other.velocity_at(&p), v0);
+        for i in 0..100 {
+            let p = Point::from(Vector::repeat(i as Real * 0.37));
+            assert!((field.velocity_at(&p) - field.base_velocity).amax() <= 0.5 * 1.5);
+        }
+    }
+}
@@ -1,5 +1,25 @@
 use crate::math::Real;
 
+/// The method used by the constraints solver to resolve the contacts and joints.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum SolverMode {
+    /// The constraints are resolved at the velocity level, with impulses (default).
+    #[default]
+    VelocityBased,
+    /// The constraints are resolved by directly correcting the positions of the rigid-bodies,
+    /// with the given number of iterations per timestep. The velocities are then deduced from
+    /// the displacement of the rigid-bodies.
+    ///
+    /// This is more robust to large stiffness and mass ratios, but joint limits, motors,
+    /// springs and couplings are ignored, and islands containing multibody joints are still
+    /// resolved at the velocity level.
+    PositionBased {
+        /// The number of position correction iterations.
+        iterations: u32,
+    },
+}
+
 /// Parameters for a time-step of the physics engine.
 #[derive(Copy, Clone)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -30,10 +50,23 @@ pub struct IntegrationParameters {
     /// (default `0.0`).
     pub erp: Real,
 
+    /// 0-1: multiplier applied to the impulses of the last timestep when they are used as the
+    /// initial guess of the constraints solver (default: `0.8`).
+    ///
+    /// Lower values reduce oscillations in highly dynamic scenes, higher values improve the
+    /// convergence of quasi-static ones, e.g., stacks. If zero, warmstarting is disabled.
+    pub warmstart_coefficient: Real,
+
     /// Amount of penetration the engine wont attempt to correct (default: `0.001m`).
     pub allowed_linear_error: Real,
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002`).
     pub prediction_distance: Real,
+    /// The maximum number of contacts of each contact manifold given to the constraints solver
+    /// (default: `4`).
+    ///
+    /// The contacts of manifolds with more contacts are reduced to the deepest one and to
+    /// those maximizing the area they cover on the contact plane.
+    pub max_manifold_contacts: usize,
     /// Maximum number of iterations performed to solve non-penetration and joint constraints (default: `4`).
     pub max_velocity_iterations: usize,
     /// Maximum number of iterations performed to solve friction constraints (default: `8`).
@@ -45,8 +78,24 @@ pub struct IntegrationParameters {
     pub interleave_restitution_and_friction_resolution: bool,
     /// Minimum number of dynamic bodies in each active island (default: `128`).
     pub min_island_size: usize,
+    /// The radius of the sphere, centered on each rigid-body waking up, within which the
+    /// sleeping rigid-bodies are woken up too (default: `0.0`).
+    ///
+    /// The sleeping rigid-bodies are woken up if the bounding sphere of the AABB of one of
+    /// their colliders intersects this sphere, even if they aren't touching the rigid-body
+    /// waking up. The rigid-bodies woken up this way wake up their own neighbors too.
+    pub wake_up_radius: Real,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// If `true`, the rigid-bodies of each island, as well as the contacts and joints, are sorted
+    /// by handle before being fed to the constraints solver (default: `false`).
+    ///
+    /// This makes the simulation results independent from the order in which the bodies,
+    /// colliders, and joints were inserted or woken up, at the cost of a small overhead. Enable
+    /// the `enhanced-determinism` feature too for results identical across platforms.
+    pub deterministic_ordering: bool,
+    /// The method used to resolve the contacts and joints (default: `SolverMode::VelocityBased`).
+    pub solver_mode: SolverMode,
 }
 
 impl IntegrationParameters {
@@ -96,8 +145,10 @@ impl Default for IntegrationParameters {
             min_ccd_dt: 1.0 / 60.0 / 100.0,
             velocity_solve_fraction: 1.0,
             erp: 0.8,
+            warmstart_coefficient: 0.8,
             allowed_linear_error: 0.001, // 0.005
             prediction_distance: 0.002,
+            max_manifold_contacts: 4,
             max_velocity_iterations: 4,
             max_velocity_friction_iterations: 8,
             max_stabilization_iterations: 1,
@@ -108,7 +159,10 @@ impl Default for IntegrationParameters {
             // However we don't want it to be too small and end up with
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
+            wake_up_radius: 0.0,
             max_ccd_substeps: 1,
+            deterministic_ordering: false,
+            solver_mode: SolverMode::VelocityBased,
         }
     }
 }
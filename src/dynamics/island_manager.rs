@@ -1,10 +1,57 @@
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
 use crate::dynamics::{
     ImpulseJointSet, MultibodyJointSet, RigidBodyActivation, RigidBodyColliders, RigidBodyHandle,
-    RigidBodyIds, RigidBodyType, RigidBodyVelocity,
+    RigidBodyIds, RigidBodyMassProps, RigidBodyType, RigidBodyVelocity,
 };
-use crate::geometry::{ColliderParent, NarrowPhase};
-use crate::math::Real;
+use crate::geometry::{BroadPhase, ColliderParent, NarrowPhase, AABB};
+use crate::math::{Point, Real, Vector};
+use crate::pipeline::EventHandler;
+
+/// Statistics about a simulation island, measured during the last timestep.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IslandMetrics {
+    /// The number of active dynamic rigid-bodies in this island.
+    pub body_count: u32,
+    /// The number of impulse joints solved in this island.
+    pub joint_count: u32,
+    /// The number of contact points solved in this island.
+    pub contact_count: u32,
+    /// The time, in nanoseconds, spent solving the constraints of this island.
+    pub last_solve_time_ns: u64,
+}
+
+/// An event emitted when the connectivity of the simulation islands changes.
+///
+/// Here, an island is a set of dynamic rigid-bodies connected through contacts or joints,
+/// independently from the way they are grouped by the constraints solver. The rigid-bodies
+/// are connected if their colliders have contact points seen by the constraints solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum IslandEvent {
+    /// Rigid-bodies from several islands, e.g., a falling crate and a sleeping pile of
+    /// crates, became connected.
+    Merged {
+        /// All the rigid-bodies of the resulting island.
+        bodies: Vec<RigidBodyHandle>,
+    },
+    /// The rigid-bodies of an island, e.g., the ones attached by a joint that has been
+    /// removed, are no longer connected.
+    Split {
+        /// The rigid-bodies of each of the resulting islands.
+        new_islands: Vec<Vec<RigidBodyHandle>>,
+    },
+}
+
+/// An event emitted when a dynamic rigid-body falls asleep or wakes up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum SleepEvent {
+    /// The rigid-body fell asleep.
+    BodySlept(RigidBodyHandle),
+    /// The rigid-body woke up.
+    BodyAwoke(RigidBodyHandle),
+}
 
 /// Structure responsible for maintaining the set of active rigid-bodies, and
 /// putting non-moving rigid-bodies to sleep to save computation times.
@@ -15,10 +62,19 @@ pub struct IslandManager {
     pub(crate) active_kinematic_set: Vec<RigidBodyHandle>,
     pub(crate) active_islands: Vec<usize>,
     active_set_timestamp: u32,
+    island_label_timestamp: u64,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub(crate) island_metrics: Vec<IslandMetrics>,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     can_sleep: Vec<RigidBodyHandle>, // Workspace.
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     stack: Vec<RigidBodyHandle>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    connected_bodies: Vec<RigidBodyHandle>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    connected_components: Vec<usize>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    label_transitions: Vec<(u64, usize)>, // Workspace.
 }
 
 impl IslandManager {
@@ -29,8 +85,13 @@ impl IslandManager {
             active_kinematic_set: vec![],
             active_islands: vec![],
             active_set_timestamp: 0,
+            island_label_timestamp: 0,
+            island_metrics: vec![],
             can_sleep: vec![],
             stack: vec![],
+            connected_bodies: vec![],
+            connected_components: vec![],
+            label_transitions: vec![],
         }
     }
 
@@ -122,6 +183,18 @@ impl IslandManager {
         &self.active_dynamic_set[..]
     }
 
+    /// The statistics of the `island_index`-th simulation island, measured during the last timestep.
+    ///
+    /// Panics if there is no island with this index.
+    pub fn island_metrics(&self, island_index: usize) -> IslandMetrics {
+        self.island_metrics[island_index]
+    }
+
+    /// The statistics of all the simulation islands, measured during the last timestep.
+    pub fn all_island_metrics(&self) -> &[IslandMetrics] {
+        &self.island_metrics
+    }
+
     pub(crate) fn active_island(&self, island_id: usize) -> &[RigidBodyHandle] {
         let island_range = self.active_islands[island_id]..self.active_islands[island_id + 1];
         &self.active_dynamic_set[island_range]
@@ -170,18 +243,38 @@ impl IslandManager {
         self.active_islands[island_id]..self.active_islands[island_id + 1]
     }
 
+    /// Sorts the rigid-bodies of each active island by handle.
+    pub(crate) fn sort_active_islands(&mut self, bodies: &mut impl ComponentSetMut<RigidBodyIds>) {
+        for island_id in 0..self.num_islands() {
+            let island_range = self.active_islands[island_id]..self.active_islands[island_id + 1];
+            let island = &mut self.active_dynamic_set[island_range.clone()];
+            island.sort_unstable_by_key(|h| h.0);
+
+            for (active_set_offset, handle) in island.iter().enumerate() {
+                bodies.map_mut_internal(handle.0, |ids: &mut RigidBodyIds| {
+                    ids.active_set_id = island_range.start + active_set_offset;
+                    ids.active_set_offset = active_set_offset;
+                });
+            }
+        }
+    }
+
     pub(crate) fn update_active_set_with_contacts<Bodies, Colliders>(
         &mut self,
         bodies: &mut Bodies,
         colliders: &Colliders,
+        broad_phase: &BroadPhase,
         narrow_phase: &NarrowPhase,
         impulse_joints: &ImpulseJointSet,
         multibody_joints: &MultibodyJointSet,
         min_island_size: usize,
+        wake_up_radius: Real,
+        events: &dyn EventHandler,
     ) where
         Bodies: ComponentSetMut<RigidBodyIds>
             + ComponentSetMut<RigidBodyActivation>
             + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
             + ComponentSet<RigidBodyColliders>
             + ComponentSet<RigidBodyType>,
         Colliders: ComponentSetOption<ColliderParent>,
@@ -206,10 +299,10 @@ impl IslandManager {
             let can_sleep = &mut self.can_sleep;
             let stack = &mut self.stack;
 
-            let vels: &RigidBodyVelocity = bodies.index(h.0);
-            let pseudo_kinetic_energy = vels.pseudo_kinetic_energy();
+            let vels: RigidBodyVelocity = *bodies.index(h.0);
 
             bodies.map_mut_internal(h.0, |activation: &mut RigidBodyActivation| {
+                let pseudo_kinetic_energy = activation.pseudo_kinetic_energy(&vels);
                 update_energy(activation, pseudo_kinetic_energy);
 
                 if activation.energy <= activation.threshold {
@@ -250,6 +343,25 @@ impl IslandManager {
             }
         }
 
+        // Push the bodies with a collider which AABB's bounding sphere intersects the given sphere.
+        fn push_nearby_bodies(
+            center: Point<Real>,
+            radius: Real,
+            colliders: &impl ComponentSetOption<ColliderParent>,
+            broad_phase: &BroadPhase,
+            stack: &mut Vec<RigidBodyHandle>,
+        ) {
+            let aabb = AABB::from_half_extents(center, Vector::repeat(radius));
+            broad_phase.colliders_intersecting_aabb(&aabb, |handle, co_aabb| {
+                let max_dist = radius + co_aabb.half_extents().norm();
+                if na::distance_squared(&center, &co_aabb.center()) <= max_dist * max_dist {
+                    if let Some(co_parent) = colliders.get(handle.0) {
+                        stack.push(co_parent.handle);
+                    }
+                }
+            });
+        }
+
         // Now iterate on all active kinematic bodies and push all the bodies
         // touching them to the stack so they can be woken up.
         for h in self.active_kinematic_set.iter() {
@@ -271,6 +383,8 @@ impl IslandManager {
         // traversal of the interaction graph.
         self.active_islands.clear();
         self.active_islands.push(0);
+        self.connected_components.clear();
+        self.connected_components.push(0);
 
         // The max avoid underflow when the stack is empty.
         let mut island_marker = self.stack.len().max(1) - 1;
@@ -296,6 +410,12 @@ impl IslandManager {
                     self.active_islands.push(self.active_dynamic_set.len());
                 }
 
+                if self.active_dynamic_set.len() > *self.connected_components.last().unwrap() {
+                    // We are starting a new connected component.
+                    self.connected_components
+                        .push(self.active_dynamic_set.len());
+                }
+
                 island_marker = self.stack.len();
             }
 
@@ -315,18 +435,88 @@ impl IslandManager {
             bodies.map_mut_internal(handle.0, |activation: &mut RigidBodyActivation| {
                 activation.wake_up(false);
             });
+            let mut awoke = false;
             bodies.map_mut_internal(handle.0, |ids: &mut RigidBodyIds| {
                 ids.active_island_id = self.active_islands.len() - 1;
                 ids.active_set_id = self.active_dynamic_set.len();
                 ids.active_set_offset =
                     ids.active_set_id - self.active_islands[ids.active_island_id];
                 ids.active_set_timestamp = self.active_set_timestamp;
+                awoke = std::mem::replace(&mut ids.reported_sleeping, false);
             });
 
+            if awoke {
+                events.handle_sleep_event(SleepEvent::BodyAwoke(handle));
+
+                if wake_up_radius > 0.0 {
+                    let mprops: &RigidBodyMassProps = bodies.index(handle.0);
+                    push_nearby_bodies(
+                        mprops.world_com,
+                        wake_up_radius,
+                        colliders,
+                        broad_phase,
+                        &mut self.stack,
+                    );
+                }
+            }
+
             self.active_dynamic_set.push(handle);
         }
 
         self.active_islands.push(self.active_dynamic_set.len());
+
+        self.connected_bodies.clear();
+        self.connected_bodies
+            .extend_from_slice(&self.active_dynamic_set);
+        if self.connected_bodies.len() > *self.connected_components.last().unwrap() {
+            self.connected_components.push(self.connected_bodies.len());
+        }
+
+        // Find the connected components of the bodies falling asleep. They may be
+        // connected to bodies that were already sleeping, but not to awake bodies.
+        for h in &self.can_sleep {
+            self.stack.push(*h);
+
+            while let Some(handle) = self.stack.pop() {
+                let (rb_status, rb_ids, rb_activation, rb_colliders): (
+                    &RigidBodyType,
+                    &RigidBodyIds,
+                    &RigidBodyActivation,
+                    &RigidBodyColliders,
+                ) = bodies.index_bundle(handle.0);
+
+                if rb_ids.active_set_timestamp == self.active_set_timestamp
+                    || !rb_status.is_dynamic()
+                    || !rb_activation.sleeping
+                {
+                    continue;
+                }
+
+                push_contacting_bodies(rb_colliders, colliders, narrow_phase, &mut self.stack);
+
+                for inter in impulse_joints.joints_with(handle) {
+                    let other = crate::utils::select_other((inter.0, inter.1), handle);
+                    self.stack.push(other);
+                }
+
+                for other in multibody_joints.attached_bodies(handle) {
+                    self.stack.push(other);
+                }
+
+                let timestamp = self.active_set_timestamp;
+                bodies.map_mut_internal(handle.0, |ids: &mut RigidBodyIds| {
+                    ids.active_set_timestamp = timestamp;
+                });
+                self.connected_bodies.push(handle);
+            }
+
+            if self.connected_bodies.len() > *self.connected_components.last().unwrap() {
+                self.connected_components.push(self.connected_bodies.len());
+            }
+        }
+
+        self.update_island_labels(bodies, events);
+
         //        println!(
         //            "Extraction: {}, num islands: {}",
         //            instant::now() - t,
@@ -341,13 +531,389 @@ impl IslandManager {
                 bodies.map_mut_internal(h.0, |activation: &mut RigidBodyActivation| {
                     activation.sleep()
                 });
+
+                let mut slept = false;
+                bodies.map_mut_internal(h.0, |ids: &mut RigidBodyIds| {
+                    slept = !std::mem::replace(&mut ids.reported_sleeping, true);
+                });
+
+                if slept {
+                    events.handle_sleep_event(SleepEvent::BodySlept(*h));
+                }
             }
         }
     }
 }
 
+impl IslandManager {
+    /// Gives a new label to each connected component, and emits the island events by
+    /// comparing them with the labels of the previous components of their bodies.
+    fn update_island_labels(
+        &mut self,
+        bodies: &mut impl ComponentSetMut<RigidBodyIds>,
+        events: &dyn EventHandler,
+    ) {
+        self.label_transitions.clear();
+
+        for (component_id, range) in self.connected_components.windows(2).enumerate() {
+            self.island_label_timestamp += 1;
+            let label = self.island_label_timestamp;
+
+            for handle in &self.connected_bodies[range[0]..range[1]] {
+                let rb_ids: &RigidBodyIds = bodies.index(handle.0);
+
+                // Bodies that never were in any component don't cause any merge.
+                if rb_ids.island_label != 0 {
+                    self.label_transitions
+                        .push((rb_ids.island_label, component_id));
+                }
+
+                bodies.map_mut_internal(handle.0, |ids: &mut RigidBodyIds| {
+                    ids.island_label = label;
+                });
+            }
+        }
+
+        self.label_transitions.sort_unstable();
+        self.label_transitions.dedup();
+
+        let component = |component_id: usize| {
+            let start = self.connected_components[component_id];
+            let end = self.connected_components[component_id + 1];
+            self.connected_bodies[start..end].to_vec()
+        };
+
+        // A component containing bodies with different previous labels is the result of a merge.
+        let mut num_previous_labels = vec![0; self.connected_components.len() - 1];
+        for (_, component_id) in &self.label_transitions {
+            num_previous_labels[*component_id] += 1;
+        }
+
+        for (component_id, num_labels) in num_previous_labels.iter().enumerate() {
+            if *num_labels > 1 {
+                events.handle_island_event(IslandEvent::Merged {
+                    bodies: component(component_id),
+                });
+            }
+        }
+
+        // A previous label found in several components is the result of a split.
+        let mut start = 0;
+        while start < self.label_transitions.len() {
+            let label = self.label_transitions[start].0;
+            let mut end = start + 1;
+
+            while end < self.label_transitions.len() && self.label_transitions[end].0 == label {
+                end += 1;
+            }
+
+            if end - start > 1 {
+                events.handle_island_event(IslandEvent::Split {
+                    new_islands: self.label_transitions[start..end]
+                        .iter()
+                        .map(|(_, component_id)| component(*component_id))
+                        .collect(),
+                });
+            }
+
+            start = end;
+        }
+    }
+}
+
 fn update_energy(activation: &mut RigidBodyActivation, pseudo_kinetic_energy: Real) {
     let mix_factor = 0.01;
     let new_energy = (1.0 - mix_factor) * activation.energy + mix_factor * pseudo_kinetic_energy;
     activation.energy = new_energy.min(activation.threshold.abs() * 4.0);
 }
+
+#[cfg(test)]
+mod test {
+    use super::{IslandEvent, SleepEvent};
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::{
+        BroadPhase, ColliderBuilder, ContactEvent, ContactPair, IntersectionEvent,
+    };
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::EventHandler;
+    use std::sync::Mutex;
+
+    #[test]
+    fn island_events_report_merges_and_splits() {
+        #[derive(Default)]
+        struct IslandEventCollector(Mutex<Vec<IslandEvent>>);
+
+        impl EventHandler for IslandEventCollector {
+            fn handle_intersection_event(&self, _: IntersectionEvent) {}
+            fn handle_contact_event(&self, _: ContactEvent, _: &ContactPair) {}
+            fn handle_island_event(&self, event: IslandEvent) {
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // Two elastic balls colliding head-on, then bouncing away from each other.
+        let mut handles = Vec::new();
+        for x in [-1.0, 1.0] {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * x)
+                .linvel(Vector::x() * -x)
+                .build();
+            let handle = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).restitution(1.0).build();
+            world
+                .colliders
+                .insert_with_parent(collider, handle, &mut world.bodies);
+            handles.push(handle);
+        }
+
+        let events = IslandEventCollector::default();
+        for _ in 0..120 {
+            world.step_with(&(), &events);
+        }
+
+        assert!(world.bodies[handles[0]].linvel().x < 0.0);
+
+        let sorted = |mut handles: Vec<RigidBodyHandle>| {
+            handles.sort_unstable_by_key(|h| h.0.into_raw_parts());
+            handles
+        };
+        let events = events.0.into_inner().unwrap();
+        assert_eq!(events.len(), 2);
+
+        match &events[0] {
+            IslandEvent::Merged { bodies } => assert_eq!(sorted(bodies.clone()), handles),
+            event => panic!("Unexpected island event: {:?}", event),
+        }
+
+        match &events[1] {
+            IslandEvent::Split { new_islands } => {
+                let mut new_islands = new_islands.clone();
+                new_islands.sort_unstable_by_key(|island| island[0].0.into_raw_parts());
+                assert_eq!(new_islands, vec![vec![handles[0]], vec![handles[1]]]);
+            }
+            event => panic!("Unexpected island event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn sleep_events_report_sleeping_transitions() {
+        #[derive(Default)]
+        struct SleepEventCollector(Mutex<Vec<SleepEvent>>);
+
+        impl EventHandler for SleepEventCollector {
+            fn handle_intersection_event(&self, _: IntersectionEvent) {}
+            fn handle_contact_event(&self, _: ContactEvent, _: &ContactPair) {}
+            fn handle_sleep_event(&self, event: SleepEvent) {
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        let ball = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 1.0)
+            .build();
+        let ball = world.bodies.insert(ball);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ball,
+            &mut world.bodies,
+        );
+
+        // The ball falls asleep once it rests on the ground.
+        let events = SleepEventCollector::default();
+        for _ in 0..300 {
+            world.step_with(&(), &events);
+        }
+
+        assert!(world.bodies[ball].is_sleeping());
+        assert_eq!(*events.0.lock().unwrap(), vec![SleepEvent::BodySlept(ball)]);
+
+        // Applying an impulse wakes it up.
+        events.0.lock().unwrap().clear();
+        world.bodies[ball].apply_impulse(Vector::y() * 1.0, true);
+        world.step_with(&(), &events);
+
+        assert!(!world.bodies[ball].is_sleeping());
+        assert_eq!(*events.0.lock().unwrap(), vec![SleepEvent::BodyAwoke(ball)]);
+    }
+
+    #[test]
+    fn per_body_sleep_threshold() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // Slow bodies fall asleep with the default threshold, unless their own is smaller.
+        // Faster bodies stay awake with the default threshold, unless their own is larger.
+        let slow = RigidBodyBuilder::new_dynamic().linvel(Vector::x() * 0.05);
+        let fast = RigidBodyBuilder::new_dynamic().linvel(Vector::x() * 0.5);
+        let handles = [
+            world.bodies.insert(slow.clone().build()),
+            world
+                .bodies
+                .insert(slow.sleep_threshold(0.01, 0.01).build()),
+            world.bodies.insert(fast.clone().build()),
+            world.bodies.insert(fast.sleep_threshold(1.0, 1.0).build()),
+        ];
+
+        for (i, handle) in handles.iter().enumerate() {
+            let collider = ColliderBuilder::ball(0.5)
+                .translation(Vector::y() * (i as Real * 2.0))
+                .build();
+            world
+                .colliders
+                .insert_with_parent(collider, *handle, &mut world.bodies);
+        }
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        let sleeping: Vec<_> = handles
+            .iter()
+            .map(|h| world.bodies[*h].is_sleeping())
+            .collect();
+        assert_eq!(sleeping, [true, false, false, true]);
+    }
+
+    #[test]
+    fn wake_up_radius() {
+        // Returns which of the sleeping balls are awake after waking up the first one.
+        let awake_after_wake_up = |wake_up_radius: Real, broad_phase: BroadPhase| {
+            let mut world = TestWorld::new(Vector::zeros());
+            world.broad_phase = broad_phase;
+            world.integration_parameters = IntegrationParameters {
+                wake_up_radius,
+                ..IntegrationParameters::default()
+            };
+
+            // None of the balls are touching each other.
+            let handles: Vec<_> = [0.0, 2.0, 4.0, 20.0]
+                .iter()
+                .map(|x| {
+                    let body = RigidBodyBuilder::new_dynamic()
+                        .translation(Vector::x() * *x)
+                        .build();
+                    let handle = world.bodies.insert(body);
+                    let collider = ColliderBuilder::ball(0.5).build();
+                    world
+                        .colliders
+                        .insert_with_parent(collider, handle, &mut world.bodies);
+                    handle
+                })
+                .collect();
+
+            world.step();
+            for handle in &handles {
+                world.bodies[*handle].sleep();
+            }
+            world.step();
+            assert!(handles.iter().all(|h| world.bodies[*h].is_sleeping()));
+
+            world.bodies[handles[0]].wake_up(true);
+            world.step();
+            handles
+                .iter()
+                .map(|h| !world.bodies[*h].is_sleeping())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            awake_after_wake_up(0.0, BroadPhase::new()),
+            [true, false, false, false]
+        );
+        // The second ball wakes up the third one, but none of them reach the last one.
+        assert_eq!(
+            awake_after_wake_up(1.5, BroadPhase::new()),
+            [true, true, true, false]
+        );
+        assert_eq!(
+            awake_after_wake_up(1.5, BroadPhase::with_spatial_hash(1.0)),
+            [true, true, true, false]
+        );
+    }
+
+    // The parallel solver doesn't support impulse joints yet.
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn island_metrics_count_the_bodies_contacts_and_joints() {
+        use crate::dynamics::FixedJoint;
+        use crate::math::Point;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        // Don't merge the small islands together.
+        world.integration_parameters.min_island_size = 1;
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // A box resting on the ground.
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y())
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world
+            .colliders
+            .insert_with_parent(shape.build(), body, &mut world.bodies);
+
+        // Two balls welded together, falling far above the ground.
+        let balls: Vec<_> = (0..2)
+            .map(|i| {
+                let body = world.bodies.insert(
+                    RigidBodyBuilder::new_dynamic()
+                        .translation(Vector::y() * (20.0 + i as Real * 2.0))
+                        .build(),
+                );
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5).build(),
+                    body,
+                    &mut world.bodies,
+                );
+                body
+            })
+            .collect();
+        let joint = FixedJoint::new().local_anchor2(Point::from(-Vector::y() * 2.0));
+        world.impulse_joints.insert(balls[0], balls[1], joint);
+
+        world.step();
+
+        let mut metrics = world.islands.all_island_metrics().to_vec();
+        assert_eq!(metrics.len(), 2);
+        metrics.sort_by_key(|metrics| metrics.joint_count);
+
+        #[cfg(feature = "dim2")]
+        let box_contacts = 2;
+        #[cfg(feature = "dim3")]
+        let box_contacts = 4;
+        assert_eq!(metrics[0].body_count, 1);
+        assert_eq!(metrics[0].joint_count, 0);
+        assert_eq!(metrics[0].contact_count, box_contacts);
+        assert_eq!(metrics[1].body_count, 2);
+        assert_eq!(metrics[1].joint_count, 1);
+        assert_eq!(metrics[1].contact_count, 0);
+
+        for (i, metrics) in world.islands.all_island_metrics().iter().enumerate() {
+            assert_eq!(world.islands.island_metrics(i), *metrics);
+        }
+    }
+}
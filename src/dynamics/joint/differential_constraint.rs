@@ -0,0 +1,246 @@
+use crate::dynamics::{RigidBody, RigidBodyHandle, RigidBodySet};
+use crate::math::{AngVector, Real};
+use crate::utils::WDot;
+
+#[cfg(feature = "dim3")]
+use crate::math::{UnitVector, Vector};
+
+/// A differential gear coupling the rotation of a driveshaft to the rotations of two wheels.
+///
+/// An open differential enforces `ω_drive = ratio * (ω_left + ω_right) / 2` while letting
+/// the wheels rotate at different speeds, e.g., when the vehicle turns. A locked differential
+/// additionally enforces `ω_left = ω_right`.
+///
+/// The constraint involves three rigid-bodies so it isn't handled by the constraints solver.
+/// Instead, `Self::update` must be called before each timestep to apply the impulses making
+/// the angular velocities of the bodies satisfy it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct DifferentialConstraint {
+    /// The rigid-body of the driveshaft.
+    pub driveshaft: RigidBodyHandle,
+    /// The rigid-body of the left wheel.
+    pub left_wheel: RigidBodyHandle,
+    /// The rigid-body of the right wheel.
+    pub right_wheel: RigidBodyHandle,
+    /// The ratio between the angular velocity of the driveshaft and the mean angular velocity
+    /// of the wheels.
+    pub ratio: Real,
+    /// Does this differential also force both wheels to rotate at the same speed?
+    pub locked: bool,
+    /// The rotation axis of the driveshaft, in its local-space.
+    #[cfg(feature = "dim3")]
+    pub driveshaft_axis: UnitVector<Real>,
+    /// The rotation axis of the left wheel, in its local-space.
+    #[cfg(feature = "dim3")]
+    pub left_axis: UnitVector<Real>,
+    /// The rotation axis of the right wheel, in its local-space.
+    #[cfg(feature = "dim3")]
+    pub right_axis: UnitVector<Real>,
+}
+
+impl DifferentialConstraint {
+    /// Creates an open differential.
+    ///
+    /// In 3D, all the rotation axes default to the local `x` axis of their rigid-body.
+    pub fn new(
+        driveshaft: RigidBodyHandle,
+        left_wheel: RigidBodyHandle,
+        right_wheel: RigidBodyHandle,
+        ratio: Real,
+    ) -> Self {
+        Self {
+            driveshaft,
+            left_wheel,
+            right_wheel,
+            ratio,
+            locked: false,
+            #[cfg(feature = "dim3")]
+            driveshaft_axis: Vector::x_axis(),
+            #[cfg(feature = "dim3")]
+            left_axis: Vector::x_axis(),
+            #[cfg(feature = "dim3")]
+            right_axis: Vector::x_axis(),
+        }
+    }
+
+    /// Sets whether this differential is locked.
+    #[must_use]
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Sets the local-space rotation axes of the driveshaft, left wheel, and right wheel.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn axes(
+        mut self,
+        driveshaft_axis: UnitVector<Real>,
+        left_axis: UnitVector<Real>,
+        right_axis: UnitVector<Real>,
+    ) -> Self {
+        self.driveshaft_axis = driveshaft_axis;
+        self.left_axis = left_axis;
+        self.right_axis = right_axis;
+        self
+    }
+
+    /// The error `ω_drive - ratio * (ω_left + ω_right) / 2` of the open differential
+    /// equation, and the error `ω_left - ω_right` of the locked differential equation.
+    ///
+    /// Returns `None` if one of the rigid-bodies doesn't exist.
+    pub fn velocity_errors(&self, bodies: &RigidBodySet) -> Option<(Real, Real)> {
+        let speeds = [
+            self.axis_and_speed(bodies.get(self.driveshaft)?, 0).1,
+            self.axis_and_speed(bodies.get(self.left_wheel)?, 1).1,
+            self.axis_and_speed(bodies.get(self.right_wheel)?, 2).1,
+        ];
+        Some((
+            speeds[0] - self.ratio * (speeds[1] + speeds[2]) / 2.0,
+            speeds[1] - speeds[2],
+        ))
+    }
+
+    /// Applies the angular impulses making the bodies satisfy the differential equations.
+    ///
+    /// Does nothing if one of the rigid-bodies doesn't exist.
+    pub fn update(&self, bodies: &mut RigidBodySet) {
+        let handles = [self.driveshaft, self.left_wheel, self.right_wheel];
+        if handles.iter().any(|h| bodies.get(*h).is_none()) {
+            return;
+        }
+
+        // The jacobians of the open (first) and locked (second) differential equations.
+        let half_ratio = self.ratio / 2.0;
+        let coeffs = [[1.0, -half_ratio, -half_ratio], [0.0, 1.0, -1.0]];
+        let mut jacobians: [[AngVector<Real>; 3]; 2] = [[na::zero(); 3]; 2];
+        let mut errors = [0.0; 2];
+        let mut lhs = [[0.0; 2]; 2];
+
+        for (i, handle) in handles.iter().enumerate() {
+            let body = &bodies[*handle];
+            let (axis, speed) = self.axis_and_speed(body, i);
+            let inv_inertia_sqrt = body.rb_mprops.effective_world_inv_inertia_sqrt;
+
+            for k in 0..2 {
+                jacobians[k][i] = axis * coeffs[k][i];
+                errors[k] += coeffs[k][i] * speed;
+            }
+
+            let weighted = [
+                inv_inertia_sqrt * jacobians[0][i],
+                inv_inertia_sqrt * jacobians[1][i],
+            ];
+            for k in 0..2 {
+                for l in 0..2 {
+                    lhs[k][l] += weighted[k].gdot(weighted[l]);
+                }
+            }
+        }
+
+        let impulses = if self.locked {
+            let det = lhs[0][0] * lhs[1][1] - lhs[0][1] * lhs[1][0];
+            if det.abs() <= Real::EPSILON {
+                return;
+            }
+            [
+                (-errors[0] * lhs[1][1] + errors[1] * lhs[0][1]) / det,
+                (-errors[1] * lhs[0][0] + errors[0] * lhs[1][0]) / det,
+            ]
+        } else {
+            if lhs[0][0] <= Real::EPSILON {
+                return;
+            }
+            [-errors[0] / lhs[0][0], 0.0]
+        };
+
+        for (i, handle) in handles.iter().enumerate() {
+            let torque_impulse = jacobians[0][i] * impulses[0] + jacobians[1][i] * impulses[1];
+            bodies[*handle].apply_torque_impulse(torque_impulse, true);
+        }
+    }
+
+    /// The world-space rotation axis of the `i`-th body of this constraint, and its angular
+    /// velocity around that axis.
+    #[cfg(feature = "dim2")]
+    fn axis_and_speed(&self, body: &RigidBody, _i: usize) -> (AngVector<Real>, Real) {
+        (1.0, body.angvel())
+    }
+
+    /// The world-space rotation axis of the `i`-th body of this constraint, and its angular
+    /// velocity around that axis.
+    #[cfg(feature = "dim3")]
+    fn axis_and_speed(&self, body: &RigidBody, i: usize) -> (AngVector<Real>, Real) {
+        let local_axis = [self.driveshaft_axis, self.left_axis, self.right_axis][i];
+        let axis = body.position().rotation * local_axis.into_inner();
+        (axis, axis.dot(body.angvel()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DifferentialConstraint;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn differential_constraints_couple_driveshaft_and_wheels() {
+        for locked in [false, true] {
+            let mut world = TestWorld::new(Vector::zeros());
+
+            let mut handles = Vec::new();
+            for (i, angvel) in [0.0, 3.0, -1.0].iter().enumerate() {
+                #[cfg(feature = "dim2")]
+                let angvel = *angvel;
+                #[cfg(feature = "dim3")]
+                let angvel = Vector::x() * *angvel;
+                let body = RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::x() * (i as Real * 3.0))
+                    .angvel(angvel)
+                    .build();
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5).build(),
+                    handle,
+                    &mut world.bodies,
+                );
+                handles.push(handle);
+            }
+
+            let differential =
+                DifferentialConstraint::new(handles[0], handles[1], handles[2], 2.0).locked(locked);
+
+            for _ in 0..30 {
+                #[cfg(feature = "dim2")]
+                world.bodies[handles[0]].apply_torque(1.0, true);
+                #[cfg(feature = "dim3")]
+                world.bodies[handles[0]].apply_torque(Vector::x(), true);
+
+                differential.update(&mut world.bodies);
+                let (open_error, locked_error) =
+                    differential.velocity_errors(&world.bodies).unwrap();
+                assert!(open_error.abs() < 1.0e-4);
+                assert_eq!(locked_error.abs() < 1.0e-4, locked);
+
+                world.step();
+            }
+
+            // The driveshaft torque spins the wheels up. The open differential splits it
+            // evenly between the identical wheels, so their speed difference is preserved.
+            #[cfg(feature = "dim2")]
+            let speeds: Vec<_> = handles.iter().map(|h| world.bodies[*h].angvel()).collect();
+            #[cfg(feature = "dim3")]
+            let speeds: Vec<_> = handles
+                .iter()
+                .map(|h| world.bodies[*h].angvel().x)
+                .collect();
+            assert!(speeds[1] + speeds[2] > 2.0);
+            if !locked {
+                assert!((speeds[1] - speeds[2] - 4.0).abs() < 1.0e-3);
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::dynamics::{JointAxesMask, JointData};
 use crate::math::{Isometry, Point, Real};
 
+/// A fixed joint, also known as a weld joint.
+pub type WeldJoint = FixedJoint;
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct FixedJoint {
@@ -46,6 +49,17 @@ impl FixedJoint {
         self.data = self.data.local_anchor2(anchor2);
         self
     }
+
+    /// Makes this joint break once the linear or angular impulse it applies during
+    /// one timestep exceeds `linear` or `angular`.
+    ///
+    /// Broken joints are removed from the `ImpulseJointSet` at the end of the velocity
+    /// resolution and reported by `ImpulseJointSet::drain_break_events`.
+    #[must_use]
+    pub fn break_threshold(mut self, linear: Real, angular: Real) -> Self {
+        self.data = self.data.break_threshold(linear, angular);
+        self
+    }
 }
 
 impl Into<JointData> for FixedJoint {
@@ -0,0 +1,140 @@
+use crate::dynamics::joint::{JointCouplingKind, JointData};
+use crate::math::Real;
+
+#[cfg(feature = "dim3")]
+use crate::math::UnitVector;
+
+/// A joint locking the ratio between the angular velocities of two bodies,
+/// like two meshing gears.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GearJoint {
+    data: JointData,
+}
+
+impl GearJoint {
+    /// Creates a new gear joint enforcing `angvel1 + ratio * angvel2 = 0`.
+    #[cfg(feature = "dim2")]
+    pub fn new(ratio: Real) -> Self {
+        let data = JointData::default().coupling(JointCouplingKind::Gear, ratio);
+        Self { data }
+    }
+
+    /// Creates a new gear joint enforcing `angvel1 · axis1 + ratio * angvel2 · axis2 = 0`.
+    ///
+    /// The rotation axes are expressed in the local-space of their respective rigid-body.
+    #[cfg(feature = "dim3")]
+    pub fn new(axis1: UnitVector<Real>, axis2: UnitVector<Real>, ratio: Real) -> Self {
+        let data = JointData::default()
+            .local_axis1(axis1)
+            .local_axis2(axis2)
+            .coupling(JointCouplingKind::Gear, ratio);
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    #[must_use]
+    pub fn data(&self) -> &JointData {
+        &self.data
+    }
+
+    /// Sets the target value of the coupled angular velocities.
+    ///
+    /// This makes the gears slip at the given relative velocity instead of being locked.
+    #[must_use]
+    pub fn motor_velocity(mut self, target_vel: Real, factor: Real) -> Self {
+        self.data = self.data.coupling_motor_velocity(target_vel, factor);
+        self
+    }
+
+    /// Sets the maximum impulse the gears can apply to each other.
+    #[must_use]
+    pub fn motor_max_impulse(mut self, max_impulse: Real) -> Self {
+        self.data = self.data.coupling_motor_max_impulse(max_impulse);
+        self
+    }
+}
+
+impl Into<JointData> for GearJoint {
+    fn into(self) -> JointData {
+        self.data
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::GearJoint;
+    use crate::dynamics::{RevoluteJoint, RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    // Returns the angular velocities of two gears, pinned to the ground, after one second.
+    fn run(gear: GearJoint) -> (Real, Real) {
+        let mut world = TestWorld::new(Vector::zeros());
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+
+        let mut gears: Vec<RigidBodyHandle> = vec![];
+        for (i, angvel) in [2.0, 0.0].into_iter().enumerate() {
+            let center = Vector::x() * (i as Real * 1.5);
+            #[cfg(feature = "dim2")]
+            let (hinge, angvel) = (RevoluteJoint::new(), angvel);
+            #[cfg(feature = "dim3")]
+            let (hinge, angvel) = (RevoluteJoint::new(Vector::z_axis()), Vector::z() * angvel);
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(center)
+                .angvel(angvel)
+                .build();
+            let handle = world.bodies.insert(body);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                handle,
+                &mut world.bodies,
+            );
+            world
+                .impulse_joints
+                .insert(ground, handle, hinge.local_anchor1(Point::from(center)));
+            gears.push(handle);
+        }
+        world.impulse_joints.insert(gears[0], gears[1], gear);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        #[cfg(feature = "dim2")]
+        return (
+            world.bodies[gears[0]].angvel(),
+            world.bodies[gears[1]].angvel(),
+        );
+        #[cfg(feature = "dim3")]
+        return (
+            world.bodies[gears[0]].angvel().z,
+            world.bodies[gears[1]].angvel().z,
+        );
+    }
+
+    #[cfg(feature = "dim2")]
+    fn gear(ratio: Real) -> GearJoint {
+        GearJoint::new(ratio)
+    }
+
+    #[cfg(feature = "dim3")]
+    fn gear(ratio: Real) -> GearJoint {
+        GearJoint::new(Vector::z_axis(), Vector::z_axis(), ratio)
+    }
+
+    #[test]
+    fn gears_lock_their_angular_velocity_ratio() {
+        let (angvel1, angvel2) = run(gear(2.0));
+        assert!(angvel1.abs() > 0.1);
+        assert!((angvel1 + 2.0 * angvel2).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn gear_motors_make_the_gears_slip() {
+        let (angvel1, angvel2) = run(gear(2.0).motor_velocity(1.0, 1.0));
+        assert!((angvel1 + 2.0 * angvel2 - 1.0).abs() < 1.0e-3);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::dynamics::{JointData, JointHandle, RigidBodyHandle};
-use crate::math::{Real, SpacialVector};
+use crate::math::{AngVector, Real, SpacialVector, Vector, DIM, SPATIAL_DIM};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq)]
@@ -18,3 +18,117 @@ pub struct ImpulseJoint {
     #[cfg(feature = "parallel")]
     pub(crate) constraint_index: usize,
 }
+
+impl ImpulseJoint {
+    /// The total impulse applied by this joint during the last timestep.
+    ///
+    /// This sums the impulses applied along each degree of freedom by the locked axes,
+    /// limits, and motors of this joint. It is expressed in the local frame of the joint
+    /// attached to the first rigid-body and is zero if the joint wasn't simulated last step.
+    pub fn accumulated_impulse(&self) -> JointImpulse {
+        let mut impulses = self.impulses;
+        let limit_bits = self.data.limit_axes.bits();
+        let motor_bits = self.data.motor_axes.bits();
+
+        for i in 0..SPATIAL_DIM {
+            if (limit_bits & (1 << i)) != 0 {
+                impulses[i] += self.data.limits[i].impulse;
+            }
+            if (motor_bits & (1 << i)) != 0 {
+                impulses[i] += self.data.motors[i].impulse;
+            }
+        }
+
+        JointImpulse {
+            linear: impulses.fixed_rows::<DIM>(0).into_owned(),
+            #[cfg(feature = "dim2")]
+            angular: impulses[DIM],
+            #[cfg(feature = "dim3")]
+            angular: impulses.fixed_rows::<3>(DIM).into_owned(),
+        }
+    }
+
+    /// Resets all the impulses stored by this joint.
+    pub(crate) fn reset_impulses(&mut self) {
+        self.impulses.fill(0.0);
+
+        for limit in &mut self.data.limits {
+            limit.impulse = 0.0;
+        }
+        for motor in &mut self.data.motors {
+            motor.impulse = 0.0;
+        }
+        if let Some(spring) = &mut self.data.spring {
+            spring.impulse = 0.0;
+        }
+        if let Some(coupling) = &mut self.data.coupling {
+            coupling.impulse = 0.0;
+        }
+    }
+}
+
+/// The impulse applied by a joint, split into its linear and angular parts.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointImpulse {
+    /// The linear impulse along each translational degree of freedom.
+    pub linear: Vector<Real>,
+    /// The angular impulse along each rotational degree of freedom.
+    pub angular: AngVector<Real>,
+}
+
+impl JointImpulse {
+    /// The norm of the angular part of this impulse.
+    pub fn angular_norm(&self) -> Real {
+        #[cfg(feature = "dim2")]
+        return self.angular.abs();
+        #[cfg(feature = "dim3")]
+        return self.angular.norm();
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use crate::dynamics::{FixedJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn accumulated_impulse_holds_a_hanging_body() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let anchor = world.bodies.insert(
+            RigidBodyBuilder::new_static()
+                .translation(Vector::y() * 10.0)
+                .build(),
+        );
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 9.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            body,
+            &mut world.bodies,
+        );
+        let joint = FixedJoint::new().local_anchor1(Point::from(-Vector::y()));
+        let joint = world.impulse_joints.insert(anchor, body, joint);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        // At rest, the joint compensates the weight of the body.
+        let weight_impulse = world.bodies[body].mass() * 9.81 * world.integration_parameters.dt;
+        let impulse = world
+            .impulse_joints
+            .get(joint)
+            .unwrap()
+            .accumulated_impulse();
+        assert!((impulse.linear.norm() - weight_impulse).abs() < weight_impulse * 0.01);
+        assert!(impulse.linear.x.abs() < weight_impulse * 0.01);
+        assert!(impulse.angular_norm() < weight_impulse * 0.01);
+    }
+}
@@ -4,7 +4,7 @@ use crate::geometry::{InteractionGraph, RigidBodyGraphIndex, TemporaryInteractio
 use crate::data::arena::Arena;
 use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetMut};
 use crate::dynamics::{IslandManager, RigidBodyActivation, RigidBodyIds, RigidBodyType};
-use crate::dynamics::{JointData, RigidBodyHandle};
+use crate::dynamics::{JointAxesMask, JointData, JointImpulse, RigidBodyHandle};
 
 /// The unique identifier of a joint added to the joint set.
 /// The unique identifier of a collider added to a collider set.
@@ -33,9 +33,71 @@ impl JointHandle {
     }
 }
 
+/// Event emitted when a joint breaks because of its break threshold.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct JointBreakEvent {
+    /// The (now invalid) handle of the broken joint.
+    pub joint: JointHandle,
+    /// The first rigid-body that was attached to the joint.
+    pub body1: RigidBodyHandle,
+    /// The second rigid-body that was attached to the joint.
+    pub body2: RigidBodyHandle,
+    /// The impulse applied by the joint during the timestep it broke.
+    pub impulse: JointImpulse,
+}
+
 pub(crate) type JointIndex = usize;
 pub(crate) type JointGraphEdge = crate::data::graph::Edge<ImpulseJoint>;
 
+/// The name of the kind of joint matching the locked axes of `data`.
+fn joint_kind(data: &JointData) -> &'static str {
+    let linear = JointAxesMask::X | JointAxesMask::Y;
+    #[cfg(feature = "dim3")]
+    let linear = linear | JointAxesMask::Z;
+
+    if data.locked_axes == JointAxesMask::all() {
+        "fixed"
+    } else if data.locked_axes == JointAxesMask::all() - JointAxesMask::X {
+        "prismatic"
+    } else if data.locked_axes == linear {
+        // In 2D, only locking the linear axes makes a revolute joint.
+        if cfg!(feature = "dim2") {
+            "revolute"
+        } else {
+            "spherical"
+        }
+    } else if data.locked_axes == JointAxesMask::all() - JointAxesMask::ANG_X {
+        "revolute"
+    } else {
+        "generic"
+    }
+}
+
+/// The names of the axes set in `mask`.
+fn joint_axes_names(mask: JointAxesMask) -> Vec<&'static str> {
+    #[cfg(feature = "dim2")]
+    let axes = [
+        (JointAxesMask::X, "X"),
+        (JointAxesMask::Y, "Y"),
+        (JointAxesMask::ANG_X, "ANG_X"),
+    ];
+    #[cfg(feature = "dim3")]
+    let axes = [
+        (JointAxesMask::X, "X"),
+        (JointAxesMask::Y, "Y"),
+        (JointAxesMask::Z, "Z"),
+        (JointAxesMask::ANG_X, "ANG_X"),
+        (JointAxesMask::ANG_Y, "ANG_Y"),
+        (JointAxesMask::ANG_Z, "ANG_Z"),
+    ];
+
+    axes.iter()
+        .filter(|(axis, _)| mask.contains(*axis))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Default)]
 /// A set of impulse_joints that can be handled by a physics `World`.
@@ -43,6 +105,7 @@ pub struct ImpulseJointSet {
     rb_graph_ids: Coarena<RigidBodyGraphIndex>,
     joint_ids: Arena<TemporaryInteractionIndex>, // Map joint handles to edge ids on the graph.
     joint_graph: InteractionGraph<RigidBodyHandle, ImpulseJoint>,
+    break_events: Vec<JointBreakEvent>,
 }
 
 impl ImpulseJointSet {
@@ -52,6 +115,7 @@ impl ImpulseJointSet {
             rb_graph_ids: Coarena::new(),
             joint_ids: Arena::new(),
             joint_graph: InteractionGraph::new(),
+            break_events: Vec::new(),
         }
     }
 
@@ -132,6 +196,13 @@ impl ImpulseJointSet {
         ))
     }
 
+    /// Drains the events of the joints that broke since the last call to this method.
+    ///
+    /// The events accumulate across timesteps, so this should typically be called after each step.
+    pub fn drain_break_events(&mut self) -> impl Iterator<Item = JointBreakEvent> + '_ {
+        self.break_events.drain(..)
+    }
+
     /// Iterates through all the joint on this set.
     pub fn iter(&self) -> impl Iterator<Item = (JointHandle, &ImpulseJoint)> {
         self.joint_graph
@@ -150,6 +221,85 @@ impl ImpulseJointSet {
             .map(|e| (e.weight.handle, &mut e.weight))
     }
 
+    /// Generates a Graphviz DOT representation of the graph formed by the joints of this set.
+    ///
+    /// Each node is a rigid-body handle, and each edge is a joint labelled with its handle, its
+    /// kind, and its motorized axes. This is meant for debugging only.
+    pub fn to_dot_graph(&self) -> String {
+        use std::fmt::Write;
+
+        let node = |handle: RigidBodyHandle| {
+            let (id, generation) = handle.into_raw_parts();
+            format!("\"{}v{}\"", id, generation)
+        };
+
+        let mut dot = String::from("graph impulse_joints {\n");
+
+        for body in self.bodies() {
+            let _ = writeln!(dot, "    {};", node(body));
+        }
+
+        for (handle, joint) in self.iter() {
+            let (id, generation) = handle.into_raw_parts();
+            let motors = joint_axes_names(joint.data.motor_axes);
+            let motors = if motors.is_empty() {
+                "no motor".to_string()
+            } else {
+                format!("motors: {}", motors.join(" | "))
+            };
+            let _ = writeln!(
+                dot,
+                "    {} -- {} [label=\"{}v{}: {}, {}\"];",
+                node(joint.body1),
+                node(joint.body2),
+                id,
+                generation,
+                joint_kind(&joint.data),
+                motors
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Generates a JSON representation of the graph formed by the joints of this set.
+    ///
+    /// The result has a `bodies` array listing the handles of the rigid-bodies attached to
+    /// joints, and a `joints` array with the handle, the attached bodies, the kind, the
+    /// motorized axes, and the full data of each joint. Handles are `[index, generation]`
+    /// arrays. This is meant for debugging only.
+    #[cfg(feature = "serde-serialize")]
+    pub fn to_json(&self) -> serde_json::Value {
+        let bodies: Vec<_> = self.bodies().map(|h| h.into_raw_parts()).collect();
+        let joints: Vec<_> = self
+            .iter()
+            .map(|(handle, joint)| {
+                serde_json::json!({
+                    "handle": handle.into_raw_parts(),
+                    "body1": joint.body1.into_raw_parts(),
+                    "body2": joint.body2.into_raw_parts(),
+                    "kind": joint_kind(&joint.data),
+                    "motor_axes": joint_axes_names(joint.data.motor_axes),
+                    "data": joint.data,
+                })
+            })
+            .collect();
+
+        serde_json::json!({ "bodies": bodies, "joints": joints })
+    }
+
+    /// The sorted handles of the rigid-bodies attached to at least one joint.
+    fn bodies(&self) -> impl Iterator<Item = RigidBodyHandle> {
+        let mut bodies: Vec<_> = self
+            .iter()
+            .flat_map(|(_, joint)| [joint.body1, joint.body2])
+            .collect();
+        bodies.sort_by_key(|h| h.into_raw_parts());
+        bodies.dedup();
+        bodies.into_iter()
+    }
+
     // /// The set of impulse_joints as an array.
     // pub(crate) fn impulse_joints(&self) -> &[JointGraphEdge] {
     //     // self.joint_graph
@@ -219,6 +369,7 @@ impl ImpulseJointSet {
         islands: &IslandManager,
         bodies: &Bodies,
         out: &mut Vec<Vec<JointIndex>>,
+        sort_by_handle: bool,
     ) where
         Bodies: ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyActivation>
@@ -256,6 +407,64 @@ impl ImpulseJointSet {
                 out[island_index].push(i);
             }
         }
+
+        if sort_by_handle {
+            let edges = &self.joint_graph.graph.edges;
+
+            for out_island in &mut out[..islands.num_islands()] {
+                out_island.sort_unstable_by_key(|i| edges[*i].weight.handle.0);
+            }
+        }
+    }
+
+    /// Resets the impulses of all the joints before they get solved.
+    pub(crate) fn reset_impulses(&mut self) {
+        for edge in &mut self.joint_graph.graph.edges {
+            edge.weight.reset_impulses();
+        }
+    }
+
+    /// Removes the active joints whose impulses exceed their break threshold.
+    ///
+    /// NOTE: this must run after all the islands were solved. The broken joints
+    /// are flagged sequentially here so there is no race with the (possibly parallel)
+    /// impulse writeback of the solver.
+    pub(crate) fn remove_broken_joints<Bodies>(
+        &mut self,
+        islands: &mut IslandManager,
+        bodies: &mut Bodies,
+        active_joints: &[Vec<JointIndex>],
+    ) where
+        Bodies: ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyType>
+            + ComponentSetMut<RigidBodyIds>,
+    {
+        let num_events = self.break_events.len();
+        let edges = &self.joint_graph.graph.edges;
+
+        for joint_i in active_joints.iter().flatten() {
+            let joint = &edges[*joint_i].weight;
+
+            if let Some(threshold) = &joint.data.break_threshold {
+                let impulse = joint.accumulated_impulse();
+
+                if threshold.is_exceeded_by(&impulse) {
+                    self.break_events.push(JointBreakEvent {
+                        joint: joint.handle,
+                        body1: joint.body1,
+                        body2: joint.body2,
+                        impulse,
+                    });
+                }
+            }
+        }
+
+        // NOTE: we can't remove the joints in the loop above because
+        //       removals invalidate the joint indices.
+        for i in num_events..self.break_events.len() {
+            let handle = self.break_events[i].joint;
+            let _ = self.remove(handle, islands, bodies, true);
+        }
     }
 
     /// Removes a joint from this set.
@@ -354,3 +563,51 @@ impl ImpulseJointSet {
         deleted
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ImpulseJointSet;
+    use crate::dynamics::{FixedJoint, RevoluteJoint, RigidBodyBuilder, RigidBodySet};
+    #[cfg(feature = "dim3")]
+    use crate::math::Vector;
+
+    #[test]
+    fn joint_graph_export() {
+        let mut bodies = RigidBodySet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let handles: Vec<_> = (0..3)
+            .map(|_| bodies.insert(RigidBodyBuilder::new_dynamic().build()))
+            .collect();
+
+        #[cfg(feature = "dim2")]
+        let revolute = RevoluteJoint::new();
+        #[cfg(feature = "dim3")]
+        let revolute = RevoluteJoint::new(Vector::z_axis());
+        impulse_joints.insert(handles[0], handles[1], revolute.motor_velocity(1.0, 0.5));
+        impulse_joints.insert(handles[2], handles[1], FixedJoint::new());
+
+        assert_eq!(
+            impulse_joints.to_dot_graph(),
+            "graph impulse_joints {\n    \
+                 \"0v0\";\n    \
+                 \"1v0\";\n    \
+                 \"2v0\";\n    \
+                 \"0v0\" -- \"1v0\" [label=\"0v0: revolute, motors: ANG_X\"];\n    \
+                 \"2v0\" -- \"1v0\" [label=\"1v0: fixed, no motor\"];\n\
+             }\n"
+        );
+
+        #[cfg(feature = "serde-serialize")]
+        {
+            let json = impulse_joints.to_json();
+            assert_eq!(json["bodies"], serde_json::json!([[0, 0], [1, 0], [2, 0]]));
+            assert_eq!(json["joints"][0]["kind"], "revolute");
+            assert_eq!(
+                json["joints"][0]["motor_axes"],
+                serde_json::json!(["ANG_X"])
+            );
+            assert_eq!(json["joints"][1]["body1"], serde_json::json!([2, 0]));
+            assert_eq!(json["joints"][1]["kind"], "fixed");
+        }
+    }
+}
@@ -1,5 +1,5 @@
-pub use self::impulse_joint::ImpulseJoint;
-pub use self::impulse_joint_set::{ImpulseJointSet, JointHandle};
+pub use self::impulse_joint::{ImpulseJoint, JointImpulse};
+pub use self::impulse_joint_set::{ImpulseJointSet, JointBreakEvent, JointHandle};
 pub(crate) use self::impulse_joint_set::{JointGraphEdge, JointIndex};
 
 mod impulse_joint;
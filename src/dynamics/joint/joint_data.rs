@@ -1,5 +1,5 @@
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::MotorModel;
+use crate::dynamics::{IntegrationParameters, JointImpulse, MotorModel};
 use crate::math::{Isometry, Point, Real, Rotation, UnitVector, SPATIAL_DIM};
 use crate::utils::WBasis;
 
@@ -94,21 +94,149 @@ impl Default for JointMotor {
 
 impl JointMotor {
     pub(crate) fn motor_params(&self, dt: Real) -> MotorParameters<Real> {
-        let (stiffness, damping, gamma, _keep_lhs) =
+        let (stiffness, damping, gamma, keep_lhs) =
             self.model
                 .combine_coefficients(dt, self.stiffness, self.damping);
-        MotorParameters {
+
+        if keep_lhs {
+            MotorParameters {
+                stiffness,
+                damping,
+                gamma,
+                // keep_lhs,
+                target_pos: self.target_pos,
+                target_vel: self.target_vel,
+                max_impulse: self.max_impulse,
+                cfm_gain: 0.0,
+            }
+        } else {
+            // The implicit spring-damper acts on the whole relative velocity,
+            // the damping only scales its target velocity.
+            MotorParameters {
+                stiffness,
+                damping: 1.0,
+                gamma,
+                target_pos: self.target_pos,
+                target_vel: self.target_vel * damping,
+                // If gamma is zero, the motor has no stiffness and no damping.
+                max_impulse: if gamma == 0.0 { 0.0 } else { self.max_impulse },
+                cfm_gain: gamma,
+            }
+        }
+    }
+}
+
+/// A spring-damper acting on the distance between the two anchors of a joint.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointSpring {
+    /// The distance between the anchors at which the spring doesn’t apply any force.
+    pub rest_length: Real,
+    /// The spring stiffness. Setting it to `Real::INFINITY` results in a rigid distance constraint.
+    pub stiffness: Real,
+    /// The spring damping.
+    pub damping: Real,
+    /// The impulse applied by the spring during the last timestep.
+    pub impulse: Real,
+}
+
+impl JointSpring {
+    /// Creates a new spring-damper with the given rest length, stiffness, and damping.
+    pub fn new(rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        Self {
+            rest_length,
             stiffness,
             damping,
-            gamma,
-            // keep_lhs,
-            target_pos: self.target_pos,
-            target_vel: self.target_vel,
-            max_impulse: self.max_impulse,
+            impulse: 0.0,
+        }
+    }
+
+    /// Is this spring infinitely stiff?
+    pub fn is_rigid(&self) -> bool {
+        self.stiffness == Real::INFINITY
+    }
+
+    /// Computes the coefficients of the implicit spring equation for a timestep of length `dt`.
+    ///
+    /// Returns `(bias_coeff, cfm_gain)` such that the solved velocity constraint is
+    /// `J * v + bias_coeff * (dist - rest_length) + cfm_gain * impulse = 0`, or `None`
+    /// if both the stiffness and damping are zero. This is not meaningful for rigid springs.
+    pub(crate) fn combine_coefficients(&self, dt: Real) -> Option<(Real, Real)> {
+        let denom = self.damping + dt * self.stiffness;
+
+        if denom == 0.0 {
+            None
+        } else {
+            Some((self.stiffness / denom, crate::utils::inv(dt * denom)))
+        }
+    }
+}
+
+/// The kind of motion coupled by a [`JointCoupling`].
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JointCouplingKind {
+    /// Couples the relative translation along the X axis of the first joint frame (the rack)
+    /// with the relative rotation around the X axis of the second joint frame (the pinion).
+    RackAndPinion,
+    /// Couples the rotation around the X axis of the first joint frame with the rotation
+    /// around the X axis of the second joint frame, such that
+    /// `angvel1 · axis1 + ratio * angvel2 · axis2 = 0`.
+    Gear,
+}
+
+/// A velocity constraint coupling two degrees of freedom of a joint with a fixed ratio.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointCoupling {
+    /// The degrees of freedom coupled by this constraint.
+    pub kind: JointCouplingKind,
+    /// The coupling ratio.
+    ///
+    /// For a rack-and-pinion, this is the distance traveled by the rack per radian of
+    /// rotation of the pinion.
+    pub ratio: Real,
+    /// The impulse applied by the coupling during the last timestep.
+    pub impulse: Real,
+    /// If set, the coupled velocities are driven toward the motor’s target velocity
+    /// instead of zero. Only the target velocity, damping, and maximum impulse of the motor are used.
+    pub motor: Option<JointMotor>,
+}
+
+impl JointCoupling {
+    /// Creates a new coupling with the given ratio.
+    pub fn new(kind: JointCouplingKind, ratio: Real) -> Self {
+        Self {
+            kind,
+            ratio,
+            impulse: 0.0,
+            motor: None,
         }
     }
 }
 
+/// The impulse magnitudes above which a joint breaks.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WeldJointBreakThreshold {
+    /// The maximum norm of the linear impulse applied by the joint during one timestep.
+    pub linear: Real,
+    /// The maximum norm of the angular impulse applied by the joint during one timestep.
+    pub angular: Real,
+}
+
+impl WeldJointBreakThreshold {
+    /// Creates a new break threshold with the given maximum linear and angular impulses.
+    pub fn new(linear: Real, angular: Real) -> Self {
+        Self { linear, angular }
+    }
+
+    /// Does the given joint impulse exceed this threshold?
+    pub fn is_exceeded_by(&self, impulse: &JointImpulse) -> bool {
+        impulse.linear.norm() > self.linear || impulse.angular_norm() > self.angular
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct JointData {
@@ -119,6 +247,23 @@ pub struct JointData {
     pub motor_axes: JointAxesMask,
     pub limits: [JointLimits; SPATIAL_DIM],
     pub motors: [JointMotor; SPATIAL_DIM],
+    /// The spring-damper applied on the distance between the joint anchors, if any.
+    pub spring: Option<JointSpring>,
+    /// The coupling between two degrees of freedom of this joint, if any.
+    pub coupling: Option<JointCoupling>,
+    /// The impulses above which this joint breaks, if any.
+    pub break_threshold: Option<WeldJointBreakThreshold>,
+    /// 0-1: the error reduction parameter of this joint, overriding `IntegrationParameters::erp`
+    /// if set (default: `None`).
+    ///
+    /// Lower values make the joint correct its position errors more slowly.
+    pub erp: Option<Real>,
+    /// The constraint force mixing of this joint (default: `0.0`).
+    ///
+    /// Non-zero values make the locked axes and limits of the joint soft: the relative velocity
+    /// they allow grows with `cfm` times the force they apply.
+    /// This is ignored by multibody joints.
+    pub cfm: Real,
 }
 
 impl Default for JointData {
@@ -131,6 +276,11 @@ impl Default for JointData {
             motor_axes: JointAxesMask::FREE,
             limits: [JointLimits::default(); SPATIAL_DIM],
             motors: [JointMotor::default(); SPATIAL_DIM],
+            spring: None,
+            coupling: None,
+            break_threshold: None,
+            erp: None,
+            cfm: 0.0,
         }
     }
 }
@@ -143,7 +293,29 @@ impl JointData {
 
     /// Can this joint use SIMD-accelerated constraint formulations?
     pub fn supports_simd_constraints(&self) -> bool {
-        self.limit_axes.is_empty() && self.motor_axes.is_empty()
+        self.limit_axes.is_empty()
+            && self.motor_axes.is_empty()
+            && self.spring.is_none()
+            && self.coupling.is_none()
+            && self.erp.is_none()
+            && self.cfm == 0.0
+    }
+
+    /// The integration parameters used to build the constraints of this joint.
+    pub(crate) fn integration_parameters(
+        &self,
+        params: &IntegrationParameters,
+    ) -> IntegrationParameters {
+        let mut params = *params;
+        if let Some(erp) = self.erp {
+            params.erp = erp;
+        }
+        params
+    }
+
+    /// The coefficient of the soft constraints of the locked axes and limits of this joint.
+    pub(crate) fn cfm_gain(&self, params: &IntegrationParameters) -> Real {
+        self.cfm * params.inv_dt()
     }
 
     #[must_use]
@@ -267,4 +439,118 @@ impl JointData {
         self.motors[axis as usize].max_impulse = max_impulse;
         self
     }
+
+    /// Adds a spring-damper acting on the distance between the joint anchors.
+    #[must_use]
+    pub fn spring(mut self, rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        self.spring = Some(JointSpring::new(rest_length, stiffness, damping));
+        self
+    }
+
+    /// Sets the error reduction parameter of this joint, overriding `IntegrationParameters::erp`.
+    #[must_use]
+    pub fn erp(mut self, erp: Real) -> Self {
+        self.erp = Some(erp);
+        self
+    }
+
+    /// Sets the constraint force mixing of this joint.
+    #[must_use]
+    pub fn cfm(mut self, cfm: Real) -> Self {
+        self.cfm = cfm;
+        self
+    }
+
+    /// Couples two degrees of freedom of this joint with the given ratio.
+    #[must_use]
+    pub fn coupling(mut self, kind: JointCouplingKind, ratio: Real) -> Self {
+        self.coupling = Some(JointCoupling::new(kind, ratio));
+        self
+    }
+
+    /// Makes this joint break as soon as the impulse it applies exceeds the given thresholds.
+    #[must_use]
+    pub fn break_threshold(mut self, linear: Real, angular: Real) -> Self {
+        self.break_threshold = Some(WeldJointBreakThreshold::new(linear, angular));
+        self
+    }
+
+    /// Sets the target velocity of the coupling of this joint, if any.
+    pub fn coupling_motor_velocity(mut self, target_vel: Real, factor: Real) -> Self {
+        if let Some(coupling) = &mut self.coupling {
+            let motor = coupling.motor.get_or_insert_with(JointMotor::default);
+            motor.target_vel = target_vel;
+            motor.damping = factor;
+        }
+        self
+    }
+
+    /// Sets the maximum impulse applied by the motor of the coupling of this joint, if any.
+    pub fn coupling_motor_max_impulse(mut self, max_impulse: Real) -> Self {
+        if let Some(coupling) = &mut self.coupling {
+            coupling
+                .motor
+                .get_or_insert_with(JointMotor::default)
+                .max_impulse = max_impulse;
+        }
+        self
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::JointData;
+    use crate::dynamics::{FixedJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    // Returns the final distance between each body and the static anchor it is welded to.
+    fn run(gravity: Vector<Real>, initial_error: Real, joints: &[JointData]) -> Vec<Real> {
+        let mut world = TestWorld::new(gravity);
+
+        let mut pairs = vec![];
+        for (k, joint) in joints.iter().enumerate() {
+            let origin = Vector::y() * (k as Real * 10.0);
+            let anchor = world
+                .bodies
+                .insert(RigidBodyBuilder::new_static().translation(origin).build());
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(origin + Vector::x() * initial_error)
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                body,
+                &mut world.bodies,
+            );
+            world.impulse_joints.insert(anchor, body, *joint);
+            pairs.push((Point::from(origin), body));
+        }
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        pairs
+            .iter()
+            .map(|(origin, body)| (world.bodies[*body].translation() - origin.coords).norm())
+            .collect()
+    }
+
+    #[test]
+    fn per_joint_erp_and_cfm() {
+        // Without any position correction, the initial joint error isn't corrected.
+        let rigid: JointData = FixedJoint::new().into();
+        let errors = run(Vector::zeros(), 1.0, &[rigid, rigid.erp(0.0)]);
+        assert!(errors[0] < 0.01);
+        assert!(errors[1] > 0.99);
+
+        // The soft joint sags under gravity.
+        let sags = run(Vector::y() * -9.81, 0.0, &[rigid, rigid.cfm(0.1)]);
+        assert!(sags[0] < 0.01);
+        assert!(sags[1] > 0.02);
+    }
 }
@@ -1,21 +1,35 @@
-pub use self::fixed_joint::FixedJoint;
+#[cfg(feature = "default-sets")]
+pub use self::differential_constraint::DifferentialConstraint;
+pub use self::fixed_joint::{FixedJoint, WeldJoint};
+pub use self::gear_joint::GearJoint;
 pub use self::impulse_joint::*;
 pub use self::joint_data::*;
 pub use self::motor_model::MotorModel;
 pub use self::multibody_joint::*;
-pub use self::prismatic_joint::PrismaticJoint;
+pub use self::prismatic_joint::{PrismaticJoint, PrismaticMotor};
+pub use self::rack_and_pinion_joint::RackAndPinionJoint;
 pub use self::revolute_joint::RevoluteJoint;
+pub use self::spring_joint::SpringJoint;
 
 #[cfg(feature = "dim3")]
 pub use self::spherical_joint::SphericalJoint;
+#[cfg(all(feature = "dim3", feature = "default-sets"))]
+pub use self::wheel_constraint::{TireModel, WheelConstraint, WheelContact};
 
+#[cfg(feature = "default-sets")]
+mod differential_constraint;
 mod fixed_joint;
+mod gear_joint;
 mod impulse_joint;
 mod joint_data;
 mod motor_model;
 mod multibody_joint;
 mod prismatic_joint;
+mod rack_and_pinion_joint;
 mod revolute_joint;
+mod spring_joint;
 
 #[cfg(feature = "dim3")]
 mod spherical_joint;
+#[cfg(all(feature = "dim3", feature = "default-sets"))]
+mod wheel_constraint;
@@ -14,9 +14,14 @@ pub enum MotorModel {
     /// The solved spring-like equation is:
     /// `acceleration(t + dt) = stiffness * (target_pos - pos(t)) + damping * (target_vel - vel(t))`
     AccelerationBased,
-    // /// The solved spring-like equation is:
-    // /// `force(t + dt) = stiffness * (target_pos - pos(t + dt)) + damping * (target_vel - vel(t + dt))`
-    // ForceBased,
+    /// The solved spring-like equation is:
+    /// `force(t + dt) = stiffness * (target_pos - pos(t + dt)) + damping * (target_vel - vel(t + dt))`
+    ///
+    /// The spring is integrated implicitly so the `stiffness` and `damping` are the physical
+    /// coefficients of a spring-damper and the response doesn't depend on the timestep length.
+    ///
+    /// Multibody joints don't support this model yet and solve it as a velocity-based motor.
+    ForceBased,
 }
 
 impl Default for MotorModel {
@@ -34,6 +39,10 @@ impl MotorModel {
     /// - `rhs = (stiffness * pos_err + damping * vel_err) / gamma`.
     /// - `new_inv_lhs = gamma * if keep_inv_lhs { inv_lhs } else { 1.0 }`.
     /// Note that the returned `gamma` will be zero if both `stiffness` and `damping` are zero.
+    ///
+    /// For `MotorModel::ForceBased`, `keep_inv_lhs` is `false` and `gamma` is instead the
+    /// constraint force mixing of the implicit equation
+    /// `J * v + stiffness * pos_err - damping * target_vel + gamma * impulse = 0`.
     pub fn combine_coefficients(
         self,
         dt: Real,
@@ -50,12 +59,22 @@ impl MotorModel {
                 // and get back to this later.
                 // let gamma = effective_stiffness * dt + effective_damping;
                 (effective_stiffness, effective_damping, 1.0, true)
-            } // MotorModel::ForceBased => {
-              //     let effective_stiffness = stiffness * dt;
-              //     let effective_damping = damping * dt;
-              //     let gamma = effective_stiffness * dt + effective_damping;
-              //     (effective_stiffness, effective_damping, gamma, false)
-              // }
+            }
+            MotorModel::ForceBased => {
+                let denom = damping + dt * stiffness;
+
+                if denom == 0.0 {
+                    (0.0, 0.0, 0.0, false)
+                } else {
+                    let inv_denom = crate::utils::inv(denom);
+                    (
+                        stiffness * inv_denom,
+                        damping * inv_denom,
+                        crate::utils::inv(dt * denom),
+                        false,
+                    )
+                }
+            }
         }
     }
 }
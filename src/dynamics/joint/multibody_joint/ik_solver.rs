@@ -0,0 +1,347 @@
+use crate::dynamics::{
+    JointAxesMask, Multibody, MultibodyJoint, MultibodyJointHandle, MultibodyJointSet,
+};
+use crate::math::{Isometry, Jacobian, Real, SpacialVector, ANG_DIM, DIM, SPATIAL_DIM};
+
+/// The result of an inverse kinematics resolution with an `IKSolver`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IKResult {
+    chain: Vec<MultibodyJointHandle>,
+    /// The position computed for each joint of the chain, i.e., its angle for a revolute joint,
+    /// or its translation for a prismatic joint.
+    pub joint_positions: Vec<Real>,
+    /// The remaining weighted distance between the end-effector pose and the target pose.
+    pub error: Real,
+    /// The number of iterations that were run.
+    pub iterations: u32,
+    /// Is the remaining error smaller than the tolerance of the solver?
+    pub converged: bool,
+}
+
+impl IKResult {
+    /// Sets the computed joint positions as the targets of position-based motors of the joints
+    /// of the chain, so the constraints solver drives the chain toward the target pose.
+    ///
+    /// The `stiffness` and `damping` are interpreted according to the `MotorModel` of each
+    /// joint. The rigid-bodies of the chain must be awake for the motors to have any effect.
+    pub fn apply_as_motor_targets(
+        &self,
+        multibody_joints: &mut MultibodyJointSet,
+        stiffness: Real,
+        damping: Real,
+    ) {
+        for (handle, position) in self.chain.iter().zip(self.joint_positions.iter()) {
+            if let Some((multibody, link_id)) = multibody_joints.get_mut_internal(*handle) {
+                let joint = &mut multibody.link_mut(link_id).unwrap().state.joint;
+                let axis = free_axis(joint);
+                joint.data.motor_axes |= JointAxesMask::from_bits_truncate(1 << axis);
+                joint.data.motors[axis].target_pos = *position;
+                joint.data.motors[axis].target_vel = 0.0;
+                joint.data.motors[axis].stiffness = stiffness;
+                joint.data.motors[axis].damping = damping;
+            }
+        }
+    }
+}
+
+/// An inverse kinematics solver computing the positions of the joints of a multibody chain
+/// that bring its end-effector to a target pose.
+///
+/// The solver uses the Jacobian-transpose method, and clamps the joint positions to the joint
+/// limits at each iteration. Only joints with a single degree of freedom, e.g., revolute and
+/// prismatic joints, are supported.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct IKSolver {
+    /// The weighted error below which the target is considered reached.
+    pub tolerance: Real,
+    /// The weight of the orientation error relative to the position error.
+    ///
+    /// Set this to zero to only reach the target position, whatever the orientation of
+    /// the end-effector.
+    pub orientation_weight: Real,
+}
+
+impl Default for IKSolver {
+    fn default() -> Self {
+        Self {
+            tolerance: 1.0e-3,
+            orientation_weight: 1.0,
+        }
+    }
+}
+
+impl IKSolver {
+    /// Computes the joint positions that bring the end-effector of the chain to the `target` pose.
+    ///
+    /// The `chain` lists the multibody joints from the closest one to the root of the multibody
+    /// to the end-effector, which is the link attached by the last joint. The current joint
+    /// positions are used as the initial guess, and the multibody isn't modified.
+    ///
+    /// Panics if the joints aren't all part of the same multibody, or if one of them has more
+    /// than one degree of freedom.
+    pub fn solve(
+        &self,
+        multibody_joints: &MultibodyJointSet,
+        chain: &[MultibodyJointHandle],
+        target: &Isometry<Real>,
+        max_iters: u32,
+    ) -> IKResult {
+        // The step used for computing the Jacobian with finite differences.
+        const EPS: Real = 1.0e-3;
+
+        let mut multibody: Option<&Multibody> = None;
+        let mut links = Vec::with_capacity(chain.len());
+
+        for handle in chain {
+            let (mb, link_id) = multibody_joints
+                .get(*handle)
+                .expect("The joints of the chain must be multibody joints.");
+            if let Some(multibody) = multibody {
+                assert!(
+                    std::ptr::eq(multibody, mb),
+                    "The joints of the chain must be part of the same multibody."
+                );
+            }
+            assert_eq!(
+                mb.link(link_id).unwrap().joint().ndofs(),
+                1,
+                "Only joints with a single degree of freedom are supported."
+            );
+            multibody = Some(mb);
+            links.push(link_id);
+        }
+
+        let mut result = IKResult {
+            chain: chain.to_vec(),
+            joint_positions: Vec::new(),
+            error: 0.0,
+            iterations: 0,
+            converged: false,
+        };
+
+        let multibody = match multibody {
+            Some(multibody) => multibody,
+            None => {
+                result.converged = true;
+                return result;
+            }
+        };
+
+        let mut positions: Vec<Real> = links
+            .iter()
+            .map(|link_id| {
+                let joint = multibody.link(*link_id).unwrap().joint();
+                joint.coords[free_axis(joint)]
+            })
+            .collect();
+        let mut jacobian = Jacobian::zeros(positions.len());
+
+        loop {
+            let pose = end_effector_pose(multibody, &links, &positions);
+            let mut error = twist(&pose, target);
+            error
+                .fixed_rows_mut::<ANG_DIM>(DIM)
+                .scale_mut(self.orientation_weight);
+
+            result.error = error.norm();
+            result.converged = result.error <= self.tolerance;
+
+            if result.converged || result.iterations >= max_iters {
+                break;
+            }
+
+            for k in 0..positions.len() {
+                positions[k] += EPS;
+                let perturbed_pose = end_effector_pose(multibody, &links, &positions);
+                positions[k] -= EPS;
+
+                let mut column = twist(&pose, &perturbed_pose) / EPS;
+                column
+                    .fixed_rows_mut::<ANG_DIM>(DIM)
+                    .scale_mut(self.orientation_weight);
+                jacobian.set_column(k, &column);
+            }
+
+            // Step length minimizing the error along the direction Jᵀe, assuming linearity.
+            let delta = jacobian.tr_mul(&error);
+            let jdelta = &jacobian * &delta;
+            let jdelta_sq = jdelta.norm_squared();
+
+            if jdelta_sq <= Real::EPSILON {
+                // The target can't be reached any closer from this configuration.
+                break;
+            }
+
+            let alpha = error.dot(&jdelta) / jdelta_sq;
+
+            for (k, link_id) in links.iter().enumerate() {
+                let joint = multibody.link(*link_id).unwrap().joint();
+                let axis = free_axis(joint);
+                positions[k] += alpha * delta[k];
+
+                if joint.data.limit_axes.bits() & (1 << axis) != 0 {
+                    let limits = &joint.data.limits[axis];
+                    positions[k] = positions[k].max(limits.min).min(limits.max);
+                }
+            }
+
+            result.iterations += 1;
+        }
+
+        result.joint_positions = positions;
+        result
+    }
+}
+
+/// The index of the only free axis of this joint.
+fn free_axis(joint: &MultibodyJoint) -> usize {
+    let locked_bits = joint.data.locked_axes.bits();
+    (0..SPATIAL_DIM)
+        .find(|i| locked_bits & (1 << i) == 0)
+        .unwrap()
+}
+
+/// The world-space pose of the last link of the chain, with the given joint positions.
+fn end_effector_pose(multibody: &Multibody, links: &[usize], positions: &[Real]) -> Isometry<Real> {
+    let mut pose = Isometry::identity();
+    let mut curr_link = links.last().copied();
+
+    while let Some(link_id) = curr_link {
+        let link = multibody.link(link_id).unwrap();
+        let local_to_parent = match links.iter().position(|id| *id == link_id) {
+            Some(k) => {
+                let mut joint = *link.joint();
+                let axis = free_axis(&joint);
+                let displacement = positions[k] - joint.coords[axis];
+                joint.apply_displacement(&[displacement]);
+                joint.body_to_parent()
+            }
+            None => link.joint().body_to_parent(),
+        };
+
+        pose = local_to_parent * pose;
+        curr_link = link.parent_id();
+    }
+
+    pose
+}
+
+/// The linear and angular displacements from `pose1` to `pose2`.
+fn twist(pose1: &Isometry<Real>, pose2: &Isometry<Real>) -> SpacialVector<Real> {
+    let mut result = SpacialVector::zeros();
+    result
+        .fixed_rows_mut::<DIM>(0)
+        .copy_from(&(pose2.translation.vector - pose1.translation.vector));
+
+    let rotation = pose2.rotation * pose1.rotation.inverse();
+    #[cfg(feature = "dim2")]
+    {
+        result[DIM] = rotation.angle();
+    }
+    #[cfg(feature = "dim3")]
+    {
+        result
+            .fixed_rows_mut::<3>(DIM)
+            .copy_from(&rotation.scaled_axis());
+    }
+
+    result
+}
+
+// NOTE: multibodies are not implemented in 2D yet.
+#[cfg(all(test, feature = "dim3"))]
+mod test {
+    use crate::dynamics::{
+        IKSolver, MultibodyJointHandle, MultibodyJointSet, RevoluteJoint, RigidBodyBuilder,
+        RigidBodyHandle,
+    };
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    // Builds a planar arm made of two links of length 1, attached to a static root.
+    fn build_arm(
+        world: &mut TestWorld,
+        z: Real,
+        elbow_limits: Option<[Real; 2]>,
+    ) -> (
+        MultibodyJointSet,
+        Vec<MultibodyJointHandle>,
+        Vec<RigidBodyHandle>,
+    ) {
+        let mut multibody_joints = MultibodyJointSet::new();
+        let root = RigidBodyBuilder::new_static()
+            .translation(Vector::z() * z)
+            .build();
+        let mut links = vec![world.bodies.insert(root)];
+        let mut chain = Vec::new();
+
+        for i in 1..3 {
+            let link = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::new(i as Real, 0.0, z))
+                .build();
+            let link = world.bodies.insert(link);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.1).build(),
+                link,
+                &mut world.bodies,
+            );
+
+            let mut joint =
+                RevoluteJoint::new(Vector::z_axis()).local_anchor2(Point::from(Vector::x() * -1.0));
+            if let (2, Some(limits)) = (i, elbow_limits) {
+                joint = joint.limit_axis(limits);
+            }
+
+            chain.push(multibody_joints.insert(links[i - 1], link, joint).unwrap());
+            links.push(link);
+        }
+
+        (multibody_joints, chain, links)
+    }
+
+    #[test]
+    fn ik_solver_reaches_targets_within_joint_limits() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let reached = |positions: &[Real]| {
+            let (q1, q2) = (positions[0], positions[0] + positions[1]);
+            Vector::x() * (q1.cos() + q2.cos()) + Vector::y() * (q1.sin() + q2.sin())
+        };
+
+        let solver = IKSolver {
+            orientation_weight: 0.0,
+            ..IKSolver::default()
+        };
+        let target_pos = Vector::x() * 1.2 + Vector::y() * 0.8;
+        let target = Isometry::from(target_pos);
+
+        #[cfg_attr(feature = "parallel", allow(unused_variables))]
+        let (multibody_joints, chain, links) = build_arm(&mut world, 0.0, None);
+        world.multibody_joints = multibody_joints;
+        let result = solver.solve(&world.multibody_joints, &chain, &target, 1000);
+        assert!(result.converged);
+        assert!((reached(&result.joint_positions) - target_pos).norm() < 1.0e-2);
+
+        // With a stiff elbow, the target can't be reached.
+        let (limited_joints, limited_chain, _) = build_arm(&mut world, 5.0, Some([-0.2, 0.2]));
+        let limited_target = Isometry::from(target_pos + Vector::z() * 5.0);
+        let limited = solver.solve(&limited_joints, &limited_chain, &limited_target, 1000);
+        assert!(!limited.converged);
+        assert!(limited.joint_positions[1].abs() <= 0.2 + 1.0e-5);
+
+        // The motors drive the arm toward the computed configuration.
+        // NOTE: the parallel solver doesn't support joints yet.
+        #[cfg(not(feature = "parallel"))]
+        {
+            result.apply_as_motor_targets(&mut world.multibody_joints, 0.2, 0.5);
+            for _ in 0..200 {
+                world.step();
+            }
+
+            let end_effector = world.bodies[links[2]].translation();
+            assert!((end_effector - target_pos).norm() < 1.0e-2);
+        }
+    }
+}
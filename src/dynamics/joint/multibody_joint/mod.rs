@@ -1,11 +1,13 @@
 //! MultibodyJoints using the reduced-coordinates formalism or using constraints.
 
+pub use self::ik_solver::{IKResult, IKSolver};
 pub use self::multibody::Multibody;
 pub use self::multibody_joint::MultibodyJoint;
 pub use self::multibody_joint_set::{MultibodyIndex, MultibodyJointHandle, MultibodyJointSet};
 pub use self::multibody_link::MultibodyLink;
 pub use self::unit_multibody_joint::{unit_joint_limit_constraint, unit_joint_motor_constraint};
 
+mod ik_solver;
 mod multibody;
 mod multibody_joint_set;
 mod multibody_link;
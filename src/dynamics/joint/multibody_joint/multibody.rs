@@ -59,6 +59,8 @@ fn concat_rb_mass_matrix(
 }
 
 /// An articulated body simulated using the reduced-coordinates approach.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 pub struct Multibody {
     links: MultibodyLinkVec,
     pub(crate) velocities: DVector<Real>,
@@ -879,6 +881,27 @@ impl Multibody {
         }
     }
 
+    /// Computes the world-space pose of every link of this multibody from its current joint
+    /// coordinates.
+    ///
+    /// The `i`-th element of the result is the pose of the `i`-th link. Neither this multibody
+    /// nor its rigid-bodies are modified, so this works independently from the types of the
+    /// rigid-bodies and from the simulation being stepped.
+    pub fn forward_kinematics(&self) -> Vec<Isometry<Real>> {
+        let mut poses: Vec<Isometry<Real>> = Vec::with_capacity(self.links.len());
+
+        for link in self.links() {
+            let local_to_parent = link.joint().body_to_parent();
+            let local_to_world = match link.parent_id() {
+                Some(parent_id) => poses[parent_id] * local_to_parent,
+                None => local_to_parent,
+            };
+            poses.push(local_to_world);
+        }
+
+        poses
+    }
+
     pub fn forward_kinematics_next<Bodies>(&mut self, bodies: &mut Bodies, update_mass_props: bool)
     where
         Bodies: ComponentSet<RigidBodyType>
@@ -15,6 +15,7 @@ use {
     na::{UnitQuaternion, Vector3, VectorSlice3},
 };
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
 pub struct MultibodyJoint {
     pub data: JointData,
@@ -483,6 +484,7 @@ impl MultibodyJoint {
         jacobians: &mut DVector<Real>,
         constraints: &mut Vec<AnyJointVelocityConstraint>,
     ) {
+        let params = &self.data.integration_parameters(params);
         let locked_bits = self.data.locked_axes.bits();
         let limit_bits = self.data.limit_axes.bits();
         let motor_bits = self.data.motor_axes.bits();
@@ -5,6 +5,7 @@ use crate::dynamics::{
     RigidBodyIds, RigidBodyType,
 };
 use crate::geometry::{InteractionGraph, RigidBodyGraphIndex};
+use crate::math::{Isometry, Real};
 use crate::parry::partitioning::IndexedData;
 use std::ops::Index;
 
@@ -55,6 +56,7 @@ impl IndexedData for MultibodyJointHandle {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct MultibodyJointLink {
     pub graph_id: RigidBodyGraphIndex,
@@ -75,7 +77,14 @@ impl Default for MultibodyJointLink {
     }
 }
 
-/// A set of rigid bodies that can be handled by a physics pipeline.
+/// A set of multibodies, i.e., articulated bodies simulated with reduced coordinates.
+///
+/// Each link of a multibody is a rigid-body of the `RigidBodySet`, so it can have colliders
+/// interacting with any other collider, and be attached to other rigid-bodies with impulse
+/// joints. Unlike impulse joints, multibody joints can't drift apart, which is preferable for
+/// robot arms, ragdolls, or mechanisms.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 pub struct MultibodyJointSet {
     pub(crate) multibodies: Arena<Multibody>, // NOTE: a Slab would be sufficient.
     pub(crate) rb2mb: Coarena<MultibodyJointLink>,
@@ -286,6 +295,16 @@ impl MultibodyJointSet {
         self.rb2mb.get(rb.0)
     }
 
+    /// Computes the world-space pose of every link of the multibody the given rigid-body is
+    /// part of, see `Multibody::forward_kinematics`.
+    ///
+    /// Returns `None` if `rb` isn’t part of any multibody.
+    pub fn compute_forward_kinematics(&self, rb: RigidBodyHandle) -> Option<Vec<Isometry<Real>>> {
+        let link = self.rigid_body_link(rb)?;
+        let multibody = self.get_multibody(link.multibody)?;
+        Some(multibody.forward_kinematics())
+    }
+
     /// Gets a reference to a multibody, based on its temporary index.
     pub fn get_multibody(&self, index: MultibodyIndex) -> Option<&Multibody> {
         self.multibodies.get(index.0)
@@ -350,3 +369,68 @@ impl Index<MultibodyIndex> for MultibodyJointSet {
 //         &self.multibodies[index.0]
 //     }
 // }
+
+// NOTE: multibody free joints are not implemented in 2D yet, and the parallel solver
+//       doesn't support joints yet.
+#[cfg(all(test, feature = "dim3", not(feature = "parallel")))]
+mod test {
+    use crate::dynamics::{RevoluteJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn multibody_forward_kinematics_matches_link_positions() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        // A double pendulum hanging from a static root.
+        let root = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let mut links = vec![root];
+        for i in 1..3 {
+            let link = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * i as Real)
+                .build();
+            let link = world.bodies.insert(link);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.1).build(),
+                link,
+                &mut world.bodies,
+            );
+
+            let joint =
+                RevoluteJoint::new(Vector::z_axis()).local_anchor2(Point::from(Vector::x() * -1.0));
+            world.multibody_joints.insert(links[i - 1], link, joint);
+            links.push(link);
+        }
+
+        let free_body = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        assert!(world
+            .multibody_joints
+            .compute_forward_kinematics(free_body)
+            .is_none());
+
+        for _ in 0..30 {
+            world.step();
+        }
+
+        // Any link of the multibody can be used to identify it.
+        let poses = world
+            .multibody_joints
+            .compute_forward_kinematics(links[2])
+            .unwrap();
+        assert_eq!(poses.len(), links.len());
+        assert!(world.bodies[links[2]].translation().y < -0.1);
+
+        let link = world.multibody_joints.rigid_body_link(root).unwrap();
+        let multibody = world
+            .multibody_joints
+            .get_multibody(link.multibody)
+            .unwrap();
+
+        for (link, pose) in multibody.links().zip(poses.iter()) {
+            let error = pose.inverse() * world.bodies[link.rigid_body_handle()].position();
+            assert!(error.translation.vector.norm() < 1.0e-4);
+            assert!(error.rotation.angle() < 1.0e-4);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::dynamics::{MultibodyJoint, RigidBodyHandle};
 use crate::math::{Isometry, Real};
 use crate::prelude::RigidBodyVelocity;
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub(crate) struct KinematicState {
     pub joint: MultibodyJoint,
     pub parent_to_world: Isometry<Real>,
@@ -24,6 +25,8 @@ impl Clone for KinematicState {
 }
 
 /// One link of a multibody.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 pub struct MultibodyLink {
     pub(crate) name: String,
     // FIXME: make all those private.
@@ -133,6 +136,8 @@ impl MultibodyLink {
 }
 
 // FIXME: keep this even if we already have the Index2 traits?
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 pub(crate) struct MultibodyLinkVec(pub Vec<MultibodyLink>);
 
 impl MultibodyLinkVec {
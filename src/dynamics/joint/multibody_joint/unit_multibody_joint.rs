@@ -52,6 +52,7 @@ pub fn unit_joint_limit_constraint(
         impulse: 0.0,
         impulse_bounds,
         inv_lhs: crate::utils::inv(lhs),
+        cfm_gain: 0.0,
         rhs: rhs_wo_bias + rhs_bias,
         rhs_wo_bias,
         writeback_id: WritebackId::Limit(dof_id),
@@ -110,6 +111,7 @@ pub fn unit_joint_motor_constraint(
         impulse: 0.0,
         impulse_bounds,
         inv_lhs: crate::utils::inv(lhs),
+        cfm_gain: 0.0,
         rhs: rhs_wo_bias,
         rhs_wo_bias,
         writeback_id: WritebackId::Limit(dof_id),
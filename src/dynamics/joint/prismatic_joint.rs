@@ -2,6 +2,33 @@ use crate::dynamics::joint::{JointAxesMask, JointData};
 use crate::dynamics::{JointAxis, MotorModel};
 use crate::math::{Point, Real, UnitVector};
 
+/// The motor driving the free translational degree of freedom of a prismatic joint.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PrismaticMotor {
+    /// A motor driving the joint toward the target velocity `target`.
+    ///
+    /// The `factor` controls how fast the target velocity is reached.
+    Velocity {
+        /// The target velocity along the joint axis.
+        target: Real,
+        /// The damping factor of the motor.
+        factor: Real,
+    },
+    /// A motor acting as a spring-damper pulling the joint toward the target position `target`.
+    ///
+    /// The `stiffness` and `damping` are the physical coefficients of the spring-damper, so the
+    /// response of the motor doesn't depend on the timestep length.
+    Position {
+        /// The target position along the joint axis.
+        target: Real,
+        /// The stiffness of the spring pulling the joint toward the target position.
+        stiffness: Real,
+        /// The damping of the spring pulling the joint toward the target position.
+        damping: Real,
+    },
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct PrismaticJoint {
@@ -38,6 +65,26 @@ impl PrismaticJoint {
         self
     }
 
+    /// Sets the motor of this joint.
+    ///
+    /// A `PrismaticMotor::Position` motor uses the `MotorModel::ForceBased` model, and a
+    /// `PrismaticMotor::Velocity` motor the default `MotorModel::VelocityBased` model.
+    #[must_use]
+    pub fn motor(self, motor: PrismaticMotor) -> Self {
+        match motor {
+            PrismaticMotor::Velocity { target, factor } => self
+                .motor_model(MotorModel::VelocityBased)
+                .motor_velocity(target, factor),
+            PrismaticMotor::Position {
+                target,
+                stiffness,
+                damping,
+            } => self
+                .motor_model(MotorModel::ForceBased)
+                .motor_position(target, stiffness, damping),
+        }
+    }
+
     /// Set the spring-like model used by the motor to reach the desired target velocity and position.
     pub fn motor_model(mut self, model: MotorModel) -> Self {
         self.data = self.data.motor_model(JointAxis::X, model);
@@ -50,7 +97,10 @@ impl PrismaticJoint {
         self
     }
 
-    /// Sets the target angle this motor needs to reach.
+    /// Sets the target position this motor needs to reach.
+    ///
+    /// With `MotorModel::ForceBased`, the `stiffness` and `damping` are the coefficients
+    /// of a spring-damper pulling the joint toward `target_pos`, independently from the timestep.
     pub fn motor_position(mut self, target_pos: Real, stiffness: Real, damping: Real) -> Self {
         self.data = self
             .data
@@ -58,7 +108,7 @@ impl PrismaticJoint {
         self
     }
 
-    /// Configure both the target angle and target velocity of the motor.
+    /// Configure both the target position and target velocity of the motor.
     pub fn motor_axis(
         mut self,
         target_pos: Real,
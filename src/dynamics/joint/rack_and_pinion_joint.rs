@@ -0,0 +1,176 @@
+use crate::dynamics::joint::{JointCouplingKind, JointData};
+use crate::dynamics::{JointAxis, MotorModel};
+use crate::math::{Point, Real, UnitVector};
+
+/// A joint coupling the translation of a rack (the first body) along an axis with
+/// the rotation of a pinion (the second body) around its own axis.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RackAndPinionJoint {
+    data: JointData,
+}
+
+impl RackAndPinionJoint {
+    /// Creates a new rack-and-pinion joint.
+    ///
+    /// The `ratio` is the distance traveled by the rack along `rack_axis` per radian of
+    /// rotation of the pinion.
+    #[cfg(feature = "dim2")]
+    pub fn new(rack_axis: UnitVector<Real>, ratio: Real) -> Self {
+        let data = JointData::default()
+            .local_axis1(rack_axis)
+            .coupling(JointCouplingKind::RackAndPinion, ratio);
+        Self { data }
+    }
+
+    /// Creates a new rack-and-pinion joint.
+    ///
+    /// The `ratio` is the distance traveled by the rack along `rack_axis` per radian of
+    /// rotation of the pinion around `pinion_axis`.
+    #[cfg(feature = "dim3")]
+    pub fn new(rack_axis: UnitVector<Real>, pinion_axis: UnitVector<Real>, ratio: Real) -> Self {
+        let data = JointData::default()
+            .local_axis1(rack_axis)
+            .local_axis2(pinion_axis)
+            .coupling(JointCouplingKind::RackAndPinion, ratio);
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    #[must_use]
+    pub fn data(&self) -> &JointData {
+        &self.data
+    }
+
+    /// Sets the joint's anchor, expressed in the local-space of the rack.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.data = self.data.local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint's anchor, expressed in the local-space of the pinion.
+    ///
+    /// This is the point of the pinion in contact with the rack.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.data = self.data.local_anchor2(anchor2);
+        self
+    }
+
+    /// Set the spring-like model used by the motor driving the rack and pinion.
+    #[must_use]
+    pub fn motor_model(mut self, model: MotorModel) -> Self {
+        self.data = self.data.motor_model(JointAxis::X, model);
+        self
+    }
+
+    /// Sets the target velocity of the pinion relative to the rack, along the rack axis.
+    #[must_use]
+    pub fn motor_velocity(mut self, target_vel: Real, factor: Real) -> Self {
+        self.data = self.data.motor_velocity(JointAxis::X, target_vel, factor);
+        self
+    }
+
+    /// Sets the target position of the pinion relative to the rack, along the rack axis.
+    #[must_use]
+    pub fn motor_position(mut self, target_pos: Real, stiffness: Real, damping: Real) -> Self {
+        self.data = self
+            .data
+            .motor_position(JointAxis::X, target_pos, stiffness, damping);
+        self
+    }
+
+    /// Configure both the target position and target velocity of the motor.
+    #[must_use]
+    pub fn motor_axis(
+        mut self,
+        target_pos: Real,
+        target_vel: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        self.data = self
+            .data
+            .motor_axis(JointAxis::X, target_pos, target_vel, stiffness, damping);
+        self
+    }
+
+    /// Sets the maximum impulse the motor can deliver.
+    #[must_use]
+    pub fn motor_max_impulse(mut self, max_impulse: Real) -> Self {
+        self.data = self.data.motor_max_impulse(JointAxis::X, max_impulse);
+        self
+    }
+}
+
+impl Into<JointData> for RackAndPinionJoint {
+    fn into(self) -> JointData {
+        self.data
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::RackAndPinionJoint;
+    use crate::dynamics::{PrismaticJoint, RevoluteJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn rack_translation_follows_the_pinion_angle() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let rack = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        #[cfg(feature = "dim2")]
+        let (rack_shape, pinion_angvel) = (ColliderBuilder::cuboid(2.0, 0.25), 3.0);
+        #[cfg(feature = "dim3")]
+        let (rack_shape, pinion_angvel) =
+            (ColliderBuilder::cuboid(2.0, 0.25, 0.25), Vector::z() * 3.0);
+        world
+            .colliders
+            .insert_with_parent(rack_shape.build(), rack, &mut world.bodies);
+        let pinion = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y())
+                .angvel(pinion_angvel)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            pinion,
+            &mut world.bodies,
+        );
+
+        // The rack slides along the x axis, and the pinion rotates around its center.
+        let slider = PrismaticJoint::new(Vector::x_axis());
+        world.impulse_joints.insert(ground, rack, slider);
+        #[cfg(feature = "dim2")]
+        let (hinge, coupling) = (
+            RevoluteJoint::new(),
+            RackAndPinionJoint::new(Vector::x_axis(), 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (hinge, coupling) = (
+            RevoluteJoint::new(Vector::z_axis()),
+            RackAndPinionJoint::new(Vector::x_axis(), Vector::z_axis(), 0.5),
+        );
+        let hinge = hinge.local_anchor1(Point::from(Vector::y()));
+        world.impulse_joints.insert(ground, pinion, hinge);
+        world.impulse_joints.insert(rack, pinion, coupling);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        let translation = world.bodies[rack].translation().x;
+        #[cfg(feature = "dim2")]
+        let angle = world.bodies[pinion].rotation().angle();
+        #[cfg(feature = "dim3")]
+        let angle = world.bodies[pinion].rotation().scaled_axis().z;
+        assert!(angle > 0.3);
+        assert!((translation - 0.5 * angle).abs() < 1.0e-3);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::dynamics::JointData;
+use crate::math::{Point, Real};
+
+/// A joint that keeps the distance between its two anchors close to a rest length
+/// using an implicit spring-damper.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpringJoint {
+    data: JointData,
+}
+
+impl SpringJoint {
+    /// Creates a new spring joint with the given rest length, stiffness, and damping.
+    ///
+    /// Setting the stiffness to `Real::INFINITY` results in a rigid distance constraint.
+    pub fn new(rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        let data = JointData::default().spring(rest_length, stiffness, damping);
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    #[must_use]
+    pub fn data(&self) -> &JointData {
+        &self.data
+    }
+
+    /// Sets the joint's anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.data = self.data.local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint's anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.data = self.data.local_anchor2(anchor2);
+        self
+    }
+}
+
+impl Into<JointData> for SpringJoint {
+    fn into(self) -> JointData {
+        self.data
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::SpringJoint;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+
+    #[test]
+    fn springs_oscillate_at_their_natural_period() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let anchor = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 2.5)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            body,
+            &mut world.bodies,
+        );
+        let mass = world.bodies[body].mass();
+        let stiffness = 40.0 * mass;
+        world
+            .impulse_joints
+            .insert(anchor, body, SpringJoint::new(2.0, stiffness, 0.0));
+
+        // Record the times at which the spring goes from stretched to compressed.
+        let dt = world.integration_parameters.dt;
+        let mut crossings = vec![];
+        let mut extension = 0.5;
+        for i in 0..300 {
+            world.step();
+            let new_extension = world.bodies[body].translation().norm() - 2.0;
+            if extension > 0.0 && new_extension <= 0.0 {
+                crossings.push(i as Real * dt);
+            }
+            extension = new_extension;
+        }
+
+        let period = 2.0 * Real::pi() * (mass / stiffness).sqrt();
+        assert!(crossings.len() >= 4);
+        for times in crossings.windows(2) {
+            assert!((times[1] - times[0] - period).abs() < 0.05 * period);
+        }
+    }
+
+    #[test]
+    fn infinitely_stiff_springs_keep_a_rigid_distance() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let anchor = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 2.0)
+                .linvel(Vector::y() * 3.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            body,
+            &mut world.bodies,
+        );
+        world
+            .impulse_joints
+            .insert(anchor, body, SpringJoint::new(2.0, Real::INFINITY, 0.0));
+
+        // The body swings like a pendulum without the distance to the anchor changing.
+        let mut lowest: Real = 0.0;
+        for _ in 0..120 {
+            world.step();
+            let translation = world.bodies[body].translation();
+            assert!((translation.norm() - 2.0).abs() < 1.0e-2);
+            lowest = lowest.min(translation.y);
+        }
+        assert!(lowest < -1.9);
+    }
+}
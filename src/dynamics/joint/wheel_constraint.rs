@@ -0,0 +1,512 @@
+use crate::dynamics::{
+    ImpulseJointSet, IntegrationParameters, JointAxesMask, JointAxis, JointData, JointHandle,
+    MotorModel, RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{ColliderHandle, NarrowPhase};
+use crate::math::{Isometry, Point, Real, Rotation, UnitVector, Vector};
+use crate::utils::WCross;
+use na::{Matrix3, Rotation3, UnitQuaternion};
+
+/// The coefficients of the simplified Pacejka "magic formula" used to compute the tire forces
+/// of a `WheelConstraint`.
+///
+/// The force along a direction is `friction * normal_load * sin(shape_factor * atan(stiffness * slip))`
+/// where `slip` is the slip ratio for the longitudinal force, and the slip angle for the
+/// lateral force.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TireModel {
+    /// The peak friction coefficient between the tire and the ground.
+    pub friction: Real,
+    /// The stiffness factor of the longitudinal force curve.
+    pub longitudinal_stiffness: Real,
+    /// The stiffness factor of the lateral force curve.
+    pub lateral_stiffness: Real,
+    /// The shape factor of both force curves.
+    pub shape_factor: Real,
+}
+
+impl Default for TireModel {
+    fn default() -> Self {
+        Self {
+            friction: 1.0,
+            longitudinal_stiffness: 10.0,
+            lateral_stiffness: 8.0,
+            shape_factor: 1.5,
+        }
+    }
+}
+
+impl TireModel {
+    /// The fraction, in `[-1, 1]`, of the maximum friction force applied for the given slip.
+    pub fn force_fraction(&self, stiffness: Real, slip: Real) -> Real {
+        (self.shape_factor * (stiffness * slip).atan()).sin()
+    }
+}
+
+/// The contact between a wheel and the ground, as seen by the last `WheelConstraint::update`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct WheelContact {
+    /// The collider the wheel is touching.
+    pub collider: ColliderHandle,
+    /// The rigid-body the touched collider is attached to, if any.
+    pub body: Option<RigidBodyHandle>,
+    /// The world-space contact point.
+    pub point: Point<Real>,
+    /// The world-space contact normal, pointing toward the wheel.
+    pub normal: Vector<Real>,
+    /// The normal force applied by the ground during the previous timestep.
+    pub normal_load: Real,
+    /// The difference between the speed of the tire surface and the speed of the wheel,
+    /// relative to the wheel speed. This is positive when the wheel spins faster than it rolls.
+    pub slip_ratio: Real,
+    /// The angle, in radians, between the heading of the wheel and its velocity.
+    pub slip_angle: Real,
+    /// The tire force applied to the wheel at the contact point.
+    pub force: Vector<Real>,
+}
+
+/// A vehicle wheel attached to a chassis, with a suspension, steering, and drive torque.
+///
+/// The wheel is attached to the chassis by an impulse joint which lets it slide along the
+/// suspension axis, where a spring-damper holds it at the rest length, and spin around its
+/// axle. Steering rotates the axle around the suspension axis.
+///
+/// The tire forces are computed from the slip of the wheel on the ground with a simplified
+/// Pacejka model. They replace the contact friction, so the colliders of the wheel should have
+/// a zero friction coefficient. `Self::update` must be called before each timestep.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct WheelConstraint {
+    /// The rigid-body the wheel is attached to.
+    pub chassis: RigidBodyHandle,
+    /// The rigid-body of the wheel.
+    pub wheel: RigidBodyHandle,
+    /// The point where the suspension is attached, in the local-space of the chassis.
+    pub chassis_anchor: Point<Real>,
+    /// The direction of the suspension, pointing upward, in the local-space of the chassis.
+    pub suspension_axis: UnitVector<Real>,
+    /// The axle of the wheel when it isn't steered, in the local-space of the chassis.
+    ///
+    /// This must be orthogonal to the suspension axis. A positive drive torque makes the
+    /// vehicle move along `axle × suspension_axis`.
+    pub axle: UnitVector<Real>,
+    /// The distance between the anchor and the center of the wheel when the suspension is at rest.
+    pub suspension_rest_length: Real,
+    /// The stiffness of the suspension spring.
+    pub suspension_stiffness: Real,
+    /// The damping of the suspension spring.
+    pub suspension_damping: Real,
+    /// The largest angle, in radians, the wheel can be steered by.
+    pub max_steering_angle: Real,
+    /// The torque applied to the wheel at full throttle.
+    pub drive_torque: Real,
+    /// The steering input, between `-1` and `1`.
+    pub steering: Real,
+    /// The throttle input, between `-1` and `1`.
+    pub throttle: Real,
+    /// The tire model used to compute the forces between the wheel and the ground.
+    pub tire: TireModel,
+    joint: Option<JointHandle>,
+    contact: Option<WheelContact>,
+}
+
+impl WheelConstraint {
+    /// Creates a wheel attached to `chassis` at the given chassis-space anchor.
+    ///
+    /// The suspension axis is the `y` axis and the axle is the `x` axis of the chassis.
+    pub fn new(
+        chassis: RigidBodyHandle,
+        wheel: RigidBodyHandle,
+        chassis_anchor: Point<Real>,
+    ) -> Self {
+        Self {
+            chassis,
+            wheel,
+            chassis_anchor,
+            suspension_axis: Vector::y_axis(),
+            axle: Vector::x_axis(),
+            suspension_rest_length: 0.5,
+            suspension_stiffness: 1.0e4,
+            suspension_damping: 1.0e3,
+            max_steering_angle: 0.6,
+            drive_torque: 0.0,
+            steering: 0.0,
+            throttle: 0.0,
+            tire: TireModel::default(),
+            joint: None,
+            contact: None,
+        }
+    }
+
+    /// The handle of the impulse joint attaching the wheel to the chassis, if it was inserted.
+    pub fn joint_handle(&self) -> Option<JointHandle> {
+        self.joint
+    }
+
+    /// The contact between the wheel and the ground found by the last update, if any.
+    pub fn contact(&self) -> Option<&WheelContact> {
+        self.contact.as_ref()
+    }
+
+    /// The current steering angle, in radians.
+    pub fn steering_angle(&self) -> Real {
+        self.steering.clamp(-1.0, 1.0) * self.max_steering_angle
+    }
+
+    /// Inserts the joint attaching the wheel to the chassis.
+    ///
+    /// The joint is created so that it is satisfied by the current poses of the wheel and of
+    /// the chassis, except for the suspension which is pulled toward its rest length.
+    pub fn insert(
+        &mut self,
+        bodies: &RigidBodySet,
+        impulse_joints: &mut ImpulseJointSet,
+    ) -> JointHandle {
+        let frame1 = self.chassis_frame();
+        let chassis_pos = bodies[self.chassis].position();
+        let wheel_pos = bodies[self.wheel].position();
+        let frame2 = Isometry::from_parts(
+            na::one(),
+            wheel_pos.rotation.inverse() * chassis_pos.rotation * frame1.rotation,
+        );
+
+        let data = JointData::new(JointAxesMask::X | JointAxesMask::Z)
+            .lock_axes(JointAxesMask::ANG_Y | JointAxesMask::ANG_Z)
+            .local_frame1(frame1)
+            .local_frame2(frame2)
+            .motor_model(JointAxis::Y, MotorModel::ForceBased);
+        let data = self.with_suspension_motor(data);
+        let handle = impulse_joints.insert(self.chassis, self.wheel, data);
+        self.joint = Some(handle);
+        handle
+    }
+
+    /// Applies the steering, the drive torque, and the tire forces for the next timestep.
+    ///
+    /// The tire forces depend on the contacts computed by the previous timestep. Does nothing
+    /// if the joint wasn't inserted or if one of the rigid-bodies no longer exists.
+    pub fn update(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        bodies: &mut RigidBodySet,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &mut ImpulseJointSet,
+    ) {
+        self.contact = None;
+
+        let frame1 = self.chassis_frame();
+        let joint = match self.joint.and_then(|handle| impulse_joints.get_mut(handle)) {
+            Some(joint) => joint,
+            None => return,
+        };
+
+        let steered = joint.data.local_frame1 != frame1;
+        joint.data.local_frame1 = frame1;
+        joint.data = self.with_suspension_motor(joint.data);
+
+        let chassis_rot = match bodies.get(self.chassis) {
+            Some(chassis) => chassis.position().rotation,
+            None => return,
+        };
+        let axle = chassis_rot * frame1.rotation * Vector::x();
+
+        if steered {
+            bodies[self.chassis].wake_up(true);
+        }
+
+        let torque = axle * (self.throttle.clamp(-1.0, 1.0) * self.drive_torque);
+        if torque != Vector::zeros() {
+            bodies[self.wheel].apply_torque(torque, true);
+            bodies[self.chassis].apply_torque(-torque, true);
+        }
+
+        self.contact = self.find_contact(integration_parameters.dt, bodies, narrow_phase);
+
+        if let Some(contact) = &mut self.contact {
+            let other_body = contact.body;
+
+            let lateral = axle - contact.normal * axle.dot(&contact.normal);
+            let lateral = match UnitVector::try_new(lateral, 1.0e-6) {
+                Some(lateral) => lateral.into_inner(),
+                None => return,
+            };
+            let forward = lateral.cross(&contact.normal);
+
+            let wheel = &bodies[self.wheel];
+            let ground_vel = |body: Option<RigidBodyHandle>, point: &Point<Real>| {
+                body.and_then(|h| bodies.get(h))
+                    .map(|body| body.velocity_at_point(point))
+                    .unwrap_or_else(Vector::zeros)
+            };
+            let slip_vel =
+                wheel.velocity_at_point(&contact.point) - ground_vel(other_body, &contact.point);
+            let wheel_vel = *wheel.linvel() - ground_vel(other_body, &wheel.rb_mprops.world_com);
+
+            // Regularize the slip at low speeds, where it is ill-defined.
+            const MIN_REFERENCE_SPEED: Real = 1.0;
+            let reference_speed = wheel_vel.dot(&forward).abs().max(MIN_REFERENCE_SPEED);
+            let long_slip = slip_vel.dot(&forward);
+            let lat_slip = slip_vel.dot(&lateral);
+            contact.slip_ratio = -long_slip / reference_speed;
+            contact.slip_angle = lat_slip.atan2(reference_speed);
+
+            let max_force = self.tire.friction * contact.normal_load;
+            let mut long_force = max_force
+                * self
+                    .tire
+                    .force_fraction(self.tire.longitudinal_stiffness, contact.slip_ratio);
+            let mut lat_force = -max_force
+                * self
+                    .tire
+                    .force_fraction(self.tire.lateral_stiffness, contact.slip_angle);
+
+            // The forces are applied explicitly, so we must not apply more than what would
+            // cancel the slip during the timestep to keep the simulation stable.
+            let supported_mass = if gravity.norm() > 0.0 {
+                (contact.normal_load / gravity.norm()).max(wheel.mass())
+            } else {
+                wheel.mass()
+            };
+            let max_cancelling_force = |dir: &Vector<Real>, slip: Real| {
+                let gcross = wheel.rb_mprops.effective_world_inv_inertia_sqrt
+                    * (contact.point - wheel.rb_mprops.world_com).gcross(*dir);
+                let inv_mass = crate::utils::inv(supported_mass) + gcross.norm_squared();
+                slip.abs() / (inv_mass * integration_parameters.dt)
+            };
+            let max_long = max_cancelling_force(&forward, long_slip);
+            let max_lat = max_cancelling_force(&lateral, lat_slip);
+            long_force = long_force.max(-max_long).min(max_long);
+            lat_force = lat_force.max(-max_lat).min(max_lat);
+
+            let mut force = forward * long_force + lateral * lat_force;
+            let force_norm = force.norm();
+            if force_norm > max_force {
+                force *= max_force / force_norm;
+            }
+            contact.force = force;
+
+            let point = contact.point;
+            bodies[self.wheel].apply_force_at_point(force, point, true);
+            if let Some(other) = other_body.and_then(|h| bodies.get_mut(h)) {
+                other.apply_force_at_point(-force, point, true);
+            }
+        }
+    }
+
+    /// The frame of the joint attached to the chassis, taking the steering into account.
+    fn chassis_frame(&self) -> Isometry<Real> {
+        let forward = self.axle.cross(&self.suspension_axis);
+        let basis = Matrix3::from_columns(&[
+            self.axle.into_inner(),
+            self.suspension_axis.into_inner(),
+            forward,
+        ]);
+        let rest_rotation =
+            UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(basis));
+        let steering = Rotation::from_axis_angle(&self.suspension_axis, self.steering_angle());
+        Isometry::from_parts(self.chassis_anchor.coords.into(), steering * rest_rotation)
+    }
+
+    fn with_suspension_motor(&self, data: JointData) -> JointData {
+        data.motor_position(
+            JointAxis::Y,
+            -self.suspension_rest_length,
+            self.suspension_stiffness,
+            self.suspension_damping,
+        )
+    }
+
+    /// The contact of the wheel with the ground that applied the largest impulse during the
+    /// previous timestep.
+    fn find_contact(
+        &self,
+        dt: Real,
+        bodies: &RigidBodySet,
+        narrow_phase: &NarrowPhase,
+    ) -> Option<WheelContact> {
+        let mut result: Option<(Real, WheelContact)> = None;
+        let mut total_impulse = 0.0;
+
+        for collider in bodies.get(self.wheel)?.colliders() {
+            for pair in narrow_phase.contacts_with(*collider) {
+                let flipped = pair.collider1 == *collider;
+                let other = if flipped {
+                    pair.collider2
+                } else {
+                    pair.collider1
+                };
+
+                for manifold in &pair.manifolds {
+                    let other_body = if flipped {
+                        manifold.data.rigid_body2
+                    } else {
+                        manifold.data.rigid_body1
+                    };
+
+                    if manifold.data.solver_contacts.is_empty() || other_body == Some(self.chassis)
+                    {
+                        continue;
+                    }
+
+                    let mut impulse = 0.0;
+                    let mut point = Point::origin();
+                    for solver_contact in &manifold.data.solver_contacts {
+                        impulse += manifold.points[solver_contact.contact_id as usize]
+                            .data
+                            .impulse;
+                        point += solver_contact.point.coords;
+                    }
+                    point /= manifold.data.solver_contacts.len() as Real;
+                    total_impulse += impulse;
+
+                    if result.map(|(best, _)| impulse > best).unwrap_or(true) {
+                        let normal = if flipped {
+                            -manifold.data.normal
+                        } else {
+                            manifold.data.normal
+                        };
+                        let contact = WheelContact {
+                            collider: other,
+                            body: other_body,
+                            point,
+                            normal,
+                            normal_load: 0.0,
+                            slip_ratio: 0.0,
+                            slip_angle: 0.0,
+                            force: Vector::zeros(),
+                        };
+                        result = Some((impulse, contact));
+                    }
+                }
+            }
+        }
+
+        result.map(|(_, mut contact)| {
+            contact.normal_load = total_impulse / dt;
+            contact
+        })
+    }
+}
+
+// NOTE: the wheels are attached with impulse joints, and the parallel solver
+//       doesn't support joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::WheelConstraint;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn run(world: &mut TestWorld, wheels: &mut [WheelConstraint], steps: usize) {
+        for _ in 0..steps {
+            for wheel in wheels.iter_mut() {
+                wheel.update(
+                    &world.gravity,
+                    &world.integration_parameters,
+                    &mut world.bodies,
+                    &world.narrow_phase,
+                    &mut world.impulse_joints,
+                );
+            }
+
+            world.step();
+        }
+    }
+
+    #[test]
+    fn wheel_constraints_drive_and_steer_a_vehicle() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(100.0, 0.1, 100.0).build(),
+            ground,
+            &mut world.bodies,
+        );
+
+        let chassis = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.1)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(1.0, 0.25, 2.0)
+                .density(100.0)
+                .build(),
+            chassis,
+            &mut world.bodies,
+        );
+
+        let mut wheels = Vec::new();
+        for anchor in [
+            Point::new(-1.2, -0.2, 1.5),
+            Point::new(1.2, -0.2, 1.5),
+            Point::new(-1.2, -0.2, -1.5),
+            Point::new(1.2, -0.2, -1.5),
+        ] {
+            let wheel = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::new(anchor.x, 0.4, anchor.z))
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.3)
+                    .density(100.0)
+                    .friction(0.0)
+                    .build(),
+                wheel,
+                &mut world.bodies,
+            );
+
+            let mut constraint = WheelConstraint::new(chassis, wheel, anchor);
+            constraint.drive_torque = 300.0;
+            constraint.insert(&world.bodies, &mut world.impulse_joints);
+            wheels.push(constraint);
+        }
+
+        // The vehicle settles on its suspensions, which carry its whole weight.
+        run(&mut world, &mut wheels, 120);
+        let chassis_height = world.bodies[chassis].translation().y;
+        assert!(chassis_height < 1.1 && chassis_height > 0.6);
+        assert!(world.bodies[chassis].linvel().norm() < 0.05);
+
+        let weight: Real = world
+            .bodies
+            .iter()
+            .filter(|(_, body)| body.is_dynamic())
+            .map(|(_, body)| body.mass())
+            .sum::<Real>()
+            * 9.81;
+        let normal_loads: Real = wheels
+            .iter()
+            .map(|wheel| wheel.contact().unwrap().normal_load)
+            .sum();
+        assert!((normal_loads - weight).abs() < weight * 0.05);
+
+        // The drive torque makes the vehicle move along `axle × suspension_axis`.
+        for wheel in &mut wheels {
+            wheel.throttle = 1.0;
+        }
+        run(&mut world, &mut wheels, 120);
+        assert!(world.bodies[chassis].linvel().z > 5.0);
+        assert!(world.bodies[chassis].linvel().x.abs() < 0.5);
+
+        // Steering the front wheels turns the vehicle toward `+x` without rolling it over.
+        for wheel in &mut wheels {
+            wheel.throttle = 0.0;
+        }
+        for wheel in &mut wheels[..2] {
+            wheel.steering = 2.0;
+            assert_eq!(wheel.steering_angle(), wheel.max_steering_angle);
+            wheel.steering = 0.5;
+        }
+        run(&mut world, &mut wheels, 120);
+        let rotation = world.bodies[chassis].rotation();
+        assert!((rotation * Vector::z()).x > 0.3);
+        assert!((rotation * Vector::y()).y > 0.9);
+    }
+}
@@ -1,9 +1,13 @@
 //! Structures related to dynamics: bodies, impulse_joints, etc.
 
+pub use self::buoyancy_volume::BuoyancyVolume;
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
-pub use self::integration_parameters::IntegrationParameters;
-pub use self::island_manager::IslandManager;
+pub use self::force_field::{
+    ForceField, RadialForceField, TurbulentWindField, VortexField, WindField,
+};
+pub use self::integration_parameters::{IntegrationParameters, SolverMode};
+pub use self::island_manager::{IslandEvent, IslandManager, IslandMetrics, SleepEvent};
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
@@ -12,22 +16,33 @@ pub use self::rigid_body_components::*;
 pub(crate) use self::solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::solver::ParallelIslandSolver;
+pub use self::solver::SolverResidual;
 pub use parry::mass_properties::MassProperties;
 
+#[cfg(all(feature = "dim3", feature = "default-sets"))]
+pub use self::pbd_cloth::PBDCloth;
 #[cfg(feature = "default-sets")]
 pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
 #[cfg(feature = "default-sets")]
-pub use self::rigid_body_set::{BodyPair, RigidBodySet};
+pub use self::rigid_body_set::{BodyPair, PhysicsPatch, PhysicsSnapshot, RigidBodySet};
+#[cfg(feature = "default-sets")]
+pub use self::xpbd_rope::{RopeAttachment, XpbdRope};
 
+mod buoyancy_volume;
 mod ccd;
 mod coefficient_combine_rule;
+mod force_field;
 mod integration_parameters;
 mod island_manager;
 mod joint;
 mod rigid_body_components;
 mod solver;
 
+#[cfg(all(feature = "dim3", feature = "default-sets"))]
+mod pbd_cloth;
 #[cfg(feature = "default-sets")]
 mod rigid_body;
 #[cfg(feature = "default-sets")]
 mod rigid_body_set;
+#[cfg(feature = "default-sets")]
+mod xpbd_rope;
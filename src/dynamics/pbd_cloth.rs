@@ -0,0 +1,704 @@
+use crate::dynamics::{ForceField, IslandManager, RigidBodySet};
+use crate::geometry::{ColliderBuilder, ColliderHandle, ColliderSet, NarrowPhase, TriMesh};
+use crate::math::{Point, Real, Vector, DEFAULT_EPSILON};
+use parry::shape::Triangle;
+use std::collections::HashMap;
+
+/// The rest shape of a triangle of a `PBDCloth`, used by its stretch and shear constraints.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct ClothTriangle {
+    vertices: [u32; 3],
+    /// The inverse of the matrix whose columns are the two rest edges of the triangle
+    /// starting at its first vertex, expressed in the plane of the triangle.
+    inv_rest_edges: na::Matrix2<Real>,
+    area: Real,
+}
+
+/// A bend constraint between the opposite vertices of two adjacent triangles of a `PBDCloth`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct ClothBend {
+    vertices: [u32; 2],
+    rest_length: Real,
+}
+
+/// A cloth simulated with Extended Position-Based Dynamics (XPBD).
+///
+/// Each triangle of the cloth resists stretching along (and shearing between) the two axes
+/// of its material frame, while each pair of adjacent triangles resists bending. Vertices
+/// closer than `thickness` are pushed apart, unless they already were in the rest mesh.
+///
+/// The cloth isn't part of the rigid-body simulation: `Self::step` must be called after each
+/// timestep. Once `Self::insert_colliders` has been called, each triangle of the cloth is
+/// represented by a sensor collider so the broad-phase finds the colliders close to the
+/// cloth. The cloth then collides with them, and applies impulses to their dynamic
+/// rigid-bodies.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PBDCloth {
+    /// The rest shape of the cloth.
+    ///
+    /// The constraints are initialized from this mesh when the cloth is created, so modifying
+    /// it afterwards has no effect on them.
+    pub mesh: TriMesh,
+    /// The mass of each vertex of the cloth.
+    pub mass_per_vertex: Real,
+    /// The stiffness of the triangles along the axes of their material frame, per unit area.
+    ///
+    /// Set this to zero to disable the stretch constraints.
+    pub stretch_stiffness: Real,
+    /// The stiffness of the angle between the axes of the material frame of the triangles,
+    /// per unit area.
+    ///
+    /// Set this to zero to disable the shear constraints.
+    pub shear_stiffness: Real,
+    /// The stiffness of the distance between the opposite vertices of adjacent triangles.
+    ///
+    /// Set this to zero to disable the bend constraints.
+    pub bend_stiffness: Real,
+    /// Damping factor slowing down the motion of the vertices.
+    pub damping: Real,
+    /// The distance kept between the cloth and the colliders, and between non-adjacent
+    /// vertices of the cloth.
+    pub thickness: Real,
+    /// Are the vertices of the cloth pushed apart from each other?
+    pub self_collisions: bool,
+    /// The number of substeps performed by each call to `Self::step`.
+    pub num_substeps: usize,
+    positions: Vec<Point<Real>>,
+    velocities: Vec<Vector<Real>>,
+    pinned: Vec<bool>,
+    triangles: Vec<ClothTriangle>,
+    bends: Vec<ClothBend>,
+    colliders: Vec<ColliderHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    prev_positions: Vec<Point<Real>>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    contact_candidates: Vec<(u32, ColliderHandle)>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    grid: Vec<([i32; 3], u32)>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    self_collision_pairs: Vec<[u32; 2]>, // Workspace.
+}
+
+impl PBDCloth {
+    /// Creates a cloth at rest with the shape of the given mesh.
+    pub fn new(
+        mesh: TriMesh,
+        mass_per_vertex: Real,
+        stretch_stiffness: Real,
+        shear_stiffness: Real,
+        bend_stiffness: Real,
+    ) -> Self {
+        let vertices = mesh.vertices();
+        let mut triangles = Vec::with_capacity(mesh.num_triangles());
+        let mut bends = Vec::new();
+        let mut edges = HashMap::new();
+
+        for idx in mesh.indices() {
+            let [a, b, c] = idx.map(|i| vertices[i as usize]);
+            let (e1, e2) = (b - a, c - a);
+            let normal = e1.cross(&e2);
+
+            // The material frame, with its first axis along the first edge.
+            let u = e1.normalize();
+            let v = normal.cross(&e1).normalize();
+            let rest_edges = na::Matrix2::new(e1.dot(&u), e2.dot(&u), e1.dot(&v), e2.dot(&v));
+
+            if let Some(inv_rest_edges) = rest_edges.try_inverse() {
+                triangles.push(ClothTriangle {
+                    vertices: *idx,
+                    inv_rest_edges,
+                    area: normal.norm() / 2.0,
+                });
+            }
+
+            for k in 0..3 {
+                let (i, j, opposite) = (idx[k], idx[(k + 1) % 3], idx[(k + 2) % 3]);
+                let key = (i.min(j), i.max(j));
+
+                if let Some(other) = edges.remove(&key) {
+                    bends.push(ClothBend {
+                        vertices: [other, opposite],
+                        rest_length: na::distance(
+                            &vertices[other as usize],
+                            &vertices[opposite as usize],
+                        ),
+                    });
+                } else {
+                    let _ = edges.insert(key, opposite);
+                }
+            }
+        }
+
+        Self {
+            positions: vertices.to_vec(),
+            velocities: vec![Vector::zeros(); vertices.len()],
+            pinned: vec![false; vertices.len()],
+            mesh,
+            mass_per_vertex,
+            stretch_stiffness,
+            shear_stiffness,
+            bend_stiffness,
+            damping: 0.0,
+            thickness: 0.02,
+            self_collisions: true,
+            num_substeps: 10,
+            triangles,
+            bends,
+            colliders: Vec::new(),
+            prev_positions: Vec::new(),
+            contact_candidates: Vec::new(),
+            grid: Vec::new(),
+            self_collision_pairs: Vec::new(),
+        }
+    }
+
+    /// The world-space positions of the vertices of the cloth.
+    pub fn positions(&self) -> &[Point<Real>] {
+        &self.positions
+    }
+
+    /// The velocities of the vertices of the cloth.
+    pub fn velocities(&self) -> &[Vector<Real>] {
+        &self.velocities
+    }
+
+    /// Teleports the `i`-th vertex of the cloth, e.g., to move a pinned vertex.
+    pub fn set_position(&mut self, i: usize, position: Point<Real>) {
+        self.positions[i] = position;
+    }
+
+    /// Pins the `i`-th vertex of the cloth, so it is no longer moved by the simulation.
+    pub fn pin(&mut self, i: usize) {
+        self.pinned[i] = true;
+        self.velocities[i] = Vector::zeros();
+    }
+
+    /// Unpins the `i`-th vertex of the cloth.
+    pub fn unpin(&mut self, i: usize) {
+        self.pinned[i] = false;
+    }
+
+    /// Is the `i`-th vertex of the cloth pinned?
+    pub fn is_pinned(&self, i: usize) -> bool {
+        self.pinned[i]
+    }
+
+    /// The handles of the colliders representing the triangles of the cloth.
+    pub fn colliders(&self) -> &[ColliderHandle] {
+        &self.colliders
+    }
+
+    /// Inserts a sensor collider for each triangle of the cloth into the collider set.
+    ///
+    /// The cloth only collides with the colliders the broad-phase finds close to these
+    /// sensors. Does nothing if the colliders have already been inserted.
+    pub fn insert_colliders(&mut self, colliders: &mut ColliderSet) {
+        if !self.colliders.is_empty() {
+            return;
+        }
+
+        for idx in self.mesh.indices() {
+            let [a, b, c] = idx.map(|i| self.positions[i as usize]);
+            let collider = ColliderBuilder::round_triangle(a, b, c, self.thickness)
+                .sensor(true)
+                .build();
+            self.colliders.push(colliders.insert(collider));
+        }
+    }
+
+    /// Removes the colliders representing the triangles of the cloth from the collider set.
+    pub fn remove_colliders(
+        &mut self,
+        colliders: &mut ColliderSet,
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+    ) {
+        for handle in self.colliders.drain(..) {
+            let _ = colliders.remove(handle, islands, bodies, false);
+        }
+    }
+
+    fn inv_mass(&self, i: u32) -> Real {
+        if self.pinned[i as usize] {
+            0.0
+        } else {
+            crate::utils::inv(self.mass_per_vertex)
+        }
+    }
+
+    /// Advances the simulation of the cloth by `dt`.
+    ///
+    /// The vertices are subjected to the gravity and to the given force fields, e.g., the
+    /// `PhysicsPipeline::force_fields`. The contacts are computed with the colliders found by
+    /// the last update of the narrow-phase, and the reaction impulses are applied to the
+    /// dynamic rigid-bodies, so they affect their next timestep. Finally, the colliders of
+    /// the cloth are moved to its new shape.
+    pub fn step(
+        &mut self,
+        gravity: &Vector<Real>,
+        dt: Real,
+        force_fields: &[Box<dyn ForceField>],
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        narrow_phase: &NarrowPhase,
+    ) {
+        if self.positions.is_empty() || self.num_substeps == 0 || dt <= 0.0 {
+            return;
+        }
+
+        self.collect_contact_candidates(colliders, narrow_phase);
+        self.collect_self_collision_candidates(gravity, dt);
+
+        let h = dt / self.num_substeps as Real;
+        let mut contact_impulses = Vec::new();
+
+        for _ in 0..self.num_substeps {
+            self.prev_positions.clone_from(&self.positions);
+
+            for ((point, vel), pinned) in self
+                .positions
+                .iter_mut()
+                .zip(self.velocities.iter_mut())
+                .zip(self.pinned.iter())
+            {
+                if *pinned {
+                    continue;
+                }
+
+                let mut acceleration = *gravity;
+                for field in force_fields {
+                    acceleration += field.acceleration_at(point, vel);
+                }
+
+                *vel += acceleration * h;
+                *vel /= 1.0 + h * self.damping;
+                *point += *vel * h;
+            }
+
+            self.solve_triangles(h);
+            self.solve_bends(h);
+
+            self.solve_self_collisions();
+            self.solve_contacts(h, bodies, colliders, &mut contact_impulses);
+
+            for ((vel, point), prev) in self
+                .velocities
+                .iter_mut()
+                .zip(self.positions.iter())
+                .zip(self.prev_positions.iter())
+            {
+                *vel = (point - prev) / h;
+            }
+        }
+
+        for (handle, impulse, point) in contact_impulses {
+            if let Some(body) = colliders.get(handle).and_then(|co| co.parent()) {
+                bodies[body].apply_impulse_at_point(impulse, point, true);
+            }
+        }
+
+        self.update_colliders(colliders);
+    }
+
+    /// Moves the vertices by one XPBD iteration on the constraint with the value `c` and the
+    /// gradients `grads` with respect to the given vertices.
+    fn project<const N: usize>(
+        &mut self,
+        vertices: [u32; N],
+        c: Real,
+        grads: [Vector<Real>; N],
+        compliance: Real,
+    ) {
+        let inv_masses = vertices.map(|i| self.inv_mass(i));
+        let denominator = inv_masses
+            .iter()
+            .zip(grads.iter())
+            .map(|(w, grad)| w * grad.norm_squared())
+            .sum::<Real>()
+            + compliance;
+
+        if denominator <= DEFAULT_EPSILON {
+            return;
+        }
+
+        let lambda = -c / denominator;
+        for k in 0..N {
+            self.positions[vertices[k] as usize] += grads[k] * (inv_masses[k] * lambda);
+        }
+    }
+
+    /// The columns of the deformation gradient of the given triangle, i.e., its current
+    /// material axes.
+    fn material_axes(&self, triangle: &ClothTriangle) -> (Vector<Real>, Vector<Real>) {
+        let [a, b, c] = triangle.vertices.map(|i| self.positions[i as usize]);
+        let (e1, e2) = (b - a, c - a);
+        let m = &triangle.inv_rest_edges;
+        (
+            e1 * m[(0, 0)] + e2 * m[(1, 0)],
+            e1 * m[(0, 1)] + e2 * m[(1, 1)],
+        )
+    }
+
+    fn solve_triangles(&mut self, h: Real) {
+        for t in 0..self.triangles.len() {
+            let triangle = self.triangles[t];
+            let m = triangle.inv_rest_edges;
+
+            if self.stretch_stiffness > 0.0 {
+                let compliance = 1.0 / (self.stretch_stiffness * triangle.area * h * h);
+
+                for axis in 0..2 {
+                    let (f0, f1) = self.material_axes(&triangle);
+                    let f = if axis == 0 { f0 } else { f1 };
+                    let length = f.norm();
+
+                    if length <= DEFAULT_EPSILON {
+                        continue;
+                    }
+
+                    let dir = f / length;
+                    let (g1, g2) = (dir * m[(0, axis)], dir * m[(1, axis)]);
+                    self.project(
+                        triangle.vertices,
+                        length - 1.0,
+                        [-g1 - g2, g1, g2],
+                        compliance,
+                    );
+                }
+            }
+
+            if self.shear_stiffness > 0.0 {
+                let compliance = 1.0 / (self.shear_stiffness * triangle.area * h * h);
+                let (f0, f1) = self.material_axes(&triangle);
+                let g1 = f1 * m[(0, 0)] + f0 * m[(0, 1)];
+                let g2 = f1 * m[(1, 0)] + f0 * m[(1, 1)];
+                self.project(
+                    triangle.vertices,
+                    f0.dot(&f1),
+                    [-g1 - g2, g1, g2],
+                    compliance,
+                );
+            }
+        }
+    }
+
+    fn solve_bends(&mut self, h: Real) {
+        if self.bend_stiffness <= 0.0 {
+            return;
+        }
+
+        let compliance = 1.0 / (self.bend_stiffness * h * h);
+
+        for b in 0..self.bends.len() {
+            let bend = self.bends[b];
+            let [i, j] = bend.vertices;
+            let delta = self.positions[j as usize] - self.positions[i as usize];
+            let length = delta.norm();
+
+            if length <= DEFAULT_EPSILON {
+                continue;
+            }
+
+            let dir = delta / length;
+            self.project(
+                bend.vertices,
+                length - bend.rest_length,
+                [-dir, dir],
+                compliance,
+            );
+        }
+    }
+
+    /// Collects the pairs of vertices that may get closer than the thickness of the cloth
+    /// during the next `dt`, using a spatial grid.
+    ///
+    /// The pairs of vertices already closer than the thickness in the rest mesh are ignored.
+    fn collect_self_collision_candidates(&mut self, gravity: &Vector<Real>, dt: Real) {
+        self.self_collision_pairs.clear();
+
+        if !self.self_collisions || self.thickness <= 0.0 {
+            return;
+        }
+
+        let max_speed = self
+            .velocities
+            .iter()
+            .map(|vel| vel.norm())
+            .fold(0.0, Real::max);
+        let cell_size = self.thickness + 2.0 * (max_speed * dt + gravity.norm() * dt * dt);
+        let cell = |p: &Point<Real>| -> [i32; 3] {
+            p.coords.map(|x| (x / cell_size).floor() as i32).into()
+        };
+
+        self.grid.clear();
+        self.grid.extend(
+            self.positions
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (cell(p), i as u32)),
+        );
+        self.grid.sort_unstable();
+
+        let rest_positions = self.mesh.vertices();
+
+        for (i, point) in self.positions.iter().enumerate() {
+            let cell_i = cell(point);
+
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let key = [cell_i[0] + dx, cell_i[1] + dy, cell_i[2] + dz];
+                        let start = self.grid.partition_point(|e| e.0 < key);
+
+                        for (_, j) in self.grid[start..].iter().take_while(|e| e.0 == key) {
+                            if (*j as usize) > i
+                                && na::distance(point, &self.positions[*j as usize]) < cell_size
+                                && na::distance(&rest_positions[i], &rest_positions[*j as usize])
+                                    >= self.thickness
+                            {
+                                self.self_collision_pairs.push([i as u32, *j]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pushes apart the candidate pairs of vertices closer than the thickness of the cloth.
+    fn solve_self_collisions(&mut self) {
+        for p in 0..self.self_collision_pairs.len() {
+            let [i, j] = self.self_collision_pairs[p];
+            let delta = self.positions[j as usize] - self.positions[i as usize];
+            let dist = delta.norm();
+
+            if dist >= self.thickness || dist <= DEFAULT_EPSILON {
+                continue;
+            }
+
+            let (wi, wj) = (self.inv_mass(i), self.inv_mass(j));
+            if wi + wj <= 0.0 {
+                continue;
+            }
+
+            let correction = delta * ((self.thickness - dist) / (dist * (wi + wj)));
+            self.positions[i as usize] -= correction * wi;
+            self.positions[j as usize] += correction * wj;
+        }
+    }
+
+    /// Collects the colliders each vertex may touch, i.e., the non-sensor colliders the
+    /// broad-phase found close to the collider of one of its triangles.
+    fn collect_contact_candidates(&mut self, colliders: &ColliderSet, narrow_phase: &NarrowPhase) {
+        self.contact_candidates.clear();
+
+        for (idx, handle) in self.mesh.indices().iter().zip(self.colliders.iter()) {
+            for (handle1, handle2, _) in narrow_phase.intersections_with(*handle) {
+                let other = if handle1 == *handle { handle2 } else { handle1 };
+
+                match colliders.get(other) {
+                    Some(co) if !co.is_sensor() => {}
+                    _ => continue,
+                }
+
+                for i in idx {
+                    self.contact_candidates.push((*i, other));
+                }
+            }
+        }
+
+        self.contact_candidates
+            .sort_unstable_by_key(|(i, handle)| (*i, handle.into_raw_parts()));
+        self.contact_candidates.dedup();
+    }
+
+    /// Pushes the vertices of the cloth out of the candidate colliders, with friction.
+    ///
+    /// The colliders are assumed to remain at their current position during the whole step.
+    fn solve_contacts(
+        &mut self,
+        h: Real,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        contact_impulses: &mut Vec<(ColliderHandle, Vector<Real>, Point<Real>)>,
+    ) {
+        for (i, handle) in &self.contact_candidates {
+            let i = *i as usize;
+            if self.pinned[i] {
+                continue;
+            }
+
+            let co = &colliders[*handle];
+            let point = self.positions[i];
+            let proj = co.shape().project_point(co.position(), &point, false);
+            let delta = point - proj.point;
+            let dist = delta.norm();
+
+            if dist <= DEFAULT_EPSILON {
+                continue;
+            }
+
+            // The contact normal, pointing from the collider toward the vertex.
+            let (normal, depth) = if proj.is_inside {
+                (-delta / dist, dist + self.thickness)
+            } else {
+                (delta / dist, self.thickness - dist)
+            };
+
+            if depth <= 0.0 {
+                continue;
+            }
+
+            // Friction cancels the tangential motion of the vertex relative to the collider
+            // during this substep, up to the friction cone.
+            let collider_vel = co
+                .parent()
+                .map(|parent| bodies[parent].velocity_at_point(&proj.point))
+                .unwrap_or_else(Vector::zeros);
+            let motion = point - self.prev_positions[i] - collider_vel * h;
+            let tangential = motion - normal * motion.dot(&normal);
+            let tangential_norm = tangential.norm();
+            let max_friction = co.friction() * depth;
+            let friction = if tangential_norm > max_friction {
+                tangential * (max_friction / tangential_norm)
+            } else {
+                tangential
+            };
+
+            let correction = normal * depth - friction;
+            self.positions[i] += correction;
+            contact_impulses.push((
+                *handle,
+                -correction * (self.mass_per_vertex / h),
+                proj.point,
+            ));
+        }
+    }
+
+    /// Moves the colliders of the triangles of the cloth to their current shape.
+    fn update_colliders(&self, colliders: &mut ColliderSet) {
+        for (idx, handle) in self.mesh.indices().iter().zip(self.colliders.iter()) {
+            if let Some(co) = colliders.get_mut(*handle) {
+                if let Some(shape) = co.shape_mut().as_round_triangle_mut() {
+                    let [a, b, c] = idx.map(|i| self.positions[i as usize]);
+                    shape.base_shape = Triangle::new(a, b, c);
+                    shape.border_radius = self.thickness;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PBDCloth;
+    use crate::dynamics::{ForceField, RigidBodyBuilder, WindField};
+    use crate::geometry::{ColliderBuilder, TriMesh};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    // A square grid of `n * n` quads of size `0.2`, centered at `center`, in the plane
+    // orthogonal to `normal_axis`.
+    fn grid(n: u32, center: Vector<Real>, normal_axis: usize) -> TriMesh {
+        let (u, v) = ((normal_axis + 1) % 3, (normal_axis + 2) % 3);
+        let vertices = (0..=n)
+            .flat_map(|i| (0..=n).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let mut p = Point::from(center);
+                p[u] += (i as Real - n as Real / 2.0) * 0.2;
+                p[v] += (j as Real - n as Real / 2.0) * 0.2;
+                p
+            })
+            .collect();
+        let id = |i: u32, j: u32| i * (n + 1) + j;
+        let indices = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .flat_map(|(i, j)| {
+                [
+                    [id(i, j), id(i + 1, j), id(i + 1, j + 1)],
+                    [id(i, j), id(i + 1, j + 1), id(i, j + 1)],
+                ]
+            })
+            .collect();
+        TriMesh::new(vertices, indices)
+    }
+
+    #[test]
+    fn pbd_cloths_carry_bodies_and_follow_force_fields() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let dt = world.integration_parameters.dt;
+
+        // A hammock pinned by its corners, carrying a ball.
+        let mut hammock = PBDCloth::new(grid(6, Vector::y() * 2.0, 1), 0.01, 1.0e6, 1.0e5, 0.0);
+        for corner in [0, 6, 42, 48] {
+            hammock.pin(corner);
+        }
+        hammock.damping = 1.0;
+        hammock.insert_colliders(&mut world.colliders);
+        let rest_positions = hammock.positions().to_vec();
+
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 2.5)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.3).density(1.0).build(),
+            ball,
+            &mut world.bodies,
+        );
+
+        // A curtain pinned along its top edge, pushed by the wind.
+        let mut curtain = PBDCloth::new(grid(4, Vector::x() * 10.0, 2), 0.01, 1.0e6, 1.0e5, 10.0);
+        for i in 0..=4 {
+            curtain.pin(i * 5 + 4);
+        }
+        let wind: Vec<Box<dyn ForceField>> = vec![Box::new(WindField::new(Vector::z(), 2.0, 0.0))];
+
+        for _ in 0..200 {
+            world.step();
+            hammock.step(
+                &world.gravity,
+                dt,
+                &world.pipeline.force_fields,
+                &mut world.bodies,
+                &mut world.colliders,
+                &world.narrow_phase,
+            );
+            curtain.step(
+                &world.gravity,
+                dt,
+                &wind,
+                &mut world.bodies,
+                &mut world.colliders,
+                &world.narrow_phase,
+            );
+        }
+
+        // The ball rests on the hammock, which keeps its corners and doesn't tear.
+        let ball_pos = world.bodies[ball].translation();
+        assert!(ball_pos.xz().norm() < 0.2);
+        assert!(ball_pos.y > 1.9 && ball_pos.y < 2.3);
+        assert!(world.bodies[ball].linvel().norm() < 0.5);
+
+        for corner in [0, 6, 42, 48] {
+            assert_eq!(hammock.positions()[corner], rest_positions[corner]);
+        }
+
+        for idx in hammock.mesh.indices() {
+            for k in 0..3 {
+                let (i, j) = (idx[k] as usize, idx[(k + 1) % 3] as usize);
+                let length = na::distance(&hammock.positions()[i], &hammock.positions()[j]);
+                let rest_length = na::distance(&rest_positions[i], &rest_positions[j]);
+                assert!((length - rest_length).abs() < rest_length * 0.1);
+            }
+        }
+
+        // The curtain is blown along the wind.
+        let mean_z = curtain.positions().iter().map(|p| p.z).sum::<Real>() / 25.0;
+        assert!(mean_z > 0.05);
+    }
+}
@@ -1,12 +1,15 @@
 use crate::dynamics::{
-    MassProperties, RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders,
-    RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyIds, RigidBodyMassProps,
-    RigidBodyMassPropsFlags, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    CcdMode, MassProperties, RigidBodyActivation, RigidBodyCcd, RigidBodyChanges,
+    RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyIds,
+    RigidBodyMassProps, RigidBodyMassPropsFlags, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity, SleepThreshold,
 };
 use crate::geometry::{
     Collider, ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderShape,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector};
+#[cfg(feature = "dim3")]
+use crate::utils::WAngularInertia;
 use crate::utils::{self, WCross};
 use na::ComplexField;
 use num::Zero;
@@ -63,7 +66,20 @@ impl RigidBody {
 
     pub(crate) fn reset_internal_references(&mut self) {
         self.rb_colliders.0 = Vec::new();
-        self.rb_ids = Default::default();
+        self.rb_ids = RigidBodyIds {
+            reported_sleeping: self.rb_activation.sleeping,
+            ..Default::default()
+        };
+    }
+
+    /// The user-defined data associated to this rigid-body.
+    pub fn user_data(&self) -> u128 {
+        self.user_data
+    }
+
+    /// Sets the user-defined data associated to this rigid-body.
+    pub fn set_user_data(&mut self, data: u128) {
+        self.user_data = data;
     }
 
     /// The activation status of this rigid-body.
@@ -101,6 +117,58 @@ impl RigidBody {
         self.rb_damping.angular_damping = damping
     }
 
+    /// The linear damping coefficients of this rigid-body along each of its local axes.
+    #[inline]
+    pub fn linear_damping_axes(&self) -> &Vector<Real> {
+        &self.rb_damping.linear_damping_axes
+    }
+
+    /// Sets the linear damping coefficients of this rigid-body along each of its local axes.
+    ///
+    /// These are applied in addition to the isotropic linear damping.
+    #[inline]
+    pub fn set_linear_damping_axes(&mut self, damping: Vector<Real>) {
+        self.rb_damping.linear_damping_axes = damping;
+    }
+
+    /// The angular damping coefficients of this rigid-body around each of its local axes.
+    #[inline]
+    pub fn angular_damping_axes(&self) -> &AngVector<Real> {
+        &self.rb_damping.angular_damping_axes
+    }
+
+    /// Sets the angular damping coefficients of this rigid-body around each of its local axes.
+    ///
+    /// These are applied in addition to the isotropic angular damping.
+    #[inline]
+    pub fn set_angular_damping_axes(&mut self, damping: AngVector<Real>) {
+        self.rb_damping.angular_damping_axes = damping;
+    }
+
+    /// The maximum magnitude of the linear velocity of this rigid-body.
+    #[inline]
+    pub fn max_linear_velocity(&self) -> Real {
+        self.rb_damping.max_linear_velocity
+    }
+
+    /// Sets the maximum magnitude of the linear velocity of this rigid-body.
+    #[inline]
+    pub fn set_max_linear_velocity(&mut self, max_linvel: Real) {
+        self.rb_damping.max_linear_velocity = max_linvel;
+    }
+
+    /// The maximum magnitude of the angular velocity of this rigid-body.
+    #[inline]
+    pub fn max_angular_velocity(&self) -> Real {
+        self.rb_damping.max_angular_velocity
+    }
+
+    /// Sets the maximum magnitude of the angular velocity of this rigid-body.
+    #[inline]
+    pub fn set_max_angular_velocity(&mut self, max_angvel: Real) {
+        self.rb_damping.max_angular_velocity = max_angvel;
+    }
+
     /// The type of this rigid-body.
     pub fn body_type(&self) -> RigidBodyType {
         self.rb_type
@@ -238,6 +306,16 @@ impl RigidBody {
         self.rb_ccd.ccd_enabled
     }
 
+    /// Sets the method used for preventing tunneling when CCD is enabled for this rigid-body.
+    pub fn set_ccd_mode(&mut self, mode: CcdMode) {
+        self.rb_ccd.ccd_mode = mode;
+    }
+
+    /// The method used for preventing tunneling when CCD is enabled for this rigid-body.
+    pub fn ccd_mode(&self) -> CcdMode {
+        self.rb_ccd.ccd_mode
+    }
+
     // This is different from `is_ccd_enabled`. This checks that CCD
     // is active for this rigid-body, i.e., if it was seen to move fast
     // enough to justify a CCD run.
@@ -314,7 +392,7 @@ impl RigidBody {
         self.rb_forces.gravity_scale
     }
 
-    /// Sets the gravity scale facter for this rigid-body.
+    /// Sets the gravity scale factor for this rigid-body.
     pub fn set_gravity_scale(&mut self, scale: Real, wake_up: bool) {
         if wake_up && self.rb_activation.sleeping {
             self.changes.insert(RigidBodyChanges::SLEEP);
@@ -324,6 +402,25 @@ impl RigidBody {
         self.rb_forces.gravity_scale = scale;
     }
 
+    /// The gravity applied to this rigid-body instead of the gravity of the simulation, if any.
+    pub fn local_gravity(&self) -> Option<Vector<Real>> {
+        self.rb_forces.local_gravity
+    }
+
+    /// Sets the gravity applied to this rigid-body instead of the gravity of the simulation.
+    ///
+    /// Unlike the gravity scale, this can change the direction of the gravity. The local gravity
+    /// is still multiplied by the gravity scale. Set it to `None` to use the gravity of the
+    /// simulation again.
+    pub fn set_local_gravity(&mut self, gravity: Option<Vector<Real>>, wake_up: bool) {
+        if wake_up && self.rb_activation.sleeping {
+            self.changes.insert(RigidBodyChanges::SLEEP);
+            self.rb_activation.sleeping = false;
+        }
+
+        self.rb_forces.local_gravity = gravity;
+    }
+
     /// The dominance group of this rigid-body.
     pub fn dominance_group(&self) -> i8 {
         self.rb_dominance.0
@@ -471,6 +568,22 @@ impl RigidBody {
         &self.rb_pos.position
     }
 
+    /// The position of this rigid-body interpolated between its position at the beginning
+    /// of the last timestep (for `alpha = 0.0`) and its current position (for `alpha = 1.0`).
+    ///
+    /// This is typically used for rendering at a different rate than the simulation.
+    /// Kinematic bodies should use `self.extrapolated_position` instead.
+    pub fn interpolated_position(&self, alpha: Real) -> Isometry<Real> {
+        self.rb_pos.interpolate(alpha)
+    }
+
+    /// The position of this rigid-body extrapolated forward by a fraction `alpha` of the
+    /// motion it had during the last timestep.
+    pub fn extrapolated_position(&self, alpha: Real) -> Isometry<Real> {
+        self.rb_pos
+            .extrapolate(alpha, &self.rb_mprops.local_mprops.local_com)
+    }
+
     /// The translational part of this rigid-body's position.
     #[inline]
     pub fn translation(&self) -> &Vector<Real> {
@@ -483,6 +596,7 @@ impl RigidBody {
         self.changes.insert(RigidBodyChanges::POSITION);
         self.rb_pos.position.translation.vector = translation;
         self.rb_pos.next_position.translation.vector = translation;
+        self.rb_pos.prev_position.translation.vector = translation;
 
         // TODO: Do we really need to check that the body isn't dynamic?
         if wake_up && self.is_dynamic() {
@@ -503,6 +617,7 @@ impl RigidBody {
         let rotation = Rotation::new(rotation);
         self.rb_pos.position.rotation = rotation;
         self.rb_pos.next_position.rotation = rotation;
+        self.rb_pos.prev_position.rotation = rotation;
 
         // TODO: Do we really need to check that the body isn't dynamic?
         if wake_up && self.is_dynamic() {
@@ -523,6 +638,7 @@ impl RigidBody {
         self.changes.insert(RigidBodyChanges::POSITION);
         self.rb_pos.position = pos;
         self.rb_pos.next_position = pos;
+        self.rb_pos.prev_position = pos;
 
         // TODO: Do we really need to check that the body isn't dynamic?
         if wake_up && self.is_dynamic() {
@@ -684,20 +800,121 @@ impl RigidBody {
         self.apply_impulse(impulse, wake_up);
         self.apply_torque_impulse(torque_impulse, wake_up);
     }
+
+    /// Queues an impulse to be applied at the given world-space point at the beginning of the
+    /// next simulation step, before the constraints are solved.
+    ///
+    /// Unlike `Self::apply_impulse_at_point`, this doesn't change the velocities right away,
+    /// so it can be called safely while the simulation is running, e.g., when handling events.
+    /// The rigid-body is always woken up, so that the impulse isn't kept queued while it sleeps.
+    ///
+    /// This does nothing on non-dynamic bodies, and returns `false` if the impulse wasn't queued
+    /// because the body isn't dynamic, or because `RigidBodyForces::MAX_DEFERRED_IMPULSES`
+    /// impulses are already queued.
+    pub fn queue_impulse(&mut self, impulse: Vector<Real>, point: Point<Real>) -> bool {
+        self.queue_deferred_impulse(impulse, Some(point))
+    }
+
+    /// Queues an impulse to be applied at the center-of-mass of this rigid-body at the beginning
+    /// of the next simulation step.
+    ///
+    /// The application point is the center-of-mass at the time the impulse is applied, so
+    /// moving the rigid-body in the meantime doesn't make it rotate. See `Self::queue_impulse`
+    /// for details.
+    pub fn apply_central_impulse_at_next_step(&mut self, impulse: Vector<Real>) -> bool {
+        self.queue_deferred_impulse(impulse, None)
+    }
+
+    fn queue_deferred_impulse(
+        &mut self,
+        impulse: Vector<Real>,
+        point: Option<Point<Real>>,
+    ) -> bool {
+        if self.rb_type == RigidBodyType::Dynamic && self.rb_forces.queue_impulse(impulse, point) {
+            self.wake_up(true);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The impulses queued by `Self::queue_impulse` and not applied yet, with their
+    /// world-space application points, or `None` for the impulses queued by
+    /// `Self::apply_central_impulse_at_next_step`.
+    pub fn queued_impulses(&self) -> &[(Vector<Real>, Option<Point<Real>>)] {
+        self.rb_forces.deferred_impulses()
+    }
 }
 
 impl RigidBody {
     /// The velocity of the given world-space point on this rigid-body.
+    ///
+    /// This is the sum of the linear velocity and of the velocity due to the rotation
+    /// around the world-space center-of-mass, i.e., `linvel + angvel × (point - world_com)`.
     pub fn velocity_at_point(&self, point: &Point<Real>) -> Vector<Real> {
-        self.rb_vels
-            .velocity_at_point(point, &self.rb_mprops.world_com)
+        // NOTE: don't use `self.rb_mprops.world_com` because it is only updated
+        //       by the timesteps, so it isn't up-to-date after a call to `set_position`.
+        let world_com = self.rb_mprops.local_mprops.world_com(&self.rb_pos.position);
+        self.rb_vels.velocity_at_point(point, &world_com)
     }
 
     /// The kinetic energy of this body.
+    ///
+    /// This includes both its translational energy `0.5 * mass * linvel²` and its rotational
+    /// energy `0.5 * angvel · (inertia * angvel)`.
     pub fn kinetic_energy(&self) -> Real {
         self.rb_vels.kinetic_energy(&self.rb_mprops)
     }
 
+    /// The linear momentum `mass * linvel` of this body.
+    pub fn linear_momentum(&self) -> Vector<Real> {
+        self.rb_vels.linvel * self.mass()
+    }
+
+    /// The angular momentum of this body about the given world-space point.
+    ///
+    /// This is the sum of its spin `inertia * angvel` around its center-of-mass and of the
+    /// angular momentum of its center-of-mass `(world_com - point) × linear_momentum`.
+    pub fn angular_momentum(&self, point: &Point<Real>) -> AngVector<Real> {
+        let local_mprops = &self.rb_mprops.local_mprops;
+        let world_com = local_mprops.world_com(&self.rb_pos.position);
+        let mut momentum = (world_com - point).gcross(self.linear_momentum());
+
+        #[cfg(feature = "dim2")]
+        if !local_mprops.inv_principal_inertia_sqrt.is_zero() {
+            let inertia_sqrt = 1.0 / local_mprops.inv_principal_inertia_sqrt;
+            momentum += inertia_sqrt * inertia_sqrt * self.rb_vels.angvel;
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            let inv_inertia_sqrt =
+                local_mprops.world_inv_inertia_sqrt(&self.rb_pos.position.rotation);
+            if !inv_inertia_sqrt.is_zero() {
+                let inertia = inv_inertia_sqrt.squared().inverse_unchecked();
+                momentum += inertia * self.rb_vels.angvel;
+            }
+        }
+
+        momentum
+    }
+
+    /// The potential energy of this body in the given gravity field, relative to the given height.
+    ///
+    /// The height of the center-of-mass of this body is measured along the opposite of its
+    /// effective gravity (taking its gravity scale and local gravity into account), so the
+    /// potential energy is zero when the center-of-mass is at the height `reference_height`.
+    pub fn potential_energy(&self, gravity: Vector<Real>, reference_height: Real) -> Real {
+        let gravity = self.rb_forces.effective_gravity(&gravity);
+        let world_com = self
+            .rb_mprops
+            .local_mprops
+            .world_com(&self.rb_pos.position)
+            .coords;
+
+        -self.mass() * (gravity.dot(&world_com) + gravity.norm() * reference_height)
+    }
+
     /// The potential energy of this body in a gravity field.
     pub fn gravitational_potential_energy(&self, dt: Real, gravity: Vector<Real>) -> Real {
         let world_com = self
@@ -710,7 +927,7 @@ impl RigidBody {
         // to sync up the potential energy with the kinetic energy:
         let world_com = world_com - self.rb_vels.linvel * (dt / 2.0);
 
-        -self.mass() * self.rb_forces.gravity_scale * gravity.dot(&world_com)
+        -self.mass() * self.rb_forces.effective_gravity(&gravity).dot(&world_com)
     }
 }
 
@@ -726,10 +943,22 @@ pub struct RigidBodyBuilder {
     pub angvel: AngVector<Real>,
     /// The scale factor applied to the gravity affecting the rigid-body to be built, `1.0` by default.
     pub gravity_scale: Real,
+    /// The gravity applied to the rigid-body to be built instead of the gravity of the simulation, `None` by default.
+    pub local_gravity: Option<Vector<Real>>,
     /// Damping factor for gradually slowing down the translational motion of the rigid-body, `0.0` by default.
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
     pub angular_damping: Real,
+    /// Damping factors for gradually slowing down the translational motion of the rigid-body
+    /// along each of its local axes, zero by default.
+    pub linear_damping_axes: Vector<Real>,
+    /// Damping factors for gradually slowing down the angular motion of the rigid-body
+    /// around each of its local axes, zero by default.
+    pub angular_damping_axes: AngVector<Real>,
+    /// The maximum magnitude of the linear velocity of the rigid-body, `Real::MAX` by default.
+    pub max_linear_velocity: Real,
+    /// The maximum magnitude of the angular velocity of the rigid-body, `Real::MAX` by default.
+    pub max_angular_velocity: Real,
     rb_type: RigidBodyType,
     mprops_flags: RigidBodyMassPropsFlags,
     /// The additional mass properties of the rigid-body being built. See [`RigidBodyBuilder::additional_mass_properties`] for more information.
@@ -738,10 +967,15 @@ pub struct RigidBodyBuilder {
     pub can_sleep: bool,
     /// Whether or not the rigid-body is to be created asleep.
     pub sleeping: bool,
+    /// The velocities below which the rigid-body to be created can fall asleep, if they
+    /// override the default pseudo-kinetic energy threshold.
+    pub sleep_threshold: Option<SleepThreshold>,
     /// Whether continuous collision-detection is enabled for the rigid-body to be built.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub ccd_enabled: bool,
+    /// The method used for preventing tunneling if CCD is enabled for the rigid-body to be built.
+    pub ccd_mode: CcdMode,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
     /// An arbitrary user-defined 128-bit integer associated to the rigid-bodies built by this builder.
@@ -756,14 +990,21 @@ impl RigidBodyBuilder {
             linvel: Vector::zeros(),
             angvel: na::zero(),
             gravity_scale: 1.0,
+            local_gravity: None,
             linear_damping: 0.0,
             angular_damping: 0.0,
+            linear_damping_axes: Vector::zeros(),
+            angular_damping_axes: na::zero(),
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
             rb_type,
             mprops_flags: RigidBodyMassPropsFlags::empty(),
             mass_properties: MassProperties::zero(),
             can_sleep: true,
             sleeping: false,
+            sleep_threshold: None,
             ccd_enabled: false,
+            ccd_mode: CcdMode::Toi,
             dominance_group: 0,
             user_data: 0,
         }
@@ -790,11 +1031,22 @@ impl RigidBodyBuilder {
     }
 
     /// Sets the scale applied to the gravity force affecting the rigid-body to be created.
+    ///
+    /// A scale of `0.0` disables gravity for this rigid-body, a negative scale reverses it.
+    /// A rigid-body without gravity can still fall asleep once it stops moving.
     pub fn gravity_scale(mut self, scale_factor: Real) -> Self {
         self.gravity_scale = scale_factor;
         self
     }
 
+    /// Sets the gravity applied to the rigid-body to be created instead of the gravity of the simulation.
+    ///
+    /// Unlike the gravity scale, this can change the direction of the gravity.
+    pub fn local_gravity(mut self, gravity: Vector<Real>) -> Self {
+        self.local_gravity = Some(gravity);
+        self
+    }
+
     /// Sets the dominance group of this rigid-body.
     pub fn dominance_group(mut self, group: i8) -> Self {
         self.dominance_group = group;
@@ -971,6 +1223,43 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the damping factors for the linear part of the rigid-body motion along each
+    /// of its local axes.
+    ///
+    /// This is useful for bodies with anisotropic drag (e.g. a wing or a boat hull). These
+    /// factors are applied in addition to the isotropic `linear_damping`.
+    pub fn linear_damping_axes(mut self, factors: Vector<Real>) -> Self {
+        self.linear_damping_axes = factors;
+        self
+    }
+
+    /// Sets the damping factors for the angular part of the rigid-body motion around each
+    /// of its local axes.
+    ///
+    /// These factors are applied in addition to the isotropic `angular_damping`.
+    pub fn angular_damping_axes(mut self, factors: AngVector<Real>) -> Self {
+        self.angular_damping_axes = factors;
+        self
+    }
+
+    /// Sets the maximum magnitude of the linear velocity of the rigid-body.
+    ///
+    /// Whenever the linear velocity resulting from the external forces exceeds this value,
+    /// it is scaled down before the constraints are solved.
+    pub fn max_linear_velocity(mut self, max_linvel: Real) -> Self {
+        self.max_linear_velocity = max_linvel;
+        self
+    }
+
+    /// Sets the maximum magnitude of the angular velocity of the rigid-body.
+    ///
+    /// Whenever the angular velocity resulting from the external forces exceeds this value,
+    /// it is scaled down before the constraints are solved.
+    pub fn max_angular_velocity(mut self, max_angvel: Real) -> Self {
+        self.max_angular_velocity = max_angvel;
+        self
+    }
+
     /// Sets the initial linear velocity of the rigid-body to be created.
     pub fn linvel(mut self, linvel: Vector<Real>) -> Self {
         self.linvel = linvel;
@@ -989,6 +1278,17 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the linear and angular velocity magnitudes below which the rigid-body to be created
+    /// can fall asleep.
+    ///
+    /// These override the default pseudo-kinetic energy threshold used for deciding whether
+    /// the rigid-body can sleep, e.g., for letting heavy bodies sleep quickly, or light ones
+    /// only once they are almost at rest.
+    pub fn sleep_threshold(mut self, linear: Real, angular: Real) -> Self {
+        self.sleep_threshold = Some(SleepThreshold::new(linear, angular));
+        self
+    }
+
     /// Sets whether or not continuous collision-detection is enabled for this rigid-body.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
@@ -997,6 +1297,12 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the method used for preventing tunneling if CCD is enabled for this rigid-body.
+    pub fn ccd_mode(mut self, mode: CcdMode) -> Self {
+        self.ccd_mode = mode;
+        self
+    }
+
     /// Sets whether or not the rigid-body is to be created asleep.
     pub fn sleeping(mut self, sleeping: bool) -> Self {
         self.sleeping = sleeping;
@@ -1008,6 +1314,7 @@ impl RigidBodyBuilder {
         let mut rb = RigidBody::new();
         rb.rb_pos.next_position = self.position; // FIXME: compute the correct value?
         rb.rb_pos.position = self.position;
+        rb.rb_pos.prev_position = self.position;
         rb.rb_vels.linvel = self.linvel;
         rb.rb_vels.angvel = self.angvel;
         rb.rb_type = self.rb_type;
@@ -1016,9 +1323,17 @@ impl RigidBodyBuilder {
         rb.rb_mprops.flags = self.mprops_flags;
         rb.rb_damping.linear_damping = self.linear_damping;
         rb.rb_damping.angular_damping = self.angular_damping;
+        rb.rb_damping.linear_damping_axes = self.linear_damping_axes;
+        rb.rb_damping.angular_damping_axes = self.angular_damping_axes;
+        rb.rb_damping.max_linear_velocity = self.max_linear_velocity;
+        rb.rb_damping.max_angular_velocity = self.max_angular_velocity;
         rb.rb_forces.gravity_scale = self.gravity_scale;
+        rb.rb_forces.local_gravity = self.local_gravity;
         rb.rb_dominance = RigidBodyDominance(self.dominance_group);
         rb.enable_ccd(self.ccd_enabled);
+        rb.set_ccd_mode(self.ccd_mode);
+
+        rb.rb_activation.sleep_threshold = self.sleep_threshold;
 
         if self.can_sleep && self.sleeping {
             rb.sleep();
@@ -1031,3 +1346,184 @@ impl RigidBodyBuilder {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyForces};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn local_gravity_replaces_world_gravity() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        let mut handles = Vec::new();
+        for (i, local_gravity) in [None, Some(Vector::x() * 2.0)].into_iter().enumerate() {
+            let mut body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * (i as Real) * 5.0)
+                .build();
+            body.set_local_gravity(local_gravity, true);
+            let body = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).build();
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+            handles.push(body);
+        }
+
+        world.step();
+
+        let dt = world.integration_parameters.dt;
+        let linvel = |i: usize| *world.bodies[handles[i]].linvel();
+        assert!((linvel(0) - Vector::y() * -9.81 * dt).norm() < 1.0e-5);
+        assert!((linvel(1) - Vector::x() * 2.0 * dt).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn velocity_at_point_of_spinning_disk() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // A disk of radius 2 spinning at 3 rad/s, with its center-of-mass shifted to (1, 1).
+        #[cfg(feature = "dim2")]
+        let angvel = 3.0;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::z() * 3.0;
+        let body = RigidBodyBuilder::new_dynamic()
+            .linvel(Vector::x() * 0.5)
+            .angvel(angvel)
+            .build();
+        let body = world.bodies.insert(body);
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::ball(2.0);
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cylinder(0.1, 2.0)
+            .rotation(Vector::x() * std::f32::consts::FRAC_PI_2 as Real);
+        let collider = collider.translation(Vector::x() + Vector::y()).build();
+        world
+            .colliders
+            .insert_with_parent(collider, body, &mut world.bodies);
+
+        // Move the body without stepping the simulation.
+        let rb = &mut world.bodies[body];
+        let mut position = *rb.position();
+        position.translation.vector = Vector::x() * 10.0;
+        rb.set_position(position, true);
+
+        // The rim point at the right of the center is moving up at 6 m/s,
+        // in addition to the linear velocity.
+        let center = Point::from(Vector::x() * 11.0 + Vector::y());
+        let rim = center + Vector::x() * 2.0;
+        let expected = Vector::x() * 0.5 + Vector::y() * 6.0;
+        assert!((rb.velocity_at_point(&rim) - expected).norm() < 1.0e-5);
+        assert!((rb.velocity_at_point(&center) - Vector::x() * 0.5).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn queued_impulses_are_applied_at_next_step() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let handle = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).density(1.0).build(),
+            handle,
+            &mut world.bodies,
+        );
+        let mass = world.bodies[handle].mass();
+
+        // The queue is bounded, and the impulses don't change the velocity right away.
+        let impulse = Vector::x() * 0.1;
+        for _ in 0..RigidBodyForces::MAX_DEFERRED_IMPULSES {
+            assert!(world.bodies[handle].apply_central_impulse_at_next_step(impulse));
+        }
+        assert!(!world.bodies[handle].apply_central_impulse_at_next_step(impulse));
+        assert_eq!(*world.bodies[handle].linvel(), Vector::zeros());
+
+        world.step();
+
+        let expected = impulse * RigidBodyForces::MAX_DEFERRED_IMPULSES as Real / mass;
+        assert!((world.bodies[handle].linvel() - expected).norm() < 1.0e-5);
+        assert!(world.bodies[handle].queued_impulses().is_empty());
+
+        // Queuing an impulse on a sleeping body wakes it up, so the impulse is applied too.
+        world.bodies[handle].set_linvel(Vector::zeros(), false);
+        world.bodies[handle].sleep();
+        world.step();
+        assert!(world.bodies[handle].is_sleeping());
+
+        assert!(world.bodies[handle].apply_central_impulse_at_next_step(impulse));
+        assert!(!world.bodies[handle].is_sleeping());
+        world.step();
+
+        assert!((world.bodies[handle].linvel() - impulse / mass).norm() < 1.0e-5);
+        assert!(world.bodies[handle].queued_impulses().is_empty());
+    }
+
+    #[test]
+    fn queued_central_impulses_follow_the_center_of_mass() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let handle = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).density(1.0).build(),
+            handle,
+            &mut world.bodies,
+        );
+        let mass = world.bodies[handle].mass();
+
+        // Moving the body after queuing the impulse doesn't make it rotate.
+        let impulse = Vector::x() * 0.1;
+        assert!(world.bodies[handle].apply_central_impulse_at_next_step(impulse));
+        world.bodies[handle].set_translation(Vector::y() * 10.0, true);
+        world.step();
+
+        assert!((world.bodies[handle].linvel() - impulse / mass).norm() < 1.0e-5);
+        #[cfg(feature = "dim2")]
+        assert_eq!(world.bodies[handle].angvel(), 0.0);
+        #[cfg(feature = "dim3")]
+        assert_eq!(*world.bodies[handle].angvel(), Vector::zeros());
+    }
+
+    #[test]
+    fn interpolated_and_extrapolated_positions() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let handle = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 1.0)
+                .linvel(Vector::x() * 6.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            handle,
+            &mut world.bodies,
+        );
+
+        world.step();
+
+        let motion = Vector::x() * 6.0 * world.integration_parameters.dt;
+        let start = Vector::x() * 1.0;
+        let body = &world.bodies[handle];
+        assert!((body.translation() - (start + motion)).norm() < 1.0e-5);
+
+        for (alpha, interpolated, extrapolated) in [
+            (0.0, start, start + motion),
+            (0.5, start + motion * 0.5, start + motion * 1.5),
+            (1.0, start + motion, start + motion * 2.0),
+        ] {
+            let pos = body.interpolated_position(alpha).translation.vector;
+            assert!((pos - interpolated).norm() < 1.0e-5);
+            let pos = body.extrapolated_position(alpha).translation.vector;
+            assert!((pos - extrapolated).norm() < 1.0e-5);
+        }
+
+        // Teleporting the body resets the motion used for the interpolation.
+        let mut teleported = *world.bodies[handle].position();
+        teleported.translation.vector = Vector::y() * 10.0;
+        world.bodies[handle].set_position(teleported, true);
+
+        let body = &world.bodies[handle];
+        for alpha in [0.0, 0.5, 1.0] {
+            assert_eq!(body.interpolated_position(alpha), teleported);
+            assert_eq!(body.extrapolated_position(alpha), teleported);
+        }
+    }
+}
@@ -136,6 +136,11 @@ pub struct RigidBodyPosition {
     /// resolution. Then it is either validated (ie. we set position := set_position)
     /// or clamped by CCD.
     pub next_position: Isometry<Real>,
+    /// The position of the rigid-body at the beginning of the last timestep.
+    ///
+    /// This is used for interpolating the rigid-body position between two timesteps, e.g.,
+    /// for rendering.
+    pub prev_position: Isometry<Real>,
 }
 
 impl Default for RigidBodyPosition {
@@ -143,6 +148,7 @@ impl Default for RigidBodyPosition {
         Self {
             position: Isometry::identity(),
             next_position: Isometry::identity(),
+            prev_position: Isometry::identity(),
         }
     }
 }
@@ -170,6 +176,27 @@ impl RigidBodyPosition {
         RigidBodyVelocity { linvel, angvel }
     }
 
+    /// Interpolates between `self.prev_position` (for `alpha = 0.0`) and `self.position`
+    /// (for `alpha = 1.0`).
+    #[must_use]
+    pub fn interpolate(&self, alpha: Real) -> Isometry<Real> {
+        self.prev_position.lerp_slerp(&self.position, alpha)
+    }
+
+    /// Extrapolates `self.position` by a fraction `alpha` of the motion from
+    /// `self.prev_position` to `self.position`, assuming constant velocities.
+    #[must_use]
+    pub fn extrapolate(&self, alpha: Real, local_com: &Point<Real>) -> Isometry<Real> {
+        let last_motion = RigidBodyPosition {
+            position: self.prev_position,
+            next_position: self.position,
+            prev_position: self.prev_position,
+        };
+        last_motion
+            .interpolate_velocity(1.0, local_com)
+            .integrate(alpha, &self.position, local_com)
+    }
+
     /// Compute new positions after integrating the given forces and velocities.
     ///
     /// This uses a symplectic Euler integration scheme.
@@ -195,6 +222,7 @@ where
         Self {
             position,
             next_position: position,
+            prev_position: position,
         }
     }
 }
@@ -338,6 +366,35 @@ impl RigidBodyMassProps {
             }
         }
     }
+
+    /// The gyroscopic torque `-ω × (I ω)` of a rigid-body with these mass properties rotating
+    /// with the world-space angular velocity `angvel`.
+    ///
+    /// This is zero if any of the rotations of the rigid-body is locked.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn gyroscopic_torque(
+        &self,
+        rotation: &Rotation<Real>,
+        angvel: &AngVector<Real>,
+    ) -> AngVector<Real> {
+        if self
+            .flags
+            .intersects(RigidBodyMassPropsFlags::ROTATION_LOCKED)
+        {
+            return AngVector::zeros();
+        }
+
+        // Compute the torque in the principal inertia frame, where the inertia is diagonal.
+        let frame = rotation * self.local_mprops.principal_inertia_local_frame;
+        let local_angvel = frame.inverse_transform_vector(angvel);
+        let principal_inertia = self
+            .local_mprops
+            .inv_principal_inertia_sqrt
+            .map(|e| crate::utils::inv(e * e));
+        let local_momentum = principal_inertia.component_mul(&local_angvel);
+        frame * -local_angvel.cross(&local_momentum)
+    }
 }
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -497,11 +554,77 @@ impl RigidBodyVelocity {
     }
 
     /// Returns the update velocities after applying the given damping.
+    ///
+    /// The per-axis damping factors are applied in the local-space of the rigid-body
+    /// with the given orientation.
     #[must_use]
-    pub fn apply_damping(&self, dt: Real, damping: &RigidBodyDamping) -> Self {
+    pub fn apply_damping(
+        &self,
+        dt: Real,
+        damping: &RigidBodyDamping,
+        rotation: &Rotation<Real>,
+    ) -> Self {
+        let mut linvel = self.linvel * (1.0 / (1.0 + dt * damping.linear_damping));
+        let mut angvel = self.angvel * (1.0 / (1.0 + dt * damping.angular_damping));
+
+        if !damping.linear_damping_axes.is_zero() {
+            let factors = damping.linear_damping_axes.map(|d| 1.0 / (1.0 + dt * d));
+            let local_linvel = rotation.inverse_transform_vector(&linvel);
+            linvel = rotation.transform_vector(&local_linvel.component_mul(&factors));
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            angvel *= 1.0 / (1.0 + dt * damping.angular_damping_axes);
+        }
+
+        #[cfg(feature = "dim3")]
+        if !damping.angular_damping_axes.is_zero() {
+            let factors = damping.angular_damping_axes.map(|d| 1.0 / (1.0 + dt * d));
+            let local_angvel = rotation.inverse_transform_vector(&angvel);
+            angvel = rotation.transform_vector(&local_angvel.component_mul(&factors));
+        }
+
+        RigidBodyVelocity { linvel, angvel }
+    }
+
+    /// Returns these velocities scaled down so that their magnitudes don't exceed
+    /// the velocity caps of the given damping.
+    #[must_use]
+    pub fn cap_magnitudes(&self, damping: &RigidBodyDamping) -> Self {
+        let mut result = *self;
+
+        let linvel_norm = self.linvel.norm();
+        if linvel_norm > damping.max_linear_velocity {
+            result.linvel *= damping.max_linear_velocity / linvel_norm;
+        }
+
+        #[cfg(feature = "dim2")]
+        let angvel_norm = self.angvel.abs();
+        #[cfg(feature = "dim3")]
+        let angvel_norm = self.angvel.norm();
+        if angvel_norm > damping.max_angular_velocity {
+            result.angvel *= damping.max_angular_velocity / angvel_norm;
+        }
+
+        result
+    }
+
+    /// Returns these velocities shifted so that they no longer exceed the velocity
+    /// caps of the given damping once the given external forces are integrated.
+    #[must_use]
+    pub(crate) fn cap_magnitudes_after_forces(
+        &self,
+        dt: Real,
+        forces: &RigidBodyForces,
+        mprops: &RigidBodyMassProps,
+        damping: &RigidBodyDamping,
+    ) -> Self {
+        let integrated = forces.integrate(dt, self, mprops);
+        let capped = integrated.cap_magnitudes(damping);
         RigidBodyVelocity {
-            linvel: self.linvel * (1.0 / (1.0 + dt * damping.linear_damping)),
-            angvel: self.angvel * (1.0 / (1.0 + dt * damping.angular_damping)),
+            linvel: self.linvel + capped.linvel - integrated.linvel,
+            angvel: self.angvel + capped.angvel - integrated.angvel,
         }
     }
 
@@ -641,6 +764,28 @@ pub struct RigidBodyDamping {
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body.
     pub angular_damping: Real,
+    /// Damping factors for gradually slowing down the translational motion of the rigid-body
+    /// along each of its local axes.
+    ///
+    /// These are applied in addition to the isotropic `linear_damping`.
+    pub linear_damping_axes: Vector<Real>,
+    /// Damping factors for gradually slowing down the angular motion of the rigid-body
+    /// around each of its local axes.
+    ///
+    /// These are applied in addition to the isotropic `angular_damping`.
+    pub angular_damping_axes: AngVector<Real>,
+    /// The maximum magnitude of the linear velocity of the rigid-body.
+    pub max_linear_velocity: Real,
+    /// The maximum magnitude of the angular velocity of the rigid-body.
+    pub max_angular_velocity: Real,
+}
+
+impl RigidBodyDamping {
+    /// Is at least one of the velocity caps of this rigid-body finite?
+    #[must_use]
+    pub fn has_velocity_caps(&self) -> bool {
+        self.max_linear_velocity != Real::MAX || self.max_angular_velocity != Real::MAX
+    }
 }
 
 impl Default for RigidBodyDamping {
@@ -648,6 +793,10 @@ impl Default for RigidBodyDamping {
         Self {
             linear_damping: 0.0,
             angular_damping: 0.0,
+            linear_damping_axes: Vector::zeros(),
+            angular_damping_axes: na::zero(),
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
         }
     }
 }
@@ -663,6 +812,15 @@ pub struct RigidBodyForces {
     /// Gravity is multiplied by this scaling factor before it's
     /// applied to this rigid-body.
     pub gravity_scale: Real,
+    /// If set, this gravity vector is applied to this rigid-body instead of
+    /// the gravity of the simulation.
+    pub local_gravity: Option<Vector<Real>>,
+    // NOTE: this is stored inline, instead of in an `ArrayVec`, so that `RigidBodyForces`
+    //       remains `Copy`. Only the first `num_deferred_impulses` elements are meaningful,
+    //       the other ones are always zero.
+    deferred_impulses:
+        [(Vector<Real>, Option<Point<Real>>); RigidBodyForces::MAX_DEFERRED_IMPULSES],
+    num_deferred_impulses: usize,
 }
 
 impl Default for RigidBodyForces {
@@ -671,11 +829,49 @@ impl Default for RigidBodyForces {
             force: na::zero(),
             torque: na::zero(),
             gravity_scale: 1.0,
+            local_gravity: None,
+            deferred_impulses: [(na::zero(), None); Self::MAX_DEFERRED_IMPULSES],
+            num_deferred_impulses: 0,
         }
     }
 }
 
 impl RigidBodyForces {
+    /// The maximum number of impulses that can be queued on a single rigid-body.
+    pub const MAX_DEFERRED_IMPULSES: usize = 16;
+
+    /// Queues an impulse to be applied at the given world-space point at the beginning of the
+    /// next simulation step.
+    ///
+    /// If `point` is `None`, the impulse is applied at the center-of-mass of the rigid-body at
+    /// the time it is applied.
+    ///
+    /// Returns `false`, and doesn't queue the impulse, if `Self::MAX_DEFERRED_IMPULSES`
+    /// impulses are already queued.
+    pub fn queue_impulse(&mut self, impulse: Vector<Real>, point: Option<Point<Real>>) -> bool {
+        if self.num_deferred_impulses < Self::MAX_DEFERRED_IMPULSES {
+            self.deferred_impulses[self.num_deferred_impulses] = (impulse, point);
+            self.num_deferred_impulses += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The impulses, and their world-space application points, queued to be applied at the
+    /// beginning of the next simulation step.
+    ///
+    /// The impulses without application point are applied at the center-of-mass.
+    pub fn deferred_impulses(&self) -> &[(Vector<Real>, Option<Point<Real>>)] {
+        &self.deferred_impulses[..self.num_deferred_impulses]
+    }
+
+    /// Removes all the impulses queued by `Self::queue_impulse`.
+    pub fn clear_deferred_impulses(&mut self) {
+        self.deferred_impulses = [(na::zero(), None); Self::MAX_DEFERRED_IMPULSES];
+        self.num_deferred_impulses = 0;
+    }
+
     /// Integrate these forces to compute new velocities.
     #[must_use]
     pub fn integrate(
@@ -694,10 +890,16 @@ impl RigidBodyForces {
         }
     }
 
+    /// The gravity applied to this rigid-body: its local gravity if set, or `gravity` otherwise,
+    /// multiplied by the gravity scale.
+    pub fn effective_gravity(&self, gravity: &Vector<Real>) -> Vector<Real> {
+        self.local_gravity.unwrap_or(*gravity) * self.gravity_scale
+    }
+
     /// Adds to `self` the gravitational force that would result in a gravitational acceleration
-    /// equal to `gravity`.
+    /// equal to `gravity`, or to the local gravity of this rigid-body if it is set.
     pub fn add_gravity_acceleration(&mut self, gravity: &Vector<Real>, mass: Real) {
-        self.force += gravity * self.gravity_scale * mass;
+        self.force += self.effective_gravity(gravity) * mass;
     }
 
     /// Applies a force at the given world-space point of the rigid-body with the given mass properties.
@@ -712,6 +914,22 @@ impl RigidBodyForces {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, Default)]
+/// The method used for preventing a rigid-body from tunneling.
+pub enum CcdMode {
+    /// The motion of the rigid-body is clamped to its first time-of-impact (TOI) with another
+    /// collider, and the end of the timestep is resimulated in CCD substeps.
+    #[default]
+    Toi,
+    /// The contacts of the rigid-body are predicted up to the maximum distance its colliders
+    /// can travel during a timestep, letting the constraints solver prevent tunneling.
+    ///
+    /// This is cheaper than `CcdMode::Toi`, but may miss impacts with fast rotating bodies, or
+    /// generate ghost collisions with the colliders close to the trajectory of the rigid-body.
+    Speculative,
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq)]
 /// Information used for Continuous-Collision-Detection.
@@ -730,6 +948,8 @@ pub struct RigidBodyCcd {
     pub ccd_active: bool,
     /// Is CCD enabled for this rigid-body?
     pub ccd_enabled: bool,
+    /// The method used for preventing tunneling if CCD is enabled.
+    pub ccd_mode: CcdMode,
 }
 
 impl Default for RigidBodyCcd {
@@ -739,6 +959,7 @@ impl Default for RigidBodyCcd {
             ccd_max_dist: 0.0,
             ccd_active: false,
             ccd_enabled: false,
+            ccd_mode: CcdMode::Toi,
         }
     }
 }
@@ -792,6 +1013,8 @@ pub struct RigidBodyIds {
     pub(crate) active_set_id: usize,
     pub(crate) active_set_offset: usize,
     pub(crate) active_set_timestamp: u32,
+    pub(crate) island_label: u64,
+    pub(crate) reported_sleeping: bool,
 }
 
 impl Default for RigidBodyIds {
@@ -801,6 +1024,8 @@ impl Default for RigidBodyIds {
             active_set_id: 0,
             active_set_offset: 0,
             active_set_timestamp: 0,
+            island_label: 0,
+            reported_sleeping: false,
         }
     }
 }
@@ -933,6 +1158,9 @@ impl RigidBodyDominance {
 pub struct RigidBodyActivation {
     /// The threshold pseudo-kinetic energy bellow which the body can fall asleep.
     pub threshold: Real,
+    /// The velocities below which this body can fall asleep, overriding the pseudo-kinetic
+    /// energy `threshold` if set.
+    pub sleep_threshold: Option<SleepThreshold>,
     /// The current pseudo-kinetic energy of the body.
     pub energy: Real,
     /// Is this body already sleeping?
@@ -955,6 +1183,7 @@ impl RigidBodyActivation {
     pub fn active() -> Self {
         RigidBodyActivation {
             threshold: Self::default_threshold(),
+            sleep_threshold: None,
             energy: Self::default_threshold() * 4.0,
             sleeping: false,
         }
@@ -964,6 +1193,7 @@ impl RigidBodyActivation {
     pub fn inactive() -> Self {
         RigidBodyActivation {
             threshold: Self::default_threshold(),
+            sleep_threshold: None,
             energy: 0.0,
             sleeping: true,
         }
@@ -977,6 +1207,22 @@ impl RigidBodyActivation {
         }
     }
 
+    /// The pseudo-kinetic energy of a body with the given velocities, compared to `threshold`
+    /// for deciding whether the body can fall asleep.
+    ///
+    /// If `sleep_threshold` is set, the velocities are scaled so that this energy reaches
+    /// `threshold` as soon as one of them reaches its sleep threshold.
+    #[inline]
+    pub fn pseudo_kinetic_energy(&self, vels: &RigidBodyVelocity) -> Real {
+        if let Some(sleep_threshold) = &self.sleep_threshold {
+            let linear = vels.linvel.norm_squared() / sleep_threshold.linear.powi(2);
+            let angular = vels.angvel.gdot(vels.angvel) / sleep_threshold.angular.powi(2);
+            linear.max(angular) * self.threshold.abs()
+        } else {
+            vels.pseudo_kinetic_energy()
+        }
+    }
+
     /// Returns `true` if the body is not asleep.
     #[inline]
     pub fn is_active(&self) -> bool {
@@ -999,3 +1245,141 @@ impl RigidBodyActivation {
         self.sleeping = true;
     }
 }
+
+/// The velocities below which a rigid-body can fall asleep.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct SleepThreshold {
+    /// The linear velocity magnitude below which the body can fall asleep. Should be `> 0`.
+    pub linear: Real,
+    /// The angular velocity magnitude below which the body can fall asleep. Should be `> 0`.
+    pub angular: Real,
+}
+
+impl SleepThreshold {
+    /// Creates new sleep thresholds from the given linear and angular velocity magnitudes.
+    pub fn new(linear: Real, angular: Real) -> Self {
+        Self { linear, angular }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RigidBodyDamping, RigidBodyVelocity};
+    use crate::math::{Real, Rotation, Vector};
+    use na::RealField;
+
+    #[test]
+    fn per_axis_damping_is_applied_in_local_space() {
+        // The local x axis of the rigid-body is aligned with the world y axis.
+        #[cfg(feature = "dim2")]
+        let rotation = Rotation::new(Real::frac_pi_2());
+        #[cfg(feature = "dim3")]
+        let rotation = Rotation::new(Vector::z() * Real::frac_pi_2());
+        let vels = RigidBodyVelocity {
+            linvel: Vector::repeat(1.0),
+            #[cfg(feature = "dim2")]
+            angvel: 1.0,
+            #[cfg(feature = "dim3")]
+            angvel: Vector::repeat(1.0),
+        };
+
+        // With `dt * damping = 1`, the damped velocities are halved.
+        let damping = RigidBodyDamping {
+            linear_damping_axes: Vector::x() * 10.0,
+            #[cfg(feature = "dim2")]
+            angular_damping_axes: 10.0,
+            #[cfg(feature = "dim3")]
+            angular_damping_axes: Vector::x() * 10.0,
+            ..RigidBodyDamping::default()
+        };
+        let damped = vels.apply_damping(0.1, &damping, &rotation);
+
+        let mut expected = Vector::repeat(1.0);
+        expected.y = 0.5;
+        assert!((damped.linvel - expected).norm() < 1.0e-6);
+        #[cfg(feature = "dim2")]
+        assert!((damped.angvel - 0.5).abs() < 1.0e-6);
+        #[cfg(feature = "dim3")]
+        assert!((damped.angvel - expected).norm() < 1.0e-6);
+
+        // The isotropic damping doesn't depend on the orientation.
+        let damping = RigidBodyDamping {
+            linear_damping: 10.0,
+            angular_damping: 10.0,
+            ..RigidBodyDamping::default()
+        };
+        let damped = vels.apply_damping(0.1, &damping, &rotation);
+        assert!((damped.linvel - vels.linvel * 0.5).norm() < 1.0e-6);
+        #[cfg(feature = "dim2")]
+        assert!((damped.angvel - 0.5).abs() < 1.0e-6);
+        #[cfg(feature = "dim3")]
+        assert!((damped.angvel - vels.angvel * 0.5).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn velocity_caps_scale_the_velocities_down() {
+        let vels = RigidBodyVelocity {
+            linvel: Vector::x() * 6.0 + Vector::y() * 8.0,
+            #[cfg(feature = "dim2")]
+            angvel: -4.0,
+            #[cfg(feature = "dim3")]
+            angvel: Vector::z() * -4.0,
+        };
+
+        // The velocities are uncapped by default.
+        assert_eq!(vels.cap_magnitudes(&RigidBodyDamping::default()), vels);
+
+        // The directions are preserved, so no energy is added along any axis.
+        let damping = RigidBodyDamping {
+            max_linear_velocity: 5.0,
+            max_angular_velocity: 2.0,
+            ..RigidBodyDamping::default()
+        };
+        let capped = vels.cap_magnitudes(&damping);
+        assert!((capped.linvel - vels.linvel * 0.5).norm() < 1.0e-6);
+        #[cfg(feature = "dim2")]
+        assert!((capped.angvel + 2.0).abs() < 1.0e-6);
+        #[cfg(feature = "dim3")]
+        assert!((capped.angvel + Vector::z() * 2.0).norm() < 1.0e-6);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn intermediate_axis_rotation_is_unstable() {
+        use crate::dynamics::RigidBodyBuilder;
+        use crate::geometry::ColliderBuilder;
+        use crate::pipeline::test_world::TestWorld;
+
+        // The principal moments of inertia of this cuboid are the largest along `x`
+        // and the smallest along `z`.
+        let spin = |axis: Vector<Real>| {
+            let mut world = TestWorld::new(Vector::zeros());
+            let perturbation = Vector::repeat(0.01);
+            let body = RigidBodyBuilder::new_dynamic()
+                .angvel(axis * 10.0 + perturbation)
+                .build();
+            let body = world.bodies.insert(body);
+            let collider = ColliderBuilder::cuboid(0.1, 0.5, 1.0).build();
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+
+            let mut min_alignment = Real::MAX;
+
+            for _ in 0..120 {
+                world.step();
+
+                let rb = &world.bodies[body];
+                let local_angvel = rb.position().rotation.inverse_transform_vector(rb.angvel());
+                min_alignment = min_alignment.min(local_angvel.normalize().dot(&axis).abs());
+            }
+
+            min_alignment
+        };
+
+        assert!(spin(Vector::x()) > 0.99);
+        assert!(spin(Vector::z()) > 0.99);
+        assert!(spin(Vector::y()) < 0.5);
+    }
+}
@@ -1,15 +1,43 @@
 use crate::data::{Arena, ComponentSet, ComponentSetMut, ComponentSetOption};
 use crate::dynamics::{
-    ImpulseJointSet, RigidBody, RigidBodyCcd, RigidBodyChanges, RigidBodyDamping, RigidBodyForces,
-    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyVelocity,
+    ImpulseJoint, ImpulseJointSet, JointHandle, RigidBody, RigidBodyCcd, RigidBodyChanges,
+    RigidBodyDamping, RigidBodyForces, RigidBodyIds, RigidBodyMassProps, RigidBodyPosition,
+    RigidBodyVelocity,
 };
 use crate::dynamics::{
     IslandManager, MultibodyJointSet, RigidBodyActivation, RigidBodyColliders, RigidBodyDominance,
     RigidBodyHandle, RigidBodyType,
 };
-use crate::geometry::ColliderSet;
+use crate::geometry::{ColliderSet, ContactPair, NarrowPhase};
+use crate::math::{AngVector, Point, Real, SpacialVector, Vector, SPATIAL_DIM};
+use parry::utils::hashmap::HashMap;
+use std::collections::HashSet;
 use std::ops::{Index, IndexMut};
 
+/// The precision at which the rigid-body states are rounded by `RigidBodySet::state_hash`.
+const STATE_HASH_PRECISION: Real = 1.0e-4;
+
+/// A FNV-1a hasher of rounded real numbers.
+struct StateHasher(u64);
+
+impl StateHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, val: Real) {
+        let rounded = (val / STATE_HASH_PRECISION).round() as i64;
+        self.write_bytes(&rounded.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// A pair of rigid body handles.
@@ -27,6 +55,180 @@ impl BodyPair {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct RigidBodySnapshot {
+    rb_pos: RigidBodyPosition,
+    rb_mprops: RigidBodyMassProps,
+    rb_vels: RigidBodyVelocity,
+    rb_forces: RigidBodyForces,
+    rb_activation: RigidBodyActivation,
+}
+
+impl RigidBodySnapshot {
+    fn differs_from(&self, other: &Self, threshold: Real) -> bool {
+        let pos1 = &self.rb_pos.position;
+        let pos2 = &other.rb_pos.position;
+        let vels1 = &self.rb_vels;
+        let vels2 = &other.rb_vels;
+
+        #[cfg(feature = "dim2")]
+        let angvel_change = (vels1.angvel - vels2.angvel).abs();
+        #[cfg(feature = "dim3")]
+        let angvel_change = (vels1.angvel - vels2.angvel).norm();
+
+        (pos1.translation.vector - pos2.translation.vector).norm() > threshold
+            || pos1.rotation.angle_to(&pos2.rotation) > threshold
+            || (vels1.linvel - vels2.linvel).norm() > threshold
+            || angvel_change > threshold
+            || self.rb_activation.sleeping != other.rb_activation.sleeping
+            || self.rb_mprops != other.rb_mprops
+            || self.rb_forces != other.rb_forces
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct JointImpulsesSnapshot {
+    impulses: SpacialVector<Real>,
+    limits: [Real; SPATIAL_DIM],
+    motors: [Real; SPATIAL_DIM],
+    spring: Option<Real>,
+    coupling: Option<Real>,
+}
+
+impl JointImpulsesSnapshot {
+    fn new(joint: &ImpulseJoint) -> Self {
+        JointImpulsesSnapshot {
+            impulses: joint.impulses,
+            limits: joint.data.limits.map(|limit| limit.impulse),
+            motors: joint.data.motors.map(|motor| motor.impulse),
+            spring: joint.data.spring.map(|spring| spring.impulse),
+            coupling: joint.data.coupling.map(|coupling| coupling.impulse),
+        }
+    }
+
+    fn apply(&self, joint: &mut ImpulseJoint) {
+        joint.impulses = self.impulses;
+
+        for (limit, impulse) in joint.data.limits.iter_mut().zip(self.limits) {
+            limit.impulse = impulse;
+        }
+        for (motor, impulse) in joint.data.motors.iter_mut().zip(self.motors) {
+            motor.impulse = impulse;
+        }
+        if let (Some(spring), Some(impulse)) = (&mut joint.data.spring, self.spring) {
+            spring.impulse = impulse;
+        }
+        if let (Some(coupling), Some(impulse)) = (&mut joint.data.coupling, self.coupling) {
+            coupling.impulse = impulse;
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// The state of all the rigid-bodies of a `RigidBodySet`, saved with `RigidBodySet::snapshot`.
+///
+/// This contains the positions, velocities, forces, and sleep states of the rigid-bodies, as
+/// well as the contacts used for warm-starting the constraints solver and the impulses
+/// accumulated by the impulse joints.
+pub struct PhysicsSnapshot {
+    bodies: Vec<(RigidBodyHandle, RigidBodySnapshot)>,
+    contact_pairs: Vec<ContactPair>,
+    joint_impulses: Vec<(JointHandle, JointImpulsesSnapshot)>,
+}
+
+impl PhysicsSnapshot {
+    /// The number of rigid-bodies saved in this snapshot.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Is this snapshot empty?
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// Computes the changes needed to turn this snapshot into `other`.
+    ///
+    /// The patch contains the rigid-bodies of `other` that don't exist on this snapshot, and the
+    /// ones with a position or velocity that moved by more than `threshold` (or with a different
+    /// sleep state, mass, or user forces). The rigid-bodies are identified by their handles, so
+    /// the patch can be applied to any snapshot with matching handles.
+    ///
+    /// The contacts and joint impulses are not part of the patch.
+    pub fn diff(&self, other: &PhysicsSnapshot, threshold: Real) -> PhysicsPatch {
+        let states: HashMap<_, _> = self.bodies.iter().map(|(h, s)| (*h, s)).collect();
+        let mut patch = PhysicsPatch::default();
+
+        for (handle, state) in &other.bodies {
+            match states.get(handle) {
+                Some(prev) if !prev.differs_from(state, threshold) => {}
+                _ => patch.bodies.push((*handle, *state)),
+            }
+        }
+
+        let other_handles: HashSet<_> = other.bodies.iter().map(|(h, _)| *h).collect();
+        patch.removed = self
+            .bodies
+            .iter()
+            .map(|(h, _)| *h)
+            .filter(|h| !other_handles.contains(h))
+            .collect();
+
+        patch
+    }
+
+    /// Applies a patch computed by `Self::diff`.
+    pub fn apply_patch(&mut self, patch: &PhysicsPatch) {
+        if !patch.removed.is_empty() {
+            let removed: HashSet<_> = patch.removed.iter().copied().collect();
+            self.bodies.retain(|(h, _)| !removed.contains(h));
+        }
+
+        let ids: HashMap<_, _> = self
+            .bodies
+            .iter()
+            .enumerate()
+            .map(|(i, (h, _))| (*h, i))
+            .collect();
+
+        for (handle, state) in &patch.bodies {
+            if let Some(i) = ids.get(handle) {
+                self.bodies[*i].1 = *state;
+            } else {
+                self.bodies.push((*handle, *state));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// The differences between two `PhysicsSnapshot`s, computed with `PhysicsSnapshot::diff`.
+pub struct PhysicsPatch {
+    bodies: Vec<(RigidBodyHandle, RigidBodySnapshot)>,
+    removed: Vec<RigidBodyHandle>,
+}
+
+impl PhysicsPatch {
+    /// The number of rigid-bodies inserted or modified by this patch.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Does this patch leave the snapshots unchanged?
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty() && self.removed.is_empty()
+    }
+
+    /// The rigid-bodies removed by this patch.
+    pub fn removed(&self) -> &[RigidBodyHandle] {
+        &self.removed
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Default)]
 /// A set of rigid bodies that can be handled by a physics pipeline.
@@ -234,6 +436,207 @@ impl RigidBodySet {
         Some(result)
     }
 
+    /// The sum of the linear momenta of all the awake dynamic rigid-bodies on this set.
+    pub fn total_linear_momentum(&self) -> Vector<Real> {
+        self.bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic() && !rb.is_sleeping())
+            .map(|(_, rb)| rb.linear_momentum())
+            .fold(Vector::zeros(), |acc, p| acc + p)
+    }
+
+    /// The sum of the angular momenta, about the given world-space point, of all the
+    /// awake dynamic rigid-bodies on this set.
+    pub fn total_angular_momentum(&self, point: &Point<Real>) -> AngVector<Real> {
+        self.bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic() && !rb.is_sleeping())
+            .map(|(_, rb)| rb.angular_momentum(point))
+            .fold(na::zero(), |acc, l| acc + l)
+    }
+
+    /// The sum of the kinetic energies of all the dynamic rigid-bodies on this set.
+    pub fn total_kinetic_energy(&self) -> Real {
+        self.bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic())
+            .map(|(_, rb)| rb.kinetic_energy())
+            .sum()
+    }
+
+    /// The sum of the potential energies, in the given gravity field and relative to the given
+    /// height, of all the dynamic rigid-bodies on this set.
+    ///
+    /// See [`RigidBody::potential_energy`] for details.
+    pub fn total_potential_energy(&self, gravity: Vector<Real>, reference_height: Real) -> Real {
+        self.bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic())
+            .map(|(_, rb)| rb.potential_energy(gravity, reference_height))
+            .sum()
+    }
+
+    /// Saves the state of all the rigid-bodies on this set, together with the contacts of
+    /// `narrow_phase` and the impulses of `impulse_joints`.
+    pub fn snapshot(
+        &self,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &ImpulseJointSet,
+    ) -> PhysicsSnapshot {
+        let bodies = self
+            .bodies
+            .iter()
+            .map(|(h, rb)| {
+                let state = RigidBodySnapshot {
+                    rb_pos: rb.rb_pos,
+                    rb_mprops: rb.rb_mprops,
+                    rb_vels: rb.rb_vels,
+                    rb_forces: rb.rb_forces,
+                    rb_activation: rb.rb_activation,
+                };
+                (RigidBodyHandle(h), state)
+            })
+            .collect();
+        let contact_pairs = narrow_phase
+            .contact_pairs()
+            .filter(|pair| !pair.manifolds.is_empty())
+            .cloned()
+            .collect();
+        let joint_impulses = impulse_joints
+            .iter()
+            .map(|(handle, joint)| (handle, JointImpulsesSnapshot::new(joint)))
+            .collect();
+
+        PhysicsSnapshot {
+            bodies,
+            contact_pairs,
+            joint_impulses,
+        }
+    }
+
+    /// Restores the state of the rigid-bodies, contacts, and joint impulses saved by
+    /// `Self::snapshot`.
+    ///
+    /// The rigid-bodies and joints removed since the snapshot was taken are ignored, and the
+    /// ones inserted since then are left unchanged. The contacts of the pairs of colliders that
+    /// still exist on `narrow_phase` are reset to their saved state, or removed if the pair had
+    /// no contact when the snapshot was taken. The colliders attached to the restored
+    /// rigid-bodies are moved accordingly. This results in identical simulation results on the
+    /// subsequent timesteps if `IntegrationParameters::deterministic_ordering` is enabled and
+    /// the same inputs are replayed.
+    pub fn restore(
+        &mut self,
+        snapshot: &PhysicsSnapshot,
+        colliders: &mut ColliderSet,
+        narrow_phase: &mut NarrowPhase,
+        impulse_joints: &mut ImpulseJointSet,
+    ) {
+        let saved_pairs: HashMap<_, _> = snapshot
+            .contact_pairs
+            .iter()
+            .map(|pair| ((pair.collider1, pair.collider2), pair))
+            .collect();
+
+        for pair in narrow_phase.contact_pairs_mut() {
+            if let Some(saved) = saved_pairs.get(&(pair.collider1, pair.collider2)) {
+                *pair = (*saved).clone();
+            } else {
+                pair.manifolds.clear();
+                pair.has_any_active_contact = false;
+                pair.contact_age_frames = 0;
+            }
+        }
+
+        for (handle, impulses) in &snapshot.joint_impulses {
+            if let Some(joint) = impulse_joints.get_mut(*handle) {
+                impulses.apply(joint);
+            }
+        }
+
+        for (handle, state) in &snapshot.bodies {
+            if let Some(rb) = self.bodies.get_mut(handle.0) {
+                let mut changes = RigidBodyChanges::empty();
+
+                if rb.rb_pos != state.rb_pos {
+                    if state.rb_activation.sleeping {
+                        changes |= RigidBodyChanges::POSITION;
+                    } else {
+                        // Move the colliders without flagging them: the restored contacts
+                        // must not be recomputed at the beginning of the next timestep. The
+                        // broad-phase is updated once the rigid-body moves again.
+                        for co_handle in &rb.rb_colliders.0 {
+                            if let Some(co) = colliders.get_mut_internal(*co_handle) {
+                                if let Some(co_parent) = &co.co_parent {
+                                    co.co_pos.0 = state.rb_pos.position * co_parent.pos_wrt_parent;
+                                }
+                            }
+                        }
+                    }
+                }
+                if rb.rb_activation != state.rb_activation {
+                    changes |= RigidBodyChanges::SLEEP;
+                }
+
+                // Leave the rigid-bodies already in the saved state untouched so they
+                // don't trigger unnecessary updates.
+                if changes.is_empty()
+                    && rb.rb_mprops == state.rb_mprops
+                    && rb.rb_vels == state.rb_vels
+                    && rb.rb_forces == state.rb_forces
+                {
+                    continue;
+                }
+
+                Self::mark_as_modified(*handle, rb, &mut self.modified_bodies);
+                rb.changes.insert(changes);
+                rb.rb_pos = state.rb_pos;
+                rb.rb_mprops = state.rb_mprops;
+                rb.rb_vels = state.rb_vels;
+                rb.rb_forces = state.rb_forces;
+                rb.rb_activation = state.rb_activation;
+            }
+        }
+    }
+
+    /// A hash of the positions and velocities of all the rigid-bodies on this set.
+    ///
+    /// This can be compared between the peers of a networked simulation in order to detect
+    /// desynchronizations cheaply. The states are rounded to a precision of `1.0e-4` before
+    /// being hashed, together with the handles of the rigid-bodies, in handle order. So two
+    /// sets only have the same hash if the same handles are associated to the same states.
+    /// The velocities of sleeping rigid-bodies are ignored.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+
+        for (handle, rb) in self.bodies.iter() {
+            let (index, generation) = handle.into_raw_parts();
+            hasher.write_bytes(&index.to_le_bytes());
+            hasher.write_bytes(&generation.to_le_bytes());
+
+            let pos = &rb.rb_pos.position;
+
+            pos.translation.vector.iter().for_each(|x| hasher.write(*x));
+            #[cfg(feature = "dim2")]
+            {
+                hasher.write(pos.rotation.re);
+                hasher.write(pos.rotation.im);
+            }
+            #[cfg(feature = "dim3")]
+            pos.rotation.coords.iter().for_each(|x| hasher.write(*x));
+
+            if !rb.rb_activation.sleeping {
+                let vels = &rb.rb_vels;
+                vels.linvel.iter().for_each(|x| hasher.write(*x));
+                #[cfg(feature = "dim2")]
+                hasher.write(vels.angvel);
+                #[cfg(feature = "dim3")]
+                vels.angvel.iter().for_each(|x| hasher.write(*x));
+            }
+        }
+
+        hasher.0
+    }
+
     /// Iterates through all the rigid-bodies on this set.
     pub fn iter(&self) -> impl Iterator<Item = (RigidBodyHandle, &RigidBody)> {
         self.bodies.iter().map(|(h, b)| (RigidBodyHandle(h), b))
@@ -275,3 +678,244 @@ impl IndexMut<RigidBodyHandle> for RigidBodySet {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::RigidBodySet;
+    use crate::dynamics::{ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn state_hash_depends_on_the_handles() {
+        let mut bodies = RigidBodySet::new();
+        let a = bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        let b = bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x())
+                .build(),
+        );
+        let hash = bodies.state_hash();
+        assert_eq!(bodies.clone().state_hash(), hash);
+
+        // Swapping the states of two bodies changes the hash.
+        bodies[a].set_translation(Vector::x(), true);
+        bodies[b].set_translation(Vector::zeros(), true);
+        assert_ne!(bodies.state_hash(), hash);
+
+        // Re-inserting a body with the same state gives it a different handle generation.
+        bodies[a].set_translation(Vector::zeros(), true);
+        bodies[b].set_translation(Vector::x(), true);
+        assert_eq!(bodies.state_hash(), hash);
+        let removed = bodies
+            .remove(
+                b,
+                &mut IslandManager::new(),
+                &mut ColliderSet::new(),
+                &mut ImpulseJointSet::new(),
+                &mut MultibodyJointSet::new(),
+            )
+            .unwrap();
+        bodies.insert(removed);
+        assert_ne!(bodies.state_hash(), hash);
+    }
+
+    #[test]
+    fn restored_snapshots_replay_identically() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world.integration_parameters.deterministic_ordering = true;
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // A stack of boxes, with resting contacts relying on warm-starting.
+        let mut handles = vec![];
+        for i in 0..4 {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * (1.0 + i as Real * 1.0) + Vector::x() * 0.1 * i as Real)
+                .build();
+            let handle = world.bodies.insert(body);
+            #[cfg(feature = "dim2")]
+            let shape = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            world
+                .colliders
+                .insert_with_parent(shape.build(), handle, &mut world.bodies);
+            handles.push(handle);
+        }
+
+        // A pendulum, with joint impulses that must be restored too.
+        // The parallel solver doesn't support impulse joints yet.
+        #[cfg(not(feature = "parallel"))]
+        {
+            use crate::dynamics::RevoluteJoint;
+            use crate::math::Point;
+
+            let anchor = world.bodies.insert(
+                RigidBodyBuilder::new_static()
+                    .translation(Vector::x() * 5.0 + Vector::y() * 5.0)
+                    .build(),
+            );
+            let bob = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::x() * 7.0 + Vector::y() * 5.0)
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.2).build(),
+                bob,
+                &mut world.bodies,
+            );
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJoint::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJoint::new(Vector::z_axis());
+            let joint = joint
+                .local_anchor1(Point::origin())
+                .local_anchor2(Point::from(-Vector::x() * 2.0));
+            world.impulse_joints.insert(anchor, bob, joint);
+            handles.push(bob);
+        }
+
+        for _ in 0..30 {
+            world.step();
+        }
+
+        let snapshot = world
+            .bodies
+            .snapshot(&world.narrow_phase, &world.impulse_joints);
+        let positions = |world: &TestWorld| -> Vec<Isometry<Real>> {
+            handles
+                .iter()
+                .map(|h| *world.bodies[*h].position())
+                .collect()
+        };
+        let joint_impulses = |world: &TestWorld| -> Vec<_> {
+            world
+                .impulse_joints
+                .iter()
+                .map(|(_, joint)| joint.accumulated_impulse())
+                .collect()
+        };
+        let saved_joint_impulses = joint_impulses(&world);
+
+        let mut expected = vec![];
+        for _ in 0..30 {
+            world.step();
+            expected.push(positions(&world));
+        }
+
+        world.bodies.restore(
+            &snapshot,
+            &mut world.colliders,
+            &mut world.narrow_phase,
+            &mut world.impulse_joints,
+        );
+        assert_eq!(joint_impulses(&world), saved_joint_impulses);
+
+        for expected in expected {
+            world.step();
+            assert_eq!(positions(&world), expected);
+        }
+    }
+
+    #[test]
+    fn momentum_is_conserved_in_closed_system() {
+        use crate::math::Point;
+
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // A glancing impact between two balls, so the friction makes them spin.
+        for (pos, vel) in [(-2.0, 4.0), (0.0, 0.0)] {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * pos + Vector::y() * (pos * 0.25))
+                .linvel(Vector::x() * vel)
+                .build();
+            let body = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).friction(1.0).build();
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+        }
+
+        let about = Point::from(Vector::y() * 3.0);
+        let linear_momentum = world.bodies.total_linear_momentum();
+        let angular_momentum = world.bodies.total_angular_momentum(&about);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        // Both balls moved and rotated after the impact.
+        let bodies = &world.bodies;
+        assert!(bodies.iter().all(|(_, rb)| rb.linvel().norm() > 0.1));
+        #[cfg(feature = "dim2")]
+        let norm = |angvel: Real| angvel.abs();
+        #[cfg(feature = "dim3")]
+        let norm = |angvel: Vector<Real>| angvel.norm();
+        assert!(bodies.iter().any(|(_, rb)| norm(rb.rb_vels.angvel) > 0.1));
+        assert!((bodies.total_linear_momentum() - linear_momentum).norm() < 1.0e-3);
+        assert!(norm(bodies.total_angular_momentum(&about) - angular_momentum) < 1.0e-3);
+    }
+
+    // The parallel solver doesn't support impulse joints yet.
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn pendulum_energy_is_conserved() {
+        use crate::dynamics::RevoluteJoint;
+        use crate::math::Point;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let anchor = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let bob = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 2.0)
+            .build();
+        let bob = world.bodies.insert(bob);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.2).build(),
+            bob,
+            &mut world.bodies,
+        );
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJoint::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJoint::new(Vector::z_axis());
+        let joint = joint.local_anchor2(Point::from(Vector::x() * -2.0));
+        world.impulse_joints.insert(anchor, bob, joint);
+
+        let gravity = world.gravity;
+        let energy = |bodies: &RigidBodySet| {
+            bodies.total_kinetic_energy() + bodies.total_potential_energy(gravity, 0.0)
+        };
+        let initial_energy = energy(&world.bodies);
+        let mut max_kinetic_energy: Real = 0.0;
+        let mass = world.bodies[bob].mass();
+        let swing_energy = mass * 9.81 * 2.0;
+
+        for _ in 0..200 {
+            world.step();
+
+            let energy = energy(&world.bodies);
+            max_kinetic_energy = max_kinetic_energy.max(world.bodies.total_kinetic_energy());
+
+            // The joint stabilization dissipates a bit of energy, but the solver must never add any.
+            assert!(energy < initial_energy + 1.0e-3 * swing_energy);
+            assert!(energy > initial_energy - 0.2 * swing_energy);
+        }
+
+        // The bob swung down to the bottom of the pendulum, 2 meters lower.
+        assert!(max_kinetic_energy > 0.9 * swing_energy);
+        let height = world.bodies[bob].translation().y + 2.0;
+        let potential_energy = world.bodies[bob].potential_energy(gravity, -2.0);
+        assert!((potential_energy - mass * 9.81 * height).abs() < 1.0e-5 * swing_energy);
+    }
+}
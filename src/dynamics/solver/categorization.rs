@@ -18,12 +18,12 @@ pub(crate) fn categorize_contacts(
         if manifold
             .data
             .rigid_body1
-            .map(|rb| multibody_joints.rigid_body_link(rb))
+            .and_then(|rb| multibody_joints.rigid_body_link(rb))
             .is_some()
             || manifold
                 .data
-                .rigid_body1
-                .map(|rb| multibody_joints.rigid_body_link(rb))
+                .rigid_body2
+                .and_then(|rb| multibody_joints.rigid_body_link(rb))
                 .is_some()
         {
             out_generic.push(*manifold_i);
@@ -65,3 +65,60 @@ pub(crate) fn categorize_joints(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::categorize_contacts;
+    use crate::dynamics::{MultibodyJointSet, RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ContactManifold, ContactManifoldData, SolverFlags};
+
+    #[test]
+    fn only_multibody_contacts_are_generic() {
+        let mut bodies = RigidBodySet::new();
+        #[cfg_attr(feature = "dim2", allow(unused_mut))]
+        let mut multibody_joints = MultibodyJointSet::new();
+        let rb1 = bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        let rb2 = bodies.insert(RigidBodyBuilder::new_dynamic().build());
+
+        let manifold = |rb1, rb2| {
+            let data = ContactManifoldData::new(Some(rb1), Some(rb2), SolverFlags::empty());
+            ContactManifold::with_data(0, 0, data)
+        };
+        #[cfg_attr(feature = "dim2", allow(unused_mut))]
+        let mut manifolds = vec![manifold(rb1, rb2)];
+
+        // NOTE: multibodies with a free root aren't supported in 2D yet.
+        #[cfg(feature = "dim3")]
+        {
+            use crate::dynamics::RevoluteJoint;
+            use crate::math::Vector;
+
+            let root = bodies.insert(RigidBodyBuilder::new_dynamic().build());
+            let link = bodies.insert(RigidBodyBuilder::new_dynamic().build());
+            multibody_joints.insert(root, link, RevoluteJoint::new(Vector::z_axis()));
+            manifolds.push(manifold(rb1, link));
+            manifolds.push(manifold(link, rb2));
+        }
+
+        let manifold_indices: Vec<_> = (0..manifolds.len()).collect();
+        let manifolds: Vec<_> = manifolds.iter_mut().collect();
+        let (mut ground, mut not_ground, mut generic) = (vec![], vec![], vec![]);
+        categorize_contacts(
+            &bodies,
+            &multibody_joints,
+            &manifolds,
+            &manifold_indices,
+            &mut ground,
+            &mut not_ground,
+            &mut generic,
+            &mut vec![],
+        );
+
+        // A contact between two rigid-bodies not attached to any multibody isn't generic.
+        // A contact is generic if any of its bodies, the first or the second, is a multibody link.
+        assert!(ground.is_empty());
+        assert_eq!(not_ground, vec![0]);
+        #[cfg(feature = "dim3")]
+        assert_eq!(generic, vec![1, 2]);
+    }
+}
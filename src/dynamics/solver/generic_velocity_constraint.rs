@@ -5,6 +5,8 @@ use crate::dynamics::{
     RigidBodyVelocity,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+#[cfg(feature = "dim3")]
+use crate::math::Vector;
 use crate::math::{Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{WAngularInertia, WCross, WDot};
 
@@ -43,6 +45,9 @@ impl GenericVelocityConstraint {
     {
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.erp_inv_dt();
+        // Constraint force mixing making the normal constraints spring-like: the penetration
+        // they allow grows with the impulse they applied at the previous timestep.
+        let cfm = manifold.data.compliance * inv_dt * inv_dt;
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -84,8 +89,15 @@ impl GenericVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(
+        let (friction_dir, friction_scale) = manifold
+            .data
+            .anisotropic_friction
+            .unwrap_or((Vector::zeros(), [1.0; 2]));
+        #[cfg(feature = "dim3")]
+        let (tangents1, friction_scale) = super::compute_anisotropic_tangent_contact_directions(
             &force_dir1,
+            &friction_dir,
+            friction_scale,
             &rb_vels1.linvel,
             &rb_vels2.linvel,
         );
@@ -112,6 +124,8 @@ impl GenericVelocityConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_scale,
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im1: if rb_type1.is_dynamic() {
                     rb_mprops1.effective_inv_mass
@@ -129,6 +143,8 @@ impl GenericVelocityConstraint {
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
                 num_contacts: manifold_points.len() as u8,
+                // NOTE: rolling and spinning friction are not supported by multibodies yet.
+                rolling_part: None,
             };
 
             for k in 0..manifold_points.len() {
@@ -198,7 +214,7 @@ impl GenericVelocityConstraint {
                         0.0
                     };
 
-                    let r = crate::utils::inv(inv_r1 + inv_r2);
+                    let r = crate::utils::inv(inv_r1 + inv_r2 + cfm);
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
                     let is_resting = 1.0 - is_bouncy;
@@ -207,15 +223,16 @@ impl GenericVelocityConstraint {
                         * (vel1 - vel2).dot(&force_dir1);
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting * params.velocity_solve_fraction;
-                    let rhs_bias =
-                        /* is_resting * */ erp_inv_dt * manifold_point.dist.min(0.0);
+                    let rhs_bias = /* is_resting * */
+                        erp_inv_dt * manifold_point.dist.min(0.0)
+                            + cfm * manifold_point.warmstart_impulse;
 
                     constraint.elements[k].normal_part = VelocityConstraintNormalPart {
                         gcross1,
                         gcross2,
                         rhs: rhs_wo_bias + rhs_bias,
                         rhs_wo_bias,
-                        impulse: na::zero(),
+                        impulse: manifold_point.warmstart_impulse * params.warmstart_coefficient,
                         r,
                     };
                 }
@@ -345,6 +362,8 @@ impl GenericVelocityConstraint {
             &self.velocity_constraint.dir1,
             #[cfg(feature = "dim3")]
             &self.velocity_constraint.tangent1,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_scale,
             self.velocity_constraint.im1,
             self.velocity_constraint.im2,
             self.velocity_constraint.limit,
@@ -367,6 +386,47 @@ impl GenericVelocityConstraint {
         }
     }
 
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
+        let mut mj_lambda1 = if self.generic_constraint_mask & 0b01 == 0 {
+            GenericRhs::DeltaVel(mj_lambdas[self.velocity_constraint.mj_lambda1])
+        } else {
+            GenericRhs::GenericId(self.velocity_constraint.mj_lambda1)
+        };
+
+        let mut mj_lambda2 = if self.generic_constraint_mask & 0b10 == 0 {
+            GenericRhs::DeltaVel(mj_lambdas[self.velocity_constraint.mj_lambda2])
+        } else {
+            GenericRhs::GenericId(self.velocity_constraint.mj_lambda2)
+        };
+
+        VelocityConstraintElement::generic_warmstart_group(
+            &self.velocity_constraint.elements[..self.velocity_constraint.num_contacts as usize],
+            jacobians,
+            &self.velocity_constraint.dir1,
+            self.velocity_constraint.im1,
+            self.velocity_constraint.im2,
+            self.ndofs1,
+            self.ndofs2,
+            self.j_id,
+            &mut mj_lambda1,
+            &mut mj_lambda2,
+            generic_mj_lambdas,
+        );
+
+        if let GenericRhs::DeltaVel(mj_lambda1) = mj_lambda1 {
+            mj_lambdas[self.velocity_constraint.mj_lambda1] = mj_lambda1;
+        }
+
+        if let GenericRhs::DeltaVel(mj_lambda2) = mj_lambda2 {
+            mj_lambdas[self.velocity_constraint.mj_lambda2] = mj_lambda2;
+        }
+    }
+
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         self.velocity_constraint.writeback_impulses(manifolds_all);
     }
@@ -99,6 +99,7 @@ impl VelocityConstraintTangentPart<Real> {
         ndofs1: usize,
         ndofs2: usize,
         limit: Real,
+        #[cfg(feature = "dim3")] friction_scale: [Real; 2],
         mj_lambda1: &mut GenericRhs,
         mj_lambda2: &mut GenericRhs,
         mj_lambdas: &mut DVector<Real>,
@@ -189,7 +190,16 @@ impl VelocityConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dimpulse_0,
                 self.impulse[1] - self.r[1] * dimpulse_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let limits = [limit * friction_scale[0], limit * friction_scale[1]];
+            let new_impulse = if friction_scale[0] != friction_scale[1] {
+                // Anisotropic friction is clamped independently along each principal direction.
+                na::Vector2::new(
+                    new_impulse[0].clamp(-limits[0], limits[0]),
+                    new_impulse[1].clamp(-limits[1], limits[1]),
+                )
+            } else {
+                new_impulse.cap_magnitude(limits[1])
+            };
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -290,12 +300,56 @@ impl VelocityConstraintNormalPart<Real> {
 }
 
 impl VelocityConstraintElement<Real> {
+    #[inline]
+    pub fn generic_warmstart_group(
+        elements: &[Self],
+        jacobians: &DVector<Real>,
+        dir1: &Vector<Real>,
+        im1: Real,
+        im2: Real,
+        ndofs1: usize,
+        ndofs2: usize,
+        j_id: usize,
+        mj_lambda1: &mut GenericRhs,
+        mj_lambda2: &mut GenericRhs,
+        mj_lambdas: &mut DVector<Real>,
+    ) {
+        let j_step = j_step(ndofs1, ndofs2) * DIM;
+        let mut nrm_j_id = normal_j_id(j_id, ndofs1, ndofs2);
+
+        for element in elements {
+            let part = &element.normal_part;
+            mj_lambda1.apply_impulse(
+                j_id1(nrm_j_id, ndofs1, ndofs2),
+                ndofs1,
+                part.impulse,
+                jacobians,
+                dir1,
+                &part.gcross1,
+                mj_lambdas,
+                im1,
+            );
+            mj_lambda2.apply_impulse(
+                j_id2(nrm_j_id, ndofs1, ndofs2),
+                ndofs2,
+                part.impulse,
+                jacobians,
+                &-dir1,
+                &part.gcross2,
+                mj_lambdas,
+                im2,
+            );
+            nrm_j_id += j_step;
+        }
+    }
+
     #[inline]
     pub fn generic_solve_group(
         elements: &mut [Self],
         jacobians: &DVector<Real>,
         dir1: &Vector<Real>,
         #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
+        #[cfg(feature = "dim3")] friction_scale: [Real; 2],
         im1: Real,
         im2: Real,
         limit: Real,
@@ -338,8 +392,19 @@ impl VelocityConstraintElement<Real> {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
                 part.generic_solve(
-                    tng_j_id, jacobians, tangents1, im1, im2, ndofs1, ndofs2, limit, mj_lambda1,
-                    mj_lambda2, mj_lambdas,
+                    tng_j_id,
+                    jacobians,
+                    tangents1,
+                    im1,
+                    im2,
+                    ndofs1,
+                    ndofs2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_scale,
+                    mj_lambda1,
+                    mj_lambda2,
+                    mj_lambdas,
                 );
                 tng_j_id += j_step;
             }
@@ -1,12 +1,13 @@
-use super::VelocitySolver;
+use super::{PositionSolver, VelocitySolver};
 use crate::counters::Counters;
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
 use crate::dynamics::solver::{
-    AnyJointVelocityConstraint, AnyVelocityConstraint, GenericVelocityConstraint, SolverConstraints,
+    AnyJointVelocityConstraint, AnyVelocityConstraint, GenericVelocityConstraint,
+    SolverConstraints, SolverResidual,
 };
 use crate::dynamics::{
     IntegrationParameters, JointGraphEdge, JointIndex, RigidBodyDamping, RigidBodyForces,
-    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType,
+    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType, SolverMode,
 };
 use crate::dynamics::{IslandManager, RigidBodyVelocity};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
@@ -16,6 +17,9 @@ pub struct IslandSolver {
     contact_constraints: SolverConstraints<AnyVelocityConstraint, GenericVelocityConstraint>,
     joint_constraints: SolverConstraints<AnyJointVelocityConstraint, ()>,
     velocity_solver: VelocitySolver,
+    position_solver: PositionSolver,
+    residual: SolverResidual,
+    solve_time_ns: u64,
 }
 
 impl Default for IslandSolver {
@@ -30,9 +34,22 @@ impl IslandSolver {
             contact_constraints: SolverConstraints::new(),
             joint_constraints: SolverConstraints::new(),
             velocity_solver: VelocitySolver::new(),
+            position_solver: PositionSolver::new(),
+            residual: SolverResidual::default(),
+            solve_time_ns: 0,
         }
     }
 
+    /// The convergence information measured during the last call to `init_and_solve`.
+    pub fn residual(&self) -> &SolverResidual {
+        &self.residual
+    }
+
+    /// The time, in nanoseconds, spent during the last call to `init_and_solve`.
+    pub fn solve_time_ns(&self) -> u64 {
+        self.solve_time_ns
+    }
+
     pub fn init_and_solve<Bodies>(
         &mut self,
         island_id: usize,
@@ -55,6 +72,9 @@ impl IslandSolver {
             + ComponentSet<RigidBodyIds>
             + ComponentSet<RigidBodyType>,
     {
+        let start_time = instant::Instant::now();
+        self.residual = SolverResidual::default();
+
         let mut has_constraints = manifold_indices.len() != 0 || joint_indices.len() != 0;
         if !has_constraints {
             // Check if the multibody_joints have internal constraints.
@@ -72,7 +92,41 @@ impl IslandSolver {
             }
         }
 
-        if has_constraints {
+        let position_iterations = match params.solver_mode {
+            SolverMode::PositionBased { iterations } => islands
+                .active_island(island_id)
+                .iter()
+                .all(|handle| multibody_joints.rigid_body_link(*handle).is_none())
+                .then_some(iterations),
+            SolverMode::VelocityBased => None,
+        };
+
+        if let (true, Some(iterations)) = (has_constraints, position_iterations) {
+            self.contact_constraints.clear();
+            self.joint_constraints.clear();
+
+            self.residual.max_position_error = manifold_indices
+                .iter()
+                .flat_map(|i| manifolds[*i].data.solver_contacts.iter())
+                .fold(0.0, |max_error, contact| {
+                    max_error.max(-contact.dist - params.allowed_linear_error)
+                });
+            self.residual.iterations_run = iterations;
+
+            counters.solver.velocity_resolution_time.resume();
+            self.position_solver.solve(
+                island_id,
+                iterations,
+                params,
+                islands,
+                bodies,
+                manifolds,
+                manifold_indices,
+                impulse_joints,
+                joint_indices,
+            );
+            counters.solver.velocity_resolution_time.pause();
+        } else if has_constraints {
             // Init the solver id for multibody_joints.
             // We need that for building the constraints.
             let mut solver_id = 0;
@@ -81,6 +135,25 @@ impl IslandSolver {
                 solver_id += multibody.ndofs();
             }
 
+            // Shift the velocities of capped bodies so that the force integration
+            // performed by the solver doesn't exceed the caps.
+            for handle in islands.active_island(island_id) {
+                if multibody_joints.rigid_body_link(*handle).is_none() {
+                    let (vels, forces, damping, mprops): (
+                        &RigidBodyVelocity,
+                        &RigidBodyForces,
+                        &RigidBodyDamping,
+                        &RigidBodyMassProps,
+                    ) = bodies.index_bundle(handle.0);
+
+                    if damping.has_velocity_caps() {
+                        let new_vels =
+                            vels.cap_magnitudes_after_forces(params.dt, forces, mprops, damping);
+                        bodies.set_internal(handle.0, new_vels);
+                    }
+                }
+            }
+
             counters.solver.velocity_assembly_time.resume();
             self.contact_constraints.init(
                 island_id,
@@ -102,8 +175,16 @@ impl IslandSolver {
             );
             counters.solver.velocity_assembly_time.pause();
 
+            self.residual.max_position_error = manifold_indices
+                .iter()
+                .flat_map(|i| manifolds[*i].data.solver_contacts.iter())
+                .fold(0.0, |max_error, contact| {
+                    max_error.max(-contact.dist - params.allowed_linear_error)
+                });
+            self.residual.iterations_run = params.max_velocity_iterations as u32;
+
             counters.solver.velocity_resolution_time.resume();
-            self.velocity_solver.solve(
+            self.residual.max_velocity_error = self.velocity_solver.solve(
                 island_id,
                 params,
                 islands,
@@ -148,7 +229,8 @@ impl IslandSolver {
                     let mut new_poss = *poss;
                     let new_vels = forces
                         .integrate(params.dt, vels, mprops)
-                        .apply_damping(params.dt, &damping);
+                        .cap_magnitudes(damping)
+                        .apply_damping(params.dt, damping, &poss.position.rotation);
                     new_poss.next_position =
                         vels.integrate(params.dt, &poss.position, &mprops.local_mprops.local_com);
 
@@ -158,5 +240,7 @@ impl IslandSolver {
             }
             counters.solver.velocity_update_time.pause();
         }
+
+        self.solve_time_ns = start_time.elapsed().as_nanos() as u64;
     }
 }
@@ -113,6 +113,7 @@ impl AnyJointVelocityConstraint {
                 jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
             }
 
+            // NOTE: joint springs and couplings are not supported by multibodies yet.
             // TODO: find a way to avoid the temporary buffer.
             let mut out_tmp = [JointGenericVelocityConstraint::invalid(); 12];
             let out_tmp_len = JointGenericVelocityConstraint::lock_axes(
@@ -318,6 +319,7 @@ impl AnyJointVelocityConstraint {
                 jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
             }
 
+            // NOTE: joint springs and couplings are not supported by multibodies yet.
             // TODO: find a way to avoid the temporary buffer.
             let mut out_tmp = [JointGenericVelocityGroundConstraint::invalid(); 12];
             let out_tmp_len = JointGenericVelocityGroundConstraint::lock_axes(
@@ -348,6 +350,7 @@ impl AnyJointVelocityConstraint {
                 &frame1,
                 &frame2,
                 &joint.data,
+                flipped,
                 &mut out_tmp,
             );
 
@@ -23,6 +23,8 @@ pub struct JointGenericVelocityConstraint {
     pub impulse: Real,
     pub impulse_bounds: [Real; 2],
     pub inv_lhs: Real,
+    // Constraint force mixing coefficient of soft constraints, zero for rigid constraints.
+    pub cfm_gain: Real,
     pub rhs: Real,
     pub rhs_wo_bias: Real,
 
@@ -44,6 +46,7 @@ impl JointGenericVelocityConstraint {
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
             inv_lhs: 0.0,
+            cfm_gain: 0.0,
             rhs: 0.0,
             rhs_wo_bias: 0.0,
             writeback_id: WritebackId::Dof(0),
@@ -64,6 +67,7 @@ impl JointGenericVelocityConstraint {
         j_id: &mut usize,
         out: &mut [Self],
     ) -> usize {
+        let params = &joint.integration_parameters(params);
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
         let motor_axes = joint.motor_axes.bits();
@@ -189,6 +193,15 @@ impl JointGenericVelocityConstraint {
             }
         }
 
+        if joint.cfm != 0.0 {
+            let cfm_gain = joint.cfm_gain(params);
+            for constraint in &mut out[..len] {
+                if let WritebackId::Dof(_) | WritebackId::Limit(_) = constraint.writeback_id {
+                    constraint.cfm_gain = cfm_gain;
+                }
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints(jacobians, &mut out[..len]);
         len
     }
@@ -265,7 +278,7 @@ impl JointGenericVelocityConstraint {
         let j2 = DVectorSlice::from_slice(&jacobians[self.j_id2..], self.ndofs2);
         let vel2 = j2.dot(&mj_lambda2);
 
-        let dvel = self.rhs + (vel2 - vel1);
+        let dvel = self.rhs + (vel2 - vel1) - self.cfm_gain * self.impulse;
         let total_impulse = na::clamp(
             self.impulse + self.inv_lhs * dvel,
             self.impulse_bounds[0],
@@ -289,6 +302,16 @@ impl JointGenericVelocityConstraint {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Spring => {
+                if let Some(spring) = &mut joint.data.spring {
+                    spring.impulse = self.impulse
+                }
+            }
+            WritebackId::Coupling => {
+                if let Some(coupling) = &mut joint.data.coupling {
+                    coupling.impulse = self.impulse
+                }
+            }
         }
     }
 
@@ -308,6 +331,8 @@ pub struct JointGenericVelocityGroundConstraint {
     pub impulse: Real,
     pub impulse_bounds: [Real; 2],
     pub inv_lhs: Real,
+    // Constraint force mixing coefficient of soft constraints, zero for rigid constraints.
+    pub cfm_gain: Real,
     pub rhs: Real,
     pub rhs_wo_bias: Real,
 
@@ -324,6 +349,7 @@ impl JointGenericVelocityGroundConstraint {
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
             inv_lhs: 0.0,
+            cfm_gain: 0.0,
             rhs: 0.0,
             rhs_wo_bias: 0.0,
             writeback_id: WritebackId::Dof(0),
@@ -343,6 +369,7 @@ impl JointGenericVelocityGroundConstraint {
         j_id: &mut usize,
         out: &mut [Self],
     ) -> usize {
+        let params = &joint.integration_parameters(params);
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
         let motor_axes = joint.motor_axes.bits();
@@ -458,6 +485,15 @@ impl JointGenericVelocityGroundConstraint {
             }
         }
 
+        if joint.cfm != 0.0 {
+            let cfm_gain = joint.cfm_gain(params);
+            for constraint in &mut out[..len] {
+                if let WritebackId::Dof(_) | WritebackId::Limit(_) = constraint.writeback_id {
+                    constraint.cfm_gain = cfm_gain;
+                }
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints_ground(
             jacobians,
             &mut out[..len],
@@ -497,7 +533,7 @@ impl JointGenericVelocityGroundConstraint {
         let j2 = DVectorSlice::from_slice(&jacobians[self.j_id2..], self.ndofs2);
         let vel2 = j2.dot(&mj_lambda2);
 
-        let dvel = self.rhs + vel2;
+        let dvel = self.rhs + vel2 - self.cfm_gain * self.impulse;
         let total_impulse = na::clamp(
             self.impulse + self.inv_lhs * dvel,
             self.impulse_bounds[0],
@@ -519,6 +555,16 @@ impl JointGenericVelocityGroundConstraint {
                 WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+                WritebackId::Spring => {
+                    if let Some(spring) = &mut joint.data.spring {
+                        spring.impulse = self.impulse
+                    }
+                }
+                WritebackId::Coupling => {
+                    if let Some(coupling) = &mut joint.data.coupling {
+                        coupling.impulse = self.impulse
+                    }
+                }
             }
         }
     }
@@ -131,6 +131,7 @@ impl JointVelocityConstraintBuilder<Real> {
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
             inv_lhs: 0.0,
+            cfm_gain: 0.0,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
@@ -450,10 +451,10 @@ impl JointVelocityConstraintBuilder<Real> {
 
             let dot_jj = jac_j1.dot(&w_jac_j1) + jac_j2.dot(&w_jac_j2);
             let inv_dot_jj = crate::utils::inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::inv(dot_jj + c_j.cfm_gain); // Don’t forget to update the inv_lhs.
 
-            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] {
-                // Don't remove constraints with limited forces from the others
+            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] || c_j.cfm_gain != 0.0 {
+                // Don't remove constraints with limited or soft forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
                 continue;
@@ -522,6 +523,7 @@ impl JointVelocityConstraintBuilder<Real> {
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
             inv_lhs: 0.0,
+            cfm_gain: 0.0,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
@@ -816,10 +818,10 @@ impl JointVelocityConstraintBuilder<Real> {
 
             let dot_jj = jac_j2.dot(&w_jac_j2);
             let inv_dot_jj = crate::utils::inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::inv(dot_jj + c_j.cfm_gain); // Don’t forget to update the inv_lhs.
 
-            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] {
-                // Don't remove constraints with limited forces from the others
+            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] || c_j.cfm_gain != 0.0 {
+                // Don't remove constraints with limited or soft forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
                 continue;
@@ -20,6 +20,7 @@ pub struct MotorParameters<N: SimdRealField> {
     pub target_pos: N,
     pub target_vel: N,
     pub max_impulse: N,
+    pub cfm_gain: N,
 }
 
 impl<N: SimdRealField> Default for MotorParameters<N> {
@@ -32,6 +33,7 @@ impl<N: SimdRealField> Default for MotorParameters<N> {
             target_pos: N::zero(),
             target_vel: N::zero(),
             max_impulse: N::zero(),
+            cfm_gain: N::zero(),
         }
     }
 }
@@ -41,6 +43,8 @@ pub enum WritebackId {
     Dof(usize),
     Limit(usize),
     Motor(usize),
+    Spring,
+    Coupling,
 }
 
 // TODO: right now we only use this for impulse_joints.
@@ -71,6 +75,8 @@ pub struct JointVelocityConstraint<N: SimdRealField, const LANES: usize> {
     pub ang_jac2: AngVector<N>,
 
     pub inv_lhs: N,
+    // Constraint force mixing coefficient of soft constraints, zero for rigid constraints.
+    pub cfm_gain: N,
     pub rhs: N,
     pub rhs_wo_bias: N,
 
@@ -92,6 +98,7 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
             ang_jac1: na::zero(),
             ang_jac2: na::zero(),
             inv_lhs: N::zero(),
+            cfm_gain: N::zero(),
             rhs: N::zero(),
             rhs_wo_bias: N::zero(),
             im1: N::zero(),
@@ -105,7 +112,7 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
         let dangvel =
             self.ang_jac2.gdot(mj_lambda2.angular) - self.ang_jac1.gdot(mj_lambda1.angular);
 
-        let rhs = dlinvel + dangvel + self.rhs;
+        let rhs = dlinvel + dangvel + self.rhs - self.cfm_gain * self.impulse;
         let total_impulse = (self.impulse + self.inv_lhs * rhs)
             .simd_clamp(self.impulse_bounds[0], self.impulse_bounds[1]);
         let delta_impulse = total_impulse - self.impulse;
@@ -137,6 +144,7 @@ impl JointVelocityConstraint<Real, 1> {
         joint: &JointData,
         out: &mut [Self],
     ) -> usize {
+        let params = &joint.integration_parameters(params);
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
         let motor_axes = joint.motor_axes.bits();
@@ -229,7 +237,33 @@ impl JointVelocityConstraint<Real, 1> {
             }
         }
 
+        if joint.cfm != 0.0 {
+            let cfm_gain = joint.cfm_gain(params);
+            for constraint in &mut out[..len] {
+                if let WritebackId::Dof(_) | WritebackId::Limit(_) = constraint.writeback_id {
+                    constraint.cfm_gain = cfm_gain;
+                }
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_constraints(&mut out[..len]);
+
+        if let Some(coupling) = &joint.coupling {
+            out[len] = JointVelocityConstraintBuilder::couple(
+                params, joint_id, body1, body2, frame1, frame2, coupling,
+            );
+            len += 1;
+        }
+
+        if let Some(spring) = &joint.spring {
+            if let Some(constraint) = JointVelocityConstraintBuilder::spring_distance(
+                params, joint_id, body1, body2, frame1, frame2, spring,
+            ) {
+                out[len] = constraint;
+                len += 1;
+            }
+        }
+
         len
     }
 
@@ -249,6 +283,16 @@ impl JointVelocityConstraint<Real, 1> {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Spring => {
+                if let Some(spring) = &mut joint.data.spring {
+                    spring.impulse = self.impulse
+                }
+            }
+            WritebackId::Coupling => {
+                if let Some(coupling) = &mut joint.data.coupling {
+                    coupling.impulse = self.impulse
+                }
+            }
         }
     }
 }
@@ -333,6 +377,16 @@ impl JointVelocityConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                WritebackId::Spring => {
+                    if let Some(spring) = &mut joint.data.spring {
+                        spring.impulse = impulses[ii]
+                    }
+                }
+                WritebackId::Coupling => {
+                    if let Some(coupling) = &mut joint.data.coupling {
+                        coupling.impulse = impulses[ii]
+                    }
+                }
             }
         }
     }
@@ -350,6 +404,7 @@ pub struct JointVelocityGroundConstraint<N: SimdRealField, const LANES: usize> {
     pub ang_jac2: AngVector<N>,
 
     pub inv_lhs: N,
+    pub cfm_gain: N,
     pub rhs: N,
     pub rhs_wo_bias: N,
 
@@ -368,6 +423,7 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
             lin_jac: Vector::zeros(),
             ang_jac2: na::zero(),
             inv_lhs: N::zero(),
+            cfm_gain: N::zero(),
             rhs: N::zero(),
             rhs_wo_bias: N::zero(),
             im2: N::zero(),
@@ -379,7 +435,8 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
         let dlinvel = mj_lambda2.linear;
         let dangvel = mj_lambda2.angular;
 
-        let dvel = self.lin_jac.dot(&dlinvel) + self.ang_jac2.gdot(dangvel) + self.rhs;
+        let dvel = self.lin_jac.dot(&dlinvel) + self.ang_jac2.gdot(dangvel) + self.rhs
+            - self.cfm_gain * self.impulse;
         let total_impulse = (self.impulse + self.inv_lhs * dvel)
             .simd_clamp(self.impulse_bounds[0], self.impulse_bounds[1]);
         let delta_impulse = total_impulse - self.impulse;
@@ -406,8 +463,10 @@ impl JointVelocityGroundConstraint<Real, 1> {
         frame1: &Isometry<Real>,
         frame2: &Isometry<Real>,
         joint: &JointData,
+        flipped: bool,
         out: &mut [Self],
     ) -> usize {
+        let params = &joint.integration_parameters(params);
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits() as u8;
         let motor_axes = joint.motor_axes.bits() as u8;
@@ -504,7 +563,33 @@ impl JointVelocityGroundConstraint<Real, 1> {
             }
         }
 
+        if joint.cfm != 0.0 {
+            let cfm_gain = joint.cfm_gain(params);
+            for constraint in &mut out[..len] {
+                if let WritebackId::Dof(_) | WritebackId::Limit(_) = constraint.writeback_id {
+                    constraint.cfm_gain = cfm_gain;
+                }
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_ground_constraints(&mut out[..len]);
+
+        if let Some(coupling) = &joint.coupling {
+            out[len] = JointVelocityConstraintBuilder::couple_ground(
+                params, joint_id, body1, body2, frame1, frame2, coupling, flipped,
+            );
+            len += 1;
+        }
+
+        if let Some(spring) = &joint.spring {
+            if let Some(constraint) = JointVelocityConstraintBuilder::spring_distance_ground(
+                params, joint_id, body1, body2, frame1, frame2, spring,
+            ) {
+                out[len] = constraint;
+                len += 1;
+            }
+        }
+
         len
     }
 
@@ -520,6 +605,16 @@ impl JointVelocityGroundConstraint<Real, 1> {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Spring => {
+                if let Some(spring) = &mut joint.data.spring {
+                    spring.impulse = self.impulse
+                }
+            }
+            WritebackId::Coupling => {
+                if let Some(coupling) = &mut joint.data.coupling {
+                    coupling.impulse = self.impulse
+                }
+            }
         }
     }
 }
@@ -602,6 +697,16 @@ impl JointVelocityGroundConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                WritebackId::Spring => {
+                    if let Some(spring) = &mut joint.data.spring {
+                        spring.impulse = impulses[ii]
+                    }
+                }
+                WritebackId::Coupling => {
+                    if let Some(coupling) = &mut joint.data.coupling {
+                        coupling.impulse = impulses[ii]
+                    }
+                }
             }
         }
     }
@@ -3,9 +3,11 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 };
 use crate::dynamics::solver::joint_constraint::SolverBody;
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::{IntegrationParameters, JointIndex};
-use crate::math::{Isometry, Matrix, Point, Real, Rotation, Vector, ANG_DIM, DIM};
-use crate::utils::{IndexMut2, WCrossMatrix, WDot, WQuat, WReal};
+use crate::dynamics::{
+    IntegrationParameters, JointCoupling, JointCouplingKind, JointIndex, JointSpring,
+};
+use crate::math::{AngVector, Isometry, Matrix, Point, Real, Rotation, Vector, ANG_DIM, DIM};
+use crate::utils::{IndexMut2, WCross, WCrossMatrix, WDot, WQuat, WReal};
 use na::SMatrix;
 use simba::simd::SimdRealField;
 
@@ -139,6 +141,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         }
 
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint.rhs = rhs_wo_bias;
         constraint.rhs_wo_bias = rhs_wo_bias;
         constraint
@@ -185,6 +188,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             ang_jac1,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -243,6 +247,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             ang_jac1,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -295,6 +300,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             ang_jac1,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
@@ -339,6 +345,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             ang_jac1,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -349,6 +356,10 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
     pub fn finalize_constraints<const LANES: usize>(
         constraints: &mut [JointVelocityConstraint<N, LANES>],
     ) {
+        if constraints.is_empty() {
+            return;
+        }
+
         let len = constraints.len();
         let imsum = constraints[0].im1 + constraints[0].im2;
 
@@ -359,10 +370,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                 + c_j.ang_jac1.gdot(c_j.ang_jac1)
                 + c_j.ang_jac2.gdot(c_j.ang_jac2);
             let inv_dot_jj = crate::utils::simd_inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::simd_inv(dot_jj + c_j.cfm_gain); // Don’t forget to update the inv_lhs.
 
-            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)] {
-                // Don't remove constraints with limited forces from the others
+            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)]
+                || c_j.cfm_gain != N::zero()
+            {
+                // Don't remove constraints with limited or soft forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
                 continue;
@@ -430,6 +443,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac,
             ang_jac2,
             inv_lhs: zero, // Will be set during ortogonalization.
+            cfm_gain: zero,
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -475,6 +489,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
@@ -515,6 +530,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac,
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -563,6 +579,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
@@ -617,6 +634,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac2,
             inv_lhs: zero, // Will be set during ortogonalization.
+            cfm_gain: zero,
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -656,6 +674,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
@@ -666,6 +685,10 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
     pub fn finalize_ground_constraints<const LANES: usize>(
         constraints: &mut [JointVelocityGroundConstraint<N, LANES>],
     ) {
+        if constraints.is_empty() {
+            return;
+        }
+
         let len = constraints.len();
         let imsum = constraints[0].im2;
 
@@ -674,10 +697,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             let c_j = &mut constraints[j];
             let dot_jj = c_j.lin_jac.norm_squared() * imsum + c_j.ang_jac2.gdot(c_j.ang_jac2);
             let inv_dot_jj = crate::utils::simd_inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::simd_inv(dot_jj + c_j.cfm_gain); // Don’t forget to update the inv_lhs.
 
-            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)] {
-                // Don't remove constraints with limited forces from the others
+            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)]
+                || c_j.cfm_gain != N::zero()
+            {
+                // Don't remove constraints with limited or soft forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
                 continue;
@@ -697,3 +722,277 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         }
     }
 }
+
+impl JointVelocityConstraintBuilder<Real> {
+    /// The direction, and length, of the segment between the two joint anchors.
+    fn spring_axis(frame1: &Isometry<Real>, frame2: &Isometry<Real>) -> (Vector<Real>, Real) {
+        let delta = frame2.translation.vector - frame1.translation.vector;
+        let dist = delta.norm();
+
+        if dist > crate::math::DEFAULT_EPSILON {
+            (delta / dist, dist)
+        } else {
+            // The anchors coincide so any direction will do.
+            (frame1.rotation * Vector::x(), dist)
+        }
+    }
+
+    /// Computes the `(rhs, rhs_wo_bias, cfm_gain)` of a spring constraint.
+    fn spring_rhs(
+        params: &IntegrationParameters,
+        spring: &JointSpring,
+        dist: Real,
+        dvel: Real,
+    ) -> Option<(Real, Real, Real)> {
+        let rhs_wo_bias = dvel * params.velocity_solve_fraction;
+        let dist_err = dist - spring.rest_length;
+
+        if spring.is_rigid() {
+            // Degenerates to a regular distance constraint.
+            Some((
+                rhs_wo_bias + dist_err * params.erp_inv_dt(),
+                rhs_wo_bias,
+                0.0,
+            ))
+        } else {
+            // The spring force is part of the dynamics, so it is not removed
+            // during the stabilization iterations.
+            let (bias_coeff, cfm_gain) = spring.combine_coefficients(params.dt)?;
+            let rhs = rhs_wo_bias + dist_err * bias_coeff;
+            Some((rhs, rhs, cfm_gain))
+        }
+    }
+
+    /// Builds the constraint for the spring-damper acting on the distance between the joint anchors.
+    pub fn spring_distance(
+        params: &IntegrationParameters,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        spring: &JointSpring,
+    ) -> Option<JointVelocityConstraint<Real, 1>> {
+        let (lin_jac, dist) = Self::spring_axis(frame1, frame2);
+        let r1 = frame1.translation.vector - body1.world_com.coords;
+        let r2 = frame2.translation.vector - body2.world_com.coords;
+        let ang_jac1 = r1.gcross(lin_jac);
+        let ang_jac2 = r2.gcross(lin_jac);
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let (rhs, rhs_wo_bias, cfm_gain) = Self::spring_rhs(params, spring, dist, dvel)?;
+
+        let ang_jac1 = body1.sqrt_ii * ang_jac1;
+        let ang_jac2 = body2.sqrt_ii * ang_jac2;
+        let lhs =
+            body1.im + body2.im + ang_jac1.gdot(ang_jac1) + ang_jac2.gdot(ang_jac2) + cfm_gain;
+
+        Some(JointVelocityConstraint {
+            joint_id: [joint_id],
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds: [-Real::MAX, Real::MAX],
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+            inv_lhs: crate::utils::inv(lhs),
+            cfm_gain,
+            rhs,
+            rhs_wo_bias,
+            writeback_id: WritebackId::Spring,
+        })
+    }
+
+    /// Builds the constraint for the spring-damper acting on the distance between the joint anchors,
+    /// assuming the first body is not dynamic.
+    pub fn spring_distance_ground(
+        params: &IntegrationParameters,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        spring: &JointSpring,
+    ) -> Option<JointVelocityGroundConstraint<Real, 1>> {
+        let (lin_jac, dist) = Self::spring_axis(frame1, frame2);
+        let r1 = frame1.translation.vector - body1.world_com.coords;
+        let r2 = frame2.translation.vector - body2.world_com.coords;
+        let ang_jac1 = r1.gcross(lin_jac);
+        let ang_jac2 = r2.gcross(lin_jac);
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let (rhs, rhs_wo_bias, cfm_gain) = Self::spring_rhs(params, spring, dist, dvel)?;
+
+        let ang_jac2 = body2.sqrt_ii * ang_jac2;
+        let lhs = body2.im + ang_jac2.gdot(ang_jac2) + cfm_gain;
+
+        Some(JointVelocityGroundConstraint {
+            joint_id: [joint_id],
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds: [-Real::MAX, Real::MAX],
+            lin_jac,
+            ang_jac2,
+            inv_lhs: crate::utils::inv(lhs),
+            cfm_gain,
+            rhs,
+            rhs_wo_bias,
+            writeback_id: WritebackId::Spring,
+        })
+    }
+
+    /// The jacobians `(lin_jac, ang_jac1, ang_jac2)` of a coupling constraint, before
+    /// their multiplication by the square root of the inverse angular inertia.
+    fn coupling_jacobians(
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        world_com1: &Point<Real>,
+        world_com2: &Point<Real>,
+        coupling: &JointCoupling,
+    ) -> (Vector<Real>, AngVector<Real>, AngVector<Real>) {
+        #[cfg(feature = "dim2")]
+        let (axis1, axis2) = (1.0, 1.0);
+        #[cfg(feature = "dim3")]
+        let (axis1, axis2) = (frame1.rotation * Vector::x(), frame2.rotation * Vector::x());
+
+        match coupling.kind {
+            JointCouplingKind::RackAndPinion => {
+                // The rack and the pinion are in contact at the pinion’s center.
+                let rack_axis = frame1.rotation * Vector::x();
+                let r1 = frame2.translation.vector - world_com1.coords;
+                let r2 = frame2.translation.vector - world_com2.coords;
+                (
+                    rack_axis,
+                    r1.gcross(rack_axis) + axis2 * coupling.ratio,
+                    r2.gcross(rack_axis) + axis2 * coupling.ratio,
+                )
+            }
+            JointCouplingKind::Gear => (Vector::zeros(), -axis1, axis2 * coupling.ratio),
+        }
+    }
+
+    /// Computes the `(rhs, impulse_bounds)` of a coupling constraint.
+    fn coupling_rhs(
+        params: &IntegrationParameters,
+        coupling: &JointCoupling,
+        dvel: Real,
+    ) -> (Real, [Real; 2]) {
+        if let Some(motor) = &coupling.motor {
+            let motor_params = motor.motor_params(params.dt);
+            (
+                (dvel - motor_params.target_vel) * motor_params.damping,
+                [-motor_params.max_impulse, motor_params.max_impulse],
+            )
+        } else {
+            (
+                dvel * params.velocity_solve_fraction,
+                [-Real::MAX, Real::MAX],
+            )
+        }
+    }
+
+    /// Builds the constraint coupling two degrees of freedom of a joint.
+    pub fn couple(
+        params: &IntegrationParameters,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        coupling: &JointCoupling,
+    ) -> JointVelocityConstraint<Real, 1> {
+        let (lin_jac, ang_jac1, ang_jac2) =
+            Self::coupling_jacobians(frame1, frame2, &body1.world_com, &body2.world_com, coupling);
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let (rhs, impulse_bounds) = Self::coupling_rhs(params, coupling, dvel);
+
+        let ang_jac1 = body1.sqrt_ii * ang_jac1;
+        let ang_jac2 = body2.sqrt_ii * ang_jac2;
+        let lhs = lin_jac.norm_squared() * (body1.im + body2.im)
+            + ang_jac1.gdot(ang_jac1)
+            + ang_jac2.gdot(ang_jac2);
+
+        // NOTE: the coupling isn’t orthogonalized with the other constraints
+        //       so we set its inv_lhs right away.
+        JointVelocityConstraint {
+            joint_id: [joint_id],
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds,
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+            inv_lhs: crate::utils::inv(lhs),
+            cfm_gain: 0.0,
+            rhs,
+            rhs_wo_bias: rhs,
+            writeback_id: WritebackId::Coupling,
+        }
+    }
+
+    /// Builds the constraint coupling two degrees of freedom of a joint, assuming the first
+    /// body is not dynamic.
+    ///
+    /// If `flipped` is `true`, the bodies and frames have been swapped so that the second
+    /// body is the dynamic one.
+    pub fn couple_ground(
+        params: &IntegrationParameters,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        coupling: &JointCoupling,
+        flipped: bool,
+    ) -> JointVelocityGroundConstraint<Real, 1> {
+        let (lin_jac, ang_jac1, ang_jac2) = if flipped {
+            // The coupling isn’t symmetric so we have to compute the jacobians
+            // with the original body order before swapping them back.
+            let (lin_jac, ang_jac1, ang_jac2) = Self::coupling_jacobians(
+                frame2,
+                frame1,
+                &body2.world_com,
+                &body1.world_com,
+                coupling,
+            );
+            (-lin_jac, -ang_jac2, -ang_jac1)
+        } else {
+            Self::coupling_jacobians(frame1, frame2, &body1.world_com, &body2.world_com, coupling)
+        };
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let (rhs, impulse_bounds) = Self::coupling_rhs(params, coupling, dvel);
+
+        let ang_jac2 = body2.sqrt_ii * ang_jac2;
+        let lhs = lin_jac.norm_squared() * body2.im + ang_jac2.gdot(ang_jac2);
+
+        // NOTE: the coupling isn’t orthogonalized with the other constraints
+        //       so we set its inv_lhs right away.
+        JointVelocityGroundConstraint {
+            joint_id: [joint_id],
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds,
+            lin_jac,
+            ang_jac2,
+            inv_lhs: crate::utils::inv(lhs),
+            cfm_gain: 0.0,
+            rhs,
+            rhs_wo_bias: rhs,
+            writeback_id: WritebackId::Coupling,
+        }
+    }
+}
@@ -6,8 +6,10 @@ pub(crate) use self::parallel_island_solver::{ParallelIslandSolver, ThreadContex
 pub(self) use self::parallel_solver_constraints::ParallelSolverConstraints;
 #[cfg(feature = "parallel")]
 pub(self) use self::parallel_velocity_solver::ParallelVelocitySolver;
+use self::position_solver::PositionSolver;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::solver_constraints::SolverConstraints;
+pub use self::solver_residual::SolverResidual;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::velocity_solver::VelocitySolver;
 pub(self) use delta_vel::DeltaVel;
@@ -39,8 +41,10 @@ mod parallel_island_solver;
 mod parallel_solver_constraints;
 #[cfg(feature = "parallel")]
 mod parallel_velocity_solver;
+mod position_solver;
 #[cfg(not(feature = "parallel"))]
 mod solver_constraints;
+mod solver_residual;
 mod velocity_constraint;
 mod velocity_constraint_element;
 #[cfg(feature = "simd-is-enabled")]
@@ -1,22 +1,22 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 use rayon::Scope;
 
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
 use crate::dynamics::solver::generic_velocity_constraint::GenericVelocityConstraint;
 use crate::dynamics::solver::{
-    AnyJointVelocityConstraint, AnyVelocityConstraint, ParallelSolverConstraints,
+    AnyJointVelocityConstraint, AnyVelocityConstraint, ParallelSolverConstraints, SolverResidual,
 };
 use crate::dynamics::{
     IntegrationParameters, IslandManager, JointGraphEdge, JointIndex, MultibodyJointSet,
     RigidBodyDamping, RigidBodyForces, RigidBodyIds, RigidBodyMassProps, RigidBodyPosition,
-    RigidBodyType, RigidBodyVelocity,
+    RigidBodyType, RigidBodyVelocity, SolverMode,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Isometry, Real};
 use crate::utils::WAngularInertia;
 
-use super::{DeltaVel, ParallelInteractionGroups, ParallelVelocitySolver};
+use super::{DeltaVel, ParallelInteractionGroups, ParallelVelocitySolver, PositionSolver};
 
 #[macro_export]
 #[doc(hidden)]
@@ -112,6 +112,7 @@ impl ThreadContext {
 
 pub struct ParallelIslandSolver {
     velocity_solver: ParallelVelocitySolver,
+    position_solver: PositionSolver,
     positions: Vec<Isometry<Real>>,
     parallel_groups: ParallelInteractionGroups,
     parallel_joint_groups: ParallelInteractionGroups,
@@ -119,6 +120,8 @@ pub struct ParallelIslandSolver {
         ParallelSolverConstraints<AnyVelocityConstraint, GenericVelocityConstraint>,
     parallel_joint_constraints: ParallelSolverConstraints<AnyJointVelocityConstraint, ()>,
     thread: ThreadContext,
+    residual: SolverResidual,
+    solve_time_ns: AtomicU64,
 }
 
 impl Default for ParallelIslandSolver {
@@ -131,15 +134,33 @@ impl ParallelIslandSolver {
     pub fn new() -> Self {
         Self {
             velocity_solver: ParallelVelocitySolver::new(),
+            position_solver: PositionSolver::new(),
             positions: Vec::new(),
             parallel_groups: ParallelInteractionGroups::new(),
             parallel_joint_groups: ParallelInteractionGroups::new(),
             parallel_contact_constraints: ParallelSolverConstraints::new(),
             parallel_joint_constraints: ParallelSolverConstraints::new(),
             thread: ThreadContext::new(8),
+            residual: SolverResidual::default(),
+            solve_time_ns: AtomicU64::new(0),
         }
     }
 
+    /// The convergence information measured during the last call to `init_and_solve`.
+    ///
+    /// The velocity error isn't measured by the parallel solver.
+    pub fn residual(&self) -> &SolverResidual {
+        &self.residual
+    }
+
+    /// The time, in nanoseconds, spent by the slowest task solving this island during the
+    /// last call to `init_and_solve`.
+    ///
+    /// This is only meaningful once all the tasks spawned by `init_and_solve` completed.
+    pub fn solve_time_ns(&self) -> u64 {
+        self.solve_time_ns.load(Ordering::Relaxed)
+    }
+
     pub fn init_and_solve<'s, Bodies>(
         &'s mut self,
         scope: &Scope<'s>,
@@ -161,6 +182,43 @@ impl ParallelIslandSolver {
             + ComponentSet<RigidBodyIds>
             + ComponentSet<RigidBodyType>,
     {
+        if let SolverMode::PositionBased { iterations } = params.solver_mode {
+            let island = islands.active_island(island_id);
+            if island
+                .iter()
+                .all(|handle| multibody_joints.rigid_body_link(*handle).is_none())
+            {
+                // The position-based resolution isn't parallelized.
+                let start_time = instant::Instant::now();
+                self.residual = SolverResidual::default();
+
+                if !manifold_indices.is_empty() || !joint_indices.is_empty() {
+                    self.residual.max_position_error = manifold_indices
+                        .iter()
+                        .flat_map(|i| manifolds[*i].data.solver_contacts.iter())
+                        .fold(0.0, |max_error, contact| {
+                            max_error.max(-contact.dist - params.allowed_linear_error)
+                        });
+                    self.residual.iterations_run = iterations;
+                }
+
+                self.position_solver.solve(
+                    island_id,
+                    iterations,
+                    params,
+                    islands,
+                    bodies,
+                    manifolds,
+                    manifold_indices,
+                    impulse_joints,
+                    joint_indices,
+                );
+                self.solve_time_ns
+                    .store(start_time.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                return;
+            }
+        }
+
         let num_threads = rayon::current_num_threads();
         let num_task_per_island = num_threads; // (num_threads / num_islands).max(1); // TODO: not sure this is the best value. Also, perhaps it is better to interleave tasks of each island?
         self.thread = ThreadContext::new(8); // TODO: could we compute some kind of optimal value here?
@@ -195,15 +253,31 @@ impl ParallelIslandSolver {
             &self.parallel_joint_groups,
         );
 
+        self.residual = SolverResidual::default();
+
+        if !manifold_indices.is_empty() || !joint_indices.is_empty() {
+            self.residual.max_position_error = manifold_indices
+                .iter()
+                .flat_map(|i| manifolds[*i].data.solver_contacts.iter())
+                .fold(0.0, |max_error, contact| {
+                    max_error.max(-contact.dist - params.allowed_linear_error)
+                });
+            self.residual.iterations_run = params.max_velocity_iterations as u32;
+        }
+
         self.velocity_solver.mj_lambdas.clear();
         self.velocity_solver
             .mj_lambdas
             .resize(islands.active_island(island_id).len(), DeltaVel::zero());
 
+        let start_time = instant::Instant::now();
+        self.solve_time_ns.store(0, Ordering::Relaxed);
+
         for _ in 0..num_task_per_island {
             // We use AtomicPtr because it is Send+Sync while *mut is not.
             // See https://internals.rust-lang.org/t/shouldnt-pointers-be-send-sync-or/8818
             let thread = &self.thread;
+            let solve_time_ns = &self.solve_time_ns;
             let velocity_solver =
                 std::sync::atomic::AtomicPtr::new(&mut self.velocity_solver as *mut _);
             let bodies = std::sync::atomic::AtomicPtr::new(bodies as *mut _);
@@ -248,6 +322,14 @@ impl ParallelIslandSolver {
                             //       by the square root of the inertia tensor:
                             dvel.angular += rb_mass_props.effective_world_inv_inertia_sqrt * rb_forces.torque * params.dt;
                             dvel.linear += rb_forces.force * (rb_mass_props.effective_inv_mass * params.dt);
+
+                            // Shift the velocities of capped bodies so that adding this
+                            // force-induced velocity delta doesn't exceed the caps.
+                            let (rb_vels, rb_damping): (&RigidBodyVelocity, &RigidBodyDamping) = bodies.index_bundle(handle.0);
+                            if rb_damping.has_velocity_caps() {
+                                let new_rb_vels = rb_vels.cap_magnitudes_after_forces(params.dt, rb_forces, rb_mass_props, rb_damping);
+                                bodies.set_internal(handle.0, new_rb_vels);
+                            }
                         }
                     }
 
@@ -299,7 +381,7 @@ impl ParallelIslandSolver {
                         new_rb_vels.linvel += dvels.linear;
                         new_rb_vels.angvel += rb_mprops.effective_world_inv_inertia_sqrt.transform_vector(dvels.angular);
 
-                        let new_rb_vels = new_rb_vels.apply_damping(params.dt, rb_damping);
+                        let new_rb_vels = new_rb_vels.apply_damping(params.dt, rb_damping, &rb_pos.position.rotation);
                         new_rb_pos.next_position =
                             new_rb_vels.integrate(params.dt, &rb_pos.position, &rb_mprops.local_mprops.local_com);
 
@@ -307,6 +389,9 @@ impl ParallelIslandSolver {
                         bodies.set_internal(handle.0, new_rb_pos);
                     }
                 }
+
+                let elapsed = start_time.elapsed().as_nanos() as u64;
+                solve_time_ns.fetch_max(elapsed, Ordering::Relaxed);
             })
         }
     }
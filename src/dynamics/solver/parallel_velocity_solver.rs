@@ -55,68 +55,75 @@ impl ParallelVelocitySolver {
             let mut target_num_desc = 0;
             let mut shift = 0;
 
-            for _ in 0..params.max_velocity_iterations {
-                macro_rules! solve {
-                    ($part: expr, $($solve_args: expr),*) => {
-                        // ImpulseJoint groups.
-                        for group in $part.parallel_desc_groups.windows(2) {
-                            let num_descs_in_group = group[1] - group[0];
-
-                            target_num_desc += num_descs_in_group;
-
-                            while start_index < group[1] {
-                                let end_index = (start_index + batch_size).min(group[1]);
-
-                                let constraints = if end_index == $part.constraint_descs.len() {
-                                    &mut $part.velocity_constraints
-                                        [$part.constraint_descs[start_index].0..]
-                                } else {
-                                    &mut $part.velocity_constraints[$part.constraint_descs
-                                        [start_index]
-                                        .0
-                                        ..$part.constraint_descs[end_index].0]
-                                };
-
-                                for constraint in constraints {
-                                    constraint.solve(
-                                        $($solve_args),*
-                                    );
-                                }
-
-                                let num_solved = end_index - start_index;
-                                batch_size -= num_solved;
-
-                                thread
-                                    .num_solved_interactions
-                                    .fetch_add(num_solved, Ordering::SeqCst);
-
-                                if batch_size == 0 {
-                                    start_index = thread
-                                        .solve_interaction_index
-                                        .fetch_add(thread.batch_size, Ordering::SeqCst);
-                                    start_index -= shift;
-                                    batch_size = thread.batch_size;
-                                } else {
-                                    start_index += num_solved;
-                                }
+            macro_rules! solve {
+                ($part: expr, $method: ident, $($solve_args: expr),*) => {
+                    // ImpulseJoint groups.
+                    for group in $part.parallel_desc_groups.windows(2) {
+                        let num_descs_in_group = group[1] - group[0];
+
+                        target_num_desc += num_descs_in_group;
+
+                        while start_index < group[1] {
+                            let end_index = (start_index + batch_size).min(group[1]);
+
+                            let constraints = if end_index == $part.constraint_descs.len() {
+                                &mut $part.velocity_constraints
+                                    [$part.constraint_descs[start_index].0..]
+                            } else {
+                                &mut $part.velocity_constraints[$part.constraint_descs
+                                    [start_index]
+                                    .0
+                                    ..$part.constraint_descs[end_index].0]
+                            };
+
+                            for constraint in constraints {
+                                constraint.$method(
+                                    $($solve_args),*
+                                );
+                            }
+
+                            let num_solved = end_index - start_index;
+                            batch_size -= num_solved;
+
+                            thread
+                                .num_solved_interactions
+                                .fetch_add(num_solved, Ordering::SeqCst);
+
+                            if batch_size == 0 {
+                                start_index = thread
+                                    .solve_interaction_index
+                                    .fetch_add(thread.batch_size, Ordering::SeqCst);
+                                start_index -= shift;
+                                batch_size = thread.batch_size;
+                            } else {
+                                start_index += num_solved;
                             }
-                            ThreadContext::lock_until_ge(
-                                &thread.num_solved_interactions,
-                                target_num_desc,
-                            );
                         }
-                    };
-                }
+                        ThreadContext::lock_until_ge(
+                            &thread.num_solved_interactions,
+                            target_num_desc,
+                        );
+                    }
+                };
+            }
 
+            if params.warmstart_coefficient != 0.0 {
+                solve!(contact_constraints, warmstart, &mut self.mj_lambdas);
+                shift += contact_descs.len();
+                start_index -= contact_descs.len();
+            }
+
+            for _ in 0..params.max_velocity_iterations {
                 solve!(
                     joint_constraints,
+                    solve,
                     &joint_constraints.generic_jacobians,
                     &mut self.mj_lambdas,
                     &mut self.generic_mj_lambdas
                 );
                 shift += joint_descs.len();
                 start_index -= joint_descs.len();
-                solve!(contact_constraints, &mut self.mj_lambdas, true, true);
+                solve!(contact_constraints, solve, &mut self.mj_lambdas, true, true);
                 shift += contact_descs.len();
                 start_index -= contact_descs.len();
             }
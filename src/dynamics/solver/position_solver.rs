@@ -0,0 +1,702 @@
+use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
+use crate::dynamics::{
+    IntegrationParameters, IslandManager, JointAxesMask, JointData, JointGraphEdge, JointIndex,
+    RigidBodyDamping, RigidBodyForces, RigidBodyHandle, RigidBodyIds, RigidBodyMassProps,
+    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+};
+use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::{
+    AngVector, AngularInertia, Isometry, Matrix, Point, Real, Rotation, Translation, Vector, DIM,
+};
+use crate::utils::{WAngularInertia, WCross, WDot};
+#[cfg(feature = "dim2")]
+use num::Zero;
+
+/// The state of a rigid-body during the position-based resolution.
+#[derive(Copy, Clone)]
+struct PositionBody {
+    im: Real,
+    inv_inertia_sqrt: AngularInertia<Real>,
+    local_com: Point<Real>,
+    start: Isometry<Real>,
+    com: Point<Real>,
+    rotation: Rotation<Real>,
+    vels: RigidBodyVelocity,
+}
+
+impl PositionBody {
+    /// A body moving from `start` to `end`, unaffected by the constraints.
+    fn fixed(
+        start: Isometry<Real>,
+        end: &Isometry<Real>,
+        local_com: Point<Real>,
+        vels: RigidBodyVelocity,
+    ) -> Self {
+        Self {
+            im: 0.0,
+            inv_inertia_sqrt: AngularInertia::zero(),
+            local_com,
+            start,
+            com: end * local_com,
+            rotation: end.rotation,
+            vels,
+        }
+    }
+
+    fn position(&self) -> Isometry<Real> {
+        let translation = self.com.coords - self.rotation * self.local_com.coords;
+        Isometry::from_parts(Translation::from(translation), self.rotation)
+    }
+
+    /// The offset of the given world-space point from the center-of-mass, expressed in the
+    /// orientation of this body at the beginning of the timestep.
+    fn local_offset(&self, point: &Point<Real>) -> Vector<Real> {
+        self.start
+            .rotation
+            .inverse_transform_vector(&(point - self.start * self.local_com))
+    }
+
+    fn inv_mass_along(&self, r: &Vector<Real>, dir: &Vector<Real>) -> Real {
+        let ang_dir = self.inv_inertia_sqrt.transform_vector(r.gcross(*dir));
+        self.im + ang_dir.gdot(ang_dir)
+    }
+
+    /// The displacement of the point at the offset `r` from the center-of-mass caused by the
+    /// given positional impulse, to first order.
+    fn point_displacement(&self, r: &Vector<Real>, impulse: &Vector<Real>) -> Vector<Real> {
+        let dangle = self
+            .inv_inertia_sqrt
+            .transform_vector(self.inv_inertia_sqrt.transform_vector(r.gcross(*impulse)));
+        impulse * self.im + dangle.gcross(*r)
+    }
+
+    fn apply_positional_impulse(&mut self, r: &Vector<Real>, impulse: Vector<Real>) {
+        self.com += impulse * self.im;
+        self.apply_angular_impulse(r.gcross(impulse));
+    }
+
+    fn apply_angular_impulse(&mut self, impulse: AngVector<Real>) {
+        let dangle = self
+            .inv_inertia_sqrt
+            .transform_vector(self.inv_inertia_sqrt.transform_vector(impulse));
+        // Going through the exponential map keeps the rotation normalized, even for
+        // large corrections.
+        self.rotation = Rotation::new(dangle) * self.rotation;
+        self.rotation.renormalize_fast();
+    }
+
+    fn apply_velocity_impulse(&mut self, r: &Vector<Real>, impulse: Vector<Real>) {
+        self.vels.linvel += impulse * self.im;
+        self.vels.angvel += self
+            .inv_inertia_sqrt
+            .transform_vector(self.inv_inertia_sqrt.transform_vector(r.gcross(impulse)));
+    }
+}
+
+struct PositionContact {
+    bodies: [usize; 2],
+    offsets: [Vector<Real>; 2],
+    normal: Vector<Real>,
+    dist: Real,
+    friction: Real,
+    restitution: Real,
+    normal_vel: Real,
+    normal_lambda: Real,
+    tangent_lambda: Vector<Real>,
+    manifold_id: ContactManifoldIndex,
+    contact_id: usize,
+}
+
+impl PositionContact {
+    fn anchors(&self, body1: &PositionBody, body2: &PositionBody) -> [Vector<Real>; 2] {
+        [
+            body1.rotation * self.offsets[0],
+            body2.rotation * self.offsets[1],
+        ]
+    }
+
+    fn solve_normal(&mut self, bodies: &mut [PositionBody], allowed_linear_error: Real) {
+        let (mut body1, mut body2) = (bodies[self.bodies[0]], bodies[self.bodies[1]]);
+
+        // Non-penetration.
+        let [r1, r2] = self.anchors(&body1, &body2);
+        let displacement = (body2.com + r2) - (body1.com + r1);
+        let error = self.dist + self.normal.dot(&displacement) + allowed_linear_error;
+
+        if error < 0.0 {
+            let inv_mass =
+                body1.inv_mass_along(&r1, &self.normal) + body2.inv_mass_along(&r2, &self.normal);
+            if inv_mass > 0.0 {
+                let dlambda = -error / inv_mass;
+                let impulse = self.normal * dlambda;
+                body1.apply_positional_impulse(&r1, -impulse);
+                body2.apply_positional_impulse(&r2, impulse);
+                self.normal_lambda += dlambda;
+            }
+        }
+
+        bodies[self.bodies[0]] = body1;
+        bodies[self.bodies[1]] = body2;
+    }
+
+    fn solve_friction(&mut self, bodies: &mut [PositionBody]) {
+        let (mut body1, mut body2) = (bodies[self.bodies[0]], bodies[self.bodies[1]]);
+
+        // Static friction: cancel the tangential displacement of the contact points since
+        // the beginning of the timestep, as long as the accumulated tangential impulse stays
+        // within the friction cone.
+        if self.normal_lambda > 0.0 {
+            let [r1, r2] = self.anchors(&body1, &body2);
+            let displacement = (body2.com + r2) - (body1.com + r1);
+            let tangent_displacement = displacement - self.normal * self.normal.dot(&displacement);
+            let tangent_dist = tangent_displacement.norm();
+
+            if tangent_dist > Real::EPSILON {
+                let dir = tangent_displacement / tangent_dist;
+                let inv_mass = body1.inv_mass_along(&r1, &dir) + body2.inv_mass_along(&r2, &dir);
+
+                if inv_mass > 0.0 {
+                    // The accumulated impulse is clamped as a vector, so that the corrections
+                    // along previous directions can be undone by the next iterations.
+                    let max_lambda = self.friction * self.normal_lambda;
+                    let mut new_lambda = self.tangent_lambda - dir * (tangent_dist / inv_mass);
+                    let new_norm = new_lambda.norm();
+                    if new_norm > max_lambda {
+                        new_lambda *= max_lambda / new_norm;
+                    }
+
+                    let impulse = new_lambda - self.tangent_lambda;
+                    body1.apply_positional_impulse(&r1, -impulse);
+                    body2.apply_positional_impulse(&r2, impulse);
+                    self.tangent_lambda = new_lambda;
+                }
+            }
+        }
+
+        bodies[self.bodies[0]] = body1;
+        bodies[self.bodies[1]] = body2;
+    }
+
+    /// Replaces the normal velocity resulting from the position corrections by the one
+    /// given by the restitution coefficient.
+    fn solve_restitution(&self, bodies: &mut [PositionBody]) {
+        if self.normal_lambda <= 0.0 {
+            return;
+        }
+
+        let (mut body1, mut body2) = (bodies[self.bodies[0]], bodies[self.bodies[1]]);
+        let [r1, r2] = self.anchors(&body1, &body2);
+        let vel1 = body1.vels.linvel + body1.vels.angvel.gcross(r1);
+        let vel2 = body2.vels.linvel + body2.vels.angvel.gcross(r2);
+        let normal_vel = self.normal.dot(&(vel2 - vel1));
+        let target_vel = (-self.restitution * self.normal_vel).max(0.0);
+        let inv_mass =
+            body1.inv_mass_along(&r1, &self.normal) + body2.inv_mass_along(&r2, &self.normal);
+
+        if inv_mass > 0.0 {
+            let impulse = self.normal * ((target_vel - normal_vel) / inv_mass);
+            body1.apply_velocity_impulse(&r1, -impulse);
+            body2.apply_velocity_impulse(&r2, impulse);
+        }
+
+        bodies[self.bodies[0]] = body1;
+        bodies[self.bodies[1]] = body2;
+    }
+}
+
+struct PositionJoint {
+    bodies: [usize; 2],
+    joint_id: JointIndex,
+    linear_lambda: Vector<Real>,
+    angular_lambda: AngVector<Real>,
+}
+
+impl PositionJoint {
+    fn solve(&mut self, bodies: &mut [PositionBody], joint: &JointData) {
+        let (mut body1, mut body2) = (bodies[self.bodies[0]], bodies[self.bodies[1]]);
+
+        // Locked translations.
+        let frame1 = body1.position() * joint.local_frame1;
+        let frame2 = body2.position() * joint.local_frame2;
+        let anchor1 = Point::from(frame1.translation.vector);
+        let anchor2 = Point::from(frame2.translation.vector);
+        let error = anchor2 - anchor1;
+        let locked = axes_projection(&frame1.rotation, joint.locked_axes, 0);
+        let locked_error = locked * error;
+
+        if locked_error.norm_squared() > Real::EPSILON * Real::EPSILON {
+            // The point of the first frame closest to the anchor of the second frame.
+            let r1 = anchor1 + (error - locked_error) - body1.com;
+            let r2 = anchor2 - body2.com;
+
+            // The displacement of the anchors caused by unit impulses, restricted to the
+            // locked axes, so that all the locked axes are corrected at once.
+            let mut lhs = Matrix::zeros();
+            for i in 0..DIM {
+                let impulse = Vector::ith(i, 1.0);
+                let response = body1.point_displacement(&r1, &impulse)
+                    + body2.point_displacement(&r2, &impulse);
+                lhs.set_column(i, &(locked * response));
+            }
+            let lhs = lhs * locked + (Matrix::identity() - locked);
+
+            if let Some(inv_lhs) = lhs.try_inverse() {
+                let impulse = -(inv_lhs * locked_error);
+                body1.apply_positional_impulse(&r1, -impulse);
+                body2.apply_positional_impulse(&r2, impulse);
+                self.linear_lambda += impulse;
+            }
+        }
+
+        // Locked rotations.
+        let frame1 = body1.position() * joint.local_frame1;
+        let frame2 = body2.position() * joint.local_frame2;
+
+        if let Some(impulse) = rotation_correction(
+            &body1,
+            &body2,
+            &frame1.rotation,
+            &frame2.rotation,
+            joint.locked_axes,
+        ) {
+            body1.apply_angular_impulse(-impulse);
+            body2.apply_angular_impulse(impulse);
+            self.angular_lambda += impulse;
+        }
+
+        bodies[self.bodies[0]] = body1;
+        bodies[self.bodies[1]] = body2;
+    }
+
+    fn writeback_impulses(
+        &self,
+        bodies: &[PositionBody],
+        inv_dt: Real,
+        joints_all: &mut [JointGraphEdge],
+    ) {
+        let joint = &mut joints_all[self.joint_id].weight;
+        let frame1 = bodies[self.bodies[0]].position() * joint.data.local_frame1;
+        let linear = frame1
+            .rotation
+            .inverse_transform_vector(&self.linear_lambda)
+            * inv_dt;
+
+        for i in 0..DIM {
+            joint.impulses[i] = linear[i];
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            joint.impulses[DIM] = self.angular_lambda * inv_dt;
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let angular = frame1
+                .rotation
+                .inverse_transform_vector(&self.angular_lambda)
+                * inv_dt;
+            for i in 0..3 {
+                joint.impulses[DIM + i] = angular[i];
+            }
+        }
+    }
+}
+
+/// The projection onto the axes of `rotation` whose bit, starting at `first_bit`, is set
+/// in `axes`.
+fn axes_projection(
+    rotation: &Rotation<Real>,
+    axes: JointAxesMask,
+    first_bit: usize,
+) -> Matrix<Real> {
+    let mut mask = Vector::zeros();
+    for i in 0..DIM {
+        if axes.contains(JointAxesMask::from_bits_truncate(1 << (first_bit + i))) {
+            mask[i] = 1.0;
+        }
+    }
+
+    let basis = rotation.to_rotation_matrix().into_inner();
+    basis * Matrix::from_diagonal(&mask) * basis.transpose()
+}
+
+/// The angular impulse to apply to `body2`, and opposite to `body1`, to cancel the rotation
+/// from `rot1` to `rot2` along the locked angular axes.
+#[cfg(feature = "dim2")]
+fn rotation_correction(
+    body1: &PositionBody,
+    body2: &PositionBody,
+    rot1: &Rotation<Real>,
+    rot2: &Rotation<Real>,
+    locked_axes: JointAxesMask,
+) -> Option<AngVector<Real>> {
+    let error = (rot2 * rot1.inverse()).angle();
+    let inv_inertia = body1.inv_inertia_sqrt.squared() + body2.inv_inertia_sqrt.squared();
+
+    if locked_axes.contains(JointAxesMask::ANG_X) && error != 0.0 && inv_inertia > 0.0 {
+        Some(-error / inv_inertia)
+    } else {
+        None
+    }
+}
+
+/// The angular impulse to apply to `body2`, and opposite to `body1`, to cancel the rotation
+/// from `rot1` to `rot2` along the locked angular axes of `rot1`.
+#[cfg(feature = "dim3")]
+fn rotation_correction(
+    body1: &PositionBody,
+    body2: &PositionBody,
+    rot1: &Rotation<Real>,
+    rot2: &Rotation<Real>,
+    locked_axes: JointAxesMask,
+) -> Option<AngVector<Real>> {
+    let locked = axes_projection(rot1, locked_axes, DIM);
+    let error = locked * (rot2 * rot1.inverse()).scaled_axis();
+
+    if error.norm_squared() <= Real::EPSILON * Real::EPSILON {
+        return None;
+    }
+
+    let inv_inertia = body1.inv_inertia_sqrt.squared().into_matrix()
+        + body2.inv_inertia_sqrt.squared().into_matrix();
+    let lhs = locked * inv_inertia * locked + (Matrix::identity() - locked);
+    lhs.try_inverse().map(|inv_lhs| -(inv_lhs * error))
+}
+
+/// Resolves the contacts and joints of an island by correcting the positions of its
+/// rigid-bodies directly, instead of applying impulses.
+pub(crate) struct PositionSolver {
+    bodies: Vec<PositionBody>,
+    contacts: Vec<PositionContact>,
+    joints: Vec<PositionJoint>,
+}
+
+impl PositionSolver {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            contacts: Vec::new(),
+            joints: Vec::new(),
+        }
+    }
+
+    /// Integrates the forces and velocities of the bodies of the given island, then projects
+    /// their positions onto the contact and joint constraints, and deduces their final
+    /// velocities from their displacements.
+    ///
+    /// The island must not contain any rigid-body attached to a multibody joint.
+    pub fn solve<Bodies>(
+        &mut self,
+        island_id: usize,
+        iterations: u32,
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &mut Bodies,
+        manifolds_all: &mut [&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
+        joints_all: &mut [JointGraphEdge],
+        joint_indices: &[JointIndex],
+    ) where
+        Bodies: ComponentSet<RigidBodyForces>
+            + ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyDamping>
+            + ComponentSet<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+    {
+        let island = islands.active_island(island_id);
+        let inv_dt = params.inv_dt();
+
+        // Predict the positions of the bodies from their velocities and external forces.
+        self.bodies.clear();
+        self.bodies.resize(
+            island.len(),
+            PositionBody::fixed(
+                Isometry::identity(),
+                &Isometry::identity(),
+                Point::origin(),
+                RigidBodyVelocity::zero(),
+            ),
+        );
+
+        for handle in island {
+            let (ids, poss, vels, forces, damping, mprops): (
+                &RigidBodyIds,
+                &RigidBodyPosition,
+                &RigidBodyVelocity,
+                &RigidBodyForces,
+                &RigidBodyDamping,
+                &RigidBodyMassProps,
+            ) = bodies.index_bundle(handle.0);
+
+            let local_com = mprops.local_mprops.local_com;
+            let vels = forces
+                .integrate(params.dt, vels, mprops)
+                .cap_magnitudes(damping)
+                .apply_damping(params.dt, damping, &poss.position.rotation);
+            let next_position = vels.integrate(params.dt, &poss.position, &local_com);
+
+            self.bodies[ids.active_set_offset] = PositionBody {
+                im: mprops.effective_inv_mass,
+                inv_inertia_sqrt: mprops.effective_world_inv_inertia_sqrt,
+                local_com,
+                start: poss.position,
+                com: next_position * local_com,
+                rotation: next_position.rotation,
+                vels,
+            };
+        }
+
+        self.contacts.clear();
+        for manifold_id in manifold_indices {
+            let manifold = &manifolds_all[*manifold_id];
+            let dominance = manifold.data.relative_dominance;
+            let body1 = self.body_index(bodies, manifold.data.rigid_body1, dominance > 0);
+            let body2 = self.body_index(bodies, manifold.data.rigid_body2, dominance < 0);
+            let normal = manifold.data.normal;
+
+            for contact in &manifold.data.solver_contacts {
+                let (rb1, rb2) = (&self.bodies[body1], &self.bodies[body2]);
+                let offsets = [
+                    rb1.local_offset(&contact.point),
+                    rb2.local_offset(&contact.point),
+                ];
+                let vel1 = rb1
+                    .vels
+                    .velocity_at_point(&contact.point, &(rb1.start * rb1.local_com));
+                let vel2 = rb2
+                    .vels
+                    .velocity_at_point(&contact.point, &(rb2.start * rb2.local_com));
+
+                self.contacts.push(PositionContact {
+                    bodies: [body1, body2],
+                    offsets,
+                    normal,
+                    dist: contact.dist,
+                    friction: contact.friction,
+                    restitution: if contact.is_bouncy() {
+                        contact.restitution
+                    } else {
+                        0.0
+                    },
+                    normal_vel: normal.dot(&(vel2 - vel1)),
+                    normal_lambda: 0.0,
+                    tangent_lambda: Vector::zeros(),
+                    manifold_id: *manifold_id,
+                    contact_id: contact.contact_id as usize,
+                });
+            }
+        }
+
+        self.joints.clear();
+        for joint_id in joint_indices {
+            let joint = &joints_all[*joint_id].weight;
+            let body1 = self.body_index(bodies, Some(joint.body1), false);
+            let body2 = self.body_index(bodies, Some(joint.body2), false);
+            self.joints.push(PositionJoint {
+                bodies: [body1, body2],
+                joint_id: *joint_id,
+                linear_lambda: na::zero(),
+                angular_lambda: na::zero(),
+            });
+        }
+
+        for _ in 0..iterations {
+            for joint in &mut self.joints {
+                joint.solve(&mut self.bodies, &joints_all[joint.joint_id].weight.data);
+            }
+
+            // Sweep the contacts forward then backward, so the result doesn't depend on the
+            // order the contacts were collected in.
+            for contact in &mut self.contacts {
+                contact.solve_normal(&mut self.bodies, params.allowed_linear_error);
+            }
+            for contact in &mut self.contacts {
+                contact.solve_friction(&mut self.bodies);
+            }
+            for contact in self.contacts.iter_mut().rev() {
+                contact.solve_normal(&mut self.bodies, params.allowed_linear_error);
+            }
+            for contact in self.contacts.iter_mut().rev() {
+                contact.solve_friction(&mut self.bodies);
+            }
+        }
+
+        // Deduce the velocities from the displacements.
+        for body in &mut self.bodies[..island.len()] {
+            let poss = RigidBodyPosition {
+                position: body.start,
+                next_position: body.position(),
+                prev_position: body.start,
+            };
+            body.vels = poss.interpolate_velocity(inv_dt, &body.local_com);
+        }
+
+        for contact in &self.contacts {
+            contact.solve_restitution(&mut self.bodies);
+        }
+
+        for handle in island {
+            let (ids, poss): (&RigidBodyIds, &RigidBodyPosition) = bodies.index_bundle(handle.0);
+            let body = &self.bodies[ids.active_set_offset];
+            let mut new_poss = *poss;
+            new_poss.next_position = body.position();
+            let new_vels = body.vels;
+
+            bodies.set_internal(handle.0, new_poss);
+            bodies.set_internal(handle.0, new_vels);
+        }
+
+        // Write impulses back into the manifold and joint structures.
+        for contact in &self.contacts {
+            let data = &mut manifolds_all[contact.manifold_id].points[contact.contact_id].data;
+            data.impulse = contact.normal_lambda * inv_dt;
+            data.tangent_impulse = na::zero();
+        }
+
+        for joint in &self.joints {
+            joint.writeback_impulses(&self.bodies, inv_dt, joints_all);
+        }
+    }
+
+    /// The index of the given rigid-body in `self.bodies`.
+    ///
+    /// Non-dynamic and dominant bodies are copied at the end of `self.bodies` as bodies
+    /// unaffected by the constraints.
+    fn body_index<Bodies>(
+        &mut self,
+        bodies: &Bodies,
+        handle: Option<RigidBodyHandle>,
+        dominant: bool,
+    ) -> usize
+    where
+        Bodies: ComponentSet<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+    {
+        let fixed = if let Some(handle) = handle {
+            let (ids, rb_type): (&RigidBodyIds, &RigidBodyType) = bodies.index_bundle(handle.0);
+
+            if rb_type.is_dynamic() {
+                if !dominant {
+                    return ids.active_set_offset;
+                }
+
+                PositionBody {
+                    im: 0.0,
+                    inv_inertia_sqrt: AngularInertia::zero(),
+                    ..self.bodies[ids.active_set_offset]
+                }
+            } else {
+                let (poss, vels, mprops): (
+                    &RigidBodyPosition,
+                    &RigidBodyVelocity,
+                    &RigidBodyMassProps,
+                ) = bodies.index_bundle(handle.0);
+                PositionBody::fixed(
+                    poss.position,
+                    &poss.next_position,
+                    mprops.local_mprops.local_com,
+                    *vels,
+                )
+            }
+        } else {
+            PositionBody::fixed(
+                Isometry::identity(),
+                &Isometry::identity(),
+                Point::origin(),
+                RigidBodyVelocity::zero(),
+            )
+        };
+
+        self.bodies.push(fixed);
+        self.bodies.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RevoluteJoint, RigidBodyBuilder, SolverMode};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn position_based_solver_mode() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world.integration_parameters = IntegrationParameters {
+            solver_mode: SolverMode::PositionBased { iterations: 8 },
+            ..IntegrationParameters::default()
+        };
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5).build());
+
+        let num_boxes = 5;
+        let mut top = None;
+        for k in 0..num_boxes {
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * (k as Real + 0.5))
+                    .build(),
+            );
+            #[cfg(feature = "dim2")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            world
+                .colliders
+                .insert_with_parent(cube.build(), body, &mut world.bodies);
+            top = Some(body);
+        }
+
+        // A pendulum, starting horizontally, away from the stack.
+        let origin = Vector::x() * 20.0 + Vector::y() * 10.0;
+        let anchor = world
+            .bodies
+            .insert(RigidBodyBuilder::new_static().translation(origin).build());
+        let bob = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(origin + Vector::x() * 2.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            bob,
+            &mut world.bodies,
+        );
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJoint::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJoint::new(Vector::z_axis());
+        world.impulse_joints.insert(
+            anchor,
+            bob,
+            joint.local_anchor2(Point::from(Vector::x() * -2.0)),
+        );
+
+        let mut lowest_bob = Real::MAX;
+        for _ in 0..120 {
+            world.step();
+
+            let bob_pos = world.bodies[bob].translation();
+            lowest_bob = lowest_bob.min(bob_pos.y);
+            assert!(((bob_pos - origin).norm() - 2.0).abs() < 1.0e-3);
+        }
+
+        // The stack rests without sinking nor toppling.
+        let top_pos = world.bodies[top.unwrap()].translation();
+        assert!((top_pos.y - (num_boxes as Real - 0.5)).abs() < 0.02);
+        assert!(top_pos.x.abs() < 0.01);
+        // The pendulum swung down.
+        assert!(lowest_bob < origin.y - 1.9);
+    }
+}
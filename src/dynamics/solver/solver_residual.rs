@@ -0,0 +1,27 @@
+use crate::math::Real;
+
+/// Convergence information about the constraints solver, measured during a timestep.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct SolverResidual {
+    /// The largest root-mean-square velocity correction applied to the bodies of an
+    /// island by the last velocity iteration.
+    ///
+    /// A value close to zero means the velocity constraints have converged.
+    pub max_velocity_error: Real,
+    /// The largest penetration depth beyond `IntegrationParameters::allowed_linear_error`
+    /// among the contacts handled by the solver.
+    pub max_position_error: Real,
+    /// The number of velocity iterations, or position iterations with
+    /// `SolverMode::PositionBased`, run on the islands with constraints.
+    pub iterations_run: u32,
+}
+
+impl SolverResidual {
+    /// Merges the residual of another island into this one.
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.max_velocity_error = self.max_velocity_error.max(other.max_velocity_error);
+        self.max_position_error = self.max_position_error.max(other.max_position_error);
+        self.iterations_run = self.iterations_run.max(other.iterations_run);
+    }
+}
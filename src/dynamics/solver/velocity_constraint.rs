@@ -7,7 +7,10 @@ use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{WAngularInertia, WBasis, WCross, WDot};
 
-use super::{DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart};
+use super::{
+    DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    VelocityConstraintRollingPart,
+};
 
 //#[repr(align(64))]
 #[derive(Copy, Clone, Debug)]
@@ -76,6 +79,18 @@ impl AnyVelocityConstraint {
         }
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        match self {
+            AnyVelocityConstraint::NongroupedGround(c) => c.warmstart(mj_lambdas),
+            AnyVelocityConstraint::Nongrouped(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyVelocityConstraint::GroupedGround(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyVelocityConstraint::Grouped(c) => c.warmstart(mj_lambdas),
+            AnyVelocityConstraint::Empty => unreachable!(),
+        }
+    }
+
     pub fn writeback_impulses(&self, manifold_all: &mut [&mut ContactManifold]) {
         match self {
             AnyVelocityConstraint::NongroupedGround(c) => c.writeback_impulses(manifold_all),
@@ -94,6 +109,8 @@ pub(crate) struct VelocityConstraint {
     pub dir1: Vector<Real>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<Real>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_scale: [Real; 2], // The friction scaling factors along each friction direction.
     pub im1: Real,
     pub im2: Real,
     pub limit: Real,
@@ -103,6 +120,7 @@ pub(crate) struct VelocityConstraint {
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
     pub elements: [VelocityConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: Option<VelocityConstraintRollingPart<Real>>,
 }
 
 impl VelocityConstraint {
@@ -128,6 +146,9 @@ impl VelocityConstraint {
 
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.erp_inv_dt();
+        // Constraint force mixing making the normal constraints spring-like: the penetration
+        // they allow grows with the impulse they applied at the previous timestep.
+        let cfm = manifold.data.compliance * inv_dt * inv_dt;
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -143,8 +164,18 @@ impl VelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (friction_dir, friction_scale) = manifold
+            .data
+            .anisotropic_friction
+            .unwrap_or((Vector::zeros(), [1.0; 2]));
+        #[cfg(feature = "dim3")]
+        let (tangents1, friction_scale) = super::compute_anisotropic_tangent_contact_directions(
+            &force_dir1,
+            &friction_dir,
+            friction_scale,
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         for (_l, manifold_points) in manifold
             .data
@@ -157,6 +188,8 @@ impl VelocityConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_scale,
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im1: mprops1.effective_inv_mass,
                 im2: mprops2.effective_inv_mass,
@@ -166,6 +199,7 @@ impl VelocityConstraint {
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
                 num_contacts: manifold_points.len() as u8,
+                rolling_part: None,
             };
 
             // TODO: this is a WIP optimization for WASM platforms.
@@ -204,6 +238,7 @@ impl VelocityConstraint {
                 #[cfg(feature = "dim3")]
                 {
                     constraint.tangent1 = tangents1[0];
+                    constraint.friction_scale = friction_scale;
                 }
                 constraint.im1 = mprops1.effective_inv_mass;
                 constraint.im2 = mprops2.effective_inv_mass;
@@ -213,6 +248,7 @@ impl VelocityConstraint {
                 constraint.manifold_id = manifold_id;
                 constraint.manifold_contact_id = [0; MAX_MANIFOLD_POINTS];
                 constraint.num_contacts = manifold_points.len() as u8;
+                constraint.rolling_part = None;
             }
 
             for k in 0..manifold_points.len() {
@@ -239,7 +275,8 @@ impl VelocityConstraint {
                         / (mprops1.effective_inv_mass
                             + mprops2.effective_inv_mass
                             + gcross1.gdot(gcross1)
-                            + gcross2.gdot(gcross2));
+                            + gcross2.gdot(gcross2)
+                            + cfm);
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
                     let is_resting = 1.0 - is_bouncy;
@@ -251,14 +288,15 @@ impl VelocityConstraint {
                     rhs_wo_bias *= is_bouncy + is_resting * params.velocity_solve_fraction;
                     let rhs_bias = /* is_resting
                         * */  erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).min(0.0);
+                        * (manifold_point.dist + params.allowed_linear_error).min(0.0)
+                        + cfm * manifold_point.warmstart_impulse;
 
                     constraint.elements[k].normal_part = VelocityConstraintNormalPart {
                         gcross1,
                         gcross2,
                         rhs: rhs_wo_bias + rhs_bias,
                         rhs_wo_bias,
-                        impulse: 0.0,
+                        impulse: manifold_point.warmstart_impulse * params.warmstart_coefficient,
                         r,
                     };
                 }
@@ -290,6 +328,35 @@ impl VelocityConstraint {
                 }
             }
 
+            // Rolling and spinning friction part.
+            if manifold.data.has_rolling_friction() {
+                #[cfg(feature = "dim2")]
+                let axes = [1.0];
+                #[cfg(feature = "dim3")]
+                let axes = [tangents1[0], tangents1[1], force_dir1];
+
+                let gcross1 = axes.map(|axis| {
+                    mprops1
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(axis)
+                });
+                let gcross2 = axes.map(|axis| {
+                    mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-axis)
+                });
+                let rhs = axes.map(|axis| (vels1.angvel - vels2.angvel).gdot(axis));
+
+                constraint.rolling_part = Some(VelocityConstraintRollingPart::new(
+                    gcross1,
+                    gcross2,
+                    rhs,
+                    manifold.data.rolling_friction,
+                    #[cfg(feature = "dim3")]
+                    manifold.data.spinning_friction,
+                ));
+            }
+
             #[cfg(not(target_arch = "wasm32"))]
             if push {
                 out_constraints.push(AnyVelocityConstraint::Nongrouped(constraint));
@@ -314,11 +381,14 @@ impl VelocityConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_scale,
             self.im1,
             self.im2,
             self.limit,
             &mut mj_lambda1,
             &mut mj_lambda2,
+            self.rolling_part.as_mut(),
             solve_normal,
             solve_friction,
         );
@@ -327,6 +397,23 @@ impl VelocityConstraint {
         mj_lambdas[self.mj_lambda2 as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda1 = mj_lambdas[self.mj_lambda1];
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2];
+
+        VelocityConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            self.im1,
+            self.im2,
+            &mut mj_lambda1,
+            &mut mj_lambda2,
+        );
+
+        mj_lambdas[self.mj_lambda1] = mj_lambda1;
+        mj_lambdas[self.mj_lambda2] = mj_lambda2;
+    }
+
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
 
@@ -389,3 +476,106 @@ where
 
     [tangent1, bitangent1]
 }
+
+/// Computes the friction directions and the friction scaling factors along these directions.
+///
+/// If the principal friction direction `friction_dir` is not aligned with `force_dir1`, its
+/// projection on the contact plane is used as the first friction direction. Otherwise, the
+/// friction directions are the same as with `compute_tangent_contact_directions`, and the
+/// second friction scaling factor applies to both of them.
+#[inline(always)]
+#[cfg(feature = "dim3")]
+pub(crate) fn compute_anisotropic_tangent_contact_directions<N>(
+    force_dir1: &Vector<N>,
+    friction_dir: &Vector<N>,
+    friction_scale: [N; 2],
+    linvel1: &Vector<N>,
+    linvel2: &Vector<N>,
+) -> ([Vector<N>; DIM - 1], [N; DIM - 1])
+where
+    N: na::SimdRealField + Copy,
+    N::Element: na::RealField + Copy,
+    Vector<N>: WBasis,
+{
+    use na::SimdValue;
+
+    let [isotropic_tangent1, _] = compute_tangent_contact_directions(force_dir1, linvel1, linvel2);
+    let mut projected_friction_dir = friction_dir - force_dir1 * force_dir1.dot(friction_dir);
+
+    let projected_friction_dir_norm = {
+        let _disable_fe_except =
+            crate::utils::DisableFloatingPointExceptionsFlags::disable_floating_point_exceptions();
+        projected_friction_dir.normalize_mut()
+    };
+
+    let threshold: N::Element = na::convert(1.0e-4);
+    let use_friction_dir = projected_friction_dir_norm.simd_ge(N::splat(threshold));
+
+    let tangent1 = projected_friction_dir.select(use_friction_dir, isotropic_tangent1);
+    let bitangent1 = force_dir1.cross(&tangent1);
+    let friction_scale = [
+        friction_scale[0].select(use_friction_dir, friction_scale[1]),
+        friction_scale[1],
+    ];
+
+    ([tangent1, bitangent1], friction_scale)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn warmstarting_improves_stacking_convergence() {
+        // Measures how much a stack of boxes sinks, with and without warmstarting, when the
+        // solver only performs one velocity iteration per timestep.
+        let sinking = |warmstart_coefficient: Real| {
+            let mut world = TestWorld::new(Vector::y() * -9.81);
+            world.integration_parameters = IntegrationParameters {
+                max_velocity_iterations: 1,
+                warmstart_coefficient,
+                ..IntegrationParameters::default()
+            };
+
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world
+                .colliders
+                .insert(ground.translation(Vector::y() * -0.5).build());
+
+            let num_boxes = 10;
+            let mut top = None;
+            for k in 0..num_boxes {
+                let body = world.bodies.insert(
+                    RigidBodyBuilder::new_dynamic()
+                        .translation(Vector::y() * (k as Real + 0.5))
+                        .build(),
+                );
+                #[cfg(feature = "dim2")]
+                let cube = ColliderBuilder::cuboid(0.5, 0.5);
+                #[cfg(feature = "dim3")]
+                let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+                world
+                    .colliders
+                    .insert_with_parent(cube.build(), body, &mut world.bodies);
+                top = Some(body);
+            }
+
+            for _ in 0..120 {
+                world.step();
+            }
+
+            num_boxes as Real - 0.5 - world.bodies[top.unwrap()].translation().y
+        };
+
+        let cold = sinking(0.0);
+        let warm = sinking(0.8);
+        assert!(cold > 0.1);
+        assert!(warm < cold / 2.0);
+    }
+}
@@ -1,5 +1,5 @@
 use super::DeltaVel;
-use crate::math::{AngVector, Vector, DIM};
+use crate::math::{AngVector, Vector, ANG_DIM, DIM};
 use crate::utils::{WBasis, WDot};
 use na::SimdRealField;
 
@@ -33,6 +33,7 @@ impl<N: SimdRealField + Copy> VelocityConstraintTangentPart<N> {
         im1: N,
         im2: N,
         limit: N,
+        #[cfg(feature = "dim3")] friction_scale: [N; 2],
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
     ) where
@@ -78,7 +79,18 @@ impl<N: SimdRealField + Copy> VelocityConstraintTangentPart<N> {
                 let _disable_fe_except =
                     crate::utils::DisableFloatingPointExceptionsFlags::
                     disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
+                let limits = [limit * friction_scale[0], limit * friction_scale[1]];
+                let isotropic_impulse = new_impulse.simd_cap_magnitude(limits[1]);
+                // Anisotropic friction is clamped independently along each principal direction.
+                let is_anisotropic = friction_scale[0].simd_ne(friction_scale[1]);
+                na::Vector2::new(
+                    new_impulse[0]
+                        .simd_clamp(-limits[0], limits[0])
+                        .select(is_anisotropic, isotropic_impulse[0]),
+                    new_impulse[1]
+                        .simd_clamp(-limits[1], limits[1])
+                        .select(is_anisotropic, isotropic_impulse[1]),
+                )
             };
 
             let dlambda = new_impulse - self.impulse;
@@ -142,6 +154,117 @@ impl<N: SimdRealField + Copy> VelocityConstraintNormalPart<N> {
         mj_lambda2.linear += dir1 * (-im2 * dlambda);
         mj_lambda2.angular += self.gcross2 * dlambda;
     }
+
+    /// Applies the initial `self.impulse` to the delta-velocities.
+    #[inline]
+    pub fn warmstart(
+        &self,
+        dir1: &Vector<N>,
+        im1: N,
+        im2: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) {
+        mj_lambda1.linear += dir1 * (im1 * self.impulse);
+        mj_lambda1.angular += self.gcross1 * self.impulse;
+
+        mj_lambda2.linear += dir1 * (-im2 * self.impulse);
+        mj_lambda2.angular += self.gcross2 * self.impulse;
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VelocityConstraintRollingPart<N: SimdRealField + Copy> {
+    // In 3D, the two first axes are the rolling friction axes (the friction directions), and
+    // the last one is the spinning friction axis (the contact normal).
+    pub gcross1: [AngVector<N>; ANG_DIM],
+    pub gcross2: [AngVector<N>; ANG_DIM],
+    pub rhs: [N; ANG_DIM],
+    pub impulse: [N; ANG_DIM],
+    pub r: [N; ANG_DIM],
+    pub rolling_friction: N,
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: N,
+}
+
+impl<N: SimdRealField + Copy> VelocityConstraintRollingPart<N> {
+    pub fn new(
+        gcross1: [AngVector<N>; ANG_DIM],
+        gcross2: [AngVector<N>; ANG_DIM],
+        rhs: [N; ANG_DIM],
+        rolling_friction: N,
+        #[cfg(feature = "dim3")] spinning_friction: N,
+    ) -> Self
+    where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let mut r = [N::zero(); ANG_DIM];
+
+        for i in 0..ANG_DIM {
+            r[i] =
+                crate::utils::simd_inv(gcross1[i].gdot(gcross1[i]) + gcross2[i].gdot(gcross2[i]));
+        }
+
+        Self {
+            gcross1,
+            gcross2,
+            rhs,
+            impulse: [N::zero(); ANG_DIM],
+            r,
+            rolling_friction,
+            #[cfg(feature = "dim3")]
+            spinning_friction,
+        }
+    }
+
+    #[inline]
+    pub fn solve(
+        &mut self,
+        total_normal_impulse: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+        N::Element: SimdRealField + Copy,
+    {
+        let mut new_impulse = [N::zero(); ANG_DIM];
+
+        for i in 0..ANG_DIM {
+            let dimpulse = self.gcross1[i].gdot(mj_lambda1.angular)
+                + self.gcross2[i].gdot(mj_lambda2.angular)
+                + self.rhs[i];
+            new_impulse[i] = self.impulse[i] - self.r[i] * dimpulse;
+        }
+
+        let rolling_limit = self.rolling_friction * total_normal_impulse;
+
+        #[cfg(feature = "dim2")]
+        {
+            new_impulse[0] = new_impulse[0].simd_clamp(-rolling_limit, rolling_limit);
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            let rolling_impulse = {
+                let _disable_fe_except =
+                    crate::utils::DisableFloatingPointExceptionsFlags::
+                    disable_floating_point_exceptions();
+                na::Vector2::new(new_impulse[0], new_impulse[1]).simd_cap_magnitude(rolling_limit)
+            };
+            let spinning_limit = self.spinning_friction * total_normal_impulse;
+            new_impulse[0] = rolling_impulse[0];
+            new_impulse[1] = rolling_impulse[1];
+            new_impulse[2] = new_impulse[2].simd_clamp(-spinning_limit, spinning_limit);
+        }
+
+        for i in 0..ANG_DIM {
+            let dlambda = new_impulse[i] - self.impulse[i];
+            mj_lambda1.angular += self.gcross1[i] * dlambda;
+            mj_lambda2.angular += self.gcross2[i] * dlambda;
+        }
+
+        self.impulse[..ANG_DIM].copy_from_slice(&new_impulse[..ANG_DIM]);
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -158,16 +281,34 @@ impl<N: SimdRealField + Copy> VelocityConstraintElement<N> {
         }
     }
 
+    #[inline]
+    pub fn warmstart_group(
+        elements: &[Self],
+        dir1: &Vector<N>,
+        im1: N,
+        im2: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) {
+        for element in elements {
+            element
+                .normal_part
+                .warmstart(dir1, im1, im2, mj_lambda1, mj_lambda2);
+        }
+    }
+
     #[inline]
     pub fn solve_group(
         elements: &mut [Self],
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
+        #[cfg(feature = "dim3")] friction_scale: [N; 2],
         im1: N,
         im2: N,
         limit: N,
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
+        rolling_part: Option<&mut VelocityConstraintRollingPart<N>>,
         solve_normal: bool,
         solve_friction: bool,
     ) where
@@ -194,7 +335,24 @@ impl<N: SimdRealField + Copy> VelocityConstraintElement<N> {
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
-                part.solve(tangents1, im1, im2, limit, mj_lambda1, mj_lambda2);
+                part.solve(
+                    tangents1,
+                    im1,
+                    im2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_scale,
+                    mj_lambda1,
+                    mj_lambda2,
+                );
+            }
+
+            // Solve rolling and spinning friction.
+            if let Some(rolling_part) = rolling_part {
+                let total_normal_impulse = elements
+                    .iter()
+                    .fold(N::zero(), |acc, element| acc + element.normal_part.impulse);
+                rolling_part.solve(total_normal_impulse, mj_lambda1, mj_lambda2);
             }
         }
     }
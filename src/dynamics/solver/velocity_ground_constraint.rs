@@ -1,6 +1,6 @@
 use super::{
     AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    VelocityGroundConstraintNormalPart, VelocityGroundConstraintRollingPart,
 };
 use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
@@ -17,6 +17,8 @@ pub(crate) struct VelocityGroundConstraint {
     pub dir1: Vector<Real>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<Real>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_scale: [Real; 2], // The friction scaling factors along each friction direction.
     pub im2: Real,
     pub limit: Real,
     pub elements: [VelocityGroundConstraintElement<Real>; MAX_MANIFOLD_POINTS],
@@ -24,6 +26,7 @@ pub(crate) struct VelocityGroundConstraint {
     pub manifold_id: ContactManifoldIndex,
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
+    pub rolling_part: Option<VelocityGroundConstraintRollingPart<Real>>,
 }
 
 impl VelocityGroundConstraint {
@@ -41,6 +44,9 @@ impl VelocityGroundConstraint {
     {
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.erp_inv_dt();
+        // Constraint force mixing making the normal constraints spring-like: the penetration
+        // they allow grows with the impulse they applied at the previous timestep.
+        let cfm = manifold.data.compliance * inv_dt * inv_dt;
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -67,8 +73,18 @@ impl VelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (friction_dir, friction_scale) = manifold
+            .data
+            .anisotropic_friction
+            .unwrap_or((Vector::zeros(), [1.0; 2]));
+        #[cfg(feature = "dim3")]
+        let (tangents1, friction_scale) = super::compute_anisotropic_tangent_contact_directions(
+            &force_dir1,
+            &friction_dir,
+            friction_scale,
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let mj_lambda2 = ids2.active_set_offset;
 
@@ -83,6 +99,8 @@ impl VelocityGroundConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_scale,
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im2: mprops2.effective_inv_mass,
                 limit: 0.0,
@@ -90,6 +108,7 @@ impl VelocityGroundConstraint {
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
                 num_contacts: manifold_points.len() as u8,
+                rolling_part: None,
             };
 
             // TODO: this is a WIP optimization for WASM platforms.
@@ -128,6 +147,7 @@ impl VelocityGroundConstraint {
                 #[cfg(feature = "dim3")]
                 {
                     constraint.tangent1 = tangents1[0];
+                    constraint.friction_scale = friction_scale;
                 }
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.limit = 0.0;
@@ -135,6 +155,7 @@ impl VelocityGroundConstraint {
                 constraint.manifold_id = manifold_id;
                 constraint.manifold_contact_id = [0; MAX_MANIFOLD_POINTS];
                 constraint.num_contacts = manifold_points.len() as u8;
+                constraint.rolling_part = None;
             }
 
             for k in 0..manifold_points.len() {
@@ -153,7 +174,7 @@ impl VelocityGroundConstraint {
                         .effective_world_inv_inertia_sqrt
                         .transform_vector(dp2.gcross(-force_dir1));
 
-                    let r = 1.0 / (mprops2.effective_inv_mass + gcross2.gdot(gcross2));
+                    let r = 1.0 / (mprops2.effective_inv_mass + gcross2.gdot(gcross2) + cfm);
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
                     let is_resting = 1.0 - is_bouncy;
@@ -165,13 +186,14 @@ impl VelocityGroundConstraint {
                     rhs_wo_bias *= is_bouncy + is_resting * params.velocity_solve_fraction;
                     let rhs_bias = /* is_resting
                         * */ erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).min(0.0);
+                        * (manifold_point.dist + params.allowed_linear_error).min(0.0)
+                        + cfm * manifold_point.warmstart_impulse;
 
                     constraint.elements[k].normal_part = VelocityGroundConstraintNormalPart {
                         gcross2,
                         rhs: rhs_wo_bias + rhs_bias,
                         rhs_wo_bias,
-                        impulse: 0.0,
+                        impulse: manifold_point.warmstart_impulse * params.warmstart_coefficient,
                         r,
                     };
                 }
@@ -196,6 +218,29 @@ impl VelocityGroundConstraint {
                 }
             }
 
+            // Rolling and spinning friction part.
+            if manifold.data.has_rolling_friction() {
+                #[cfg(feature = "dim2")]
+                let axes = [1.0];
+                #[cfg(feature = "dim3")]
+                let axes = [tangents1[0], tangents1[1], force_dir1];
+
+                let gcross2 = axes.map(|axis| {
+                    mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-axis)
+                });
+                let rhs = axes.map(|axis| (vels1.angvel - vels2.angvel).gdot(axis));
+
+                constraint.rolling_part = Some(VelocityGroundConstraintRollingPart::new(
+                    gcross2,
+                    rhs,
+                    manifold.data.rolling_friction,
+                    #[cfg(feature = "dim3")]
+                    manifold.data.spinning_friction,
+                ));
+            }
+
             #[cfg(not(target_arch = "wasm32"))]
             if push {
                 out_constraints.push(AnyVelocityConstraint::NongroupedGround(constraint));
@@ -219,9 +264,12 @@ impl VelocityGroundConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_scale,
             self.im2,
             self.limit,
             &mut mj_lambda2,
+            self.rolling_part.as_mut(),
             solve_normal,
             solve_friction,
         );
@@ -229,6 +277,19 @@ impl VelocityGroundConstraint {
         mj_lambdas[self.mj_lambda2 as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2];
+
+        VelocityGroundConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            self.im2,
+            &mut mj_lambda2,
+        );
+
+        mj_lambdas[self.mj_lambda2] = mj_lambda2;
+    }
+
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
@@ -255,3 +316,53 @@ impl VelocityGroundConstraint {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn compliant_contacts() {
+        // Returns the penetration depth of a cube resting on the ground.
+        let penetration = |compliance: Real| {
+            let mut world = TestWorld::new(Vector::y() * -9.81);
+
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world
+                .colliders
+                .insert(ground.translation(Vector::y() * -0.5).build());
+
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * 0.5)
+                    .build(),
+            );
+            #[cfg(feature = "dim2")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            world.colliders.insert_with_parent(
+                cube.contact_compliance(compliance).build(),
+                body,
+                &mut world.bodies,
+            );
+
+            for _ in 0..100 {
+                world.step();
+            }
+
+            0.5 - world.bodies[body].translation().y
+        };
+
+        let hard = penetration(0.0);
+        let soft = penetration(1.0e-3);
+        assert!(hard < 0.01);
+        assert!(soft > hard * 2.0);
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    VelocityGroundConstraintNormalPart, VelocityGroundConstraintRollingPart,
 };
 use crate::data::ComponentSet;
 use crate::dynamics::{IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodyVelocity};
@@ -19,6 +19,8 @@ pub(crate) struct WVelocityGroundConstraint {
     pub dir1: Vector<SimdReal>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<SimdReal>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_scale: [SimdReal; 2], // The friction scaling factors along each friction direction.
     pub elements: [VelocityGroundConstraintElement<SimdReal>; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
     pub im2: SimdReal,
@@ -26,6 +28,7 @@ pub(crate) struct WVelocityGroundConstraint {
     pub mj_lambda2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
     pub manifold_contact_id: [[u8; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
+    pub rolling_part: Option<VelocityGroundConstraintRollingPart<SimdReal>>,
 }
 
 impl WVelocityGroundConstraint {
@@ -45,6 +48,7 @@ impl WVelocityGroundConstraint {
         let velocity_solve_fraction = SimdReal::splat(params.velocity_solve_fraction);
         let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
+        let warmstart_coefficient = SimdReal::splat(params.warmstart_coefficient);
 
         let mut handles1 = gather![|ii| manifolds[ii].data.rigid_body1];
         let mut handles2 = gather![|ii| manifolds[ii].data.rigid_body2];
@@ -98,7 +102,32 @@ impl WVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let friction_dir = Vector::from(gather![|ii| manifolds[ii]
+            .data
+            .anisotropic_friction
+            .map(|aniso| aniso.0)
+            .unwrap_or_else(Vector::zeros)]);
+        #[cfg(feature = "dim3")]
+        let friction_scale = [0, 1].map(|i| {
+            SimdReal::from(gather![|ii| manifolds[ii]
+                .data
+                .anisotropic_friction
+                .map(|aniso| aniso.1[i])
+                .unwrap_or(1.0)])
+        });
+        #[cfg(feature = "dim3")]
+        let (tangents1, friction_scale) = super::compute_anisotropic_tangent_contact_directions(
+            &force_dir1,
+            &friction_dir,
+            friction_scale,
+            &linvel1,
+            &linvel2,
+        );
+
+        let cfm = SimdReal::from(gather![|ii| manifolds[ii].data.compliance]) * inv_dt * inv_dt;
+        let rolling_friction = SimdReal::from(gather![|ii| manifolds[ii].data.rolling_friction]);
+        #[cfg(feature = "dim3")]
+        let spinning_friction = SimdReal::from(gather![|ii| manifolds[ii].data.spinning_friction]);
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points = gather![|ii| &manifolds[ii].data.solver_contacts[l..]];
@@ -108,6 +137,8 @@ impl WVelocityGroundConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_scale,
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im2,
                 limit: SimdReal::splat(0.0),
@@ -115,6 +146,7 @@ impl WVelocityGroundConstraint {
                 manifold_id,
                 manifold_contact_id: [[0; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
                 num_contacts: num_points as u8,
+                rolling_part: None,
             };
 
             for k in 0..num_points {
@@ -126,6 +158,8 @@ impl WVelocityGroundConstraint {
                 let is_resting = SimdReal::splat(1.0) - is_bouncy;
                 let point = Point::from(gather![|ii| manifold_points[ii][k].point]);
                 let dist = SimdReal::from(gather![|ii| manifold_points[ii][k].dist]);
+                let warmstart_impulse =
+                    SimdReal::from(gather![|ii| manifold_points[ii][k].warmstart_impulse]);
                 let tangent_velocity =
                     Vector::from(gather![|ii| manifold_points[ii][k].tangent_velocity]);
 
@@ -142,20 +176,21 @@ impl WVelocityGroundConstraint {
                 {
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
 
-                    let r = SimdReal::splat(1.0) / (im2 + gcross2.gdot(gcross2));
+                    let r = SimdReal::splat(1.0) / (im2 + gcross2.gdot(gcross2) + cfm);
                     let projected_velocity = (vel1 - vel2).dot(&force_dir1);
                     let mut rhs_wo_bias =
                         (SimdReal::splat(1.0) + is_bouncy * restitution) * projected_velocity;
                     rhs_wo_bias += (dist + allowed_lin_err).simd_max(SimdReal::zero()) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting * velocity_solve_fraction;
                     let rhs_bias = (dist + allowed_lin_err).simd_min(SimdReal::zero())
-                        * (erp_inv_dt/* * is_resting */);
+                        * (erp_inv_dt/* * is_resting */)
+                        + cfm * warmstart_impulse;
 
                     constraint.elements[k].normal_part = VelocityGroundConstraintNormalPart {
                         gcross2,
                         rhs: rhs_wo_bias + rhs_bias,
                         rhs_wo_bias,
-                        impulse: na::zero(),
+                        impulse: warmstart_impulse * warmstart_coefficient,
                         r,
                     };
                 }
@@ -174,6 +209,25 @@ impl WVelocityGroundConstraint {
                 }
             }
 
+            // Rolling and spinning friction part.
+            if manifolds.iter().any(|m| m.data.has_rolling_friction()) {
+                #[cfg(feature = "dim2")]
+                let axes = [SimdReal::splat(1.0)];
+                #[cfg(feature = "dim3")]
+                let axes = [tangents1[0], tangents1[1], force_dir1];
+
+                let gcross2 = axes.map(|axis| ii2.transform_vector(-axis));
+                let rhs = axes.map(|axis| (angvel1 - angvel2).gdot(axis));
+
+                constraint.rolling_part = Some(VelocityGroundConstraintRollingPart::new(
+                    gcross2,
+                    rhs,
+                    rolling_friction,
+                    #[cfg(feature = "dim3")]
+                    spinning_friction,
+                ));
+            }
+
             if push {
                 out_constraints.push(AnyVelocityConstraint::GroupedGround(constraint));
             } else {
@@ -201,9 +255,12 @@ impl WVelocityGroundConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_scale,
             self.im2,
             self.limit,
             &mut mj_lambda2,
+            self.rolling_part.as_mut(),
             solve_normal,
             solve_friction,
         );
@@ -214,6 +271,27 @@ impl WVelocityGroundConstraint {
         }
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = DeltaVel {
+            linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda2[ii] as usize].linear]),
+            angular: AngVector::from(gather![
+                |ii| mj_lambdas[self.mj_lambda2[ii] as usize].angular
+            ]),
+        };
+
+        VelocityGroundConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            self.im2,
+            &mut mj_lambda2,
+        );
+
+        for ii in 0..SIMD_WIDTH {
+            mj_lambdas[self.mj_lambda2[ii] as usize].linear = mj_lambda2.linear.extract(ii);
+            mj_lambdas[self.mj_lambda2[ii] as usize].angular = mj_lambda2.angular.extract(ii);
+        }
+    }
+
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         for k in 0..self.num_contacts as usize {
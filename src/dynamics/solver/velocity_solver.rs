@@ -16,6 +16,8 @@ use na::DVector;
 pub(crate) struct VelocitySolver {
     pub mj_lambdas: Vec<DeltaVel<Real>>,
     pub generic_mj_lambdas: DVector<Real>,
+    prev_mj_lambdas: Vec<DeltaVel<Real>>,   // Workspace.
+    prev_generic_mj_lambdas: DVector<Real>, // Workspace.
 }
 
 impl VelocitySolver {
@@ -23,9 +25,15 @@ impl VelocitySolver {
         Self {
             mj_lambdas: Vec::new(),
             generic_mj_lambdas: DVector::zeros(0),
+            prev_mj_lambdas: Vec::new(),
+            prev_generic_mj_lambdas: DVector::zeros(0),
         }
     }
 
+    /// Solves the constraints of the given island.
+    ///
+    /// Returns the root-mean-square velocity correction applied to the bodies by the last
+    /// velocity iteration.
     pub fn solve<Bodies>(
         &mut self,
         island_id: usize,
@@ -40,7 +48,8 @@ impl VelocitySolver {
         generic_contact_jacobians: &DVector<Real>,
         joint_constraints: &mut [AnyJointVelocityConstraint],
         generic_joint_jacobians: &DVector<Real>,
-    ) where
+    ) -> Real
+    where
         Bodies: ComponentSet<RigidBodyForces>
             + ComponentSet<RigidBodyIds>
             + ComponentSet<RigidBodyType>
@@ -77,10 +86,37 @@ impl VelocitySolver {
             mj_lambdas.axpy(params.dt, &multibody.accelerations, 0.0);
         }
 
+        /*
+         * Warmstart constraints.
+         */
+        if params.warmstart_coefficient != 0.0 {
+            for constraint in &*contact_constraints {
+                constraint.warmstart(&mut self.mj_lambdas[..]);
+            }
+
+            for constraint in &*generic_contact_constraints {
+                constraint.warmstart(
+                    generic_contact_jacobians,
+                    &mut self.mj_lambdas[..],
+                    &mut self.generic_mj_lambdas,
+                );
+            }
+        }
+
         /*
          * Solve constraints.
          */
+        let mut velocity_error = 0.0;
+
         for i in 0..params.max_velocity_iterations {
+            let is_last_iteration = i + 1 == params.max_velocity_iterations;
+
+            if is_last_iteration {
+                self.prev_mj_lambdas.clone_from(&self.mj_lambdas);
+                self.prev_generic_mj_lambdas
+                    .clone_from(&self.generic_mj_lambdas);
+            }
+
             let solve_friction = params.interleave_restitution_and_friction_resolution
                 && params.max_velocity_friction_iterations + i >= params.max_velocity_iterations;
 
@@ -105,6 +141,10 @@ impl VelocitySolver {
                     solve_friction,
                 );
             }
+
+            if is_last_iteration {
+                velocity_error = self.velocity_correction_rms();
+            }
         }
 
         let remaining_friction_iterations =
@@ -173,7 +213,7 @@ impl VelocitySolver {
                 let mut new_vels = *vels;
                 new_vels.linvel += dvel.linear;
                 new_vels.angvel += dangvel;
-                new_vels = new_vels.apply_damping(params.dt, damping);
+                new_vels = new_vels.apply_damping(params.dt, damping, &poss.position.rotation);
                 new_poss.next_position =
                     new_vels.integrate(params.dt, &poss.position, &mprops.local_mprops.local_com);
                 bodies.set_internal(handle.0, new_poss);
@@ -274,5 +314,27 @@ impl VelocitySolver {
         for constraint in &*generic_contact_constraints {
             constraint.writeback_impulses(manifolds_all);
         }
+
+        velocity_error
+    }
+
+    /// The root-mean-square of the delta-velocity changes since the last
+    /// copy to `prev_mj_lambdas`.
+    fn velocity_correction_rms(&self) -> Real {
+        let num_bodies = self.mj_lambdas.len();
+
+        if num_bodies == 0 {
+            return 0.0;
+        }
+
+        let mut sq_norm = (&self.generic_mj_lambdas - &self.prev_generic_mj_lambdas).norm_squared();
+
+        for (dvel, prev_dvel) in self.mj_lambdas.iter().zip(self.prev_mj_lambdas.iter()) {
+            for (a, b) in dvel.as_slice().iter().zip(prev_dvel.as_slice().iter()) {
+                sq_norm += (a - b) * (a - b);
+            }
+        }
+
+        (sq_norm / num_bodies as Real).sqrt()
     }
 }
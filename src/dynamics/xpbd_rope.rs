@@ -0,0 +1,455 @@
+use crate::dynamics::{RigidBodyHandle, RigidBodySet};
+use crate::geometry::{ColliderHandle, ColliderSet};
+use crate::math::{Point, Real, Vector};
+use crate::pipeline::QueryPipeline;
+use crate::utils::{WCross, WDot};
+use parry::bounding_volume::BoundingVolume;
+use parry::shape::{Capsule, Shape};
+
+/// The attachment of a point of an `XpbdRope` to a rigid-body.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RopeAttachment {
+    /// The index of the attached point of the rope.
+    pub point: usize,
+    /// The rigid-body the point is attached to.
+    pub body: RigidBodyHandle,
+    /// The attachment point, in the local-space of the rigid-body.
+    pub local_anchor: Point<Real>,
+    /// The impulse applied by the rope to the rigid-body during the last call to `XpbdRope::step`.
+    pub impulse: Vector<Real>,
+}
+
+/// A rope simulated as a chain of particles with Extended Position-Based Dynamics (XPBD).
+///
+/// The rope isn't part of the rigid-body simulation: `Self::step` must be called after each
+/// timestep, once the `QueryPipeline` has been updated. It collides with the colliders of the
+/// scene, and applies impulses to the dynamic rigid-bodies it is attached to or pushes against.
+/// Because XPBD corrects the positions directly, stiff ropes remain stable without requiring
+/// small timesteps.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct XpbdRope {
+    /// The world-space positions of the particles of the rope.
+    pub points: Vec<Point<Real>>,
+    /// The rest length of the segments between two consecutive points.
+    pub segment_length: Real,
+    /// The stiffness of the segments, i.e., the inverse of their compliance.
+    pub stiffness: Real,
+    /// The mass of one meter of rope.
+    pub mass_per_meter: Real,
+    /// The thickness of the rope used for collision detection.
+    pub radius: Real,
+    /// Damping factor slowing down the motion of the particles.
+    pub damping: Real,
+    /// The number of substeps performed by each call to `Self::step`.
+    pub num_substeps: usize,
+    velocities: Vec<Vector<Real>>,
+    attachments: Vec<RopeAttachment>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    prev_points: Vec<Point<Real>>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    anchor_offsets: Vec<Vector<Real>>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    candidates: Vec<(usize, ColliderHandle)>, // Workspace.
+}
+
+impl XpbdRope {
+    /// Creates a rope at rest made of `num_segments` segments between `start` and `end`.
+    pub fn new(
+        start: Point<Real>,
+        end: Point<Real>,
+        num_segments: usize,
+        stiffness: Real,
+        mass_per_meter: Real,
+    ) -> Self {
+        assert!(num_segments > 0, "A rope needs at least one segment.");
+
+        let points = (0..=num_segments)
+            .map(|i| start + (end - start) * (i as Real / num_segments as Real))
+            .collect();
+
+        Self {
+            points,
+            segment_length: na::distance(&start, &end) / num_segments as Real,
+            stiffness,
+            mass_per_meter,
+            radius: 0.05,
+            damping: 0.0,
+            num_substeps: 10,
+            velocities: vec![Vector::zeros(); num_segments + 1],
+            attachments: Vec::new(),
+            prev_points: Vec::new(),
+            anchor_offsets: Vec::new(),
+            candidates: Vec::new(),
+        }
+    }
+
+    /// The velocities of the particles of the rope.
+    pub fn velocities(&self) -> &[Vector<Real>] {
+        &self.velocities
+    }
+
+    /// The attachments of this rope to rigid-bodies.
+    pub fn attachments(&self) -> &[RopeAttachment] {
+        &self.attachments
+    }
+
+    /// Attaches the given point of the rope to a rigid-body.
+    ///
+    /// The anchor is expressed in the local-space of the rigid-body.
+    pub fn attach(&mut self, point: usize, body: RigidBodyHandle, local_anchor: Point<Real>) {
+        assert!(point < self.points.len(), "Invalid rope point index.");
+        self.attachments.push(RopeAttachment {
+            point,
+            body,
+            local_anchor,
+            impulse: Vector::zeros(),
+        });
+    }
+
+    /// Removes all the attachments of the given point of the rope.
+    pub fn detach(&mut self, point: usize) {
+        self.attachments.retain(|a| a.point != point);
+    }
+
+    /// The mass of the `i`-th particle of the rope.
+    fn particle_mass(&self, i: usize) -> Real {
+        let mass = self.mass_per_meter * self.segment_length;
+        if i == 0 || i == self.points.len() - 1 {
+            mass / 2.0
+        } else {
+            mass
+        }
+    }
+
+    /// Advances the simulation of the rope by `dt`.
+    ///
+    /// The attached points follow the current poses of their rigid-bodies, and the reaction
+    /// impulses are applied to the dynamic rigid-bodies, so they affect their next timestep.
+    pub fn step(
+        &mut self,
+        gravity: &Vector<Real>,
+        dt: Real,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        query_pipeline: &QueryPipeline,
+    ) {
+        self.attachments
+            .retain(|a| bodies.get(a.body).is_some() && a.point < self.points.len());
+        self.velocities.resize(self.points.len(), Vector::zeros());
+
+        if self.points.is_empty() || self.num_substeps == 0 || dt <= 0.0 {
+            return;
+        }
+
+        for attachment in &mut self.attachments {
+            attachment.impulse = Vector::zeros();
+        }
+
+        self.anchor_offsets.clear();
+        self.anchor_offsets
+            .resize(self.attachments.len(), Vector::zeros());
+        let anchor_velocities: Vec<_> = self
+            .attachments
+            .iter()
+            .map(|a| {
+                let body = &bodies[a.body];
+                body.velocity_at_point(&(body.position() * a.local_anchor))
+            })
+            .collect();
+
+        let h = dt / self.num_substeps as Real;
+        let inv_masses: Vec<Real> = (0..self.points.len())
+            .map(|i| crate::utils::inv(self.particle_mass(i)))
+            .collect();
+        let compliance = crate::utils::inv(self.stiffness) / (h * h);
+        let mut contact_impulses = Vec::new();
+        self.collect_contact_candidates(gravity, dt, colliders, query_pipeline);
+
+        for _ in 0..self.num_substeps {
+            self.prev_points.clone_from(&self.points);
+
+            for (point, vel) in self.points.iter_mut().zip(self.velocities.iter_mut()) {
+                *vel += gravity * h;
+                *vel /= 1.0 + h * self.damping;
+                *point += *vel * h;
+            }
+
+            // Distance constraints.
+            for i in 0..self.points.len() - 1 {
+                let (w1, w2) = (inv_masses[i], inv_masses[i + 1]);
+                let delta = self.points[i + 1] - self.points[i];
+                let length = delta.norm();
+
+                if length <= crate::math::DEFAULT_EPSILON {
+                    continue;
+                }
+
+                let dir = delta / length;
+                let lambda = (length - self.segment_length) / (w1 + w2 + compliance);
+                self.points[i] += dir * (w1 * lambda);
+                self.points[i + 1] -= dir * (w2 * lambda);
+            }
+
+            // Attachments. The rigid-bodies only move during the next timestep, with the
+            // velocity they have at the end of this step. So their anchors are extrapolated
+            // from their initial velocities, and the impulses applied to them are accounted
+            // for as displacements over the whole step, i.e., `num_substeps` substeps.
+            for ((attachment, offset), vel) in self
+                .attachments
+                .iter_mut()
+                .zip(self.anchor_offsets.iter_mut())
+                .zip(anchor_velocities.iter())
+            {
+                *offset += vel * h;
+
+                let body = &mut bodies[attachment.body];
+                let anchor = body.position() * attachment.local_anchor;
+                let point = &mut self.points[attachment.point];
+                let delta = *point - (anchor + *offset);
+                let dist = delta.norm();
+
+                if dist <= crate::math::DEFAULT_EPSILON {
+                    continue;
+                }
+
+                let dir = delta / dist;
+                let body_inv_mass = if body.is_dynamic() {
+                    let gcross = body.rb_mprops.effective_world_inv_inertia_sqrt
+                        * (anchor - body.rb_mprops.world_com).gcross(dir);
+                    (body.rb_mprops.effective_inv_mass + gcross.gdot(gcross))
+                        * self.num_substeps as Real
+                } else {
+                    0.0
+                };
+                let lambda = dist / (inv_masses[attachment.point] + body_inv_mass);
+                *point -= dir * (inv_masses[attachment.point] * lambda);
+                *offset += dir * (body_inv_mass * lambda);
+                body.apply_impulse_at_point(dir * (lambda / h), anchor, true);
+                attachment.impulse += dir * (lambda / h);
+            }
+
+            self.solve_contacts(
+                h,
+                &inv_masses,
+                colliders,
+                query_pipeline,
+                &mut contact_impulses,
+            );
+
+            for ((vel, point), prev) in self
+                .velocities
+                .iter_mut()
+                .zip(self.points.iter())
+                .zip(self.prev_points.iter())
+            {
+                *vel = (point - prev) / h;
+            }
+        }
+
+        for (handle, impulse, point) in contact_impulses {
+            if let Some(body) = colliders.get(handle).and_then(|co| co.parent()) {
+                bodies[body].apply_impulse_at_point(impulse, point, true);
+            }
+        }
+    }
+
+    /// Collects the colliders each segment of the rope may touch during the next `dt`.
+    ///
+    /// The colliders of the rigid-bodies the rope is attached to, and the sensors, are ignored.
+    fn collect_contact_candidates(
+        &mut self,
+        gravity: &Vector<Real>,
+        dt: Real,
+        colliders: &ColliderSet,
+        query_pipeline: &QueryPipeline,
+    ) {
+        self.candidates.clear();
+
+        for i in 0..self.points.len() - 1 {
+            let max_speed = self.velocities[i].norm().max(self.velocities[i + 1].norm());
+            let margin = self.radius + max_speed * dt + gravity.norm() * dt * dt;
+            let segment = Capsule::new(self.points[i], self.points[i + 1], self.radius);
+            let aabb = segment.compute_local_aabb().loosened(margin);
+            let candidates = &mut self.candidates;
+            let attachments = &self.attachments;
+
+            query_pipeline.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+                if let Some(co) = colliders.get(*handle) {
+                    let attached = co
+                        .parent()
+                        .map(|parent| attachments.iter().any(|a| a.body == parent))
+                        .unwrap_or(false);
+
+                    if !co.is_sensor() && !attached {
+                        candidates.push((i, *handle));
+                    }
+                }
+                true
+            });
+        }
+    }
+
+    /// Pushes the segments of the rope out of the candidate colliders they penetrate, with friction.
+    fn solve_contacts(
+        &mut self,
+        h: Real,
+        inv_masses: &[Real],
+        colliders: &ColliderSet,
+        query_pipeline: &QueryPipeline,
+        contact_impulses: &mut Vec<(ColliderHandle, Vector<Real>, Point<Real>)>,
+    ) {
+        let dispatcher = query_pipeline.query_dispatcher();
+
+        for (i, handle) in &self.candidates {
+            let i = *i;
+            let co = &colliders[*handle];
+            let segment = Capsule::new(self.points[i], self.points[i + 1], self.radius);
+            let contact = match dispatcher.contact(co.position(), &segment, co.shape(), 0.0) {
+                Ok(Some(contact)) if contact.dist < 0.0 => contact,
+                _ => continue,
+            };
+
+            // The contact normal, pointing from the collider toward the rope.
+            let normal = -contact.normal1.into_inner();
+            let depth = -contact.dist;
+            let (a, b) = (self.points[i], self.points[i + 1]);
+            let ab = b - a;
+            let t = if ab.norm_squared() > 0.0 {
+                ((contact.point1 - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            let (w1, w2) = (inv_masses[i] * (1.0 - t), inv_masses[i + 1] * t);
+            let inv_mass = w1 * (1.0 - t) + w2 * t;
+
+            if inv_mass <= 0.0 {
+                continue;
+            }
+
+            // Friction cancels the tangential motion of the contact point during this
+            // substep, up to the friction cone.
+            let motion = (a - self.prev_points[i]) * (1.0 - t) + (b - self.prev_points[i + 1]) * t;
+            let tangential = motion - normal * motion.dot(&normal);
+            let tangential_norm = tangential.norm();
+            let max_friction = co.friction() * depth;
+            let friction = if tangential_norm > max_friction {
+                tangential * (max_friction / tangential_norm)
+            } else {
+                tangential
+            };
+
+            let correction = (normal * depth - friction) / inv_mass;
+            self.points[i] += correction * w1;
+            self.points[i + 1] += correction * w2;
+
+            let contact_point = co.position() * contact.point2;
+            contact_impulses.push((*handle, -correction / h, contact_point));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::XpbdRope;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::QueryPipeline;
+
+    #[test]
+    fn xpbd_ropes_hold_bodies_and_collide() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let mut query_pipeline = QueryPipeline::new();
+        let dt = world.integration_parameters.dt;
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(15.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(15.0, 0.1, 15.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // A weight hanging from a ceiling by a rope.
+        let ceiling = world.bodies.insert(
+            RigidBodyBuilder::new_static()
+                .translation(Vector::x() * 10.0 + Vector::y() * 5.0)
+                .build(),
+        );
+        let weight = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 10.0 + Vector::y() * 2.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.2).density(1.0).build(),
+            weight,
+            &mut world.bodies,
+        );
+
+        let mut hanging_rope = XpbdRope::new(
+            Point::from(Vector::x() * 10.0 + Vector::y() * 5.0),
+            Point::from(Vector::x() * 10.0 + Vector::y() * 2.2),
+            10,
+            1.0e6,
+            0.5,
+        );
+        hanging_rope.attach(0, ceiling, Point::origin());
+        hanging_rope.attach(10, weight, Point::from(Vector::y() * 0.2));
+
+        // A rope falling on the ground.
+        let mut falling_rope = XpbdRope::new(
+            Point::from(Vector::x() * -3.0 + Vector::y() * 1.0),
+            Point::from(Vector::x() * 3.0 + Vector::y() * 2.0),
+            10,
+            1.0e6,
+            0.5,
+        );
+        falling_rope.damping = 0.5;
+        let mut settled_y = 0.0;
+
+        for i in 0..200 {
+            if i == 150 {
+                settled_y = world.bodies[weight].translation().y;
+            }
+
+            world.step();
+            query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+            for rope in [&mut hanging_rope, &mut falling_rope] {
+                rope.step(
+                    &world.gravity,
+                    dt,
+                    &mut world.bodies,
+                    &world.colliders,
+                    &query_pipeline,
+                );
+            }
+        }
+
+        // The rope carries the weight, and remains close to its rest length.
+        let weight_y = world.bodies[weight].translation().y;
+        assert!((weight_y - 2.0).abs() < 0.02);
+        assert!((weight_y - settled_y).abs() < 1.0e-3);
+        let weight_impulse = world.bodies[weight].mass() * 9.81 * dt;
+        let attachments = hanging_rope.attachments();
+        assert!((attachments[1].impulse.y - weight_impulse).abs() < weight_impulse * 0.05);
+        let rope_impulse = hanging_rope.mass_per_meter * 2.8 * 9.81 * dt;
+        assert!((attachments[0].impulse.y + weight_impulse + rope_impulse).abs() < 1.0e-2);
+
+        for segment in hanging_rope.points.windows(2) {
+            let length = na::distance(&segment[0], &segment[1]);
+            assert!((length - hanging_rope.segment_length).abs() < 0.01);
+        }
+
+        // The falling rope rests on the ground.
+        for (point, vel) in falling_rope.points.iter().zip(falling_rope.velocities()) {
+            assert!((point.y - 0.1 - falling_rope.radius).abs() < 0.01);
+            assert!(vel.norm() < 0.05);
+        }
+    }
+}
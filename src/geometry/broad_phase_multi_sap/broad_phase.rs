@@ -3,7 +3,8 @@ use super::{
 };
 use crate::geometry::broad_phase_multi_sap::SAPProxyIndex;
 use crate::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderShape,
+    ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderHandle, ColliderPosition,
+    ColliderShape, SpatialHashBroadPhase, AABB,
 };
 use crate::math::Real;
 use crate::utils::IndexMut2;
@@ -92,6 +93,10 @@ pub struct BroadPhase {
     //       just use a Coarena. But this seems like it could use too
     //       much memory.
     colliders_proxy_ids: HashMap<ColliderHandle, SAPProxyIndex>,
+    // The pairs of colliders with intersecting AABBs that haven't been reported to the
+    // narrow-phase because their collision groups are incompatible. They are reported
+    // if the groups of one of the colliders are changed to compatible ones.
+    pruned_pairs: HashMap<ColliderPair, ()>,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     region_pool: SAPRegionPool, // To avoid repeated allocations.
     // We could think serializing this workspace is useless.
@@ -113,6 +118,8 @@ pub struct BroadPhase {
         )
     )]
     reporting: HashMap<(u32, u32), bool>, // Workspace
+    // If set, this replaces the Hierarchical-SAP altogether.
+    spatial_hash: Option<SpatialHashBroadPhase>,
 }
 
 impl Default for BroadPhase {
@@ -132,6 +139,44 @@ impl BroadPhase {
             region_pool: Vec::new(),
             reporting: HashMap::default(),
             colliders_proxy_ids: HashMap::default(),
+            pruned_pairs: HashMap::default(),
+            spatial_hash: None,
+        }
+    }
+
+    /// Create a new empty broad-phase relying on a spatial hash instead of the
+    /// Hierarchical-SAP.
+    ///
+    /// See [`SpatialHashBroadPhase`] for details about when this is beneficial.
+    pub fn with_spatial_hash(cell_size: Real) -> Self {
+        BroadPhase {
+            spatial_hash: Some(SpatialHashBroadPhase::new(cell_size)),
+            ..Self::new()
+        }
+    }
+
+    /// The spatial hash used by this broad-phase, if it was created with
+    /// [`BroadPhase::with_spatial_hash`].
+    pub fn spatial_hash(&self) -> Option<&SpatialHashBroadPhase> {
+        self.spatial_hash.as_ref()
+    }
+
+    /// Calls `f` with the handle and the AABB of every collider with an AABB intersecting
+    /// `aabb`.
+    ///
+    /// The AABBs of the colliders are the ones computed during the last update of this
+    /// broad-phase, so they are slightly enlarged. A collider may be reported several times.
+    pub fn colliders_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        mut f: impl FnMut(ColliderHandle, &AABB),
+    ) {
+        if let Some(spatial_hash) = &self.spatial_hash {
+            spatial_hash.colliders_intersecting_aabb(aabb, f);
+        } else {
+            for layer in &self.layers {
+                layer.for_each_collider_intersecting_aabb(aabb, &self.proxies, &mut f);
+            }
         }
     }
 
@@ -147,6 +192,53 @@ impl BroadPhase {
                 self.predelete_proxy(proxy_id);
             }
         }
+
+        if !removed_colliders.is_empty() && !self.pruned_pairs.is_empty() {
+            self.pruned_pairs.retain(|pair, _| {
+                !removed_colliders.contains(&pair.collider1)
+                    && !removed_colliders.contains(&pair.collider2)
+            });
+        }
+    }
+
+    /// Reports the pruned pairs involving a collider which collision groups changed, if
+    /// they are now compatible.
+    fn handle_modified_groups<Colliders>(
+        &mut self,
+        colliders: &Colliders,
+        handle: ColliderHandle,
+        proxy_index: SAPProxyIndex,
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSet<ColliderFlags>,
+    {
+        let co_flags: &ColliderFlags = colliders.index(handle.0);
+
+        if let Some(proxy) = self.proxies.get_mut(proxy_index) {
+            proxy.groups = co_flags.collision_groups;
+        }
+
+        self.pruned_pairs.retain(|pair, _| {
+            let other = if pair.collider1 == handle {
+                pair.collider2
+            } else if pair.collider2 == handle {
+                pair.collider1
+            } else {
+                return true;
+            };
+
+            let other_flags: Option<&ColliderFlags> = colliders.get(other.0);
+
+            match other_flags {
+                Some(other_flags)
+                    if co_flags.collision_groups.test(other_flags.collision_groups) =>
+                {
+                    events.push(BroadPhasePairEvent::AddPair(*pair));
+                    false
+                }
+                _ => true,
+            }
+        });
     }
 
     /// Pre-deletes a proxy from this broad-phase.
@@ -345,13 +437,19 @@ impl BroadPhase {
         prediction_distance: Real,
         handle: ColliderHandle,
         proxy_index: &mut u32,
-        collider: (&ColliderPosition, &ColliderShape, &ColliderChanges),
+        collider: (
+            &ColliderPosition,
+            &ColliderShape,
+            &ColliderFlags,
+            &ColliderChanges,
+            &ColliderBroadPhaseData,
+        ),
     ) -> bool {
-        let (co_pos, co_shape, co_changes) = collider;
+        let (co_pos, co_shape, co_flags, co_changes, co_bf_data) = collider;
 
         let mut aabb = co_shape
             .compute_aabb(co_pos)
-            .loosened(prediction_distance / 2.0);
+            .loosened(prediction_distance / 2.0 + co_bf_data.speculative_margin);
 
         aabb.mins = super::clamp_point(aabb.mins);
         aabb.maxs = super::clamp_point(aabb.maxs);
@@ -385,7 +483,13 @@ impl BroadPhase {
             let layer_id = self.ensure_layer_exists(layer_depth);
 
             // Create the proxy.
-            let proxy = SAPProxy::collider(handle, aabb, layer_id, layer_depth);
+            let proxy = SAPProxy::collider(
+                handle,
+                aabb,
+                co_flags.collision_groups,
+                layer_id,
+                layer_depth,
+            );
             prev_aabb = aabb;
             *proxy_index = self.proxies.insert(proxy);
             layer_id
@@ -435,6 +539,8 @@ impl BroadPhase {
     }
 
     /// Updates the broad-phase, taking into account the new collider positions.
+    ///
+    /// The pairs of colliders with incompatible collision groups are not reported.
     pub fn update<Colliders>(
         &mut self,
         prediction_distance: Real,
@@ -446,8 +552,19 @@ impl BroadPhase {
         Colliders: ComponentSetMut<ColliderBroadPhaseData>
             + ComponentSet<ColliderChanges>
             + ComponentSet<ColliderPosition>
-            + ComponentSet<ColliderShape>,
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>,
     {
+        if let Some(spatial_hash) = &mut self.spatial_hash {
+            return spatial_hash.update(
+                prediction_distance,
+                colliders,
+                modified_colliders,
+                removed_colliders,
+                events,
+            );
+        }
+
         // Phase 1: pre-delete the collisions that have been deleted.
         self.handle_removed_colliders(removed_colliders);
 
@@ -460,14 +577,19 @@ impl BroadPhase {
             let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
 
             if let Some(co_changes) = co_changes {
-                let (co_bf_data, co_pos, co_shape): (
+                let (co_bf_data, co_pos, co_shape, co_flags): (
                     &ColliderBroadPhaseData,
                     &ColliderPosition,
                     &ColliderShape,
+                    &ColliderFlags,
                 ) = colliders.index_bundle(handle.0);
 
+                if co_changes.contains(ColliderChanges::GROUPS) {
+                    self.handle_modified_groups(colliders, *handle, co_bf_data.proxy_index, events);
+                }
+
                 if !co_changes.needs_broad_phase_update() {
-                    return;
+                    continue;
                 }
                 let mut new_proxy_id = co_bf_data.proxy_index;
 
@@ -475,7 +597,7 @@ impl BroadPhase {
                     prediction_distance,
                     *handle,
                     &mut new_proxy_id,
-                    (co_pos, co_shape, co_changes),
+                    (co_pos, co_shape, co_flags, co_changes, co_bf_data),
                 ) {
                     need_region_propagation = true;
                 }
@@ -489,6 +611,7 @@ impl BroadPhase {
                         handle.0,
                         ColliderBroadPhaseData {
                             proxy_index: new_proxy_id,
+                            ..*co_bf_data
                         },
                     );
                 }
@@ -578,14 +701,16 @@ impl BroadPhase {
 
                 match (&mut proxy1.data, &mut proxy2.data) {
                     (SAPProxyData::Collider(handle1), SAPProxyData::Collider(handle2)) => {
+                        let pair = ColliderPair::new(*handle1, *handle2);
+
                         if *colliding {
-                            out_events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
-                                *handle1, *handle2,
-                            )));
-                        } else {
-                            out_events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
-                                *handle1, *handle2,
-                            )));
+                            if proxy1.groups.test(proxy2.groups) {
+                                out_events.push(BroadPhasePairEvent::AddPair(pair));
+                            } else {
+                                let _ = self.pruned_pairs.insert(pruned_pair_key(pair), ());
+                            }
+                        } else if self.pruned_pairs.remove(&pruned_pair_key(pair)).is_none() {
+                            out_events.push(BroadPhasePairEvent::DeletePair(pair));
                         }
                     }
                     (SAPProxyData::Collider(_), SAPProxyData::Region(_)) => {
@@ -618,12 +743,24 @@ impl BroadPhase {
     }
 }
 
+/// The pairs may be reported in any order by the regions, so sort them before
+/// using them as keys of the pruned pairs.
+fn pruned_pair_key(pair: ColliderPair) -> ColliderPair {
+    if pair.collider1.0.into_raw_parts() > pair.collider2.0.into_raw_parts() {
+        pair.swap()
+    } else {
+        pair
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet};
+    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, InteractionGroups};
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
 
     #[test]
     fn test_add_update_remove() {
@@ -660,4 +797,50 @@ mod test {
         // Make sure the proxy handles is recycled properly.
         broad_phase.update(0.0, &mut colliders, &[coh], &[], &mut events);
     }
+
+    #[test]
+    fn broad_phase_prunes_pairs_with_incompatible_groups() {
+        for broad_phase in [BroadPhase::new(), BroadPhase::with_spatial_hash(1.0)] {
+            let mut world = TestWorld::new(Vector::zeros());
+            world.broad_phase = broad_phase;
+
+            let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+            #[cfg(feature = "dim2")]
+            let ground_collider = ColliderBuilder::cuboid(1.0, 1.0);
+            #[cfg(feature = "dim3")]
+            let ground_collider = ColliderBuilder::cuboid(1.0, 1.0, 1.0);
+            let ground_collider = ground_collider
+                .collision_groups(InteractionGroups::new(0b01, 0b01))
+                .build();
+            let ground_collider =
+                world
+                    .colliders
+                    .insert_with_parent(ground_collider, ground, &mut world.bodies);
+
+            let body = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+            let collider = ColliderBuilder::ball(0.5)
+                .collision_groups(InteractionGroups::new(0b10, 0b10))
+                .build();
+            let collider = world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+
+            world.step();
+            assert!(world
+                .narrow_phase
+                .contact_pair(ground_collider, collider)
+                .is_none());
+
+            // The pruned pair must be reported once the groups become compatible.
+            world.colliders[collider].set_collision_groups(InteractionGroups::new(0b11, 0b11));
+            world.step();
+            assert!(
+                world
+                    .narrow_phase
+                    .contact_pair(ground_collider, collider)
+                    .unwrap()
+                    .has_any_active_contact
+            );
+        }
+    }
 }
@@ -1,6 +1,6 @@
-use super::{SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
+use super::{SAPProxies, SAPProxy, SAPProxyData, SAPRegion, SAPRegionPool};
 use crate::geometry::broad_phase_multi_sap::DELETED_AABB_VALUE;
-use crate::geometry::{SAPProxyIndex, AABB};
+use crate::geometry::{ColliderHandle, SAPProxyIndex, AABB};
 use crate::math::{Point, Real};
 use parry::bounding_volume::BoundingVolume;
 use parry::utils::hashmap::{Entry, HashMap};
@@ -66,6 +66,72 @@ impl SAPLayer {
         }
     }
 
+    /// Calls `f` with the handle and the AABB of the collider proxies of this layer with an AABB
+    /// intersecting `aabb`.
+    ///
+    /// A collider intersecting several regions of this layer may be reported several times.
+    pub fn for_each_collider_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        proxies: &SAPProxies,
+        mut f: impl FnMut(ColliderHandle, &AABB),
+    ) {
+        let mins = super::point_key(aabb.mins, self.region_width);
+        let maxs = super::point_key(aabb.maxs, self.region_width);
+        let num_regions = (maxs - mins)
+            .iter()
+            .fold(1.0, |num, extent| num * (*extent as Real + 1.0));
+
+        let mut report = |region_id: SAPProxyIndex| {
+            let region = proxies[region_id].data.as_region();
+
+            for endpoint in &region.axes[0].endpoints {
+                if endpoint.is_start() && !endpoint.is_sentinel() {
+                    let proxy = &proxies[endpoint.proxy()];
+
+                    if let SAPProxyData::Collider(handle) = proxy.data {
+                        if proxy.aabb.intersects(aabb) {
+                            f(handle, &proxy.aabb);
+                        }
+                    }
+                }
+            }
+        };
+
+        if num_regions > self.regions.len() as Real {
+            // Cheaper than looking up every region covered by the AABB.
+            for (key, region_id) in &self.regions {
+                if key
+                    .iter()
+                    .zip(mins.iter().zip(maxs.iter()))
+                    .all(|(k, (a, b))| a <= k && k <= b)
+                {
+                    report(*region_id);
+                }
+            }
+        } else {
+            #[cfg(feature = "dim2")]
+            let k_range = 0..1;
+            #[cfg(feature = "dim3")]
+            let k_range = mins.z..=maxs.z;
+
+            for i in mins.x..=maxs.x {
+                for j in mins.y..=maxs.y {
+                    for _k in k_range.clone() {
+                        #[cfg(feature = "dim2")]
+                        let key = Point::new(i, j);
+                        #[cfg(feature = "dim3")]
+                        let key = Point::new(i, j, _k);
+
+                        if let Some(region_id) = self.regions.get(&key) {
+                            report(*region_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Register into `larger_layer` all the region proxies of the recently-created regions
     /// contained by `self`.
     ///
@@ -1,6 +1,6 @@
 use super::NEXT_FREE_SENTINEL;
 use crate::geometry::broad_phase_multi_sap::SAPRegion;
-use crate::geometry::ColliderHandle;
+use crate::geometry::{ColliderHandle, InteractionGroups};
 use parry::bounding_volume::AABB;
 use std::ops::{Index, IndexMut};
 
@@ -52,6 +52,8 @@ impl SAPProxyData {
 pub struct SAPProxy {
     pub data: SAPProxyData,
     pub aabb: AABB,
+    // The collision groups of the collider, used to prune the pairs that can't interact.
+    pub groups: InteractionGroups,
     pub next_free: SAPProxyIndex,
     // TODO: pack the layer_id and layer_depth into a single u16?
     pub layer_id: u8,
@@ -59,10 +61,17 @@ pub struct SAPProxy {
 }
 
 impl SAPProxy {
-    pub fn collider(handle: ColliderHandle, aabb: AABB, layer_id: u8, layer_depth: i8) -> Self {
+    pub fn collider(
+        handle: ColliderHandle,
+        aabb: AABB,
+        groups: InteractionGroups,
+        layer_id: u8,
+        layer_depth: i8,
+    ) -> Self {
         Self {
             data: SAPProxyData::Collider(handle),
             aabb,
+            groups,
             next_free: NEXT_FREE_SENTINEL,
             layer_id,
             layer_depth,
@@ -73,6 +82,7 @@ impl SAPProxy {
         Self {
             data: SAPProxyData::Region(Some(subregion)),
             aabb,
+            groups: InteractionGroups::all(),
             next_free: NEXT_FREE_SENTINEL,
             layer_id,
             layer_depth,
@@ -0,0 +1,351 @@
+use crate::data::{BundleSet, ComponentSet};
+use crate::geometry::{
+    BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderHandle,
+    ColliderPair, ColliderPosition, ColliderShape, InteractionGroups, AABB,
+};
+use crate::math::{Point, Real};
+use parry::bounding_volume::BoundingVolume;
+use parry::utils::hashmap::HashMap;
+
+/// The number of cells, along any axis, above which an AABB is no longer discretized
+/// on the grid.
+const MAX_CELLS_PER_AXIS: i32 = 10;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+struct SpatialHashProxy {
+    aabb: AABB,
+    // The range of cells covered by the AABB, or `None` for large proxies.
+    cells: Option<(Point<i32>, Point<i32>)>,
+    groups: InteractionGroups,
+    // The colliders with an AABB currently intersecting this proxy's AABB, including
+    // the ones with incompatible collision groups.
+    neighbors: Vec<ColliderHandle>,
+}
+
+/// A broad-phase based on a uniform grid with hashed cells.
+///
+/// Each collider is registered into all the grid cells intersected by its AABB, so this works
+/// best when most colliders have a size similar to the cell size, which is typically the case
+/// of particles or voxels. Colliders spanning more than 10 cells along any axis are not
+/// discretized on the grid: they are tested against all the other colliders instead. This
+/// keeps the memory usage bounded, but becomes slow if there are many such large colliders.
+///
+/// Use [`BroadPhase::with_spatial_hash`](crate::geometry::BroadPhase::with_spatial_hash) to
+/// run a simulation with this broad-phase.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct SpatialHashBroadPhase {
+    cell_size: Real,
+    proxies: HashMap<ColliderHandle, SpatialHashProxy>,
+    cells: HashMap<Point<i32>, Vec<ColliderHandle>>,
+    large_proxies: Vec<ColliderHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    candidates: Vec<ColliderHandle>, // Workspace.
+}
+
+impl SpatialHashBroadPhase {
+    /// Creates a new empty spatial-hash broad-phase with the given cell size.
+    pub fn new(cell_size: Real) -> Self {
+        assert!(cell_size > 0.0, "The cell size must be positive.");
+
+        Self {
+            cell_size,
+            proxies: HashMap::default(),
+            cells: HashMap::default(),
+            large_proxies: Vec::new(),
+            candidates: Vec::new(),
+        }
+    }
+
+    /// The size of the cells of the grid.
+    pub fn cell_size(&self) -> Real {
+        self.cell_size
+    }
+
+    /// Updates the broad-phase, taking into account the new collider positions.
+    ///
+    /// The pairs of colliders with incompatible collision groups are not reported.
+    pub fn update<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSet<ColliderChanges>
+            + ComponentSet<ColliderBroadPhaseData>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>,
+    {
+        for handle in removed_colliders {
+            self.remove_proxy(*handle, events);
+        }
+
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
+
+            if let Some(co_changes) = co_changes {
+                let co_flags: &ColliderFlags = colliders.index(handle.0);
+
+                if co_changes.contains(ColliderChanges::GROUPS) {
+                    self.update_groups(*handle, co_flags.collision_groups, events);
+                }
+
+                if !co_changes.needs_broad_phase_update() {
+                    continue;
+                }
+
+                let (co_pos, co_shape, co_bf_data): (
+                    &ColliderPosition,
+                    &ColliderShape,
+                    &ColliderBroadPhaseData,
+                ) = colliders.index_bundle(handle.0);
+                let aabb = co_shape
+                    .compute_aabb(co_pos)
+                    .loosened(prediction_distance / 2.0 + co_bf_data.speculative_margin);
+
+                self.update_proxy(*handle, aabb, co_flags.collision_groups);
+                self.find_pairs(*handle, events);
+            }
+        }
+    }
+
+    /// Calls `f` with the handle and the AABB of every collider with an AABB intersecting
+    /// `aabb`. A collider may be reported several times.
+    pub(crate) fn colliders_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        mut f: impl FnMut(ColliderHandle, &AABB),
+    ) {
+        let mut report = |handle: &ColliderHandle| {
+            if let Some(proxy) = self.proxies.get(handle) {
+                if proxy.aabb.intersects(aabb) {
+                    f(*handle, &proxy.aabb);
+                }
+            }
+        };
+
+        if let Some(range) = self.cell_range(aabb) {
+            Self::for_each_cell(range, |key| {
+                if let Some(cell) = self.cells.get(&key) {
+                    cell.iter().for_each(&mut report);
+                }
+            });
+            self.large_proxies.iter().for_each(&mut report);
+        } else {
+            self.proxies.keys().for_each(report);
+        }
+    }
+
+    fn cell_range(&self, aabb: &AABB) -> Option<(Point<i32>, Point<i32>)> {
+        let mins = aabb.mins / self.cell_size;
+        let maxs = aabb.maxs / self.cell_size;
+
+        if (maxs - mins).max() >= MAX_CELLS_PER_AXIS as Real {
+            None
+        } else {
+            Some((
+                mins.map(|e| e.floor() as i32),
+                maxs.map(|e| e.floor() as i32),
+            ))
+        }
+    }
+
+    fn for_each_cell(range: (Point<i32>, Point<i32>), mut f: impl FnMut(Point<i32>)) {
+        let (mins, maxs) = range;
+
+        #[cfg(feature = "dim2")]
+        let k_range = 0..1;
+        #[cfg(feature = "dim3")]
+        let k_range = mins.z..=maxs.z;
+
+        for i in mins.x..=maxs.x {
+            for j in mins.y..=maxs.y {
+                for _k in k_range.clone() {
+                    #[cfg(feature = "dim2")]
+                    let key = Point::new(i, j);
+                    #[cfg(feature = "dim3")]
+                    let key = Point::new(i, j, _k);
+                    f(key)
+                }
+            }
+        }
+    }
+
+    fn register(&mut self, handle: ColliderHandle, cells: Option<(Point<i32>, Point<i32>)>) {
+        if let Some(range) = cells {
+            let grid = &mut self.cells;
+            Self::for_each_cell(range, |key| grid.entry(key).or_default().push(handle));
+        } else {
+            self.large_proxies.push(handle);
+        }
+    }
+
+    fn unregister(&mut self, handle: ColliderHandle, cells: Option<(Point<i32>, Point<i32>)>) {
+        if let Some(range) = cells {
+            let grid = &mut self.cells;
+            Self::for_each_cell(range, |key| {
+                if let Some(cell) = grid.get_mut(&key) {
+                    remove_handle(cell, handle);
+
+                    if cell.is_empty() {
+                        grid.remove(&key);
+                    }
+                }
+            });
+        } else {
+            remove_handle(&mut self.large_proxies, handle);
+        }
+    }
+
+    fn update_proxy(&mut self, handle: ColliderHandle, aabb: AABB, groups: InteractionGroups) {
+        let cells = self.cell_range(&aabb);
+
+        if let Some(proxy) = self.proxies.get_mut(&handle) {
+            let prev_cells = proxy.cells;
+            proxy.aabb = aabb;
+            proxy.cells = cells;
+            proxy.groups = groups;
+
+            if prev_cells != cells {
+                self.unregister(handle, prev_cells);
+                self.register(handle, cells);
+            }
+        } else {
+            let proxy = SpatialHashProxy {
+                aabb,
+                cells,
+                groups,
+                neighbors: Vec::new(),
+            };
+            self.proxies.insert(handle, proxy);
+            self.register(handle, cells);
+        }
+    }
+
+    /// Reports the pairs with the neighbors of this proxy that become compatible with its
+    /// new collision groups.
+    ///
+    /// The pairs that were already reported are reported again, which is harmless.
+    fn update_groups(
+        &mut self,
+        handle: ColliderHandle,
+        groups: InteractionGroups,
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        if let Some(proxy) = self.proxies.get_mut(&handle) {
+            proxy.groups = groups;
+            let proxy = &self.proxies[&handle];
+
+            for neighbor in &proxy.neighbors {
+                if let Some(neighbor_proxy) = self.proxies.get(neighbor) {
+                    if groups.test(neighbor_proxy.groups) {
+                        events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
+                            handle, *neighbor,
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_proxy(&mut self, handle: ColliderHandle, events: &mut Vec<BroadPhasePairEvent>) {
+        if let Some(proxy) = self.proxies.remove(&handle) {
+            self.unregister(handle, proxy.cells);
+
+            for neighbor in proxy.neighbors {
+                if let Some(neighbor_proxy) = self.proxies.get_mut(&neighbor) {
+                    remove_handle(&mut neighbor_proxy.neighbors, handle);
+                }
+
+                events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
+                    handle, neighbor,
+                )));
+            }
+        }
+    }
+
+    fn find_pairs(&mut self, handle: ColliderHandle, events: &mut Vec<BroadPhasePairEvent>) {
+        let (aabb, cells, groups) = {
+            let proxy = &self.proxies[&handle];
+            (proxy.aabb, proxy.cells, proxy.groups)
+        };
+
+        // Collect all the colliders that may intersect this proxy.
+        let mut candidates = std::mem::take(&mut self.candidates);
+        candidates.clear();
+
+        if let Some(range) = cells {
+            let grid = &self.cells;
+            Self::for_each_cell(range, |key| {
+                if let Some(cell) = grid.get(&key) {
+                    candidates.extend_from_slice(cell);
+                }
+            });
+            candidates.extend_from_slice(&self.large_proxies);
+            // A proxy may be registered into several of these cells.
+            candidates.sort_unstable_by_key(|h| h.0);
+            candidates.dedup();
+        } else {
+            candidates.extend(self.proxies.keys().copied());
+        }
+
+        for candidate in &candidates {
+            if *candidate == handle {
+                continue;
+            }
+
+            let other = self.proxies.get_mut(candidate).unwrap();
+
+            if aabb.intersects(&other.aabb) && !other.neighbors.contains(&handle) {
+                other.neighbors.push(handle);
+                let compatible = groups.test(other.groups);
+                self.proxies
+                    .get_mut(&handle)
+                    .unwrap()
+                    .neighbors
+                    .push(*candidate);
+
+                if compatible {
+                    events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
+                        handle, *candidate,
+                    )));
+                }
+            }
+        }
+
+        self.candidates = candidates;
+
+        // Remove the pairs with colliders that are no longer intersecting.
+        let mut i = 0;
+        while i < self.proxies[&handle].neighbors.len() {
+            let neighbor = self.proxies[&handle].neighbors[i];
+            let neighbor_proxy = self.proxies.get_mut(&neighbor).unwrap();
+
+            if aabb.intersects(&neighbor_proxy.aabb) {
+                i += 1;
+            } else {
+                remove_handle(&mut neighbor_proxy.neighbors, handle);
+                self.proxies
+                    .get_mut(&handle)
+                    .unwrap()
+                    .neighbors
+                    .swap_remove(i);
+                events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
+                    handle, neighbor,
+                )));
+            }
+        }
+    }
+}
+
+fn remove_handle(handles: &mut Vec<ColliderHandle>, handle: ColliderHandle) {
+    if let Some(i) = handles.iter().position(|h| *h == handle) {
+        let _ = handles.swap_remove(i);
+    }
+}
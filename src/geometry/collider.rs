@@ -2,14 +2,20 @@ use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
 use crate::geometry::{
     ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
     ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition, ColliderShape,
-    ColliderType, InteractionGroups, SharedShape,
+    ColliderType, Ellipsoid, HeightField, InteractionGroups, SharedShape,
 };
-use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
+#[cfg(feature = "dim3")]
+use crate::geometry::{AnisotropicFriction, HeightFieldCellStatus, Torus};
+use crate::math::{AngVector, Isometry, Point, Real, Rotation, UnitVector, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
 use na::Unit;
 use parry::bounding_volume::AABB;
-use parry::shape::Shape;
+#[cfg(feature = "dim2")]
+use parry::shape::ConvexPolygon;
+#[cfg(feature = "dim3")]
+use parry::shape::ConvexPolyhedron;
+use parry::shape::{Cuboid, HalfSpace, Shape, TypedShape};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
@@ -26,6 +32,8 @@ pub struct Collider {
     pub(crate) co_material: ColliderMaterial,
     pub(crate) co_flags: ColliderFlags,
     pub(crate) co_bf_data: ColliderBroadPhaseData,
+    pub(crate) scale: Vector<Real>,
+    pub(crate) unscaled_shape: Option<SharedShape>,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -56,6 +64,16 @@ impl Collider {
         self.co_flags.active_hooks = active_hooks;
     }
 
+    /// The user-defined data associated to this collider.
+    pub fn user_data(&self) -> u128 {
+        self.user_data
+    }
+
+    /// Sets the user-defined data associated to this collider.
+    pub fn set_user_data(&mut self, data: u128) {
+        self.user_data = data;
+    }
+
     /// The events enabled for this collider.
     pub fn active_events(&self) -> ActiveEvents {
         self.co_flags.active_events
@@ -100,6 +118,70 @@ impl Collider {
         self.co_material.friction_combine_rule = rule;
     }
 
+    /// The rolling friction coefficient of this collider.
+    pub fn rolling_friction(&self) -> Real {
+        self.co_material.rolling_friction
+    }
+
+    /// Sets the rolling friction coefficient of this collider.
+    ///
+    /// This is ignored by the contacts involving a multibody link.
+    pub fn set_rolling_friction(&mut self, coefficient: Real) {
+        self.co_material.rolling_friction = coefficient
+    }
+
+    /// The compliance of the contacts involving this collider.
+    pub fn contact_compliance(&self) -> Real {
+        self.co_material.contact_compliance
+    }
+
+    /// Sets the compliance of the contacts involving this collider.
+    ///
+    /// A compliance of `0.0` results in hard contacts, and larger values result in softer contacts.
+    pub fn set_contact_compliance(&mut self, compliance: Real) {
+        self.co_material.contact_compliance = compliance
+    }
+
+    /// The spinning friction coefficient of this collider.
+    #[cfg(feature = "dim3")]
+    pub fn spinning_friction(&self) -> Real {
+        self.co_material.spinning_friction
+    }
+
+    /// Sets the spinning friction coefficient of this collider.
+    ///
+    /// This is ignored by the contacts involving a multibody link.
+    #[cfg(feature = "dim3")]
+    pub fn set_spinning_friction(&mut self, coefficient: Real) {
+        self.co_material.spinning_friction = coefficient
+    }
+
+    /// The direction-dependent friction coefficients of this collider, if any.
+    #[cfg(feature = "dim3")]
+    pub fn anisotropic_friction(&self) -> Option<&AnisotropicFriction> {
+        self.co_material.anisotropic_friction.as_ref()
+    }
+
+    /// Sets the direction-dependent friction coefficients of this collider.
+    ///
+    /// Set this to `None` to use the isotropic `friction` coefficient instead.
+    #[cfg(feature = "dim3")]
+    pub fn set_anisotropic_friction(&mut self, friction: Option<AnisotropicFriction>) {
+        self.co_material.anisotropic_friction = friction;
+    }
+
+    /// The local-space direction along which this one-way collider blocks other colliders, if any.
+    pub fn one_way_direction(&self) -> Option<UnitVector<Real>> {
+        self.co_material.one_way_direction
+    }
+
+    /// Sets the local-space direction along which this collider blocks other colliders.
+    ///
+    /// Set this to `None` to make the collider block other colliders in all directions.
+    pub fn set_one_way_direction(&mut self, direction: Option<UnitVector<Real>>) {
+        self.co_material.one_way_direction = direction;
+    }
+
     /// The restitution coefficient of this collider.
     pub fn restitution(&self) -> Real {
         self.co_material.restitution
@@ -249,17 +331,100 @@ impl Collider {
     /// If that shape is shared by multiple colliders, it will be
     /// cloned first so that `self` contains a unique copy of that
     /// shape that you can modify.
+    ///
+    /// The modified shape is considered unscaled, i.e., this resets the scale
+    /// of this collider to 1.
     pub fn shape_mut(&mut self) -> &mut dyn Shape {
         self.co_changes.insert(ColliderChanges::SHAPE);
+        self.reset_scale();
         self.co_shape.make_mut()
     }
 
     /// Sets the shape of this collider.
+    ///
+    /// The new shape is considered unscaled, i.e., this resets the scale
+    /// of this collider to 1.
     pub fn set_shape(&mut self, shape: SharedShape) {
         self.co_changes.insert(ColliderChanges::SHAPE);
+        self.reset_scale();
         self.co_shape = shape;
     }
 
+    /// The scale applied to the shape of this collider along each axis of its local frame.
+    pub fn scale(&self) -> &Vector<Real> {
+        &self.scale
+    }
+
+    /// Scales the shape of this collider along each axis of its local frame.
+    ///
+    /// The scale is applied to the shape this collider had before its first call to
+    /// `set_scale`, so successive calls don't accumulate. Balls become ellipsoids if the
+    /// scale isn't uniform. The radii of capsules, cylinders and cones are scaled by the
+    /// largest scale component orthogonal to their axis.
+    ///
+    /// Returns `false`, and leaves the shape unchanged, if that shape cannot be scaled (round
+    /// and custom shapes other than ellipsoids). As for `set_shape`, the mass properties of
+    /// the rigid-body this collider is attached to are not updated.
+    pub fn set_scale(&mut self, scale: Vector<Real>) -> bool {
+        let unscaled_shape = self.unscaled_shape.as_ref().unwrap_or(&self.co_shape);
+
+        if let Some(scaled_shape) = scale_shape(&**unscaled_shape, &scale) {
+            if self.unscaled_shape.is_none() {
+                self.unscaled_shape = Some(self.co_shape.clone());
+            }
+
+            self.co_changes.insert(ColliderChanges::SHAPE);
+            self.co_shape = scaled_shape;
+            self.scale = scale;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn reset_scale(&mut self) {
+        self.scale = Vector::repeat(1.0);
+        self.unscaled_shape = None;
+    }
+
+    /// Enables or disables the given heightfield segment.
+    ///
+    /// Disabled segments are ignored by contact generation and scene queries. This does
+    /// nothing if the shape of this collider isn't a heightfield.
+    #[cfg(feature = "dim2")]
+    pub fn set_heightfield_cell_active(&mut self, i: usize, active: bool) {
+        if self.co_shape.as_heightfield().is_some() {
+            self.co_changes.insert(ColliderChanges::SHAPE);
+
+            // Keep the unscaled heightfield in sync so the cell stays removed after rescaling.
+            for shape in std::iter::once(&mut self.co_shape).chain(self.unscaled_shape.as_mut()) {
+                if let Some(heightfield) = shape.make_mut().as_heightfield_mut() {
+                    heightfield.set_segment_removed(i, !active);
+                }
+            }
+        }
+    }
+
+    /// Enables or disables the heightfield cell at the given row and column.
+    ///
+    /// Disabled cells are ignored by contact generation and scene queries. This does
+    /// nothing if the shape of this collider isn't a heightfield.
+    #[cfg(feature = "dim3")]
+    pub fn set_heightfield_cell_active(&mut self, i: usize, j: usize, active: bool) {
+        if self.co_shape.as_heightfield().is_some() {
+            self.co_changes.insert(ColliderChanges::SHAPE);
+
+            // Keep the unscaled heightfield in sync so the cell stays removed after rescaling.
+            for shape in std::iter::once(&mut self.co_shape).chain(self.unscaled_shape.as_mut()) {
+                if let Some(heightfield) = shape.make_mut().as_heightfield_mut() {
+                    let mut status = heightfield.cell_status(i, j);
+                    status.set(HeightFieldCellStatus::CELL_REMOVED, !active);
+                    heightfield.set_cell_status(i, j, status);
+                }
+            }
+        }
+    }
+
     /// Retrieve the SharedShape. Also see the `shape()` function
     pub fn shared_shape(&self) -> &SharedShape {
         &self.co_shape
@@ -279,10 +444,7 @@ impl Collider {
 
     /// Compute the local-space mass properties of this collider.
     pub fn mass_properties(&self) -> MassProperties {
-        match &self.co_mprops {
-            ColliderMassProps::Density(density) => self.co_shape.mass_properties(*density),
-            ColliderMassProps::MassProperties(mass_properties) => **mass_properties,
-        }
+        self.co_mprops.mass_properties(&*self.co_shape)
     }
 }
 
@@ -302,6 +464,19 @@ pub struct ColliderBuilder {
     pub friction: Real,
     /// The rule used to combine two friction coefficients.
     pub friction_combine_rule: CoefficientCombineRule,
+    /// The rolling friction coefficient of the collider to be built.
+    pub rolling_friction: Real,
+    /// The spinning friction coefficient of the collider to be built.
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: Real,
+    /// The direction-dependent friction coefficients of the collider to be built.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// The local-space direction along which the collider to be built blocks other colliders, if
+    /// it is a one-way collider.
+    pub one_way_direction: Option<UnitVector<Real>>,
+    /// The compliance of the contacts involving the collider to be built.
+    pub contact_compliance: Real,
     /// The restitution coefficient of the collider to be built.
     pub restitution: Real,
     /// The rule used to combine two restitution coefficients.
@@ -339,6 +514,13 @@ impl ColliderBuilder {
             collision_groups: InteractionGroups::all(),
             solver_groups: InteractionGroups::all(),
             friction_combine_rule: CoefficientCombineRule::Average,
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            spinning_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
+            one_way_direction: None,
+            contact_compliance: 0.0,
             restitution_combine_rule: CoefficientCombineRule::Average,
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
@@ -363,14 +545,16 @@ impl ColliderBuilder {
     }
 
     /// Initialize a new collider builder with a cylindrical shape defined by its half-height
-    /// (along along the y axis) and its radius.
+    /// (along the y axis) and its radius.
+    ///
+    /// Unlike a capsule, this cylinder has flat end-caps.
     #[cfg(feature = "dim3")]
     pub fn cylinder(half_height: Real, radius: Real) -> Self {
         Self::new(SharedShape::cylinder(half_height, radius))
     }
 
     /// Initialize a new collider builder with a rounded cylindrical shape defined by its half-height
-    /// (along along the y axis), its radius, and its roundedness (the
+    /// (along the y axis), its radius, and its roundedness (the
     /// radius of the sphere used for dilating the cylinder).
     #[cfg(feature = "dim3")]
     pub fn round_cylinder(half_height: Real, radius: Real, border_radius: Real) -> Self {
@@ -382,20 +566,39 @@ impl ColliderBuilder {
     }
 
     /// Initialize a new collider builder with a cone shape defined by its half-height
-    /// (along along the y axis) and its basis radius.
+    /// (along the y axis) and its basis radius.
     #[cfg(feature = "dim3")]
     pub fn cone(half_height: Real, radius: Real) -> Self {
         Self::new(SharedShape::cone(half_height, radius))
     }
 
     /// Initialize a new collider builder with a rounded cone shape defined by its half-height
-    /// (along along the y axis), its radius, and its roundedness (the
-    /// radius of the sphere used for dilating the cylinder).
+    /// (along the y axis), its radius, and its roundedness (the
+    /// radius of the sphere used for dilating the cone).
     #[cfg(feature = "dim3")]
     pub fn round_cone(half_height: Real, radius: Real, border_radius: Real) -> Self {
         Self::new(SharedShape::round_cone(half_height, radius, border_radius))
     }
 
+    /// Initialize a new collider builder with an ellipse shape defined by its semi-axes.
+    #[cfg(feature = "dim2")]
+    pub fn ellipse(semi_axes: Vector<Real>) -> Self {
+        Self::new(SharedShape::new(Ellipsoid::new(semi_axes)))
+    }
+
+    /// Initialize a new collider builder with an ellipsoid shape defined by its semi-axes.
+    #[cfg(feature = "dim3")]
+    pub fn ellipsoid(semi_axes: Vector<Real>) -> Self {
+        Self::new(SharedShape::new(Ellipsoid::new(semi_axes)))
+    }
+
+    /// Initialize a new collider builder with a torus shape defined by the radius of its main
+    /// circle (lying on the `xz` plane) and the radius of its tube.
+    #[cfg(feature = "dim3")]
+    pub fn torus(main_radius: Real, tube_radius: Real) -> Self {
+        Self::new(SharedShape::new(Torus::new(main_radius, tube_radius)))
+    }
+
     /// Initialize a new collider builder with a cuboid shape defined by its half-extents.
     #[cfg(feature = "dim2")]
     pub fn cuboid(hx: Real, hy: Real) -> Self {
@@ -582,6 +785,59 @@ impl ColliderBuilder {
         Self::new(SharedShape::heightfield(heights, scale))
     }
 
+    /// Initializes a collider builder with a heightfield shape where the segments with
+    /// a `false` entry in `mask` are removed.
+    ///
+    /// The `mask` must have one entry per heightfield segment, i.e., one less than `heights`.
+    #[cfg(feature = "dim2")]
+    pub fn heightfield_with_mask(
+        heights: na::DVector<Real>,
+        mask: na::DVector<bool>,
+        scale: Vector<Real>,
+    ) -> Self {
+        assert_eq!(
+            mask.len() + 1,
+            heights.len(),
+            "The heightfield mask must have one entry per segment."
+        );
+        let mut heightfield = HeightField::new(heights, scale);
+
+        for (i, active) in mask.iter().enumerate() {
+            heightfield.set_segment_removed(i, !*active);
+        }
+
+        Self::new(SharedShape::new(heightfield))
+    }
+
+    /// Initializes a collider builder with a heightfield shape where the cells with
+    /// a `false` entry in `mask` are removed.
+    ///
+    /// The `mask` must have one entry per heightfield cell, i.e., one less row and column
+    /// than `heights`.
+    #[cfg(feature = "dim3")]
+    pub fn heightfield_with_mask(
+        heights: na::DMatrix<Real>,
+        mask: na::DMatrix<bool>,
+        scale: Vector<Real>,
+    ) -> Self {
+        assert_eq!(
+            (mask.nrows() + 1, mask.ncols() + 1),
+            heights.shape(),
+            "The heightfield mask must have one entry per cell."
+        );
+        let mut heightfield = HeightField::new(heights, scale);
+
+        for i in 0..mask.nrows() {
+            for j in 0..mask.ncols() {
+                if !mask[(i, j)] {
+                    heightfield.set_cell_status(i, j, HeightFieldCellStatus::CELL_REMOVED);
+                }
+            }
+        }
+
+        Self::new(SharedShape::new(heightfield))
+    }
+
     /// The default friction coefficient used by the collider builder.
     pub fn default_friction() -> Real {
         0.5
@@ -655,6 +911,57 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the rolling friction coefficient of the collider this builder will build.
+    ///
+    /// This is ignored by the contacts involving a multibody link.
+    pub fn rolling_friction(mut self, rolling_friction: Real) -> Self {
+        self.rolling_friction = rolling_friction;
+        self
+    }
+
+    /// Sets the spinning friction coefficient of the collider this builder will build.
+    ///
+    /// This is ignored by the contacts involving a multibody link.
+    #[cfg(feature = "dim3")]
+    pub fn spinning_friction(mut self, spinning_friction: Real) -> Self {
+        self.spinning_friction = spinning_friction;
+        self
+    }
+
+    /// Sets the direction-dependent friction coefficients of the collider this builder will build.
+    ///
+    /// The principal friction direction `local_axis` is expressed in the collider's local-space.
+    #[cfg(feature = "dim3")]
+    pub fn anisotropic_friction(
+        mut self,
+        local_axis: UnitVector<Real>,
+        coefficients: [Real; 2],
+    ) -> Self {
+        self.anisotropic_friction = Some(AnisotropicFriction::new(local_axis, coefficients));
+        self
+    }
+
+    /// Makes the collider this builder will build a one-way collider, e.g., a jump-through platform.
+    ///
+    /// The collider will only block the colliders touching it from the side pointed by the
+    /// local-space direction `normal`. Colliders coming from any other side pass through it,
+    /// and keep doing so until they are no longer penetrating it.
+    pub fn one_way_direction(mut self, normal: UnitVector<Real>) -> Self {
+        self.one_way_direction = Some(normal);
+        self
+    }
+
+    /// Sets the compliance of the contacts involving the collider this builder will build.
+    ///
+    /// The compliance is the inverse of the contact stiffness. It is used as a constraint force
+    /// mixing term of the contact constraints along their normal, making them spring-like. A
+    /// compliance of `0.0` results in hard contacts, and larger values result in softer, but more
+    /// stable, contacts.
+    pub fn contact_compliance(mut self, compliance: Real) -> Self {
+        self.contact_compliance = compliance;
+        self
+    }
+
     /// Sets the restitution coefficient of the collider this builder will build.
     pub fn restitution(mut self, restitution: Real) -> Self {
         self.restitution = restitution;
@@ -741,6 +1048,8 @@ impl ColliderBuilder {
             co_bf_data,
             co_flags,
             co_type,
+            scale: Vector::repeat(1.0),
+            unscaled_shape: None,
             user_data: self.user_data,
         }
     }
@@ -773,6 +1082,13 @@ impl ColliderBuilder {
             restitution: self.restitution,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
+            rolling_friction: self.rolling_friction,
+            #[cfg(feature = "dim3")]
+            spinning_friction: self.spinning_friction,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: self.anisotropic_friction,
+            one_way_direction: self.one_way_direction,
+            contact_compliance: self.contact_compliance,
         };
         let co_flags = ColliderFlags {
             collision_groups: self.collision_groups,
@@ -802,3 +1118,301 @@ impl ColliderBuilder {
         )
     }
 }
+
+/// Scales `shape` along each axis of its local frame.
+///
+/// Returns `None` if this kind of shape cannot be scaled.
+fn scale_shape(shape: &dyn Shape, scale: &Vector<Real>) -> Option<SharedShape> {
+    let scale = scale.abs();
+    let scale_pt = |pt: &Point<Real>| Point::from(pt.coords.component_mul(&scale));
+    let scale_pts = |pts: &[Point<Real>]| pts.iter().map(scale_pt).collect::<Vec<_>>();
+
+    match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => {
+            if scale.iter().all(|s| *s == scale[0]) {
+                Some(SharedShape::ball(ball.radius * scale[0]))
+            } else {
+                Some(SharedShape::new(Ellipsoid::new(scale * ball.radius)))
+            }
+        }
+        TypedShape::Cuboid(cuboid) => Some(SharedShape::new(Cuboid::new(
+            cuboid.half_extents.component_mul(&scale),
+        ))),
+        TypedShape::Capsule(capsule) => {
+            // The largest scale factor applied to the directions orthogonal to the segment.
+            let dir = capsule
+                .segment
+                .direction()
+                .map(|dir| dir.into_inner())
+                .unwrap_or_else(Vector::zeros);
+            let radius_scale = scale
+                .iter()
+                .zip(dir.iter())
+                .map(|(s, d)| *s * (1.0 - d * d).max(0.0).sqrt())
+                .fold(0.0, Real::max);
+            Some(SharedShape::capsule(
+                scale_pt(&capsule.segment.a),
+                scale_pt(&capsule.segment.b),
+                capsule.radius * radius_scale,
+            ))
+        }
+        TypedShape::Segment(segment) => Some(SharedShape::segment(
+            scale_pt(&segment.a),
+            scale_pt(&segment.b),
+        )),
+        TypedShape::Triangle(triangle) => Some(SharedShape::triangle(
+            scale_pt(&triangle.a),
+            scale_pt(&triangle.b),
+            scale_pt(&triangle.c),
+        )),
+        TypedShape::TriMesh(trimesh) => Some(SharedShape::trimesh(
+            scale_pts(trimesh.vertices()),
+            trimesh.indices().to_vec(),
+        )),
+        TypedShape::Polyline(polyline) => Some(SharedShape::polyline(
+            scale_pts(polyline.vertices()),
+            Some(polyline.indices().to_vec()),
+        )),
+        TypedShape::HalfSpace(halfspace) => {
+            let normal = halfspace.normal.component_div(&scale);
+            Some(SharedShape::new(HalfSpace::new(Unit::try_new(
+                normal,
+                crate::math::DEFAULT_EPSILON,
+            )?)))
+        }
+        #[cfg(feature = "dim2")]
+        TypedShape::HeightField(heightfield) => {
+            let mut scaled = HeightField::new(
+                heightfield.heights().clone(),
+                heightfield.scale().component_mul(&scale),
+            );
+            for i in 0..heightfield.num_cells() {
+                scaled.set_segment_removed(i, heightfield.is_segment_removed(i));
+            }
+            Some(SharedShape::new(scaled))
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::HeightField(heightfield) => {
+            let mut scaled = HeightField::new(
+                heightfield.heights().clone(),
+                heightfield.scale().component_mul(&scale),
+            );
+            scaled
+                .cells_statuses_mut()
+                .copy_from(heightfield.cells_statuses());
+            Some(SharedShape::new(scaled))
+        }
+        TypedShape::Compound(compound) => {
+            // Each part is scaled by the scale factors along its own local axes. This is
+            // exact unless a non-uniform scale is applied to a part that isn't axis-aligned.
+            let parts = compound
+                .shapes()
+                .iter()
+                .map(|(pos, part)| {
+                    let rot = pos.rotation.to_rotation_matrix();
+                    let part_scale =
+                        Vector::from_fn(|i, _| rot.matrix().column(i).component_mul(&scale).norm());
+                    let mut part_pos = *pos;
+                    part_pos.translation.vector = pos.translation.vector.component_mul(&scale);
+                    Some((part_pos, scale_shape(&**part, &part_scale)?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(SharedShape::compound(parts))
+        }
+        #[cfg(feature = "dim2")]
+        TypedShape::ConvexPolygon(polygon) => Some(SharedShape::new(
+            ConvexPolygon::from_convex_polyline(scale_pts(polygon.points()))?,
+        )),
+        #[cfg(feature = "dim3")]
+        TypedShape::ConvexPolyhedron(polyhedron) => Some(SharedShape::new(
+            ConvexPolyhedron::from_convex_hull(&scale_pts(polyhedron.points()))?,
+        )),
+        #[cfg(feature = "dim3")]
+        TypedShape::Cylinder(cylinder) => Some(SharedShape::cylinder(
+            cylinder.half_height * scale.y,
+            cylinder.radius * scale.x.max(scale.z),
+        )),
+        #[cfg(feature = "dim3")]
+        TypedShape::Cone(cone) => Some(SharedShape::cone(
+            cone.half_height * scale.y,
+            cone.radius * scale.x.max(scale.z),
+        )),
+        TypedShape::Custom(_) => {
+            let ellipsoid = shape.downcast_ref::<Ellipsoid>()?;
+            Some(SharedShape::new(Ellipsoid::new(
+                ellipsoid.semi_axes.component_mul(&scale),
+            )))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, Ellipsoid};
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn set_scale_scales_the_unscaled_shape() {
+        #[cfg(feature = "dim2")]
+        let (mut collider, scale) = (
+            ColliderBuilder::cuboid(1.0, 2.0).build(),
+            Vector::new(2.0, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (mut collider, scale) = (
+            ColliderBuilder::cuboid(1.0, 2.0, 3.0).build(),
+            Vector::new(2.0, 0.5, 3.0),
+        );
+        let half_extents = collider.shape().as_cuboid().unwrap().half_extents;
+
+        // Successive calls don't accumulate.
+        assert!(collider.set_scale(scale));
+        assert!(collider.set_scale(scale));
+        assert_eq!(*collider.scale(), scale);
+        assert_eq!(
+            collider.shape().as_cuboid().unwrap().half_extents,
+            half_extents.component_mul(&scale)
+        );
+
+        // Non-uniformly scaled balls become ellipsoids.
+        let mut collider = ColliderBuilder::ball(0.5).build();
+        assert!(collider.set_scale(Vector::repeat(2.0)));
+        assert_eq!(collider.shape().as_ball().unwrap().radius, 1.0);
+        assert!(collider.set_scale(scale));
+        let ellipsoid = collider.shape().downcast_ref::<Ellipsoid>().unwrap();
+        assert_eq!(ellipsoid.semi_axes, scale * 0.5);
+
+        // Round shapes can't be scaled.
+        #[cfg(feature = "dim2")]
+        let mut collider = ColliderBuilder::round_cuboid(1.0, 2.0, 0.1).build();
+        #[cfg(feature = "dim3")]
+        let mut collider = ColliderBuilder::round_cuboid(1.0, 2.0, 3.0, 0.1).build();
+        assert!(!collider.set_scale(scale));
+        assert_eq!(*collider.scale(), Vector::repeat(1.0));
+        assert!(collider.shape().as_round_cuboid().is_some());
+    }
+
+    #[test]
+    fn scaled_colliders_regenerate_their_contacts() {
+        // Without gravity, the box rests on the ground and nothing moves.
+        let mut world = TestWorld::new(Vector::zeros());
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 1.0);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 1.0, 10.0);
+        let ground_collider =
+            world
+                .colliders
+                .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.5)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let collider = world
+            .colliders
+            .insert_with_parent(shape.build(), body, &mut world.bodies);
+
+        let contact_dists = |world: &TestWorld| {
+            let pair = world
+                .narrow_phase
+                .contact_pair(ground_collider, collider)
+                .unwrap();
+            pair.manifolds
+                .iter()
+                .flat_map(|m| m.points.iter().map(|pt| pt.dist))
+                .collect::<Vec<_>>()
+        };
+
+        world.step();
+        let dists = contact_dists(&world);
+        assert!(!dists.is_empty());
+        assert!(dists.iter().all(|dist| dist.abs() < 1.0e-4));
+
+        // Shrinking the ground leaves a gap, smaller than the prediction distance, that the
+        // contacts must reflect even though neither collider moved.
+        let mut scale = Vector::repeat(1.0);
+        scale.y = 0.999;
+        assert!(world.colliders[ground_collider].set_scale(scale));
+        world.step();
+        let dists = contact_dists(&world);
+        assert!(!dists.is_empty());
+        assert!(dists.iter().all(|dist| (dist - 1.0e-3).abs() < 1.0e-4));
+        assert!(world.bodies[body].linvel().norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn bodies_fall_through_removed_heightfield_cells() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        // A flat heightfield with cells of size 1, the third one along each axis removed.
+        #[cfg(feature = "dim2")]
+        let heightfield = {
+            let mut mask = na::DVector::repeat(10, true);
+            mask[2] = false;
+            ColliderBuilder::heightfield_with_mask(
+                na::DVector::zeros(11),
+                mask,
+                Vector::new(10.0, 1.0),
+            )
+        };
+        #[cfg(feature = "dim3")]
+        let heightfield = {
+            let mut mask = na::DMatrix::repeat(10, 10, true);
+            mask[(2, 2)] = false;
+            ColliderBuilder::heightfield_with_mask(
+                na::DMatrix::zeros(11, 11),
+                mask,
+                Vector::new(10.0, 1.0, 10.0),
+            )
+        };
+        let heightfield = world.colliders.insert(heightfield.build());
+
+        // One ball above the removed cell, one above the cell at the opposite corner.
+        let balls: Vec<_> = [-2.5, 2.5]
+            .iter()
+            .map(|x| {
+                let mut translation = Vector::repeat(*x);
+                translation.y = 1.0;
+                let body = world.bodies.insert(
+                    RigidBodyBuilder::new_dynamic()
+                        .translation(translation)
+                        .build(),
+                );
+                let collider = ColliderBuilder::ball(0.3).build();
+                world
+                    .colliders
+                    .insert_with_parent(collider, body, &mut world.bodies);
+                body
+            })
+            .collect();
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        assert!(world.bodies[balls[0]].translation().y < -1.0);
+        assert!((world.bodies[balls[1]].translation().y - 0.3).abs() < 1.0e-2);
+
+        // Removing the cell under the resting ball lets it fall too.
+        #[cfg(feature = "dim2")]
+        world.colliders[heightfield].set_heightfield_cell_active(7, false);
+        #[cfg(feature = "dim3")]
+        world.colliders[heightfield].set_heightfield_cell_active(7, 7, false);
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        assert!(world.bodies[balls[1]].translation().y < -1.0);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
-use crate::geometry::{InteractionGroups, SAPProxyIndex, Shape, SharedShape};
-use crate::math::{Isometry, Real};
+use crate::geometry::{
+    shape_volume_and_mass_properties, InteractionGroups, SAPProxyIndex, Shape, SharedShape,
+};
+use crate::math::{Isometry, Real, UnitVector};
 use crate::parry::partitioning::IndexedData;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 
 /// The unique identifier of a collider added to a collider set.
@@ -107,21 +110,43 @@ impl ColliderType {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// Data associated to a collider that takes part to a broad-phase algorithm.
 pub struct ColliderBroadPhaseData {
     pub(crate) proxy_index: SAPProxyIndex,
+    /// The distance the collider may travel during the next timestep, if it is attached to a
+    /// rigid-body with speculative CCD enabled.
+    pub(crate) speculative_margin: Real,
 }
 
 impl Default for ColliderBroadPhaseData {
     fn default() -> Self {
         ColliderBroadPhaseData {
             proxy_index: crate::INVALID_U32,
+            speculative_margin: 0.0,
         }
     }
 }
 
+// NOTE: the speculative margin is compared bitwise, so that `ColliderBroadPhaseData`
+//       can still implement `Eq` and `Hash`.
+impl PartialEq for ColliderBroadPhaseData {
+    fn eq(&self, other: &Self) -> bool {
+        self.proxy_index == other.proxy_index
+            && self.speculative_margin.to_bits() == other.speculative_margin.to_bits()
+    }
+}
+
+impl Eq for ColliderBroadPhaseData {}
+
+impl Hash for ColliderBroadPhaseData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.proxy_index.hash(state);
+        self.speculative_margin.to_bits().hash(state);
+    }
+}
+
 /// The shape of a collider.
 pub type ColliderShape = SharedShape;
 
@@ -159,7 +184,7 @@ impl ColliderMassProps {
     /// If `self` is the `MassProperties` variant, then this returns the stored mass-properties.
     pub fn mass_properties(&self, shape: &dyn Shape) -> MassProperties {
         match self {
-            Self::Density(density) => shape.mass_properties(*density),
+            Self::Density(density) => shape_volume_and_mass_properties(shape, *density),
             Self::MassProperties(mprops) => **mprops,
         }
     }
@@ -249,6 +274,36 @@ pub struct ColliderMaterial {
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The rolling friction coefficient of this collider.
+    ///
+    /// This generates a torque opposing the rolling motion of this collider on the surface of
+    /// the other collider. Should be `>= 0`. This is ignored by the contacts involving a
+    /// multibody link.
+    pub rolling_friction: Real,
+    /// The spinning friction coefficient of this collider.
+    ///
+    /// This generates a torque opposing the rotation of this collider around the contact normal.
+    /// Should be `>= 0`. This is ignored by the contacts involving a multibody link.
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: Real,
+    /// The direction-dependent friction coefficients of this collider, if any.
+    ///
+    /// If set, this overrides `friction`.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// The local-space direction along which this collider blocks the other colliders, if it
+    /// is a one-way collider.
+    ///
+    /// If set, contacts are only solved if the contact normal pointing outward from this
+    /// collider is close to this direction, allowing other colliders to pass through it from
+    /// the other sides (e.g., jump-through platforms).
+    pub one_way_direction: Option<UnitVector<Real>>,
+    /// The compliance, i.e., the inverse of the stiffness, of the contacts involving this collider.
+    ///
+    /// This is used as a constraint force mixing term of the contact constraints along their
+    /// normal, making the contacts softer as it increases. Should be `>= 0`, and `0.0` results in
+    /// hard contacts.
+    pub contact_compliance: Real,
 }
 
 impl ColliderMaterial {
@@ -269,6 +324,41 @@ impl Default for ColliderMaterial {
             restitution: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            spinning_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
+            one_way_direction: None,
+            contact_compliance: 0.0,
+        }
+    }
+}
+
+#[cfg(feature = "dim3")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// Friction coefficients that depend on the sliding direction on the surface of a collider.
+///
+/// The friction along each principal direction is resolved independently.
+pub struct AnisotropicFriction {
+    /// The principal friction direction, expressed in the collider's local-space.
+    ///
+    /// The second principal direction is orthogonal to this one and to the contact normal.
+    pub local_axis: UnitVector<Real>,
+    /// The friction coefficients along the principal direction and along the second principal direction.
+    ///
+    /// Both should be `>= 0`. Setting one of them to zero results in frictionless sliding along that direction.
+    pub coefficients: [Real; 2],
+}
+
+#[cfg(feature = "dim3")]
+impl AnisotropicFriction {
+    /// Creates new anisotropic friction coefficients along the given local principal direction.
+    pub fn new(local_axis: UnitVector<Real>, coefficients: [Real; 2]) -> Self {
+        Self {
+            local_axis,
+            coefficients,
         }
     }
 }
@@ -0,0 +1,333 @@
+use crate::dynamics::MassProperties;
+use crate::math::{Isometry, Point, Real, Vector};
+use num::Zero;
+use parry::bounding_volume::{BoundingSphere, BoundingVolume, AABB};
+use parry::partitioning::QBVH;
+use parry::query::details::{
+    PointCompositeShapeProjBestFirstVisitor, RayCompositeShapeToiAndNormalBestFirstVisitor,
+    RayCompositeShapeToiBestFirstVisitor,
+};
+use parry::query::visitors::CompositePointContainmentTest;
+use parry::query::{PointProjection, PointQuery, Ray, RayCast, RayIntersection};
+use parry::shape::{
+    Compound, FeatureId, Shape, ShapeType, SharedShape, SimdCompositeShape, TypedShape,
+    TypedSimdCompositeShape,
+};
+
+/// The identifier returned by `TypedShape::Custom` for compound shapes with parts that can be
+/// disabled.
+pub const COMPOUND_SHAPE_ID: u32 = 0xc0b0_0001;
+
+/// A compound shape whose parts can be individually disabled.
+///
+/// Disabled parts are ignored by contact generation and scene queries, and don't contribute
+/// to the AABB nor to the mass properties of the compound, but they are kept so they can be
+/// re-enabled cheaply. If all the parts are disabled, the compound doesn't generate any
+/// contact, just like a sensor.
+///
+/// Use `Collider::shape_mut` followed by `downcast_mut::<CompoundShape>()` in order to
+/// modify the compound shape of an existing collider.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CompoundShape {
+    compound: Compound,
+    enabled: Vec<bool>,
+    local_aabb: AABB,
+}
+
+impl CompoundShape {
+    /// Builds a new compound shape with all its parts enabled.
+    ///
+    /// Panics if `shapes` is empty or if some of the shapes are also composite shapes.
+    pub fn new(shapes: Vec<(Isometry<Real>, SharedShape)>) -> Self {
+        let compound = Compound::new(shapes);
+        let enabled = vec![true; compound.shapes().len()];
+        let local_aabb = *compound.local_aabb();
+
+        Self {
+            compound,
+            enabled,
+            local_aabb,
+        }
+    }
+
+    /// The parts of this compound, including the disabled ones.
+    pub fn shapes(&self) -> &[(Isometry<Real>, SharedShape)] {
+        self.compound.shapes()
+    }
+
+    /// Is the `i`-th part of this compound enabled?
+    pub fn is_part_enabled(&self, i: usize) -> bool {
+        self.enabled[i]
+    }
+
+    /// Enables or disables the `i`-th part of this compound.
+    pub fn set_part_enabled(&mut self, i: usize, enabled: bool) {
+        if self.enabled[i] != enabled {
+            self.enabled[i] = enabled;
+            self.update_local_aabb();
+        }
+    }
+
+    /// Are all the parts of this compound disabled?
+    pub fn is_fully_disabled(&self) -> bool {
+        !self.enabled.contains(&true)
+    }
+
+    fn enabled_parts(&self) -> impl Iterator<Item = &(Isometry<Real>, SharedShape)> {
+        self.compound
+            .shapes()
+            .iter()
+            .zip(self.enabled.iter())
+            .filter(|(_, enabled)| **enabled)
+            .map(|(part, _)| part)
+    }
+
+    fn update_local_aabb(&mut self) {
+        let mut aabb = AABB::new_invalid();
+
+        for (part_aabb, enabled) in self.compound.aabbs().iter().zip(self.enabled.iter()) {
+            if *enabled {
+                aabb.merge(part_aabb);
+            }
+        }
+
+        if self.is_fully_disabled() {
+            // Keep a valid, but empty, AABB so the collider stays in the broad-phase.
+            aabb = AABB::from_half_extents(self.compound.local_aabb().center(), Vector::zeros());
+        }
+
+        self.local_aabb = aabb;
+    }
+}
+
+impl SimdCompositeShape for CompoundShape {
+    fn map_part_at(&self, shape_id: u32, f: &mut dyn FnMut(Option<&Isometry<Real>>, &dyn Shape)) {
+        self.map_typed_part_at(shape_id, f)
+    }
+
+    fn qbvh(&self) -> &QBVH<u32> {
+        self.compound.qbvh()
+    }
+}
+
+impl TypedSimdCompositeShape for CompoundShape {
+    type PartShape = dyn Shape;
+    type PartId = u32;
+
+    fn map_typed_part_at(
+        &self,
+        i: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &Self::PartShape),
+    ) {
+        if self.enabled.get(i as usize) == Some(&true) {
+            let (part_pos, part) = &self.compound.shapes()[i as usize];
+            f(Some(part_pos), &**part)
+        }
+    }
+
+    fn map_untyped_part_at(
+        &self,
+        i: u32,
+        f: impl FnMut(Option<&Isometry<Real>>, &Self::PartShape),
+    ) {
+        self.map_typed_part_at(i, f)
+    }
+
+    fn typed_qbvh(&self) -> &QBVH<u32> {
+        self.compound.qbvh()
+    }
+}
+
+impl RayCast for CompoundShape {
+    fn cast_local_ray(&self, ray: &Ray, max_toi: Real, solid: bool) -> Option<Real> {
+        let mut visitor = RayCompositeShapeToiBestFirstVisitor::new(self, ray, max_toi, solid);
+
+        self.qbvh()
+            .traverse_best_first(&mut visitor)
+            .map(|res| res.1 .1)
+    }
+
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let mut visitor =
+            RayCompositeShapeToiAndNormalBestFirstVisitor::new(self, ray, max_toi, solid);
+
+        self.qbvh()
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (_, res))| res)
+    }
+}
+
+impl PointQuery for CompoundShape {
+    fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        let mut visitor = PointCompositeShapeProjBestFirstVisitor::new(self, point, solid);
+
+        // Without any enabled part, the point is infinitely far from the compound.
+        self.qbvh()
+            .traverse_best_first(&mut visitor)
+            .map(|res| res.1 .0)
+            .unwrap_or_else(|| PointProjection::new(false, Point::from(Vector::repeat(Real::MAX))))
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        point: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        (self.project_local_point(point, false), FeatureId::Unknown)
+    }
+
+    fn contains_local_point(&self, point: &Point<Real>) -> bool {
+        let mut visitor = CompositePointContainmentTest::new(self, point);
+        self.qbvh().traverse_depth_first(&mut visitor);
+        visitor.found
+    }
+}
+
+impl Shape for CompoundShape {
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn compute_local_aabb(&self) -> AABB {
+        self.local_aabb
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        self.local_aabb.bounding_sphere()
+    }
+
+    fn compute_aabb(&self, position: &Isometry<Real>) -> AABB {
+        self.local_aabb.transform_by(position)
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        self.enabled_parts()
+            .fold(MassProperties::zero(), |mprops, (pos, part)| {
+                mprops + part.mass_properties(density).transform_by(pos)
+            })
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(COMPOUND_SHAPE_ID)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.enabled_parts()
+            .fold(Real::MAX, |curr, (_, part)| curr.min(part.ccd_thickness()))
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        self.enabled_parts().fold(Real::MAX, |curr, (_, part)| {
+            curr.max(part.ccd_angular_thickness())
+        })
+    }
+
+    fn as_composite_shape(&self) -> Option<&dyn SimdCompositeShape> {
+        Some(self as &dyn SimdCompositeShape)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CompoundShape;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, SharedShape};
+    use crate::math::{Isometry, Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use parry::query::{PointQuery, Ray, RayCast};
+    use parry::shape::Shape;
+
+    // Two balls of radius 0.5, centered at `x = -1` and `x = 1`.
+    fn compound() -> CompoundShape {
+        CompoundShape::new(vec![
+            (Isometry::from(-Vector::x()), SharedShape::ball(0.5)),
+            (Isometry::from(Vector::x()), SharedShape::ball(0.5)),
+        ])
+    }
+
+    #[test]
+    fn disabled_parts_are_ignored_by_queries_and_mass_properties() {
+        let mut shape = compound();
+        let ray = Ray::new(Point::from(Vector::x() * 5.0), -Vector::x());
+        let part = Point::from(Vector::x());
+        let full_mass = 1.0 / shape.mass_properties(1.0).inv_mass;
+        let full_aabb = shape.compute_local_aabb();
+
+        shape.set_part_enabled(1, false);
+        assert!(!shape.is_part_enabled(1));
+        let toi = shape.cast_local_ray(&ray, 10.0, true).unwrap();
+        assert!((toi - 5.5).abs() < 1.0e-5);
+        assert!(!shape.contains_local_point(&part));
+        let mass = 1.0 / shape.mass_properties(1.0).inv_mass;
+        assert!((mass - full_mass / 2.0).abs() < 1.0e-5);
+        assert!((shape.compute_local_aabb().maxs.x + 0.5).abs() < 1.0e-5);
+
+        shape.set_part_enabled(0, false);
+        assert!(shape.is_fully_disabled());
+        assert!(shape.cast_local_ray(&ray, 10.0, true).is_none());
+        assert_eq!(shape.mass_properties(1.0).inv_mass, 0.0);
+
+        shape.set_part_enabled(0, true);
+        shape.set_part_enabled(1, true);
+        let toi = shape.cast_local_ray(&ray, 10.0, true).unwrap();
+        assert!((toi - 3.5).abs() < 1.0e-5);
+        assert!(shape.contains_local_point(&part));
+        assert!((1.0 / shape.mass_properties(1.0).inv_mass - full_mass).abs() < 1.0e-5);
+        assert_eq!(shape.compute_local_aabb(), full_aabb);
+    }
+
+    #[test]
+    fn disabled_parts_dont_generate_contacts() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let compound = world
+            .colliders
+            .insert(ColliderBuilder::new(SharedShape::new(compound())).build());
+
+        // A ball touching the second part of the compound.
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() + Vector::y())
+                .build(),
+        );
+        let ball = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            body,
+            &mut world.bodies,
+        );
+
+        let set_part_enabled = |world: &mut TestWorld, enabled: bool| {
+            world.colliders[compound]
+                .shape_mut()
+                .downcast_mut::<CompoundShape>()
+                .unwrap()
+                .set_part_enabled(1, enabled);
+        };
+        let has_contacts = |world: &TestWorld| {
+            world
+                .narrow_phase
+                .contact_pair(compound, ball)
+                .map(|pair| pair.has_any_active_contact)
+                .unwrap_or(false)
+        };
+
+        world.step();
+        assert!(has_contacts(&world));
+
+        set_part_enabled(&mut world, false);
+        world.step();
+        assert!(!has_contacts(&world));
+
+        set_part_enabled(&mut world, true);
+        world.step();
+        assert!(has_contacts(&world));
+    }
+}
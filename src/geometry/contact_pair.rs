@@ -19,6 +19,33 @@ impl Default for SolverFlags {
     }
 }
 
+/// A persistent identifier of a contact point, derived from the features (vertex, edge, face)
+/// of the two collider shapes it involves.
+///
+/// A contact between the same pair of features keeps the same identifier across timesteps,
+/// which is used to transfer its impulses from one timestep to the next for warmstarting.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct ContactId(pub u64);
+
+impl ContactId {
+    /// The identifier of a contact between the feature `fid1` of the first shape and the
+    /// feature `fid2` of the second shape.
+    pub fn new(fid1: u32, fid2: u32) -> Self {
+        Self(((fid1 as u64) << 32) | fid2 as u64)
+    }
+
+    /// The feature of the first shape involved in this contact.
+    pub fn fid1(self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+
+    /// The feature of the second shape involved in this contact.
+    pub fn fid2(self) -> u32 {
+        self.0 as u32
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// A single contact between two collider.
@@ -46,6 +73,49 @@ impl Default for ContactData {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// The impulses applied by the constraints solver at a single contact point during the last timestep.
+pub struct ContactImpulse {
+    /// The world-space contact point.
+    pub point: Point<Real>,
+    /// The world-space contact normal.
+    pub normal: Vector<Real>,
+    /// The impulse, along the contact normal, applied to the first collider's rigid-body.
+    pub normal_impulse: Real,
+    /// The friction impulse along the vector orthonormal to the contact normal, applied to the first
+    /// collider's rigid-body.
+    #[cfg(feature = "dim2")]
+    pub tangent_impulse: Real,
+    /// The friction impulses along the basis orthonormal to the contact normal, applied to the first
+    /// collider's rigid-body.
+    #[cfg(feature = "dim3")]
+    pub tangent_impulse: na::Vector2<Real>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// A contact pair of the contact graph, with the impulses applied by the constraints solver
+/// during the last timestep it was solved.
+///
+/// See `NarrowPhase::contact_graph_snapshot`.
+pub struct ContactEdge {
+    /// The first collider involved in the contact pair.
+    pub collider_a: ColliderHandle,
+    /// The second collider involved in the contact pair.
+    pub collider_b: ColliderHandle,
+    /// The rigid-body attached to the first collider, if any.
+    pub body_a: Option<RigidBodyHandle>,
+    /// The rigid-body attached to the second collider, if any.
+    pub body_b: Option<RigidBodyHandle>,
+    /// The sum of the impulses along the contact normal applied at each contact point.
+    pub normal_impulse_sum: Real,
+    /// The sum of the magnitudes of the friction impulses applied at each contact point.
+    pub tangent_impulse_magnitude: Real,
+    /// The number of contact points seen by the constraints solver.
+    pub contact_point_count: u32,
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 /// The description of all the contacts between a pair of colliders.
@@ -60,6 +130,10 @@ pub struct ContactPair {
     pub manifolds: Vec<ContactManifold>,
     /// Is there any active contact in this contact pair?
     pub has_any_active_contact: bool,
+    /// The number of consecutive timesteps this contact pair had any active contact for.
+    ///
+    /// This is zero if the colliders are not touching.
+    pub contact_age_frames: u32,
     pub(crate) workspace: Option<ContactManifoldsWorkspace>,
 }
 
@@ -69,6 +143,7 @@ impl ContactPair {
             collider1,
             collider2,
             has_any_active_contact: false,
+            contact_age_frames: 0,
             manifolds: Vec::new(),
             workspace: None,
         }
@@ -147,8 +222,23 @@ pub struct ContactManifoldData {
     pub solver_contacts: Vec<SolverContact>,
     /// The relative dominance of the bodies involved in this contact manifold.
     pub relative_dominance: i16,
+    /// The rolling friction coefficient of this contact manifold.
+    pub rolling_friction: Real,
+    /// The spinning friction coefficient of this contact manifold.
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: Real,
+    /// The world-space principal friction direction, and the factors applied to the friction
+    /// coefficients of the solver contacts along this direction and along the second principal
+    /// direction, if the friction of this manifold is anisotropic.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<(Vector<Real>, [Real; 2])>,
+    /// The compliance of the normal contact constraints of this manifold, i.e., the sum of the
+    /// contact compliances of both colliders.
+    pub compliance: Real,
     /// A user-defined piece of data.
     pub user_data: u32,
+    /// The state of the one-way collision filters of the two colliders of this manifold.
+    pub(crate) one_way_states: [u32; 2],
 }
 
 /// A contact seen by the constraints solver for computing forces.
@@ -157,6 +247,8 @@ pub struct ContactManifoldData {
 pub struct SolverContact {
     /// The index of the manifold contact used to generate this solver contact.
     pub(crate) contact_id: u8,
+    /// The persistent identifier of the manifold contact used to generate this solver contact.
+    pub id: ContactId,
     /// The world-space contact point.
     pub point: Point<Real>,
     /// The distance between the two original contacts points along the contact normal.
@@ -173,6 +265,11 @@ pub struct SolverContact {
     pub tangent_velocity: Vector<Real>,
     /// Whether or not this contact existed during the last timestep.
     pub is_new: bool,
+    /// The impulse applied along the contact normal at this contact point during the last timestep.
+    ///
+    /// This is used to warmstart the constraints solver, see
+    /// [`IntegrationParameters::warmstart_coefficient`](crate::dynamics::IntegrationParameters::warmstart_coefficient).
+    pub warmstart_impulse: Real,
 }
 
 impl SolverContact {
@@ -210,10 +307,27 @@ impl ContactManifoldData {
             normal: Vector::zeros(),
             solver_contacts: Vec::new(),
             relative_dominance: 0,
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            spinning_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
+            compliance: 0.0,
             user_data: 0,
+            one_way_states: [0; 2],
         }
     }
 
+    /// Does this contact manifold have non-zero rolling or spinning friction?
+    #[inline]
+    pub fn has_rolling_friction(&self) -> bool {
+        #[cfg(feature = "dim2")]
+        let has_spinning_friction = false;
+        #[cfg(feature = "dim3")]
+        let has_spinning_friction = self.spinning_friction != 0.0;
+        self.rolling_friction != 0.0 || has_spinning_friction
+    }
+
     /// Number of actives contacts, i.e., contacts that will be seen by
     /// the constraints solver.
     #[inline]
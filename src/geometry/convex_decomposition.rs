@@ -0,0 +1,251 @@
+use crate::math::{Point, Real, DIM};
+use parry::bounding_volume::AABB;
+use parry::transformation::vhacd::{VHACDParameters, VHACD};
+
+/// A convex part resulting from a convex decomposition.
+///
+/// Wrap it into a `SharedShape` in order to use it as part of a compound shape.
+#[cfg(feature = "dim2")]
+pub type ConvexHull = parry::shape::ConvexPolygon;
+/// A convex part resulting from a convex decomposition.
+///
+/// Wrap it into a `SharedShape` in order to use it as part of a compound shape.
+#[cfg(feature = "dim3")]
+pub type ConvexHull = parry::shape::ConvexPolyhedron;
+
+/// Parameters controlling the VHACD convex decomposition.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct VhacdParams {
+    /// The maximum number of convex hulls generated by the decomposition.
+    ///
+    /// Default: 1024.
+    pub max_convex_hulls: u32,
+    /// The resolution used during the voxelization stage.
+    ///
+    /// Default: 256 (in 2D), 64 (in 3D).
+    pub resolution: u32,
+    /// The maximum number of vertices of each convex hull.
+    ///
+    /// Default: 64.
+    pub max_vertices_per_hull: usize,
+    /// The maximum concavity of each convex hull, in `[0.0, 1.0]`.
+    ///
+    /// Default: 0.1 (in 2D), 0.01 (in 3D).
+    pub concavity: Real,
+}
+
+impl Default for VhacdParams {
+    fn default() -> Self {
+        let params = VHACDParameters::default();
+        Self {
+            max_convex_hulls: params.max_convex_hulls,
+            resolution: params.resolution,
+            max_vertices_per_hull: 64,
+            concavity: params.concavity,
+        }
+    }
+}
+
+impl VhacdParams {
+    fn to_vhacd_parameters(self) -> VHACDParameters {
+        VHACDParameters {
+            max_convex_hulls: self.max_convex_hulls,
+            resolution: self.resolution,
+            concavity: self.concavity,
+            ..VHACDParameters::default()
+        }
+    }
+}
+
+/// Decomposes the given triangle mesh (in 3D) or polyline (in 2D) into convex parts using
+/// the VHACD algorithm.
+pub fn convex_decomposition(
+    vertices: &[Point<Real>],
+    indices: &[[u32; DIM]],
+    params: VhacdParams,
+) -> Vec<ConvexHull> {
+    let decomp = VHACD::decompose(&params.to_vhacd_parameters(), vertices, indices, true);
+
+    #[cfg(feature = "dim2")]
+    let mut hulls = decomp.compute_exact_convex_hulls(vertices, indices);
+    #[cfg(feature = "dim3")]
+    let mut hulls: Vec<_> = decomp
+        .compute_exact_convex_hulls(vertices, indices)
+        .into_iter()
+        .map(|(points, _)| points)
+        .collect();
+
+    // Parts with too few points to span a hull are degenerate.
+    hulls.retain(|points| points.len() > DIM);
+
+    // VHACD only uses `max_convex_hulls` to bound the depth of its recursive splits, so it
+    // may output more hulls than requested. Merge the closest ones until we are in budget.
+    while hulls.len() > params.max_convex_hulls.max(1) as usize {
+        let centers: Vec<_> = hulls
+            .iter()
+            .map(|points| AABB::from_points(points).center())
+            .collect();
+        let mut closest = (0, 1, Real::MAX);
+
+        for i in 0..centers.len() {
+            for j in i + 1..centers.len() {
+                let dist = na::distance_squared(&centers[i], &centers[j]);
+                if dist < closest.2 {
+                    closest = (i, j, dist);
+                }
+            }
+        }
+
+        let merged = hulls.swap_remove(closest.1);
+        hulls[closest.0].extend(merged);
+    }
+
+    hulls
+        .into_iter()
+        .filter_map(|points| {
+            ConvexHull::from_convex_hull(&simplify_hull(points, params.max_vertices_per_hull))
+        })
+        .collect()
+}
+
+/// Keeps at most `max_vertices` of the given hull vertices, selected by farthest-point sampling
+/// so the simplified hull keeps the overall extent of the original one.
+fn simplify_hull(points: Vec<Point<Real>>, max_vertices: usize) -> Vec<Point<Real>> {
+    if points.len() <= max_vertices || max_vertices <= DIM {
+        return points;
+    }
+
+    // Start with the points with the smallest and largest coordinates along the first axis.
+    let (imin, imax) = points
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold((0, 0), |(imin, imax), (i, pt)| {
+            (
+                if pt.x < points[imin].x { i } else { imin },
+                if pt.x > points[imax].x { i } else { imax },
+            )
+        });
+    let mut selected = vec![points[imin], points[imax]];
+    let mut distances: Vec<Real> = points
+        .iter()
+        .map(|pt| {
+            na::distance_squared(pt, &points[imin]).min(na::distance_squared(pt, &points[imax]))
+        })
+        .collect();
+
+    while selected.len() < max_vertices {
+        let (farthest, _) =
+            distances.iter().enumerate().fold(
+                (0, -1.0),
+                |best, (i, d)| if *d > best.1 { (i, *d) } else { best },
+            );
+        let new_pt = points[farthest];
+        selected.push(new_pt);
+
+        for (dist, pt) in distances.iter_mut().zip(points.iter()) {
+            *dist = dist.min(na::distance_squared(pt, &new_pt));
+        }
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convex_decomposition, simplify_hull, VhacdParams};
+    use crate::math::{Point, Real, Vector, DIM};
+    use parry::bounding_volume::{BoundingVolume, AABB};
+
+    // An L-shaped polyline (in 2D) or triangle mesh (in 3D) fitting in [0, 2]^DIM.
+    #[cfg(feature = "dim2")]
+    fn l_shape() -> (Vec<Point<Real>>, Vec<[u32; DIM]>) {
+        let vertices = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ];
+        let indices = (0..6).map(|i| [i, (i + 1) % 6]).collect();
+        (vertices, indices)
+    }
+
+    // An L-shaped polyline (in 2D) or triangle mesh (in 3D) fitting in [0, 2]^DIM.
+    #[cfg(feature = "dim3")]
+    fn l_shape() -> (Vec<Point<Real>>, Vec<[u32; DIM]>) {
+        use parry::shape::Cuboid;
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // The union of two overlapping boxes.
+        for (center, half_extents) in [
+            (Vector::new(1.0, 0.5, 1.0), Vector::new(1.0, 0.5, 1.0)),
+            (Vector::new(0.5, 1.0, 1.0), Vector::new(0.5, 1.0, 1.0)),
+        ] {
+            let (pts, idx) = Cuboid::new(half_extents).to_trimesh();
+            let base = vertices.len() as u32;
+            vertices.extend(pts.into_iter().map(|pt| pt + center));
+            indices.extend(idx.into_iter().map(|tri| tri.map(|i| i + base)));
+        }
+
+        (vertices, indices)
+    }
+
+    #[test]
+    fn concave_shapes_are_decomposed_into_several_parts() {
+        let (vertices, indices) = l_shape();
+        let expected_aabb = AABB::new(Point::origin(), Point::from(Vector::repeat(2.0)));
+
+        // A coarse voxelization keeps this test fast.
+        let params = VhacdParams {
+            #[cfg(feature = "dim2")]
+            resolution: 64,
+            #[cfg(feature = "dim3")]
+            resolution: 16,
+            ..VhacdParams::default()
+        };
+        let parts = convex_decomposition(&vertices, &indices, params);
+        assert!(parts.len() >= 2);
+
+        // The parts cover the whole shape without leaving it.
+        let mut aabb = AABB::new_invalid();
+        for part in &parts {
+            for pt in part.points() {
+                assert!(expected_aabb.loosened(1.0e-2).contains_local_point(pt));
+                aabb.take_point(*pt);
+            }
+        }
+        assert!((aabb.mins - expected_aabb.mins).norm() < 5.0e-2);
+        assert!((aabb.maxs - expected_aabb.maxs).norm() < 5.0e-2);
+
+        let params = VhacdParams {
+            max_convex_hulls: 1,
+            ..params
+        };
+        assert_eq!(convex_decomposition(&vertices, &indices, params).len(), 1);
+    }
+
+    #[test]
+    fn simplified_hulls_keep_their_extreme_points() {
+        let points: Vec<_> = (0..100)
+            .map(|i| {
+                let angle = i as Real * 0.0628;
+                Point::from(Vector::repeat(angle.cos()) + Vector::x() * angle.sin())
+            })
+            .collect();
+        let simplified = simplify_hull(points.clone(), 8);
+        assert_eq!(simplified.len(), 8);
+
+        let xmin = points.iter().map(|pt| pt.x).fold(Real::MAX, Real::min);
+        let xmax = points.iter().map(|pt| pt.x).fold(-Real::MAX, Real::max);
+        assert!(simplified.iter().any(|pt| pt.x == xmin));
+        assert!(simplified.iter().any(|pt| pt.x == xmax));
+
+        // Hulls that are already small enough are left untouched.
+        assert_eq!(simplify_hull(points[..8].to_vec(), 8), points[..8]);
+    }
+}
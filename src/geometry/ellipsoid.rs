@@ -0,0 +1,351 @@
+use crate::math::{Isometry, Point, Real, Vector};
+use na::{RealField, Unit};
+use parry::bounding_volume::{BoundingSphere, AABB};
+use parry::mass_properties::MassProperties;
+use parry::query::gjk::VoronoiSimplex;
+use parry::query::point::local_point_projection_on_support_map;
+use parry::query::{PointProjection, PointQuery, Ray, RayCast, RayIntersection};
+use parry::shape::{
+    Ball, FeatureId, PolygonalFeature, PolygonalFeatureMap, Shape, ShapeType, SupportMap,
+    TypedShape,
+};
+
+/// The identifier returned by `TypedShape::Custom` for ellipsoids.
+pub const ELLIPSOID_SHAPE_ID: u32 = 0x0e11_1950;
+
+/// An ellipsoid shape, centered at the origin, with its semi-axes aligned with the coordinate axes.
+///
+/// This is a custom shape implemented by Rapier itself, so it cannot be serialized as part of
+/// a `SharedShape`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Ellipsoid {
+    /// The half-lengths of the ellipsoid along each coordinate axis.
+    pub semi_axes: Vector<Real>,
+}
+
+/// An ellipse shape, centered at the origin, with its semi-axes aligned with the coordinate axes.
+#[cfg(feature = "dim2")]
+pub type Ellipse = Ellipsoid;
+
+impl Ellipsoid {
+    /// Creates a new ellipsoid with the given semi-axes.
+    pub fn new(semi_axes: Vector<Real>) -> Self {
+        Self { semi_axes }
+    }
+
+    /// The volume (or area in 2D) of this ellipsoid.
+    pub fn volume(&self) -> Real {
+        #[cfg(feature = "dim2")]
+        return Real::pi() * self.semi_axes.x * self.semi_axes.y;
+        #[cfg(feature = "dim3")]
+        return Real::pi() * self.semi_axes.x * self.semi_axes.y * self.semi_axes.z * 4.0 / 3.0;
+    }
+}
+
+impl SupportMap for Ellipsoid {
+    fn local_support_point(&self, dir: &Vector<Real>) -> Point<Real> {
+        let scaled_dir = dir.component_mul(&self.semi_axes);
+        let norm = scaled_dir.norm();
+
+        if norm <= crate::math::DEFAULT_EPSILON {
+            return Point::from(self.semi_axes.component_mul(&Vector::x()));
+        }
+
+        Point::from(scaled_dir.component_mul(&self.semi_axes) / norm)
+    }
+}
+
+impl PolygonalFeatureMap for Ellipsoid {
+    #[cfg(feature = "dim2")]
+    fn local_support_feature(&self, dir: &Unit<Vector<Real>>, out_feature: &mut PolygonalFeature) {
+        // The curved boundary is approximated by a tiny segment tangent to the support point.
+        let support = self.local_support_point_toward(dir);
+        let half_length = self.semi_axes.min() * 1.0e-2;
+        let tangent = Vector::new(-dir.y, dir.x) * half_length;
+        *out_feature = PolygonalFeature {
+            vertices: [support - tangent, support + tangent],
+            vids: [0, 0],
+            fid: 0,
+            num_vertices: 2,
+        };
+    }
+
+    #[cfg(feature = "dim3")]
+    fn local_support_feature(&self, dir: &Unit<Vector<Real>>, out_feature: &mut PolygonalFeature) {
+        let support = self.local_support_point_toward(dir);
+        *out_feature = PolygonalFeature {
+            vertices: [support; 4],
+            vids: [0; 4],
+            eids: [0; 4],
+            fid: 0,
+            num_vertices: 1,
+        };
+    }
+}
+
+impl RayCast for Ellipsoid {
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        // Cast the ray on the unit ball obtained by scaling down the ellipsoid.
+        let scaled_ray = Ray::new(
+            Point::from(ray.origin.coords.component_div(&self.semi_axes)),
+            ray.dir.component_div(&self.semi_axes),
+        );
+        let mut inter =
+            Ball::new(1.0).cast_local_ray_and_get_normal(&scaled_ray, max_toi, solid)?;
+
+        if let Some(normal) = inter
+            .normal
+            .component_div(&self.semi_axes)
+            .try_normalize(crate::math::DEFAULT_EPSILON)
+        {
+            inter.normal = normal;
+        }
+
+        Some(inter)
+    }
+}
+
+impl PointQuery for Ellipsoid {
+    fn project_local_point(&self, pt: &Point<Real>, solid: bool) -> PointProjection {
+        local_point_projection_on_support_map(self, &mut VoronoiSimplex::new(), pt, solid)
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        pt: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        (self.project_local_point(pt, false), FeatureId::Face(0))
+    }
+}
+
+impl Shape for Ellipsoid {
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(*self)
+    }
+
+    fn compute_local_aabb(&self) -> AABB {
+        AABB::from_half_extents(Point::origin(), self.semi_axes)
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new(Point::origin(), self.semi_axes.max())
+    }
+
+    fn compute_aabb(&self, position: &Isometry<Real>) -> AABB {
+        // The half-extent along each axis is the norm of the corresponding row
+        // of the rotation matrix scaled by the semi-axes.
+        let rot = position.rotation.to_rotation_matrix();
+        let scaled_rot = rot.matrix() * na::Matrix::from_diagonal(&self.semi_axes);
+        let half_extents = Vector::from_fn(|i, _| scaled_rot.row(i).norm());
+        AABB::from_half_extents(Point::from(position.translation.vector), half_extents)
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        let mass = self.volume() * density;
+        let sq_axes = self.semi_axes.component_mul(&self.semi_axes);
+
+        #[cfg(feature = "dim2")]
+        let principal_inertia = mass * (sq_axes.x + sq_axes.y) / 4.0;
+        #[cfg(feature = "dim3")]
+        let principal_inertia = Vector::new(
+            sq_axes.y + sq_axes.z,
+            sq_axes.x + sq_axes.z,
+            sq_axes.x + sq_axes.y,
+        ) * (mass / 5.0);
+
+        MassProperties::new(Point::origin(), mass, principal_inertia)
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(ELLIPSOID_SHAPE_ID)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.semi_axes.min()
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        Real::frac_pi_2()
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn as_support_map(&self) -> Option<&dyn SupportMap> {
+        Some(self as &dyn SupportMap)
+    }
+
+    fn as_polygonal_feature_map(&self) -> Option<(&dyn PolygonalFeatureMap, Real)> {
+        Some((self as &dyn PolygonalFeatureMap, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ellipsoid;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{AngVector, Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+    use parry::mass_properties::MassProperties;
+    use parry::query::{PointQuery, Ray, RayCast};
+    use parry::shape::{Ball, Shape, SupportMap};
+
+    fn ellipsoid() -> Ellipsoid {
+        #[cfg(feature = "dim2")]
+        return Ellipsoid::new(Vector::new(2.0, 0.5));
+        #[cfg(feature = "dim3")]
+        return Ellipsoid::new(Vector::new(2.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn ellipsoid_support_points_are_extremal() {
+        let shape = ellipsoid();
+        assert_eq!(
+            shape.local_support_point(&Vector::x()),
+            Point::from(Vector::x() * 2.0)
+        );
+        assert_eq!(
+            shape.local_support_point(&-Vector::y()),
+            Point::from(Vector::y() * -0.5)
+        );
+
+        let dir = Vector::repeat(1.0);
+        let support = shape.local_support_point(&dir);
+        // The support point lies on the surface and no other surface point goes further.
+        assert!((support.coords.component_div(&shape.semi_axes).norm() - 1.0).abs() < 1.0e-5);
+        for i in 0..100 {
+            let other = shape.local_support_point(&Vector::from_fn(|k, _| {
+                (i as Real * (1.3 + k as Real)).sin()
+            }));
+            assert!(other.coords.dot(&dir) <= support.coords.dot(&dir) + 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn ellipsoid_ray_casts_and_point_projections() {
+        let shape = ellipsoid();
+        let ray = Ray::new(Point::from(Vector::x() * -10.0), Vector::x());
+        let hit = shape
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.toi - 8.0).abs() < 1.0e-5);
+        assert!((hit.normal + Vector::x()).norm() < 1.0e-5);
+
+        let ray = Ray::new(Point::from(Vector::y() * 10.0), -Vector::y());
+        let hit = shape
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.toi - 9.5).abs() < 1.0e-5);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-5);
+        let ray = Ray::new(Point::from(Vector::y() * 10.0), Vector::x());
+        assert!(shape.cast_local_ray(&ray, Real::MAX, true).is_none());
+
+        let proj = shape.project_local_point(&Point::from(Vector::x() * 5.0), true);
+        assert!(!proj.is_inside);
+        assert!((proj.point - Point::from(Vector::x() * 2.0)).norm() < 1.0e-4);
+        let proj = shape.project_local_point(&Point::from(Vector::x() * 1.0), true);
+        assert!(proj.is_inside);
+    }
+
+    // The mass and the principal angular inertia of the given mass properties.
+    fn mass_and_inertia(mprops: MassProperties) -> (Real, AngVector<Real>) {
+        #[cfg(feature = "dim2")]
+        let inertia = 1.0 / (mprops.inv_principal_inertia_sqrt.powi(2));
+        #[cfg(feature = "dim3")]
+        let inertia = mprops.inv_principal_inertia_sqrt.map(|x| 1.0 / (x * x));
+        (1.0 / mprops.inv_mass, inertia)
+    }
+
+    #[test]
+    fn ellipsoid_aabbs_and_mass_properties() {
+        let shape = ellipsoid();
+        #[cfg(feature = "dim2")]
+        let rotation = Isometry::rotation(Real::frac_pi_2());
+        #[cfg(feature = "dim3")]
+        let rotation = Isometry::rotation(Vector::z() * Real::frac_pi_2());
+        let aabb = shape.compute_aabb(&rotation);
+        assert!((aabb.half_extents().x - 0.5).abs() < 1.0e-5);
+        assert!((aabb.half_extents().y - 2.0).abs() < 1.0e-5);
+
+        // An ellipsoid with equal semi-axes has the mass properties of a ball.
+        let round = mass_and_inertia(Ellipsoid::new(Vector::repeat(0.5)).mass_properties(2.0));
+        let ball = mass_and_inertia(Ball::new(0.5).mass_properties(2.0));
+        assert!((round.0 - ball.0).abs() < 1.0e-4);
+        #[cfg(feature = "dim2")]
+        assert!((round.1 - ball.1).abs() < 1.0e-4);
+        #[cfg(feature = "dim3")]
+        assert!((round.1 - ball.1).norm() < 1.0e-4);
+
+        let (mass, inertia) = mass_and_inertia(shape.mass_properties(1.0));
+        #[cfg(feature = "dim2")]
+        {
+            assert!((mass - Real::pi()).abs() < 1.0e-4);
+            assert!((inertia - Real::pi() * 4.25 / 4.0).abs() < 1.0e-4);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let expected_mass = Real::pi() * 4.0 / 3.0;
+            let expected_inertia = Vector::new(1.25, 5.0, 4.25) * (expected_mass / 5.0);
+            assert!((mass - expected_mass).abs() < 1.0e-4);
+            assert!((inertia - expected_inertia).norm() < 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn ellipsoids_roll_on_their_boundary() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // Drop a tilted ellipsoid so it has to rock on its curved boundary.
+        let shape = ellipsoid();
+        #[cfg(feature = "dim2")]
+        let tilt = 0.3;
+        #[cfg(feature = "dim3")]
+        let tilt = Vector::z() * 0.3;
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 3.0)
+                .rotation(tilt)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::ellipse(shape.semi_axes);
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::ellipsoid(shape.semi_axes);
+        world
+            .colliders
+            .insert_with_parent(collider.build(), body, &mut world.bodies);
+
+        for i in 0..400 {
+            world.step();
+
+            if i >= 100 {
+                // Once landed, the lowest point of the ellipsoid stays on the ground's top face.
+                let pos = world.bodies[body].position();
+                let dir = pos.inverse_transform_vector(&-Vector::y());
+                let lowest = pos * shape.local_support_point(&dir);
+                assert!((lowest.y - 0.5).abs() < 2.0e-2);
+            }
+        }
+    }
+}
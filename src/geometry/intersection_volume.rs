@@ -0,0 +1,182 @@
+use crate::math::{Isometry, Real, Vector, DIM};
+use na::RealField;
+use parry::bounding_volume::BoundingVolume;
+use parry::shape::{Ball, Cuboid, Shape, TypedShape};
+
+/// The number of samples along each axis used to estimate the intersection volume of
+/// shapes without an analytic formula.
+#[cfg(feature = "dim2")]
+const SAMPLES_PER_AXIS: usize = 64;
+#[cfg(feature = "dim3")]
+const SAMPLES_PER_AXIS: usize = 24;
+
+/// Computes the volume (or area in 2D) of the intersection between two shapes.
+///
+/// The result is exact for pairs of balls and pairs of cuboids with aligned axes. For other
+/// pairs of shapes, it is estimated by sampling the intersection of their AABBs on a regular
+/// grid, so it is approximate and significantly slower. This relies on point containment
+/// tests so shapes not enclosing any volume, like triangle meshes, have a zero intersection
+/// volume with all the other shapes.
+///
+/// Returns `0.0` if the shapes don't intersect.
+pub fn intersection_volume(
+    shape_a: &dyn Shape,
+    pos_a: &Isometry<Real>,
+    shape_b: &dyn Shape,
+    pos_b: &Isometry<Real>,
+) -> Real {
+    let pos_ab = pos_a.inv_mul(pos_b);
+
+    match (shape_a.as_typed_shape(), shape_b.as_typed_shape()) {
+        (TypedShape::Ball(ball_a), TypedShape::Ball(ball_b)) => {
+            ball_ball_intersection_volume(ball_a, ball_b, pos_ab.translation.vector.norm())
+        }
+        (TypedShape::Cuboid(cuboid_a), TypedShape::Cuboid(cuboid_b))
+            if pos_ab.rotation.angle() <= crate::math::DEFAULT_EPSILON =>
+        {
+            aligned_cuboid_cuboid_intersection_volume(
+                cuboid_a,
+                cuboid_b,
+                &pos_ab.translation.vector,
+            )
+        }
+        _ => sampled_intersection_volume(shape_a, pos_a, shape_b, pos_b),
+    }
+}
+
+fn ball_ball_intersection_volume(ball_a: &Ball, ball_b: &Ball, dist: Real) -> Real {
+    let (r1, r2) = (ball_a.radius, ball_b.radius);
+
+    if dist >= r1 + r2 {
+        return 0.0;
+    }
+
+    if dist <= (r1 - r2).abs() {
+        // One ball is completely inside of the other.
+        let r = r1.min(r2);
+        #[cfg(feature = "dim2")]
+        return Real::pi() * r * r;
+        #[cfg(feature = "dim3")]
+        return Real::pi() * r * r * r * 4.0 / 3.0;
+    }
+
+    // Area of the lens formed by the two disks.
+    #[cfg(feature = "dim2")]
+    {
+        let d2 = dist * dist;
+        let angle1 = ((d2 + r1 * r1 - r2 * r2) / (2.0 * dist * r1)).acos();
+        let angle2 = ((d2 + r2 * r2 - r1 * r1) / (2.0 * dist * r2)).acos();
+        let kite = (-dist + r1 + r2) * (dist + r1 - r2) * (dist - r1 + r2) * (dist + r1 + r2);
+        r1 * r1 * angle1 + r2 * r2 * angle2 - kite.max(0.0).sqrt() / 2.0
+    }
+
+    // Volume of the lens formed by the two spherical caps.
+    #[cfg(feature = "dim3")]
+    {
+        let depth = r1 + r2 - dist;
+        let dr = r1 - r2;
+        Real::pi() * depth * depth * (dist * dist + 2.0 * dist * (r1 + r2) - 3.0 * dr * dr)
+            / (12.0 * dist)
+    }
+}
+
+fn aligned_cuboid_cuboid_intersection_volume(
+    cuboid_a: &Cuboid,
+    cuboid_b: &Cuboid,
+    shift: &Vector<Real>,
+) -> Real {
+    let mut volume = 1.0;
+
+    for i in 0..DIM {
+        let min = (-cuboid_a.half_extents[i]).max(shift[i] - cuboid_b.half_extents[i]);
+        let max = cuboid_a.half_extents[i].min(shift[i] + cuboid_b.half_extents[i]);
+        volume *= (max - min).max(0.0);
+    }
+
+    volume
+}
+
+fn sampled_intersection_volume(
+    shape_a: &dyn Shape,
+    pos_a: &Isometry<Real>,
+    shape_b: &dyn Shape,
+    pos_b: &Isometry<Real>,
+) -> Real {
+    let aabb_a = shape_a.compute_aabb(pos_a);
+    let aabb_b = shape_b.compute_aabb(pos_b);
+
+    if !aabb_a.intersects(&aabb_b) {
+        return 0.0;
+    }
+
+    let mut aabb = aabb_a;
+    aabb.mins = aabb.mins.sup(&aabb_b.mins);
+    aabb.maxs = aabb.maxs.inf(&aabb_b.maxs);
+
+    let cell_extents = aabb.extents() / SAMPLES_PER_AXIS as Real;
+    let num_samples = SAMPLES_PER_AXIS.pow(DIM as u32);
+    let mut num_inside = 0;
+
+    for sample in 0..num_samples {
+        // Sample the center of each cell of the grid.
+        let mut pt = aabb.mins;
+        let mut index = sample;
+        for i in 0..DIM {
+            pt[i] += cell_extents[i] * ((index % SAMPLES_PER_AXIS) as Real + 0.5);
+            index /= SAMPLES_PER_AXIS;
+        }
+
+        if shape_a.contains_point(pos_a, &pt) && shape_b.contains_point(pos_b, &pt) {
+            num_inside += 1;
+        }
+    }
+
+    aabb.volume() * num_inside as Real / num_samples as Real
+}
+
+#[cfg(test)]
+mod test {
+    use super::intersection_volume;
+    use crate::math::{Isometry, Real, Vector};
+    use na::RealField;
+    use parry::shape::{Ball, Cuboid};
+
+    #[test]
+    fn intersection_volume_of_aligned_cuboids() {
+        let cuboid = Cuboid::new(Vector::repeat(1.0));
+        let pos_a = Isometry::identity();
+        let mut shift = Vector::repeat(0.25);
+        shift.x = 1.5;
+        shift.y = 0.5;
+        let pos_b = Isometry::from(shift);
+
+        // The overlap is [0.5, 1] along `x`, [-0.5, 1] along `y` and [-0.75, 1] along `z`.
+        #[cfg(feature = "dim2")]
+        let expected = 0.5 * 1.5;
+        #[cfg(feature = "dim3")]
+        let expected = 0.5 * 1.5 * 1.75;
+        let volume = intersection_volume(&cuboid, &pos_a, &cuboid, &pos_b);
+        assert!((volume - expected).abs() < 1.0e-5);
+
+        let pos_b = Isometry::from(Vector::x() * 2.5);
+        assert_eq!(intersection_volume(&cuboid, &pos_a, &cuboid, &pos_b), 0.0);
+    }
+
+    #[test]
+    fn sampled_intersection_volume_of_a_ball_inside_of_a_cuboid() {
+        let cuboid = Cuboid::new(Vector::repeat(1.0));
+        let ball = Ball::new(0.5);
+        let pos_a = Isometry::identity();
+        let pos_b = Isometry::from(Vector::x() * 0.25);
+
+        #[cfg(feature = "dim2")]
+        let expected = Real::pi() * 0.25;
+        #[cfg(feature = "dim3")]
+        let expected = Real::pi() * 0.5 / 3.0;
+        let volume = intersection_volume(&cuboid, &pos_a, &ball, &pos_b);
+        assert!((volume - expected).abs() < expected * 0.02);
+
+        let pos_b = Isometry::from(Vector::x() * 2.0);
+        assert_eq!(intersection_volume(&cuboid, &pos_a, &ball, &pos_b), 0.0);
+    }
+}
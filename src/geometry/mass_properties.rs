@@ -0,0 +1,165 @@
+use crate::dynamics::MassProperties;
+use crate::math::Real;
+use parry::shape::Shape;
+#[cfg(feature = "dim3")]
+use {
+    crate::math::{Matrix, Point},
+    num::Zero,
+    parry::mass_properties::details::tetrahedron_unit_inertia_tensor_wrt_point,
+    parry::shape::{Tetrahedron, TriMesh, TypedShape},
+};
+
+/// Computes the mass properties of a shape with the given uniform density.
+///
+/// The volume (or area in 2D) of the shape is the computed mass divided by `density`. This is
+/// the function used to compute the mass properties of colliders defined by a density so its
+/// results are identical to the ones of a collider with the same shape. Analytic shapes are
+/// handled without allocation. In 3D, the mass properties of a triangle mesh are obtained by
+/// integrating over its surface, which is only exact if the mesh is closed.
+pub fn shape_volume_and_mass_properties(shape: &dyn Shape, density: Real) -> MassProperties {
+    #[cfg(feature = "dim3")]
+    if let TypedShape::TriMesh(trimesh) = shape.as_typed_shape() {
+        return closed_trimesh_mass_properties(trimesh, density);
+    }
+
+    shape.mass_properties(density)
+}
+
+/// The mass properties of the volume enclosed by a triangle mesh.
+///
+/// The volume is decomposed into signed tetrahedra joining each triangle to a common reference
+/// point, so the mesh doesn't have to be convex.
+#[cfg(feature = "dim3")]
+fn closed_trimesh_mass_properties(trimesh: &TriMesh, density: Real) -> MassProperties {
+    let vertices = trimesh.vertices();
+    let indices = trimesh.indices();
+    let tetrahedra = || {
+        let reference = trimesh.local_aabb().center();
+        indices.iter().map(move |idx| {
+            Tetrahedron::new(
+                reference,
+                vertices[idx[0] as usize],
+                vertices[idx[1] as usize],
+                vertices[idx[2] as usize],
+            )
+        })
+    };
+
+    let mut volume = 0.0;
+    let mut com = Point::origin();
+
+    for tetra in tetrahedra() {
+        let tetra_volume = tetra.signed_volume();
+        volume += tetra_volume;
+        com += tetra.center().coords * tetra_volume;
+    }
+
+    // A flat mesh doesn't enclose any volume.
+    if volume.abs() <= crate::math::DEFAULT_EPSILON {
+        return MassProperties::zero();
+    }
+
+    com /= volume;
+
+    let mut inertia = Matrix::zeros();
+
+    for tetra in tetrahedra() {
+        let unit_inertia =
+            tetrahedron_unit_inertia_tensor_wrt_point(&com, &tetra.a, &tetra.b, &tetra.c, &tetra.d);
+        inertia += unit_inertia * tetra.signed_volume();
+    }
+
+    // The signed volumes are all negated if the triangles are oriented inward.
+    let sign = volume.signum();
+    MassProperties::with_inertia_matrix(com, volume * sign * density, inertia * (sign * density))
+}
+
+#[cfg(test)]
+mod test {
+    use super::shape_volume_and_mass_properties;
+    use crate::dynamics::MassProperties;
+    use crate::math::{AngVector, Real, Vector};
+    use na::RealField;
+    use parry::shape::{Ball, Cuboid};
+
+    // The mass and the principal angular inertia of the given mass properties.
+    fn mass_and_inertia(mprops: MassProperties) -> (Real, AngVector<Real>) {
+        #[cfg(feature = "dim2")]
+        let inertia = 1.0 / (mprops.inv_principal_inertia_sqrt.powi(2));
+        #[cfg(feature = "dim3")]
+        let inertia = mprops.inv_principal_inertia_sqrt.map(|x| 1.0 / (x * x));
+        (1.0 / mprops.inv_mass, inertia)
+    }
+
+    #[test]
+    fn ball_volume_and_mass_properties() {
+        let (mass, inertia) =
+            mass_and_inertia(shape_volume_and_mass_properties(&Ball::new(0.5), 2.0));
+
+        #[cfg(feature = "dim2")]
+        {
+            let expected_mass = Real::pi() * 0.25 * 2.0;
+            assert!((mass - expected_mass).abs() < 1.0e-5);
+            assert!((inertia - expected_mass * 0.25 / 2.0).abs() < 1.0e-5);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let expected_mass = Real::pi() * 0.125 * 4.0 / 3.0 * 2.0;
+            let expected_inertia = Vector::repeat(expected_mass * 0.25 * 2.0 / 5.0);
+            assert!((mass - expected_mass).abs() < 1.0e-5);
+            assert!((inertia - expected_inertia).norm() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn cuboid_volume_and_mass_properties() {
+        // A cuboid with sides of length 2, 1 (and 0.5 in 3D).
+        let mut half_extents = Vector::repeat(0.25);
+        half_extents.x = 1.0;
+        half_extents.y = 0.5;
+        let (mass, inertia) = mass_and_inertia(shape_volume_and_mass_properties(
+            &Cuboid::new(half_extents),
+            3.0,
+        ));
+
+        #[cfg(feature = "dim2")]
+        {
+            assert!((mass - 6.0).abs() < 1.0e-5);
+            assert!((inertia - 6.0 * (4.0 + 1.0) / 12.0).abs() < 1.0e-5);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let expected_inertia = Vector::new(1.0 + 0.25, 4.0 + 0.25, 4.0 + 1.0) * (3.0 / 12.0);
+            assert!((mass - 3.0).abs() < 1.0e-5);
+            assert!((inertia - expected_inertia).norm() < 1.0e-5);
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn trimesh_volume_and_mass_properties() {
+        use crate::math::Point;
+        use parry::shape::TriMesh;
+
+        let cuboid = Cuboid::new(Vector::new(1.0, 0.5, 0.25));
+        let (vertices, indices) = cuboid.to_trimesh();
+
+        // A closed mesh has the mass properties of the volume it encloses.
+        let closed = TriMesh::new(vertices, indices);
+        let (mass, inertia) = mass_and_inertia(shape_volume_and_mass_properties(&closed, 3.0));
+        let (expected_mass, expected_inertia) =
+            mass_and_inertia(shape_volume_and_mass_properties(&cuboid, 3.0));
+        assert!((mass - expected_mass).abs() < 1.0e-4);
+        assert!((inertia - expected_inertia).norm() < 1.0e-4);
+
+        // A flat mesh doesn't enclose any volume.
+        let flat_vertices = vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 1.0),
+            Point::new(0.0, 0.0, 1.0),
+        ];
+        let flat = TriMesh::new(flat_vertices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(shape_volume_and_mass_properties(&flat, 3.0).inv_mass, 0.0);
+    }
+}
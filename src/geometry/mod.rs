@@ -1,14 +1,32 @@
 //! Structures related to geometry: colliders, shapes, etc.
 
 pub use self::broad_phase_multi_sap::{BroadPhase, BroadPhasePairEvent, ColliderPair};
+pub use self::broad_phase_spatial_hash::SpatialHashBroadPhase;
 pub use self::collider_components::*;
-pub use self::contact_pair::{ContactData, ContactManifoldData};
+pub use self::compound_shape::{CompoundShape, COMPOUND_SHAPE_ID};
+pub use self::contact_pair::{
+    ContactData, ContactEdge, ContactId, ContactImpulse, ContactManifoldData,
+};
 pub use self::contact_pair::{ContactPair, SolverContact, SolverFlags};
+#[cfg(feature = "vhacd")]
+pub use self::convex_decomposition::{convex_decomposition, ConvexHull, VhacdParams};
+#[cfg(feature = "dim2")]
+pub use self::ellipsoid::Ellipse;
+pub use self::ellipsoid::{Ellipsoid, ELLIPSOID_SHAPE_ID};
 pub use self::interaction_graph::{
     ColliderGraphIndex, InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex,
 };
 pub use self::interaction_groups::InteractionGroups;
+pub use self::intersection_volume::intersection_volume;
+pub use self::mass_properties::shape_volume_and_mass_properties;
 pub use self::narrow_phase::NarrowPhase;
+pub use self::shape_contact::{contact_between, ShapeContact};
+#[cfg(feature = "dim3")]
+pub use self::torus::{Torus, TORUS_SHAPE_ID};
+#[cfg(feature = "binary-serde")]
+pub use self::trimesh_bvh::{
+    TriMeshBvhBytes, TriMeshBvhError, TRIMESH_BVH_FORMAT_VERSION, TRIMESH_BVH_MAGIC_NUMBER,
+};
 
 #[cfg(feature = "default-sets")]
 pub use self::collider::{Collider, ColliderBuilder};
@@ -33,10 +51,15 @@ pub type Ball = parry::shape::Ball;
 pub type Capsule = parry::shape::Capsule;
 /// A heightfield shape.
 pub type HeightField = parry::shape::HeightField;
-/// A cylindrical shape.
+/// A cylindrical shape with flat end-caps, aligned with the `y` axis.
+///
+/// This is a support-map shape, so it is handled exactly by the contact generation,
+/// ray-casting, and shape-casting (including CCD) queries.
 #[cfg(feature = "dim3")]
 pub type Cylinder = parry::shape::Cylinder;
-/// A cone shape.
+/// A cone shape aligned with the `y` axis, with its apex pointing toward `+y`.
+///
+/// This is a support-map shape with analytic mass properties.
 #[cfg(feature = "dim3")]
 pub type Cone = parry::shape::Cone;
 /// An axis-aligned bounding box.
@@ -51,7 +74,8 @@ pub type PointProjection = parry::query::PointProjection;
 pub type TOI = parry::query::TOI;
 pub use parry::shape::SharedShape;
 
-#[derive(Copy, Clone, Hash, Debug)]
+#[derive(Copy, Clone, Hash, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// Events occurring when two collision objects start or stop being in contact (or penetration).
 pub enum ContactEvent {
     /// Event occurring when two collision objects start being in contact.
@@ -64,7 +88,8 @@ pub enum ContactEvent {
     Stopped(ColliderHandle, ColliderHandle),
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// Events occurring when two collision objects start or stop being in close proximity, contact, or disjoint.
 pub struct IntersectionEvent {
     /// The first collider to which the proximity event applies.
@@ -105,11 +130,23 @@ pub(crate) fn default_query_dispatcher() -> std::sync::Arc<dyn parry::query::Que
 }
 
 mod broad_phase_multi_sap;
+mod broad_phase_spatial_hash;
 mod collider_components;
+mod compound_shape;
 mod contact_pair;
+#[cfg(feature = "vhacd")]
+mod convex_decomposition;
+mod ellipsoid;
 mod interaction_graph;
 mod interaction_groups;
+mod intersection_volume;
+mod mass_properties;
 mod narrow_phase;
+mod shape_contact;
+#[cfg(feature = "dim3")]
+mod torus;
+#[cfg(feature = "binary-serde")]
+mod trimesh_bvh;
 
 #[cfg(feature = "default-sets")]
 mod collider;
@@ -1,18 +1,22 @@
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+use crate::data::graph::Edge;
 use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetMut, ComponentSetOption};
 use crate::dynamics::CoefficientCombineRule;
 use crate::dynamics::{
-    IslandManager, RigidBodyActivation, RigidBodyDominance, RigidBodyIds, RigidBodyType,
+    IslandManager, RigidBodyActivation, RigidBodyDominance, RigidBodyHandle, RigidBodyIds,
+    RigidBodyType,
 };
 use crate::geometry::{
-    BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle, ColliderMaterial,
-    ColliderPair, ColliderParent, ColliderPosition, ColliderShape, ColliderType, ContactData,
-    ContactEvent, ContactManifold, ContactManifoldData, ContactPair, InteractionGraph,
-    IntersectionEvent, SolverContact, SolverFlags,
+    BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges, ColliderGraphIndex,
+    ColliderHandle, ColliderMaterial, ColliderPair, ColliderParent, ColliderPosition,
+    ColliderShape, ColliderType, ContactData, ContactEdge, ContactEvent, ContactId, ContactImpulse,
+    ContactManifold, ContactManifoldData, ContactPair, InteractionGraph, IntersectionEvent,
+    SolverContact, SolverFlags,
 };
 use crate::math::{Real, Vector};
+use crate::pipeline::update_oneway_solver_contacts;
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
@@ -20,9 +24,13 @@ use crate::pipeline::{
 use crate::prelude::ColliderFlags;
 use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
 use parry::utils::IsometryOpt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// The largest angle between the contact normal and the direction of a one-way collider for
+/// which the contacts are solved.
+const ONE_WAY_ALLOWED_ANGLE: Real = std::f32::consts::FRAC_PI_4 as Real;
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct ColliderGraphIndices {
@@ -58,6 +66,7 @@ pub struct NarrowPhase {
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     intersection_graph: InteractionGraph<ColliderHandle, bool>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    excluded_pairs: HashSet<(ColliderHandle, ColliderHandle)>,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
@@ -84,6 +93,7 @@ impl NarrowPhase {
             contact_graph: InteractionGraph::new(),
             intersection_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            excluded_pairs: HashSet::new(),
         }
     }
 
@@ -166,6 +176,34 @@ impl NarrowPhase {
             })
     }
 
+    /// The number of colliders currently intersecting the given sensor.
+    pub fn sensor_overlap_count(&self, sensor: ColliderHandle) -> usize {
+        self.intersections_with(sensor)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .count()
+    }
+
+    /// The colliders currently intersecting the given sensor, together with the rigid-bodies
+    /// they are attached to.
+    ///
+    /// The colliders not attached to any rigid-body are ignored.
+    pub fn sensor_overlapping_bodies<'a, Colliders>(
+        &'a self,
+        colliders: &'a Colliders,
+        sensor: ColliderHandle,
+    ) -> impl Iterator<Item = (ColliderHandle, RigidBodyHandle)> + 'a
+    where
+        Colliders: ComponentSetOption<ColliderParent>,
+    {
+        self.intersections_with(sensor)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .filter_map(move |(co1, co2, _)| {
+                let other = if co1 == sensor { co2 } else { co1 };
+                let co_parent: Option<&ColliderParent> = colliders.get(other.0);
+                co_parent.map(|p| (other, p.handle))
+            })
+    }
+
     /// The contact pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::contact_pair`] method instead. This
@@ -235,6 +273,62 @@ impl NarrowPhase {
         self.contact_graph.interactions()
     }
 
+    pub(crate) fn contact_pairs_mut(&mut self) -> impl Iterator<Item = &mut ContactPair> {
+        self.contact_graph
+            .graph
+            .edges
+            .iter_mut()
+            .map(|edge| &mut edge.weight)
+    }
+
+    /// A snapshot of the contact graph, listing the contact pairs with at least one active
+    /// contact, and the impulses applied by the constraints solver to each of them.
+    ///
+    /// The impulses are those of the last timestep each pair was solved, e.g., before the
+    /// rigid-bodies involved fell asleep.
+    pub fn contact_graph_snapshot(&self) -> Vec<ContactEdge> {
+        let mut edges = vec![];
+
+        for pair in self.contact_pairs() {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+
+            let mut edge = ContactEdge {
+                collider_a: pair.collider1,
+                collider_b: pair.collider2,
+                body_a: None,
+                body_b: None,
+                normal_impulse_sum: 0.0,
+                tangent_impulse_magnitude: 0.0,
+                contact_point_count: 0,
+            };
+
+            for manifold in &pair.manifolds {
+                edge.body_a = edge.body_a.or(manifold.data.rigid_body1);
+                edge.body_b = edge.body_b.or(manifold.data.rigid_body2);
+
+                for solver_contact in &manifold.data.solver_contacts {
+                    let data = &manifold.points[solver_contact.contact_id as usize].data;
+                    #[cfg(feature = "dim2")]
+                    let tangent_impulse = data.tangent_impulse.abs();
+                    #[cfg(feature = "dim3")]
+                    let tangent_impulse = data.tangent_impulse.norm();
+
+                    edge.normal_impulse_sum += data.impulse;
+                    edge.tangent_impulse_magnitude += tangent_impulse;
+                    edge.contact_point_count += 1;
+                }
+            }
+
+            if edge.contact_point_count > 0 {
+                edges.push(edge);
+            }
+        }
+
+        edges
+    }
+
     /// All the intersection pairs maintained by this narrow-phase.
     pub fn intersection_pairs(
         &self,
@@ -244,6 +338,32 @@ impl NarrowPhase {
             .map(|e| (e.0, e.1, *e.2))
     }
 
+    /// Disables the contacts and intersections between two specific colliders.
+    ///
+    /// The exclusion takes effect at the next timestep and is removed automatically if one of
+    /// the colliders is removed. The rigid-bodies attached to the colliders are not woken up.
+    pub fn exclude_pair(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) {
+        let _ = self
+            .excluded_pairs
+            .insert(excluded_pair_key(collider1, collider2));
+    }
+
+    /// Re-enables the contacts and intersections between two colliders excluded with
+    /// [`NarrowPhase::exclude_pair`].
+    ///
+    /// Returns `false` if this pair was not excluded.
+    pub fn include_pair(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) -> bool {
+        self.excluded_pairs
+            .remove(&excluded_pair_key(collider1, collider2))
+    }
+
+    /// Are the contacts and intersections between these two colliders disabled by
+    /// [`NarrowPhase::exclude_pair`]?
+    pub fn is_pair_excluded(&self, collider1: ColliderHandle, collider2: ColliderHandle) -> bool {
+        self.excluded_pairs
+            .contains(&excluded_pair_key(collider1, collider2))
+    }
+
     // #[cfg(feature = "parallel")]
     // pub(crate) fn contact_pairs_vec_mut(&mut self) -> &mut Vec<ContactPair> {
     //     &mut self.contact_graph.interactions
@@ -273,6 +393,12 @@ impl NarrowPhase {
         let mut prox_id_remap = HashMap::new();
         let mut contact_id_remap = HashMap::new();
 
+        if !removed_colliders.is_empty() && !self.excluded_pairs.is_empty() {
+            self.excluded_pairs.retain(|(collider1, collider2)| {
+                !removed_colliders.contains(collider1) && !removed_colliders.contains(collider2)
+            });
+        }
+
         for collider in removed_colliders {
             // NOTE: if the collider does not have any graph indices currently, there is nothing
             // to remove in the narrow-phase for this collider.
@@ -707,9 +833,9 @@ impl NarrowPhase {
 
         let nodes = &self.intersection_graph.graph.nodes;
         let query_dispatcher = &*self.query_dispatcher;
+        let excluded_pairs = &self.excluded_pairs;
 
-        // TODO: don't iterate on all the edges.
-        par_iter_mut!(&mut self.intersection_graph.graph.edges).for_each(|edge| {
+        let update_pair = |edge: &mut Edge<bool>| {
             let handle1 = nodes[edge.source().index()].weight;
             let handle2 = nodes[edge.target().index()].weight;
 
@@ -729,10 +855,25 @@ impl NarrowPhase {
                 &ColliderFlags,
             ) = colliders.index_bundle(handle2.0);
 
+            if !excluded_pairs.is_empty()
+                && excluded_pairs.contains(&excluded_pair_key(handle1, handle2))
+            {
+                let was_intersecting = std::mem::replace(&mut edge.weight, false);
+                let active_events = co_flags1.active_events | co_flags2.active_events;
+
+                return if was_intersecting
+                    && active_events.contains(ActiveEvents::INTERSECTION_EVENTS)
+                {
+                    Some(IntersectionEvent::new(handle1, handle2, false))
+                } else {
+                    None
+                };
+            }
+
             if !co_changes1.needs_narrow_phase_update() && !co_changes2.needs_narrow_phase_update()
             {
                 // No update needed for these colliders.
-                return;
+                return None;
             }
 
             // TODO: avoid lookup into bodies.
@@ -751,12 +892,12 @@ impl NarrowPhase {
             if !co_flags1.active_collision_types.test(rb_type1, rb_type2)
                 && !co_flags2.active_collision_types.test(rb_type1, rb_type2)
             {
-                return;
+                return None;
             }
 
             // Filter based on collision groups.
             if !co_flags1.collision_groups.test(co_flags2.collision_groups) {
-                return;
+                return None;
             }
 
             let active_hooks = co_flags1.active_hooks | co_flags2.active_hooks;
@@ -774,32 +915,55 @@ impl NarrowPhase {
 
                 if !hooks.filter_intersection_pair(&context) {
                     // No intersection allowed.
-                    return;
+                    return None;
                 }
             }
 
             let pos12 = co_pos1.inv_mul(co_pos2);
 
-            if let Ok(intersection) =
-                query_dispatcher.intersection_test(&pos12, &**co_shape1, &**co_shape2)
-            {
-                if active_events.contains(ActiveEvents::INTERSECTION_EVENTS)
-                    && intersection != edge.weight
-                {
-                    events.handle_intersection_event(IntersectionEvent::new(
-                        handle1,
-                        handle2,
-                        intersection,
-                    ));
-                }
-                edge.weight = intersection;
+            let intersection = query_dispatcher
+                .intersection_test(&pos12, &**co_shape1, &**co_shape2)
+                .ok()?;
+            let changed = intersection != edge.weight;
+            edge.weight = intersection;
+
+            if active_events.contains(ActiveEvents::INTERSECTION_EVENTS) && changed {
+                Some(IntersectionEvent::new(handle1, handle2, intersection))
+            } else {
+                None
             }
-        });
+        };
+
+        // TODO: don't iterate on all the edges.
+        #[cfg(not(feature = "parallel"))]
+        for edge in &mut self.intersection_graph.graph.edges {
+            if let Some(event) = update_pair(edge) {
+                events.handle_intersection_event(event);
+            }
+        }
+
+        // The pairs are updated in parallel, but the events are collected and emitted
+        // afterwards, in the same order as the sequential version.
+        #[cfg(feature = "parallel")]
+        {
+            let pending_events: Vec<_> = self
+                .intersection_graph
+                .graph
+                .edges
+                .par_iter_mut()
+                .filter_map(update_pair)
+                .collect();
+
+            for event in pending_events {
+                events.handle_intersection_event(event);
+            }
+        }
     }
 
     pub(crate) fn compute_contacts<Bodies, Colliders>(
         &mut self,
         prediction_distance: Real,
+        max_manifold_contacts: usize,
         bodies: &Bodies,
         colliders: &Colliders,
         modified_colliders: &[ColliderHandle],
@@ -814,16 +978,17 @@ impl NarrowPhase {
             + ComponentSet<ColliderShape>
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderMaterial>
-            + ComponentSet<ColliderFlags>,
+            + ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderBroadPhaseData>,
     {
         if modified_colliders.is_empty() {
             return;
         }
 
         let query_dispatcher = &*self.query_dispatcher;
+        let excluded_pairs = &self.excluded_pairs;
 
-        // TODO: don't iterate on all the edges.
-        par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
+        let update_pair = |edge: &mut Edge<ContactPair>| {
             let pair = &mut edge.weight;
 
             let co_parent1: Option<&ColliderParent> = colliders.get(pair.collider1.0);
@@ -844,10 +1009,30 @@ impl NarrowPhase {
                 &ColliderFlags,
             ) = colliders.index_bundle(pair.collider2.0);
 
+            if !excluded_pairs.is_empty()
+                && excluded_pairs.contains(&excluded_pair_key(pair.collider1, pair.collider2))
+            {
+                pair.manifolds.clear();
+                pair.workspace = None;
+
+                if !pair.has_any_active_contact {
+                    return None;
+                }
+
+                pair.has_any_active_contact = false;
+                let active_events = co_flags1.active_events | co_flags2.active_events;
+
+                return if active_events.contains(ActiveEvents::CONTACT_EVENTS) {
+                    Some(ContactEvent::Stopped(pair.collider1, pair.collider2))
+                } else {
+                    None
+                };
+            }
+
             if !co_changes1.needs_narrow_phase_update() && !co_changes2.needs_narrow_phase_update()
             {
                 // No update needed for these colliders.
-                return;
+                return None;
             }
 
             // TODO: avoid lookup into bodies.
@@ -866,12 +1051,12 @@ impl NarrowPhase {
             if !co_flags1.active_collision_types.test(rb_type1, rb_type2)
                 && !co_flags2.active_collision_types.test(rb_type1, rb_type2)
             {
-                return;
+                return None;
             }
 
             // Filter based on collision groups.
             if !co_flags1.collision_groups.test(co_flags2.collision_groups) {
-                return;
+                return None;
             }
 
             let active_hooks = co_flags1.active_hooks | co_flags2.active_hooks;
@@ -887,12 +1072,8 @@ impl NarrowPhase {
                     collider2: pair.collider2,
                 };
 
-                if let Some(solver_flags) = hooks.filter_contact_pair(&context) {
-                    solver_flags
-                } else {
-                    // No contact allowed.
-                    return;
-                }
+                // No contact allowed if this returns `None`.
+                hooks.filter_contact_pair(&context)?
             } else {
                 SolverFlags::default()
             };
@@ -904,10 +1085,21 @@ impl NarrowPhase {
             if co_changes1.contains(ColliderChanges::SHAPE)
                 || co_changes2.contains(ColliderChanges::SHAPE)
             {
-                // The shape changed so the workspace is no longer valid.
+                // The shape changed so the workspace is no longer valid. The
+                // manifolds must be recomputed too because the contact points they
+                // hold would otherwise be reused as long as the pair doesn't move.
                 pair.workspace = None;
+                pair.manifolds.clear();
             }
 
+            // Predict the contacts farther away for the colliders with speculative CCD.
+            let co_bf_data1: &ColliderBroadPhaseData = colliders.index(pair.collider1.0);
+            let co_bf_data2: &ColliderBroadPhaseData = colliders.index(pair.collider2.0);
+            let prediction_distance = prediction_distance
+                + co_bf_data1.speculative_margin
+                + co_bf_data2.speculative_margin;
+
+            let contact_cache = cache_contact_data(&pair.manifolds);
             let pos12 = co_pos1.inv_mul(co_pos2);
             let _ = query_dispatcher.contact_manifolds(
                 &pos12,
@@ -917,21 +1109,42 @@ impl NarrowPhase {
                 &mut pair.manifolds,
                 &mut pair.workspace,
             );
+            restore_contact_data(&mut pair.manifolds, &contact_cache);
 
             let mut has_any_active_contact = false;
 
+            #[cfg(feature = "dim2")]
             let friction = CoefficientCombineRule::combine(
                 co_material1.friction,
                 co_material2.friction,
                 co_material1.friction_combine_rule as u8,
                 co_material2.friction_combine_rule as u8,
             );
+            #[cfg(feature = "dim3")]
+            let (friction, anisotropic_friction) =
+                Self::combine_friction(co_pos1, co_material1, co_pos2, co_material2);
             let restitution = CoefficientCombineRule::combine(
                 co_material1.restitution,
                 co_material2.restitution,
                 co_material1.restitution_combine_rule as u8,
                 co_material2.restitution_combine_rule as u8,
             );
+            let rolling_friction = CoefficientCombineRule::combine(
+                co_material1.rolling_friction,
+                co_material2.rolling_friction,
+                co_material1.friction_combine_rule as u8,
+                co_material2.friction_combine_rule as u8,
+            );
+            #[cfg(feature = "dim3")]
+            let spinning_friction = CoefficientCombineRule::combine(
+                co_material1.spinning_friction,
+                co_material2.spinning_friction,
+                co_material1.friction_combine_rule as u8,
+                co_material2.friction_combine_rule as u8,
+            );
+
+            // The contacts of both colliders act like springs in series.
+            let compliance = co_material1.contact_compliance + co_material2.contact_compliance;
 
             let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
             let dominance1 = co_parent1
@@ -950,6 +1163,13 @@ impl NarrowPhase {
                 manifold.data.relative_dominance =
                     dominance1.effective_group(&rb_type1) - dominance2.effective_group(&rb_type2);
                 manifold.data.normal = world_pos1 * manifold.local_n1;
+                manifold.data.rolling_friction = rolling_friction;
+                manifold.data.compliance = compliance;
+                #[cfg(feature = "dim3")]
+                {
+                    manifold.data.spinning_friction = spinning_friction;
+                    manifold.data.anisotropic_friction = anisotropic_friction;
+                }
 
                 // Generate solver contacts.
                 for (contact_id, contact) in manifold.points.iter().enumerate() {
@@ -962,6 +1182,7 @@ impl NarrowPhase {
                         // Generate the solver contact.
                         let solver_contact = SolverContact {
                             contact_id: contact_id as u8,
+                            id: ContactId::new(contact.fid1, contact.fid2),
                             point: world_pos1 * contact.local_p1
                                 + manifold.data.normal * contact.dist / 2.0,
                             dist: contact.dist,
@@ -969,6 +1190,7 @@ impl NarrowPhase {
                             restitution,
                             tangent_velocity: Vector::zeros(),
                             is_new: contact.data.impulse == 0.0,
+                            warmstart_impulse: contact.data.impulse,
                         };
 
                         manifold.data.solver_contacts.push(solver_contact);
@@ -976,6 +1198,27 @@ impl NarrowPhase {
                     }
                 }
 
+                // Apply the one-way collision filters.
+                let one_way_filters = [
+                    (co_material1.one_way_direction, manifold.local_n1),
+                    (co_material2.one_way_direction, manifold.local_n2),
+                ];
+                for (k, (direction, local_n)) in one_way_filters.iter().enumerate() {
+                    if let Some(direction) = direction {
+                        update_oneway_solver_contacts(
+                            local_n,
+                            direction,
+                            ONE_WAY_ALLOWED_ANGLE,
+                            &mut manifold.data.solver_contacts,
+                            &mut manifold.data.one_way_states[k],
+                        );
+                    }
+                }
+
+                if manifold.data.solver_contacts.len() > max_manifold_contacts {
+                    reduce_solver_contacts(manifold, max_manifold_contacts);
+                }
+
                 // Apply the user-defined contact modification.
                 if active_hooks.contains(ActiveHooks::MODIFY_SOLVER_CONTACTS) {
                     let mut modifiable_solver_contacts =
@@ -1004,24 +1247,195 @@ impl NarrowPhase {
                 }
             }
 
-            if has_any_active_contact != pair.has_any_active_contact {
-                if active_events.contains(ActiveEvents::CONTACT_EVENTS) {
-                    if has_any_active_contact {
-                        events.handle_contact_event(
-                            ContactEvent::Started(pair.collider1, pair.collider2),
-                            pair,
-                        );
-                    } else {
-                        events.handle_contact_event(
-                            ContactEvent::Stopped(pair.collider1, pair.collider2),
-                            pair,
-                        );
-                    }
-                }
+            if has_any_active_contact == pair.has_any_active_contact {
+                return None;
+            }
+
+            pair.has_any_active_contact = has_any_active_contact;
+
+            if !active_events.contains(ActiveEvents::CONTACT_EVENTS) {
+                None
+            } else if has_any_active_contact {
+                Some(ContactEvent::Started(pair.collider1, pair.collider2))
+            } else {
+                Some(ContactEvent::Stopped(pair.collider1, pair.collider2))
+            }
+        };
+
+        // TODO: don't iterate on all the edges.
+        #[cfg(not(feature = "parallel"))]
+        for edge in &mut self.contact_graph.graph.edges {
+            if let Some(event) = update_pair(edge) {
+                events.handle_contact_event(event, &edge.weight);
+            }
+        }
+
+        // The contact manifolds are computed in parallel directly into their respective
+        // contact pairs, but the events are collected and emitted afterwards, in the same
+        // order as the sequential version.
+        #[cfg(feature = "parallel")]
+        {
+            let edges = &mut self.contact_graph.graph.edges;
+            let pending_events: Vec<_> = edges
+                .par_iter_mut()
+                .enumerate()
+                .filter_map(|(i, edge)| update_pair(edge).map(|event| (i, event)))
+                .collect();
+
+            for (i, event) in pending_events {
+                events.handle_contact_event(event, &edges[i].weight);
+            }
+        }
+    }
 
-                pair.has_any_active_contact = has_any_active_contact;
+    /// Combines the friction coefficients of two colliders.
+    ///
+    /// Returns the friction coefficient of the solver contacts, as well as the world-space
+    /// principal friction direction and the friction scaling factors along the principal
+    /// directions if at least one of the colliders has anisotropic friction.
+    #[cfg(feature = "dim3")]
+    fn combine_friction(
+        co_pos1: &ColliderPosition,
+        co_material1: &ColliderMaterial,
+        co_pos2: &ColliderPosition,
+        co_material2: &ColliderMaterial,
+    ) -> (Real, Option<(Vector<Real>, [Real; 2])>) {
+        let dir = match (
+            &co_material1.anisotropic_friction,
+            &co_material2.anisotropic_friction,
+        ) {
+            (None, None) => {
+                let friction = CoefficientCombineRule::combine(
+                    co_material1.friction,
+                    co_material2.friction,
+                    co_material1.friction_combine_rule as u8,
+                    co_material2.friction_combine_rule as u8,
+                );
+                return (friction, None);
             }
+            // NOTE: if both colliders have anisotropic friction, we use the principal direction
+            //       of the first one and combine the coefficients axis-wise.
+            (Some(aniso1), _) => co_pos1.0 * aniso1.local_axis,
+            (None, Some(aniso2)) => co_pos2.0 * aniso2.local_axis,
+        };
+
+        let coefficients1 = co_material1
+            .anisotropic_friction
+            .map(|aniso| aniso.coefficients)
+            .unwrap_or([co_material1.friction; 2]);
+        let coefficients2 = co_material2
+            .anisotropic_friction
+            .map(|aniso| aniso.coefficients)
+            .unwrap_or([co_material2.friction; 2]);
+        let combined = [0, 1].map(|i| {
+            CoefficientCombineRule::combine(
+                coefficients1[i],
+                coefficients2[i],
+                co_material1.friction_combine_rule as u8,
+                co_material2.friction_combine_rule as u8,
+            )
         });
+
+        // The solver contacts get the largest coefficient so it can still be modified
+        // by the physics hooks. The scaling factors are then in [0, 1].
+        let friction = combined[0].max(combined[1]);
+        let scale = if friction > 0.0 {
+            [combined[0] / friction, combined[1] / friction]
+        } else {
+            [1.0; 2]
+        };
+
+        (friction, Some((dir.into_inner(), scale)))
+    }
+
+    /// Updates the `contact_age_frames` of all the contact pairs at the end of a timestep.
+    pub(crate) fn update_contact_ages(&mut self) {
+        for edge in &mut self.contact_graph.graph.edges {
+            let pair = &mut edge.weight;
+
+            if pair.has_any_active_contact {
+                pair.contact_age_frames = pair.contact_age_frames.saturating_add(1);
+            } else {
+                pair.contact_age_frames = 0;
+            }
+        }
+    }
+
+    /// Reports the impulses computed by the constraints solver to the event handler.
+    pub(crate) fn emit_contact_impulse_events<Bodies, Colliders>(
+        &self,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        events: &dyn EventHandler,
+    ) where
+        Bodies: ComponentSet<RigidBodyType> + ComponentSet<RigidBodyActivation>,
+        Colliders: ComponentSet<ColliderFlags>,
+    {
+        let mut impulses = Vec::new();
+
+        for edge in &self.contact_graph.graph.edges {
+            let pair = &edge.weight;
+
+            if !pair.has_any_active_contact {
+                continue;
+            }
+
+            let co_flags1: &ColliderFlags = colliders.index(pair.collider1.0);
+            let co_flags2: &ColliderFlags = colliders.index(pair.collider2.0);
+
+            if !(co_flags1.active_events | co_flags2.active_events)
+                .contains(ActiveEvents::CONTACT_IMPULSE_EVENTS)
+            {
+                continue;
+            }
+
+            impulses.clear();
+
+            for manifold in &pair.manifolds {
+                if !manifold
+                    .data
+                    .solver_flags
+                    .contains(SolverFlags::COMPUTE_IMPULSES)
+                    || !Self::is_solved(bodies, manifold)
+                {
+                    continue;
+                }
+
+                for solver_contact in &manifold.data.solver_contacts {
+                    let data = &manifold.points[solver_contact.contact_id as usize].data;
+                    impulses.push(ContactImpulse {
+                        point: solver_contact.point,
+                        normal: manifold.data.normal,
+                        normal_impulse: data.impulse,
+                        tangent_impulse: data.tangent_impulse,
+                    });
+                }
+            }
+
+            if !impulses.is_empty() {
+                events.handle_contact_impulses(pair, &impulses);
+            }
+        }
+    }
+
+    /// Was this manifold handled by the constraints solver during the last timestep, i.e., does
+    /// it involve at least one dynamic body, and no sleeping body?
+    fn is_solved<Bodies>(bodies: &Bodies, manifold: &ContactManifold) -> bool
+    where
+        Bodies: ComponentSet<RigidBodyType> + ComponentSet<RigidBodyActivation>,
+    {
+        let body_state = |handle: Option<RigidBodyHandle>| {
+            handle.map_or((RigidBodyType::Static, true), |handle| {
+                let data: (&RigidBodyType, &RigidBodyActivation) = bodies.index_bundle(handle.0);
+                (*data.0, data.1.sleeping)
+            })
+        };
+        let (rb_type1, sleeping1) = body_state(manifold.data.rigid_body1);
+        let (rb_type2, sleeping2) = body_state(manifold.data.rigid_body2);
+
+        (rb_type1.is_dynamic() || rb_type2.is_dynamic())
+            && (!rb_type1.is_dynamic() || !sleeping1)
+            && (!rb_type2.is_dynamic() || !sleeping2)
     }
 
     /// Retrieve all the interactions with at least one contact point, happening between two active bodies.
@@ -1032,6 +1446,7 @@ impl NarrowPhase {
         bodies: &Bodies,
         out_manifolds: &mut Vec<&'a mut ContactManifold>,
         out: &mut Vec<Vec<ContactManifoldIndex>>,
+        sort_by_handle: bool,
     ) where
         Bodies: ComponentSet<RigidBodyIds>
             + ComponentSet<RigidBodyType>
@@ -1041,9 +1456,14 @@ impl NarrowPhase {
             out_island.clear();
         }
 
+        // The key identifying each selected manifold, used to sort them.
+        let mut keys = Vec::new();
+
         // TODO: don't iterate through all the interactions.
         for inter in self.contact_graph.graph.edges.iter_mut() {
-            for manifold in &mut inter.weight.manifolds {
+            let (co1, co2) = (inter.weight.collider1, inter.weight.collider2);
+
+            for (manifold_id, manifold) in inter.weight.manifolds.iter_mut().enumerate() {
                 if manifold
                     .data
                     .solver_flags
@@ -1080,9 +1500,521 @@ impl NarrowPhase {
 
                         out[island_index].push(out_manifolds.len());
                         out_manifolds.push(manifold);
+
+                        if sort_by_handle {
+                            keys.push((co1.0, co2.0, manifold_id));
+                        }
                     }
                 }
             }
         }
+
+        if sort_by_handle {
+            for out_island in &mut out[..islands.num_islands()] {
+                out_island.sort_unstable_by_key(|i| keys[*i]);
+            }
+        }
+    }
+}
+
+/// The pairs are excluded regardless of the order of their colliders, so sort them before
+/// using them as keys of the excluded pairs.
+fn excluded_pair_key(
+    collider1: ColliderHandle,
+    collider2: ColliderHandle,
+) -> (ColliderHandle, ColliderHandle) {
+    if collider1.0.into_raw_parts() > collider2.0.into_raw_parts() {
+        (collider2, collider1)
+    } else {
+        (collider1, collider2)
+    }
+}
+
+/// The data of the contacts of the given manifolds, sorted by the sub-shapes of their manifold
+/// and by their persistent identifier.
+fn cache_contact_data(manifolds: &[ContactManifold]) -> Vec<((u32, u32, ContactId), ContactData)> {
+    let mut cache: Vec<_> = manifolds
+        .iter()
+        .flat_map(|manifold| {
+            manifold.points.iter().map(move |contact| {
+                let id = ContactId::new(contact.fid1, contact.fid2);
+                ((manifold.subshape1, manifold.subshape2, id), contact.data)
+            })
+        })
+        .collect();
+    cache.sort_unstable_by_key(|(key, _)| *key);
+    cache
+}
+
+/// Transfers the cached contact data to the new contacts involving the same features.
+///
+/// The contacts that weren't cached keep the data set by the contact manifold generator.
+fn restore_contact_data(
+    manifolds: &mut [ContactManifold],
+    cache: &[((u32, u32, ContactId), ContactData)],
+) {
+    if cache.is_empty() {
+        return;
+    }
+
+    for manifold in manifolds {
+        for contact in &mut manifold.points {
+            let key = (
+                manifold.subshape1,
+                manifold.subshape2,
+                ContactId::new(contact.fid1, contact.fid2),
+            );
+            if let Ok(i) = cache.binary_search_by_key(&key, |(cached_key, _)| *cached_key) {
+                contact.data = cache[i].1;
+            }
+        }
+    }
+}
+
+/// Removes solver contacts from the manifold until at most `max_contacts` remain.
+///
+/// The deepest contact is kept first, then the contacts maximizing the area of the convex hull
+/// of the kept contacts, projected on the contact plane. The impulses of the manifold contacts
+/// that were removed are left untouched, so they can still be used for warmstarting if these
+/// contacts are kept again at the next timestep.
+fn reduce_solver_contacts(manifold: &mut ContactManifold, max_contacts: usize) {
+    let contacts = &manifold.data.solver_contacts;
+    let normal = manifold.data.normal;
+    let projected = |i: usize| {
+        let pt = contacts[i].point.coords;
+        pt - normal * normal.dot(&pt)
+    };
+    let area = |a: usize, b: usize, c: usize| {
+        let origin = projected(a);
+        signed_area(&(projected(b) - origin), &(projected(c) - origin), &normal)
+    };
+
+    // The kept contacts, ordered counterclockwise once there are at least three of them.
+    let mut hull: Vec<usize> = Vec::with_capacity(max_contacts);
+
+    while hull.len() < max_contacts {
+        // The contact enlarging the hull the most, and where to insert it in the hull.
+        let mut best = None;
+        let mut best_gain = 0.0;
+
+        for i in (0..contacts.len()).filter(|i| !hull.contains(i)) {
+            let (gain, position) = match hull.len() {
+                0 => (0.0, 0),
+                1 => ((projected(i) - projected(hull[0])).norm_squared(), 1),
+                2 => (area(hull[0], hull[1], i).abs(), 2),
+                len => (0..len)
+                    .map(|e| (-area(hull[e], hull[(e + 1) % len], i), e + 1))
+                    .fold((0.0, 0), |best, candidate| {
+                        if candidate.0 > best.0 {
+                            candidate
+                        } else {
+                            best
+                        }
+                    }),
+            };
+
+            if gain > best_gain {
+                best_gain = gain;
+                best = Some((i, position));
+            }
+        }
+
+        if let Some((i, position)) = best {
+            hull.insert(position, i);
+            if hull.len() == 3 && area(hull[0], hull[1], hull[2]) < 0.0 {
+                hull.swap(1, 2);
+            }
+        } else {
+            // This is the first contact, or none of the remaining contacts enlarge the hull,
+            // so keep the deepest one.
+            let deepest = (0..contacts.len())
+                .filter(|i| !hull.contains(i))
+                .min_by(|a, b| {
+                    contacts[*a]
+                        .dist
+                        .partial_cmp(&contacts[*b].dist)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            hull.push(deepest);
+        }
+    }
+
+    hull.sort_unstable();
+
+    let mut i = 0;
+    manifold.data.solver_contacts.retain(|_| {
+        i += 1;
+        hull.binary_search(&(i - 1)).is_ok()
+    });
+}
+
+/// Twice the signed area of the triangle with the edges `ab` and `ac`, lying on the plane
+/// orthogonal to `normal`.
+#[cfg(feature = "dim2")]
+fn signed_area(ab: &Vector<Real>, ac: &Vector<Real>, _normal: &Vector<Real>) -> Real {
+    ab.perp(ac)
+}
+
+/// Twice the signed area of the triangle with the edges `ab` and `ac`, lying on the plane
+/// orthogonal to `normal`.
+#[cfg(feature = "dim3")]
+fn signed_area(ab: &Vector<Real>, ac: &Vector<Real>, normal: &Vector<Real>) -> Real {
+    ab.cross(ac).dot(normal)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn one_way_collider_lets_bodies_through_from_below() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let platform = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * -3.0)
+                .linvel(Vector::y() * 12.0)
+                .build(),
+        );
+        let collider = ColliderBuilder::ball(1.0)
+            .one_way_direction(Vector::y_axis())
+            .build();
+        world
+            .colliders
+            .insert_with_parent(collider, platform, &mut world.bodies);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ball,
+            &mut world.bodies,
+        );
+
+        let mut max_height = Real::MIN;
+        for _ in 0..300 {
+            world.step();
+            max_height = max_height.max(world.bodies[ball].translation().y);
+        }
+
+        // The ball went through the platform, then landed on top of it.
+        assert!(max_height > 3.0);
+        assert!((world.bodies[ball].translation().y - 1.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn sensor_overlap_queries() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let zone = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let sensor = ColliderBuilder::ball(2.0).sensor(true).build();
+        let sensor = world
+            .colliders
+            .insert_with_parent(sensor, zone, &mut world.bodies);
+
+        let mut inside = Vec::new();
+        for x in [-1.0, 1.0, 5.0] {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * x)
+                .build();
+            let body = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.1).build();
+            let collider = world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+
+            if x < 2.0 {
+                inside.push((collider, body));
+            }
+        }
+
+        world.step();
+
+        assert_eq!(world.narrow_phase.sensor_overlap_count(sensor), 2);
+        let mut overlapping: Vec<_> = world
+            .narrow_phase
+            .sensor_overlapping_bodies(&world.colliders, sensor)
+            .collect();
+        overlapping.sort_by_key(|(collider, _)| collider.0);
+        assert_eq!(overlapping, inside);
+    }
+
+    #[test]
+    fn excluded_pairs_dont_collide() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_collider = ColliderBuilder::cuboid(10.0, 0.1).build();
+        #[cfg(feature = "dim3")]
+        let ground_collider = ColliderBuilder::cuboid(10.0, 0.1, 10.0).build();
+        let ground_collider =
+            world
+                .colliders
+                .insert_with_parent(ground_collider, ground, &mut world.bodies);
+
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 0.5)
+            .build();
+        let body = world.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.5).build();
+        let collider = world
+            .colliders
+            .insert_with_parent(collider, body, &mut world.bodies);
+
+        world.narrow_phase.exclude_pair(collider, ground_collider);
+        assert!(world
+            .narrow_phase
+            .is_pair_excluded(ground_collider, collider));
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        // The ball fell through the ground.
+        assert!(world.bodies[body].translation().y < -1.0);
+
+        world
+            .colliders
+            .remove(collider, &mut world.islands, &mut world.bodies, true);
+        world.step();
+        assert!(!world
+            .narrow_phase
+            .is_pair_excluded(ground_collider, collider));
+    }
+
+    #[test]
+    fn persistent_contact_ids() {
+        use crate::geometry::ContactId;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let ground = world
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5).build());
+
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 0.5)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let cube = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let cube = world
+            .colliders
+            .insert_with_parent(cube.build(), body, &mut world.bodies);
+
+        let mut ids = vec![];
+        for _ in 0..20 {
+            world.step();
+
+            let pair = world.narrow_phase.contact_pair(ground, cube).unwrap();
+            let mut step_ids: Vec<_> = pair
+                .manifolds
+                .iter()
+                .flat_map(|m| m.data.solver_contacts.iter())
+                .map(|c| (c.id, c.is_new))
+                .collect();
+            step_ids.sort();
+            ids.push(step_ids);
+        }
+
+        // The resting contacts keep their identifiers, and their impulses, across timesteps.
+        let last = ids.last().unwrap();
+        assert!(!last.is_empty());
+        assert!(last.iter().all(|(_, is_new)| !is_new));
+        assert!(ids[10..].iter().all(|step_ids| step_ids == last));
+
+        let id = ContactId::new(3, 7);
+        assert_eq!((id.fid1(), id.fid2()), (3, 7));
+    }
+
+    #[test]
+    fn manifold_contacts_reduction() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        // The cube face touching the ground generates one contact per vertex.
+        world.integration_parameters = IntegrationParameters {
+            max_manifold_contacts: if cfg!(feature = "dim2") { 1 } else { 2 },
+            ..IntegrationParameters::default()
+        };
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let ground = world
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5).build());
+
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 0.5)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let cube = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let cube = world
+            .colliders
+            .insert_with_parent(cube.build(), body, &mut world.bodies);
+
+        for _ in 0..50 {
+            world.step();
+
+            let pair = world.narrow_phase.contact_pair(ground, cube).unwrap();
+            let contacts: Vec<_> = pair
+                .manifolds
+                .iter()
+                .flat_map(|m| m.data.solver_contacts.iter())
+                .collect();
+            assert_eq!(
+                contacts.len(),
+                world.integration_parameters.max_manifold_contacts
+            );
+
+            // Two opposite vertices of the face are kept, covering it as much as possible.
+            #[cfg(feature = "dim3")]
+            assert!(
+                (na::distance(&contacts[0].point, &contacts[1].point) - Real::sqrt(2.0)).abs()
+                    < 1.0e-2
+            );
+        }
+
+        assert!((world.bodies[body].translation().y - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn contact_graph_snapshot() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        let ball = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 1.0)
+            .build();
+        let ball = world.bodies.insert(ball);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ball,
+            &mut world.bodies,
+        );
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        let snapshot = world.narrow_phase.contact_graph_snapshot();
+        assert_eq!(snapshot.len(), 1);
+
+        let edge = &snapshot[0];
+        let mut edge_bodies = [edge.body_a, edge.body_b];
+        edge_bodies.sort_by_key(|h| h.map(|h| h.into_raw_parts()));
+        assert_eq!(edge_bodies, [Some(ground), Some(ball)]);
+        assert!(edge.contact_point_count >= 1);
+        assert!(edge.tangent_impulse_magnitude < 1.0e-3);
+
+        // At rest, the contact compensates the gravity.
+        let weight_impulse = world.bodies[ball].mass() * 9.81 * world.integration_parameters.dt;
+        assert!((edge.normal_impulse_sum - weight_impulse).abs() < weight_impulse * 0.1);
+    }
+
+    // The parallel narrow-phase must emit its events in the same order as the sequential one,
+    // i.e., in the order of the edges of the contact and intersection graphs.
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_events_follow_the_sequential_order() {
+        use crate::geometry::{ContactEvent, ContactPair, IntersectionEvent};
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct EventRecorder {
+            intersections: Mutex<Vec<IntersectionEvent>>,
+            contacts: Mutex<Vec<ContactEvent>>,
+        }
+
+        impl EventHandler for EventRecorder {
+            fn handle_intersection_event(&self, event: IntersectionEvent) {
+                self.intersections.lock().unwrap().push(event);
+            }
+
+            fn handle_contact_event(&self, event: ContactEvent, _: &ContactPair) {
+                self.contacts.lock().unwrap().push(event);
+            }
+        }
+
+        let mut world = TestWorld::new(Vector::zeros());
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(100.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(100.0, 0.5, 100.0);
+        world
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5).build());
+
+        // Half of the balls are sensors, and all of them touch the ground.
+        for i in 0..64 {
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::x() * (i as Real * 2.0 - 64.0) + Vector::y() * 0.4)
+                    .build(),
+            );
+            let collider = if i % 2 == 0 {
+                ColliderBuilder::ball(0.5).active_events(ActiveEvents::CONTACT_EVENTS)
+            } else {
+                ColliderBuilder::ball(0.5)
+                    .sensor(true)
+                    .active_events(ActiveEvents::INTERSECTION_EVENTS)
+            };
+            world
+                .colliders
+                .insert_with_parent(collider.build(), body, &mut world.bodies);
+        }
+
+        let events = EventRecorder::default();
+        world.step_with(&(), &events);
+
+        let contact_edges = world.narrow_phase.contact_graph.graph.raw_edges();
+        let expected_contacts: Vec<_> = contact_edges
+            .iter()
+            .filter(|edge| edge.weight.has_any_active_contact)
+            .map(|edge| ContactEvent::Started(edge.weight.collider1, edge.weight.collider2))
+            .collect();
+        assert_eq!(expected_contacts.len(), 32);
+        assert_eq!(events.contacts.into_inner().unwrap(), expected_contacts);
+
+        let graph = &world.narrow_phase.intersection_graph.graph;
+        let nodes = graph.raw_nodes();
+        let expected_intersections: Vec<_> = graph
+            .raw_edges()
+            .iter()
+            .filter(|edge| edge.weight)
+            .map(|edge| {
+                IntersectionEvent::new(
+                    nodes[edge.source().index()].weight,
+                    nodes[edge.target().index()].weight,
+                    true,
+                )
+            })
+            .collect();
+        assert_eq!(expected_intersections.len(), 32);
+        assert_eq!(
+            events.intersections.into_inner().unwrap(),
+            expected_intersections
+        );
     }
 }
@@ -0,0 +1,84 @@
+use crate::math::{Isometry, Point, Real, Vector};
+use na::Unit;
+use parry::shape::Shape;
+
+/// A single contact between two shapes, computed by `contact_between`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct ShapeContact {
+    /// The contact point on the first shape, in world-space.
+    pub point_on_a: Point<Real>,
+    /// The contact point on the second shape, in world-space.
+    pub point_on_b: Point<Real>,
+    /// The contact normal, in world-space, pointing from the first shape toward the second one.
+    pub normal: Unit<Vector<Real>>,
+    /// The penetration depth between the shapes.
+    ///
+    /// This is negative if the shapes are separated by a distance smaller than the prediction
+    /// distance.
+    pub depth: Real,
+}
+
+impl ShapeContact {
+    /// The translation to apply to the first shape in order to resolve the penetration.
+    pub fn separation_vector(&self) -> Vector<Real> {
+        *self.normal * -self.depth.max(0.0)
+    }
+}
+
+/// Computes the deepest contact between two shapes, without going through the narrow-phase.
+///
+/// Returns `None` if the shapes are separated by a distance larger than `prediction`, or if
+/// this pair of shapes is not supported (e.g. between two triangle meshes, which only generate
+/// contacts through contact manifolds).
+pub fn contact_between(
+    shape_a: &dyn Shape,
+    pos_a: &Isometry<Real>,
+    shape_b: &dyn Shape,
+    pos_b: &Isometry<Real>,
+    prediction: Real,
+) -> Option<ShapeContact> {
+    let contact = parry::query::contact(pos_a, shape_a, pos_b, shape_b, prediction).ok()??;
+
+    Some(ShapeContact {
+        point_on_a: contact.point1,
+        point_on_b: contact.point2,
+        normal: contact.normal1,
+        depth: -contact.dist,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::contact_between;
+    use crate::geometry::Ball;
+    use crate::math::{Isometry, Point, Vector};
+
+    #[test]
+    fn contact_between_two_balls() {
+        let ball = Ball::new(1.0);
+        let pos_a = Isometry::identity();
+        let pos_b = Isometry::from(Vector::x() * 1.5);
+
+        let contact = contact_between(&ball, &pos_a, &ball, &pos_b, 0.0).unwrap();
+        assert!((contact.depth - 0.5).abs() < 1.0e-5);
+        assert!((*contact.normal - Vector::x()).norm() < 1.0e-5);
+        assert!((contact.point_on_a - Point::from(Vector::x())).norm() < 1.0e-5);
+        assert!((contact.point_on_b - Point::from(Vector::x() * 0.5)).norm() < 1.0e-5);
+        assert!((contact.separation_vector() + Vector::x() * 0.5).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn contact_between_separated_balls() {
+        let ball = Ball::new(1.0);
+        let pos_a = Isometry::identity();
+        let pos_b = Isometry::from(Vector::x() * 3.0);
+
+        assert!(contact_between(&ball, &pos_a, &ball, &pos_b, 0.5).is_none());
+
+        // Within the prediction distance, the contact has a negative depth.
+        let contact = contact_between(&ball, &pos_a, &ball, &pos_b, 1.5).unwrap();
+        assert!((contact.depth + 1.0).abs() < 1.0e-5);
+        assert_eq!(contact.separation_vector(), Vector::zeros());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::math::{Isometry, Point, Real, Vector};
+use na::{RealField, Unit, Vector2};
+use parry::bounding_volume::{BoundingSphere, AABB};
+use parry::mass_properties::MassProperties;
+use parry::query::{PointProjection, PointQuery, Ray, RayCast, RayIntersection};
+use parry::shape::{
+    FeatureId, PolygonalFeature, PolygonalFeatureMap, Shape, ShapeType, SupportMap, TypedShape,
+};
+
+/// The identifier returned by `TypedShape::Custom` for tori.
+pub const TORUS_SHAPE_ID: u32 = 0x7025_0001;
+
+/// A torus shape, centered at the origin, with its main circle lying on the `xz` plane.
+///
+/// Point projection and ray casting are exact. Because the torus isn't convex, the contact
+/// generation with shapes other than balls relies on its convex hull, which is exact for
+/// contacts with flat surfaces but fills its central hole.
+///
+/// This is a custom shape implemented by Rapier itself, so it cannot be serialized as part of
+/// a `SharedShape`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Torus {
+    /// The radius of the circle going through the center of the tube.
+    pub main_radius: Real,
+    /// The radius of the tube.
+    pub tube_radius: Real,
+}
+
+impl Torus {
+    /// Creates a new torus.
+    pub fn new(main_radius: Real, tube_radius: Real) -> Self {
+        Self {
+            main_radius,
+            tube_radius,
+        }
+    }
+
+    /// The volume of this torus.
+    pub fn volume(&self) -> Real {
+        2.0 * Real::pi() * Real::pi() * self.main_radius * self.tube_radius * self.tube_radius
+    }
+
+    /// The point of the tube center circle closest to `pt`.
+    fn closest_center(&self, pt: &Point<Real>) -> Point<Real> {
+        let dir = Vector2::new(pt.x, pt.z)
+            .try_normalize(crate::math::DEFAULT_EPSILON)
+            .unwrap_or_else(Vector2::x);
+        Point::new(dir.x * self.main_radius, 0.0, dir.y * self.main_radius)
+    }
+
+    /// The signed distance between `pt` and the boundary of this torus.
+    fn signed_distance(&self, pt: &Point<Real>) -> Real {
+        na::distance(pt, &self.closest_center(pt)) - self.tube_radius
+    }
+}
+
+impl SupportMap for Torus {
+    fn local_support_point(&self, dir: &Vector<Real>) -> Point<Real> {
+        let dir2 = Vector2::new(dir.x, dir.z)
+            .try_normalize(crate::math::DEFAULT_EPSILON)
+            .unwrap_or_else(Vector2::x);
+        let tube_dir = dir
+            .try_normalize(crate::math::DEFAULT_EPSILON)
+            .unwrap_or_else(Vector::x);
+
+        Point::new(dir2.x * self.main_radius, 0.0, dir2.y * self.main_radius)
+            + tube_dir * self.tube_radius
+    }
+}
+
+impl PolygonalFeatureMap for Torus {
+    fn local_support_feature(&self, dir: &Unit<Vector<Real>>, out_feature: &mut PolygonalFeature) {
+        let dir2 = Vector2::new(dir.x, dir.z);
+
+        // Directions close to the torus axis see its flat top or bottom
+        // circle, approximated by a square like the cylinder caps.
+        if dir2.norm() < 1.0e-2 {
+            let y = self.tube_radius.copysign(dir.y);
+            let r = self.main_radius;
+            out_feature.vertices = [
+                Point::new(r, y, 0.0),
+                Point::new(0.0, y, r),
+                Point::new(-r, y, 0.0),
+                Point::new(0.0, y, -r),
+            ];
+            out_feature.vids = [1, 3, 5, 7];
+            out_feature.eids = [2, 4, 6, 8];
+            out_feature.fid = if dir.y > 0.0 { 9 } else { 10 };
+            out_feature.num_vertices = 4;
+        } else {
+            let support = self.local_support_point_toward(dir);
+            out_feature.vertices = [support; 4];
+            out_feature.vids = [0; 4];
+            out_feature.eids = [0; 4];
+            out_feature.fid = 0;
+            out_feature.num_vertices = 1;
+        }
+    }
+}
+
+impl RayCast for Torus {
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let dir_norm = ray.dir.norm();
+        let unit_dir = ray.dir.try_normalize(crate::math::DEFAULT_EPSILON)?;
+        let bounding_radius = self.main_radius + self.tube_radius;
+
+        // Sphere-tracing: the distance to the torus boundary is a safe step along the ray.
+        let max_dist = (max_toi * dir_norm).min(ray.origin.coords.norm() + bounding_radius);
+        let eps = self.tube_radius * 1.0e-4;
+        let mut dist = 0.0;
+
+        if solid && self.signed_distance(&ray.origin) <= 0.0 {
+            let normal = -unit_dir;
+            return Some(RayIntersection::new(0.0, normal, FeatureId::Face(0)));
+        }
+
+        for _ in 0..256 {
+            let pt = ray.origin + unit_dir * dist;
+            let step = self.signed_distance(&pt).abs();
+
+            if step <= eps {
+                let normal = (pt - self.closest_center(&pt))
+                    .try_normalize(crate::math::DEFAULT_EPSILON)
+                    .unwrap_or(-unit_dir);
+                return Some(RayIntersection::new(
+                    dist / dir_norm,
+                    normal,
+                    FeatureId::Face(0),
+                ));
+            }
+
+            dist += step;
+
+            if dist > max_dist {
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
+impl PointQuery for Torus {
+    fn project_local_point(&self, pt: &Point<Real>, solid: bool) -> PointProjection {
+        let center = self.closest_center(pt);
+        let dpt = pt - center;
+        let dist = dpt.norm();
+        let is_inside = dist <= self.tube_radius;
+
+        if is_inside && solid {
+            return PointProjection::new(true, *pt);
+        }
+
+        let dir = dpt
+            .try_normalize(crate::math::DEFAULT_EPSILON)
+            .unwrap_or_else(Vector::y);
+        PointProjection::new(is_inside, center + dir * self.tube_radius)
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        pt: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        (self.project_local_point(pt, false), FeatureId::Face(0))
+    }
+}
+
+impl Shape for Torus {
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(*self)
+    }
+
+    fn compute_local_aabb(&self) -> AABB {
+        let extent = self.main_radius + self.tube_radius;
+        AABB::from_half_extents(
+            Point::origin(),
+            Vector::new(extent, self.tube_radius, extent),
+        )
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new(Point::origin(), self.main_radius + self.tube_radius)
+    }
+
+    fn compute_aabb(&self, position: &Isometry<Real>) -> AABB {
+        // The extent along each axis is the one of the main circle dilated by the tube radius.
+        let axis = position.rotation * Vector::y();
+        let half_extents = Vector::from_fn(|i, _| {
+            (1.0 - axis[i] * axis[i]).max(0.0).sqrt() * self.main_radius + self.tube_radius
+        });
+        AABB::from_half_extents(Point::from(position.translation.vector), half_extents)
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        let mass = self.volume() * density;
+        let sq_main = self.main_radius * self.main_radius;
+        let sq_tube = self.tube_radius * self.tube_radius;
+        let axial_inertia = mass * (sq_main + sq_tube * 3.0 / 4.0);
+        let diametral_inertia = mass * (sq_main / 2.0 + sq_tube * 5.0 / 8.0);
+
+        MassProperties::new(
+            Point::origin(),
+            mass,
+            Vector::new(diametral_inertia, axial_inertia, diametral_inertia),
+        )
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(TORUS_SHAPE_ID)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.tube_radius
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        Real::frac_pi_4()
+    }
+
+    fn is_convex(&self) -> bool {
+        false
+    }
+
+    fn as_support_map(&self) -> Option<&dyn SupportMap> {
+        Some(self as &dyn SupportMap)
+    }
+
+    fn as_polygonal_feature_map(&self) -> Option<(&dyn PolygonalFeatureMap, Real)> {
+        Some((self as &dyn PolygonalFeatureMap, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Torus;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+    use parry::query::{PointQuery, Ray, RayCast};
+    use parry::shape::{Shape, SupportMap};
+
+    fn torus() -> Torus {
+        Torus::new(2.0, 0.5)
+    }
+
+    #[test]
+    fn torus_support_points_are_extremal() {
+        let shape = torus();
+        assert_eq!(
+            shape.local_support_point(&Vector::x()),
+            Point::from(Vector::x() * 2.5)
+        );
+        assert_eq!(shape.local_support_point(&-Vector::y()).y, -0.5);
+
+        let dir = Vector::new(1.0, 2.0, -0.5);
+        let support = shape.local_support_point(&dir);
+        // The support point lies on the surface and no other surface point goes further.
+        assert!(shape.signed_distance(&support).abs() < 1.0e-5);
+        for i in 0..100 {
+            let (main_angle, tube_angle) = (i as Real * 0.37, i as Real * 1.7);
+            let center = Vector::new(main_angle.cos(), 0.0, main_angle.sin());
+            let other =
+                center * (2.0 + 0.5 * tube_angle.cos()) + Vector::y() * 0.5 * tube_angle.sin();
+            assert!(other.dot(&dir) <= support.coords.dot(&dir) + 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn torus_ray_casts_and_point_projections() {
+        let shape = torus();
+        let ray = Ray::new(Point::from(Vector::x() * -10.0), Vector::x());
+        let hit = shape
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.toi - 7.5).abs() < 1.0e-3);
+        assert!((hit.normal + Vector::x()).norm() < 1.0e-3);
+
+        let ray = Ray::new(Point::new(2.0, 10.0, 0.0), -Vector::y());
+        let hit = shape
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.toi - 9.5).abs() < 1.0e-3);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-3);
+
+        // The ray goes through the central hole.
+        let ray = Ray::new(Point::from(Vector::y() * 10.0), -Vector::y());
+        assert!(shape.cast_local_ray(&ray, Real::MAX, true).is_none());
+
+        let proj = shape.project_local_point(&Point::from(Vector::x() * 5.0), true);
+        assert!(!proj.is_inside);
+        assert!((proj.point - Point::from(Vector::x() * 2.5)).norm() < 1.0e-5);
+        let proj = shape.project_local_point(&Point::origin(), true);
+        assert!(!proj.is_inside);
+        assert!((proj.point - Point::from(Vector::x() * 1.5)).norm() < 1.0e-5);
+        let proj = shape.project_local_point(&Point::new(2.0, 0.2, 0.0), true);
+        assert!(proj.is_inside);
+    }
+
+    #[test]
+    fn torus_aabbs_and_mass_properties() {
+        let shape = torus();
+        let aabb = shape.compute_local_aabb();
+        assert_eq!(aabb.half_extents(), Vector::new(2.5, 0.5, 2.5));
+
+        // Tilted on its side, the torus is thin along `x`.
+        let rotation = Isometry::rotation(Vector::z() * Real::frac_pi_2());
+        let aabb = shape.compute_aabb(&rotation);
+        assert!((aabb.half_extents() - Vector::new(0.5, 2.5, 2.5)).norm() < 1.0e-3);
+
+        let mprops = shape.mass_properties(2.0);
+        let expected_mass = Real::pi() * Real::pi() * 2.0;
+        let inertia = mprops.inv_principal_inertia_sqrt.map(|x| 1.0 / (x * x));
+        let expected_inertia =
+            Vector::new(2.0 + 0.625 * 0.25, 4.0 + 0.75 * 0.25, 2.0 + 0.625 * 0.25) * expected_mass;
+        assert!((1.0 / mprops.inv_mass - expected_mass).abs() < 1.0e-4);
+        assert!((inertia - expected_inertia).norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn tori_rest_flat_on_the_ground() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0).build(),
+            ground,
+            &mut world.bodies,
+        );
+
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 2.0)
+                .build(),
+        );
+        let collider = ColliderBuilder::torus(2.0, 0.5);
+        world
+            .colliders
+            .insert_with_parent(collider.build(), body, &mut world.bodies);
+
+        for _ in 0..200 {
+            world.step();
+        }
+
+        // The bottom of the tube lies on the ground's top face.
+        let pos = world.bodies[body].position();
+        assert!((pos.translation.y - 1.0).abs() < 2.0e-2);
+        assert!((pos.rotation * Vector::y()).y > 0.999);
+    }
+}
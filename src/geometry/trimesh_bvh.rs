@@ -0,0 +1,144 @@
+//! Baking of the BVH of triangle meshes.
+
+use crate::math::{Point, Real};
+use parry::shape::TriMesh;
+
+/// The magic number at the beginning of the bytes produced by [`TriMeshBvhBytes::to_bvh_bytes`].
+pub const TRIMESH_BVH_MAGIC_NUMBER: [u8; 4] = *b"RBVH";
+
+/// The version of the format of the bytes produced by [`TriMeshBvhBytes::to_bvh_bytes`].
+///
+/// This is incremented whenever the format changes, including when the BVH layout of the
+/// underlying triangle meshes changes, so that baked data from a previous version is rejected.
+pub const TRIMESH_BVH_FORMAT_VERSION: u8 = 1;
+
+/// Error returned when a triangle mesh can't be read from baked BVH bytes.
+#[derive(Debug)]
+pub enum TriMeshBvhError {
+    /// The bytes don't start with `TRIMESH_BVH_MAGIC_NUMBER`.
+    InvalidMagicNumber,
+    /// The bytes were written with an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The bytes were baked from a mesh with different vertices or indices.
+    MismatchedMesh,
+    /// The bytes following the header could not be decoded.
+    Decode(postcard::Error),
+}
+
+impl std::fmt::Display for TriMeshBvhError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TriMeshBvhError::InvalidMagicNumber => {
+                write!(f, "the data is not a baked triangle mesh BVH")
+            }
+            TriMeshBvhError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported triangle mesh BVH format version {} (expected {})",
+                version, TRIMESH_BVH_FORMAT_VERSION
+            ),
+            TriMeshBvhError::MismatchedMesh => write!(
+                f,
+                "the triangle mesh BVH was baked from different vertices or indices"
+            ),
+            TriMeshBvhError::Decode(e) => write!(f, "invalid triangle mesh BVH data: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TriMeshBvhError {}
+
+/// Baking of the BVH of a triangle mesh, so that it doesn't have to be rebuilt when the mesh
+/// is loaded, e.g., by an asset pipeline.
+pub trait TriMeshBvhBytes: Sized {
+    /// Serializes this triangle mesh, including its BVH, into a compact binary format.
+    ///
+    /// The bytes start with `TRIMESH_BVH_MAGIC_NUMBER` followed by one byte containing
+    /// `TRIMESH_BVH_FORMAT_VERSION`, so stale baked data can be detected by
+    /// `Self::from_vertices_and_bvh`.
+    fn to_bvh_bytes(&self) -> Vec<u8>;
+
+    /// Creates a triangle mesh from its vertex and index buffers, and from the BVH baked by
+    /// `Self::to_bvh_bytes`, without building the BVH again.
+    ///
+    /// This fails if the bytes weren't produced by the same format version, or from a mesh
+    /// with the same vertices and indices.
+    fn from_vertices_and_bvh(
+        vertices: Vec<Point<Real>>,
+        indices: Vec<[u32; 3]>,
+        bvh_bytes: &[u8],
+    ) -> Result<Self, TriMeshBvhError>;
+}
+
+impl TriMeshBvhBytes for TriMesh {
+    fn to_bvh_bytes(&self) -> Vec<u8> {
+        let mut bytes = TRIMESH_BVH_MAGIC_NUMBER.to_vec();
+        bytes.push(TRIMESH_BVH_FORMAT_VERSION);
+        postcard::to_extend(self, bytes)
+            .expect("Serializing a triangle mesh into memory should not fail.")
+    }
+
+    fn from_vertices_and_bvh(
+        vertices: Vec<Point<Real>>,
+        indices: Vec<[u32; 3]>,
+        bvh_bytes: &[u8],
+    ) -> Result<Self, TriMeshBvhError> {
+        let header_len = TRIMESH_BVH_MAGIC_NUMBER.len();
+
+        if bvh_bytes.len() <= header_len || bvh_bytes[..header_len] != TRIMESH_BVH_MAGIC_NUMBER {
+            return Err(TriMeshBvhError::InvalidMagicNumber);
+        }
+
+        let version = bvh_bytes[header_len];
+        if version != TRIMESH_BVH_FORMAT_VERSION {
+            return Err(TriMeshBvhError::UnsupportedVersion(version));
+        }
+
+        let mesh: TriMesh =
+            postcard::from_bytes(&bvh_bytes[header_len + 1..]).map_err(TriMeshBvhError::Decode)?;
+
+        // The BVH indexes the triangles, so it is only valid for the mesh it was baked from.
+        if mesh.vertices() != &vertices[..] || mesh.indices() != &indices[..] {
+            return Err(TriMeshBvhError::MismatchedMesh);
+        }
+
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TriMeshBvhBytes, TriMeshBvhError};
+    use crate::math::{Point, Vector};
+    use parry::shape::TriMesh;
+
+    #[test]
+    fn trimesh_bvh_round_trip() {
+        let vertices = vec![
+            Point::origin(),
+            Point::from(Vector::x()),
+            Point::from(Vector::y()),
+            Point::from(Vector::x() + Vector::y()),
+        ];
+        let indices = vec![[0, 1, 2], [1, 3, 2]];
+        let mesh = TriMesh::new(vertices.clone(), indices.clone());
+        let bytes = mesh.to_bvh_bytes();
+
+        let baked = TriMesh::from_vertices_and_bvh(vertices.clone(), indices.clone(), &bytes);
+        let baked = baked.unwrap();
+        assert_eq!(baked.qbvh().root_aabb(), mesh.qbvh().root_aabb());
+
+        // Stale data is rejected.
+        let other_indices = vec![[0, 1, 2]];
+        assert!(matches!(
+            TriMesh::from_vertices_and_bvh(vertices.clone(), other_indices, &bytes),
+            Err(TriMeshBvhError::MismatchedMesh)
+        ));
+
+        let mut bytes = bytes;
+        bytes[4] += 1;
+        assert!(matches!(
+            TriMesh::from_vertices_and_bvh(vertices, indices, &bytes),
+            Err(TriMeshBvhError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -78,17 +78,6 @@ macro_rules! par_iter {
     }};
 }
 
-macro_rules! par_iter_mut {
-    ($t: expr) => {{
-        #[cfg(not(feature = "parallel"))]
-        let it = $t.iter_mut();
-
-        #[cfg(feature = "parallel")]
-        let it = $t.par_iter_mut();
-        it
-    }};
-}
-
 // macro_rules! par_chunks_mut {
 //     ($t: expr, $sz: expr) => {{
 //         #[cfg(not(feature = "parallel"))]
@@ -0,0 +1,788 @@
+//! Kinematic character controller.
+
+use crate::dynamics::{RigidBody, RigidBodySet, RigidBodyType};
+use crate::geometry::{ColliderHandle, ColliderSet, InteractionGroups};
+use crate::math::{Isometry, Point, Real, Rotation, UnitVector, Vector, DEFAULT_EPSILON};
+use crate::pipeline::QueryPipeline;
+use parry::query::TOI;
+use parry::shape::Shape;
+
+/// The movement computed by `KinematicCharacterController::move_shape`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EffectiveCharacterMovement {
+    /// The translation to apply to the character shape.
+    pub translation: Vector<Real>,
+    /// Was the character standing on the ground at its initial position?
+    pub grounded: bool,
+    /// The collider the character was standing on, if any.
+    pub ground: Option<ColliderHandle>,
+    /// The velocity of the ground at its contact point with the character, averaged over the
+    /// timestep.
+    ///
+    /// This is zero unless the ground is attached to a kinematic rigid-body.
+    pub ground_velocity: Vector<Real>,
+    /// The fraction of the character shape below the `fluid_plane` at its initial position.
+    ///
+    /// This is zero if the controller has no `fluid_plane`.
+    pub submerged_fraction: Real,
+}
+
+/// A ladder the character controller is climbing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CharacterLadder {
+    /// The direction the character moves along when climbing the ladder.
+    pub axis: UnitVector<Real>,
+    /// A point of the line, along `axis`, the character is kept on while climbing.
+    pub position: Point<Real>,
+    /// The maximum speed of the character along the ladder.
+    pub climb_speed: Real,
+}
+
+/// A controller moving a character shape through the scene, sliding along the obstacles.
+///
+/// The character isn't a rigid-body: the translation computed by `Self::move_shape` must be
+/// applied to the character shape by the user. When the character stands on a collider attached
+/// to a kinematic rigid-body, e.g., a moving platform, it is carried along by that collider.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct KinematicCharacterController {
+    /// The direction pointing up.
+    pub up: UnitVector<Real>,
+    /// The gap kept between the character and the obstacles.
+    pub offset: Real,
+    /// The maximum angle between the up direction and the normal of a surface the character
+    /// can stand on, or climb.
+    pub max_slope_climb_angle: Real,
+    /// The maximum distance below the character for a collider to be considered its ground.
+    ///
+    /// This should be larger than the `offset`.
+    pub ground_detection_distance: Real,
+    /// The maximum number of times the character slides along an obstacle during a move.
+    pub max_slides: usize,
+    /// The maximum height of the steps the character climbs, or descends without falling.
+    ///
+    /// When the character standing on the ground is blocked by an obstacle too steep to be
+    /// climbed, it tries to move over it after being lifted by at most `step_height`. The
+    /// obstacles attached to dynamic rigid-bodies are never climbed this way. When the
+    /// character walks off an edge, it snaps down to the ground up to `step_height` below.
+    /// Set this to zero to disable stepping.
+    pub step_height: Real,
+    /// The height, along the `up` direction, of the surface of the fluid the character swims
+    /// in, e.g., the `BuoyancyVolume::plane` of the fluid. Set this to `None` to disable
+    /// swimming.
+    pub fluid_plane: Option<Real>,
+    /// The upward acceleration applied to the fully submerged character.
+    ///
+    /// It should be larger than the gravity included in the desired translations for the
+    /// character to float.
+    pub buoyancy_force: Real,
+    /// Damping factor slowing down the swimming character.
+    pub water_drag: Real,
+    /// The maximum speed of the fully submerged character.
+    pub swim_speed: Real,
+    /// The ladder the character is climbing, if any.
+    ///
+    /// See `Self::enter_ladder`.
+    pub ladder: Option<CharacterLadder>,
+    /// The maximum distance the character is moved by, at each move, toward the line of the
+    /// ladder it climbs.
+    pub ladder_snap_tolerance: Real,
+}
+
+impl Default for KinematicCharacterController {
+    fn default() -> Self {
+        Self {
+            up: Vector::y_axis(),
+            offset: 0.01,
+            max_slope_climb_angle: std::f64::consts::FRAC_PI_4 as Real,
+            ground_detection_distance: 0.05,
+            max_slides: 4,
+            step_height: 0.0,
+            fluid_plane: None,
+            buoyancy_force: 10.0,
+            water_drag: 2.0,
+            swim_speed: 3.0,
+            ladder: None,
+            ladder_snap_tolerance: 0.1,
+        }
+    }
+}
+
+impl KinematicCharacterController {
+    /// Makes the character climb a ladder.
+    ///
+    /// Until `Self::exit_ladder` is called, the component of the desired translations along the
+    /// `up` direction moves the character along `ladder_axis`, at most at `climb_speed`, while
+    /// the rest of the desired translation is ignored. The character is also snapped to the line
+    /// passing through `ladder_position` along `ladder_axis`, see `Self::ladder_snap_tolerance`.
+    /// The desired translations shouldn't include gravity while climbing.
+    pub fn enter_ladder(
+        &mut self,
+        ladder_axis: Vector<Real>,
+        ladder_position: Point<Real>,
+        climb_speed: Real,
+    ) {
+        self.ladder = Some(CharacterLadder {
+            axis: UnitVector::new_normalize(ladder_axis),
+            position: ladder_position,
+            climb_speed,
+        });
+    }
+
+    /// Makes the character stop climbing its ladder, if any.
+    pub fn exit_ladder(&mut self) {
+        self.ladder = None;
+    }
+
+    /// Computes the translation of the character shape, starting at `shape_pos`, toward the
+    /// `desired_translation` for a timestep of length `dt`.
+    ///
+    /// If the character stands on a collider attached to a kinematic rigid-body, the motion of
+    /// that collider at the contact point during `dt` is added to the translation, including
+    /// the motion due to its angular velocity. The orientation of the character isn't modified.
+    /// This must be called before the `PhysicsPipeline::step` moving the kinematic bodies, once
+    /// their velocities, or their next kinematic positions, have been set.
+    ///
+    /// When the character is below the `fluid_plane`, the desired translation is blended,
+    /// according to the submerged fraction of the shape, with a swimming motion damped by the
+    /// `water_drag`, pushed up by the `buoyancy_force`, and limited to the `swim_speed`. For the
+    /// drag and the buoyancy to accumulate over time, the desired translation should be derived
+    /// from the velocity `translation / dt` of the previous effective movement.
+    ///
+    /// When the character climbs a ladder, it isn't carried by its ground, doesn't swim, and
+    /// doesn't climb steps, see `Self::enter_ladder`.
+    ///
+    /// The `filter` should exclude the colliders attached to the character itself, if any.
+    pub fn move_shape(
+        &self,
+        dt: Real,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        queries: &QueryPipeline,
+        shape: &dyn Shape,
+        shape_pos: &Isometry<Real>,
+        desired_translation: Vector<Real>,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> EffectiveCharacterMovement {
+        let mut result = EffectiveCharacterMovement {
+            translation: Vector::zeros(),
+            grounded: false,
+            ground: None,
+            ground_velocity: Vector::zeros(),
+            submerged_fraction: 0.0,
+        };
+
+        let ctx = CharacterQueries {
+            bodies,
+            colliders,
+            queries,
+            shape,
+            filter,
+        };
+        let up = self.up.into_inner();
+
+        if let Some((handle, toi)) = ctx.cast(shape_pos, &-up, self.ground_detection_distance) {
+            if self.is_walkable(&toi.normal1) {
+                result.grounded = true;
+                result.ground = Some(handle);
+
+                let platform = colliders[handle]
+                    .parent()
+                    .map(|parent| &bodies[parent])
+                    .filter(|body| body.is_kinematic());
+
+                if let Some(platform) = platform.filter(|_| dt > 0.0 && self.ladder.is_none()) {
+                    // The contact point right below the character, less sensitive to the
+                    // accuracy of the witness points than the witness itself.
+                    let center = Point::from(shape_pos.translation.vector);
+                    let contact = center - up * (center - toi.witness1).dot(&up);
+                    let displacement = ground_displacement(platform, &contact, dt);
+                    result.ground_velocity = displacement / dt;
+
+                    // The platform hasn't moved yet, so it must not block its own motion.
+                    let ground_filter =
+                        |h: ColliderHandle| h != handle && filter.map(|f| f(h)).unwrap_or(true);
+                    let platform_ctx = CharacterQueries {
+                        filter: Some(&ground_filter),
+                        ..ctx
+                    };
+                    result.translation = self.slide(&platform_ctx, shape_pos, displacement, false);
+                }
+            }
+        }
+
+        if let Some(ladder) = &self.ladder {
+            let translation = self.ladder_translation(ladder, shape_pos, desired_translation, dt);
+            result.translation = self.slide(&ctx, shape_pos, translation, false);
+            return result;
+        }
+
+        let mut desired_translation = desired_translation;
+
+        if let Some(fluid_plane) = self.fluid_plane {
+            result.submerged_fraction = self.submerged_fraction(shape, shape_pos, fluid_plane);
+
+            if result.submerged_fraction > 0.0 && dt > 0.0 {
+                let swim_translation = self.swim_velocity(desired_translation / dt, dt) * dt;
+                desired_translation =
+                    desired_translation.lerp(&swim_translation, result.submerged_fraction);
+            }
+        }
+
+        let mut pos = *shape_pos;
+        pos.translation.vector += result.translation;
+        let translation = self.slide(&ctx, &pos, desired_translation, result.grounded);
+        pos.translation.vector += translation;
+        result.translation += translation;
+
+        // Snap down to the ground below the edge the character may have walked off.
+        if result.grounded && self.step_height > 0.0 && desired_translation.dot(&up) <= 0.0 {
+            if let Some((_, toi)) = ctx.cast(&pos, &-up, self.step_height + self.offset) {
+                if self.is_walkable(&toi.normal1) {
+                    result.translation -= up * (toi.toi - self.offset).max(0.0);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The fraction of the AABB of the shape lying below the given fluid plane, along `up`.
+    fn submerged_fraction(
+        &self,
+        shape: &dyn Shape,
+        shape_pos: &Isometry<Real>,
+        fluid_plane: Real,
+    ) -> Real {
+        let aabb = shape.compute_aabb(shape_pos);
+        let center = aabb.center().coords.dot(&self.up);
+        let half_height = aabb.half_extents().dot(&self.up.abs());
+
+        if half_height <= DEFAULT_EPSILON {
+            return if center < fluid_plane { 1.0 } else { 0.0 };
+        }
+
+        ((fluid_plane - center + half_height) / (2.0 * half_height)).clamp(0.0, 1.0)
+    }
+
+    /// The velocity of the fully submerged character after `dt`, starting at `velocity`.
+    fn swim_velocity(&self, velocity: Vector<Real>, dt: Real) -> Vector<Real> {
+        let velocity = (velocity + self.up.into_inner() * self.buoyancy_force * dt)
+            / (1.0 + self.water_drag * dt);
+        let speed = velocity.norm();
+
+        if speed > self.swim_speed {
+            velocity * (self.swim_speed / speed)
+        } else {
+            velocity
+        }
+    }
+
+    /// The translation of the character climbing the given ladder, before collisions.
+    fn ladder_translation(
+        &self,
+        ladder: &CharacterLadder,
+        shape_pos: &Isometry<Real>,
+        desired_translation: Vector<Real>,
+        dt: Real,
+    ) -> Vector<Real> {
+        let max_climb = ladder.climb_speed * dt;
+        let climb = desired_translation
+            .dot(&self.up)
+            .clamp(-max_climb, max_climb);
+
+        // Move back toward the ladder line to prevent drifting.
+        let axis = ladder.axis.into_inner();
+        let to_ladder = ladder.position - Point::from(shape_pos.translation.vector);
+        let mut snap = to_ladder - axis * to_ladder.dot(&axis);
+        let snap_dist = snap.norm();
+
+        if snap_dist > self.ladder_snap_tolerance {
+            snap *= self.ladder_snap_tolerance / snap_dist;
+        }
+
+        axis * climb + snap
+    }
+
+    fn is_walkable(&self, normal: &Vector<Real>) -> bool {
+        normal.dot(&self.up) >= self.max_slope_climb_angle.cos() - DEFAULT_EPSILON
+    }
+
+    /// Moves the shape along `translation`, sliding along the obstacles it hits, and climbing
+    /// the steps if `climb_steps` is `true`.
+    fn slide(
+        &self,
+        ctx: &CharacterQueries,
+        shape_pos: &Isometry<Real>,
+        translation: Vector<Real>,
+        climb_steps: bool,
+    ) -> Vector<Real> {
+        let mut pos = *shape_pos;
+        let mut remaining = translation;
+
+        for _ in 0..=self.max_slides {
+            let length = remaining.norm();
+            if length <= DEFAULT_EPSILON {
+                break;
+            }
+
+            let dir = remaining / length;
+
+            match ctx.cast(&pos, &dir, length + self.offset) {
+                Some((handle, toi)) => {
+                    let step = dir * (toi.toi - self.offset).max(0.0).min(length);
+                    pos.translation.vector += step;
+                    remaining -= step;
+
+                    let normal = toi.normal1.into_inner();
+
+                    if climb_steps && !self.is_walkable(&normal) && !ctx.is_dynamic(handle) {
+                        let up = self.up.into_inner();
+                        let horizontal = remaining - up * remaining.dot(&up);
+
+                        if let Some(step) = self.climb_step(ctx, &pos, horizontal) {
+                            pos.translation.vector += step;
+                            break;
+                        }
+                    }
+
+                    // Remove the part of the remaining motion going into the obstacle, and
+                    // don't climb it if it is too steep.
+                    remaining -= normal * remaining.dot(&normal).min(0.0);
+
+                    if !self.is_walkable(&normal) {
+                        remaining -= self.up.into_inner() * remaining.dot(&self.up).max(0.0);
+                    }
+                }
+                None => {
+                    pos.translation.vector += remaining;
+                    break;
+                }
+            }
+        }
+
+        pos.translation.vector - shape_pos.translation.vector
+    }
+
+    /// The translation moving the shape onto the step blocking its `horizontal` motion, i.e.,
+    /// up by at most `self.step_height`, along `horizontal`, then down onto walkable ground.
+    ///
+    /// Returns `None` if the shape can't move forward this way, or doesn't land on the step.
+    fn climb_step(
+        &self,
+        ctx: &CharacterQueries,
+        shape_pos: &Isometry<Real>,
+        horizontal: Vector<Real>,
+    ) -> Option<Vector<Real>> {
+        let up = self.up.into_inner();
+        let length = horizontal.norm();
+
+        if self.step_height <= 0.0 || length <= DEFAULT_EPSILON {
+            return None;
+        }
+
+        let lift = match ctx.cast(shape_pos, &up, self.step_height + self.offset) {
+            Some((_, toi)) => (toi.toi - self.offset).max(0.0).min(self.step_height),
+            None => self.step_height,
+        };
+
+        let dir = horizontal / length;
+        let mut pos = *shape_pos;
+        pos.translation.vector += up * lift;
+
+        let advance = match ctx.cast(&pos, &dir, length + self.offset) {
+            Some((_, toi)) => (toi.toi - self.offset).max(0.0).min(length),
+            None => length,
+        };
+
+        if advance <= DEFAULT_EPSILON {
+            return None;
+        }
+
+        pos.translation.vector += dir * advance;
+        let (_, toi) = ctx.cast(&pos, &-up, lift + self.offset)?;
+
+        if !self.is_walkable(&toi.normal1) {
+            return None;
+        }
+
+        Some(up * (lift - (toi.toi - self.offset).max(0.0)) + dir * advance)
+    }
+}
+
+/// The scene and the shape involved in the shape-casts of a character controller.
+struct CharacterQueries<'a> {
+    bodies: &'a RigidBodySet,
+    colliders: &'a ColliderSet,
+    queries: &'a QueryPipeline,
+    shape: &'a dyn Shape,
+    filter: Option<&'a dyn Fn(ColliderHandle) -> bool>,
+}
+
+impl<'a> CharacterQueries<'a> {
+    fn cast(
+        &self,
+        shape_pos: &Isometry<Real>,
+        dir: &Vector<Real>,
+        max_dist: Real,
+    ) -> Option<(ColliderHandle, TOI)> {
+        self.queries.cast_shape(
+            self.colliders,
+            shape_pos,
+            dir,
+            self.shape,
+            max_dist,
+            InteractionGroups::all(),
+            self.filter,
+        )
+    }
+
+    fn is_dynamic(&self, collider: ColliderHandle) -> bool {
+        self.colliders[collider]
+            .parent()
+            .map(|parent| self.bodies[parent].is_dynamic())
+            .unwrap_or(false)
+    }
+}
+
+/// The displacement of the given point attached to a kinematic rigid-body during `dt`.
+fn ground_displacement(body: &RigidBody, point: &Point<Real>, dt: Real) -> Vector<Real> {
+    if body.body_type() == RigidBodyType::KinematicPositionBased
+        && body.next_position() != body.position()
+    {
+        return body.next_position() * body.position().inverse() * point - point;
+    }
+
+    // Rotate around the center-of-mass, like the integration of the kinematic body velocity.
+    // NOTE: the `world_com` isn't updated for kinematic bodies.
+    let arm = point - body.position() * body.rb_mprops.local_mprops.local_com;
+    let rotation = Rotation::new(body.rb_vels.angvel * dt);
+    body.rb_vels.linvel * dt + rotation * arm - arm
+}
+
+#[cfg(test)]
+mod test {
+    use super::KinematicCharacterController;
+    use crate::dynamics::{IslandManager, RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{Ball, ColliderBuilder, ColliderSet, Cuboid};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::QueryPipeline;
+
+    #[test]
+    fn character_controllers_ride_kinematic_platforms() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let mut query_pipeline = QueryPipeline::new();
+        let dt = world.integration_parameters.dt;
+        let controller = KinematicCharacterController::default();
+        let character_shape = Ball::new(0.5);
+
+        // A platform sliding along `x`.
+        let platform = world
+            .bodies
+            .insert(RigidBodyBuilder::new_kinematic_velocity_based().build());
+        #[cfg(feature = "dim2")]
+        let platform_shape = ColliderBuilder::cuboid(2.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let platform_shape = ColliderBuilder::cuboid(2.0, 0.1, 2.0);
+        let platform_collider =
+            world
+                .colliders
+                .insert_with_parent(platform_shape.build(), platform, &mut world.bodies);
+        #[cfg(feature = "dim2")]
+        let mut characters = [Isometry::translation(0.5, 0.61)];
+        #[cfg(feature = "dim3")]
+        let mut characters = vec![Isometry::translation(0.5, 0.61, 0.0)];
+
+        // A turntable rotating around `y`.
+        #[cfg(feature = "dim3")]
+        let turntable = world.bodies.insert(
+            RigidBodyBuilder::new_kinematic_velocity_based()
+                .translation(Vector::x() * 20.0)
+                .build(),
+        );
+        #[cfg(feature = "dim3")]
+        {
+            world.colliders.insert_with_parent(
+                ColliderBuilder::cuboid(3.0, 0.1, 3.0).build(),
+                turntable,
+                &mut world.bodies,
+            );
+            characters.push(Isometry::translation(21.5, 0.61, 0.0));
+        }
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        for _ in 0..60 {
+            // The kinematic velocities are reset at the end of each timestep.
+            world.bodies[platform].set_linvel(Vector::x() * 2.0, true);
+            #[cfg(feature = "dim3")]
+            world.bodies[turntable].set_angvel(Vector::y(), true);
+
+            for (k, character_pos) in characters.iter_mut().enumerate() {
+                let movement = controller.move_shape(
+                    dt,
+                    &world.bodies,
+                    &world.colliders,
+                    &query_pipeline,
+                    &character_shape,
+                    character_pos,
+                    world.gravity * dt * dt,
+                    None,
+                );
+                assert!(movement.grounded);
+
+                if k == 0 {
+                    assert_eq!(movement.ground, Some(platform_collider));
+                    assert!((movement.ground_velocity - Vector::x() * 2.0).norm() < 1.0e-4);
+                }
+
+                character_pos.translation.vector += movement.translation;
+            }
+
+            world.step();
+            query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+        }
+
+        // The character kept its place on the sliding platform.
+        let offset = characters[0].translation.vector - world.bodies[platform].translation();
+        assert!((offset.x - 0.5).abs() < 1.0e-3);
+        assert!((offset.y - 0.6).abs() < 0.02);
+
+        // The character followed the rotation of the turntable, without drifting away from
+        // its axis.
+        #[cfg(feature = "dim3")]
+        {
+            // The turntable rotates around its own center-of-mass.
+            assert!((world.bodies[turntable].translation() - Vector::x() * 20.0).norm() < 1.0e-4);
+            let offset = characters[1].translation.vector - Vector::x() * 20.0;
+            let expected = crate::math::Rotation::new(Vector::y()) * Vector::x() * 1.5;
+            assert!((offset.xz() - expected.xz()).norm() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn character_controllers_climb_and_descend_steps() {
+        let islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        let cuboid =
+            |half_width: Real, half_height: Real| ColliderBuilder::cuboid(half_width, half_height);
+        #[cfg(feature = "dim3")]
+        let cuboid = |half_width: Real, half_height: Real| {
+            ColliderBuilder::cuboid(half_width, half_height, 1.0)
+        };
+
+        colliders.insert(cuboid(50.0, 0.1).build());
+
+        // Four lanes, each with an obstacle in front of a character: a step, the same step
+        // with stepping disabled, the same step attached to a dynamic body, and a wall.
+        let steps = [(0.0, 0.3), (10.0, 0.3), (20.0, 0.3), (30.0, 0.6)];
+        for (k, (x, height)) in steps.iter().enumerate() {
+            let translation = Vector::x() * (x + 2.0) + Vector::y() * (0.1 + height / 2.0);
+
+            if k == 2 {
+                let body = bodies.insert(
+                    RigidBodyBuilder::new_dynamic()
+                        .translation(translation)
+                        .build(),
+                );
+                colliders.insert_with_parent(cuboid(0.5, height / 2.0).build(), body, &mut bodies);
+            } else {
+                colliders.insert(cuboid(0.5, height / 2.0).translation(translation).build());
+            }
+        }
+
+        query_pipeline.update(&islands, &bodies, &colliders);
+
+        let controller = KinematicCharacterController {
+            step_height: 0.35,
+            ..KinematicCharacterController::default()
+        };
+        let mut no_step_controller = controller;
+        no_step_controller.step_height = 0.0;
+
+        #[cfg(feature = "dim2")]
+        let character_shape = Cuboid::new(Vector::new(0.25, 0.5));
+        #[cfg(feature = "dim3")]
+        let character_shape = Cuboid::new(Vector::new(0.25, 0.5, 0.25));
+        let mut characters: Vec<Isometry<Real>> = steps
+            .iter()
+            .map(|(x, _)| Isometry::from(Vector::x() * *x + Vector::y() * 0.61))
+            .collect();
+        let mut max_climbed_height: Real = 0.0;
+
+        for _ in 0..100 {
+            for (k, character_pos) in characters.iter_mut().enumerate() {
+                let controller = if k == 1 {
+                    &no_step_controller
+                } else {
+                    &controller
+                };
+                let movement = controller.move_shape(
+                    1.0 / 60.0,
+                    &bodies,
+                    &colliders,
+                    &query_pipeline,
+                    &character_shape,
+                    character_pos,
+                    Vector::x() * 0.05 - Vector::y() * 0.02,
+                    None,
+                );
+                character_pos.translation.vector += movement.translation;
+
+                if k == 0 {
+                    let pos = character_pos.translation.vector;
+                    max_climbed_height = max_climbed_height.max(pos.y);
+
+                    if pos.x > 1.5 + 0.25 && pos.x < 2.5 - 0.25 {
+                        // On the step.
+                        assert!((pos.y - 0.91).abs() < 0.02);
+                    } else if pos.x > 2.5 + 0.3 {
+                        // Snapped down after walking off the step.
+                        assert!((pos.y - 0.61).abs() < 0.02);
+                    }
+                }
+            }
+        }
+
+        // The first character climbed the step, and walked down from it.
+        assert!((max_climbed_height - 0.91).abs() < 0.02);
+        assert!((characters[0].translation.x - 5.0).abs() < 0.02);
+
+        // The other characters remained blocked.
+        for (character_pos, (x, _)) in characters.iter().zip(steps.iter()).skip(1) {
+            let offset = character_pos.translation.vector - Vector::x() * *x;
+            assert!((offset.x - (1.5 - 0.25 - controller.offset)).abs() < 0.02);
+            assert!((offset.y - 0.61).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn character_controllers_swim_and_float() {
+        let islands = IslandManager::new();
+        let bodies = RigidBodySet::new();
+        let colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&islands, &bodies, &colliders);
+
+        let dt = 1.0 / 60.0;
+        let gravity = Vector::y() * -9.81;
+        let controller = KinematicCharacterController {
+            fluid_plane: Some(0.0),
+            buoyancy_force: 20.0,
+            water_drag: 5.0,
+            ..KinematicCharacterController::default()
+        };
+
+        // One character falling into the fluid, and one rising from the bottom of the fluid.
+        let shape = Ball::new(0.5);
+        let mut characters = [
+            (Isometry::from(Vector::y() * 2.0), Vector::zeros()),
+            (
+                Isometry::from(Vector::x() * 5.0 - Vector::y() * 5.0),
+                Vector::zeros(),
+            ),
+        ];
+        let mut submerged_fractions = [0.0; 2];
+
+        for _ in 0..600 {
+            for (k, (pos, velocity)) in characters.iter_mut().enumerate() {
+                let desired_translation = (*velocity + gravity * dt) * dt;
+                let movement = controller.move_shape(
+                    dt,
+                    &bodies,
+                    &colliders,
+                    &query_pipeline,
+                    &shape,
+                    pos,
+                    desired_translation,
+                    None,
+                );
+
+                if movement.submerged_fraction == 0.0 {
+                    // Normal gravity above the fluid.
+                    assert!((movement.translation - desired_translation).norm() < 1.0e-6);
+                } else if movement.submerged_fraction == 1.0 {
+                    assert!(movement.translation.norm() <= controller.swim_speed * dt + 1.0e-5);
+                }
+
+                pos.translation.vector += movement.translation;
+                *velocity = movement.translation / dt;
+                submerged_fractions[k] = movement.submerged_fraction;
+            }
+        }
+
+        // Both characters float, half-submerged, at the fluid surface.
+        for ((pos, velocity), fraction) in characters.iter().zip(submerged_fractions.iter()) {
+            assert!(*fraction > 0.4 && *fraction < 0.6);
+            assert!(pos.translation.y.abs() < 0.1);
+            assert!(velocity.norm() < 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn character_controllers_climb_ladders() {
+        let islands = IslandManager::new();
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        colliders.insert(ColliderBuilder::cuboid(50.0, 0.1).build());
+        #[cfg(feature = "dim3")]
+        colliders.insert(ColliderBuilder::cuboid(50.0, 0.1, 50.0).build());
+        query_pipeline.update(&islands, &bodies, &colliders);
+
+        #[cfg(feature = "dim2")]
+        let character_shape = Cuboid::new(Vector::new(0.25, 0.5));
+        #[cfg(feature = "dim3")]
+        let character_shape = Cuboid::new(Vector::new(0.25, 0.5, 0.25));
+        let mut character_pos = Isometry::from(Vector::x() * 0.95 + Vector::y() * 0.61);
+
+        let dt = 1.0 / 60.0;
+        let mut controller = KinematicCharacterController::default();
+        controller.enter_ladder(Vector::y() * 2.0, Point::from(Vector::x()), 1.0);
+
+        let mut move_character = |controller: &KinematicCharacterController,
+                                  desired_translation| {
+            let movement = controller.move_shape(
+                dt,
+                &bodies,
+                &colliders,
+                &query_pipeline,
+                &character_shape,
+                &character_pos,
+                desired_translation,
+                None,
+            );
+            character_pos.translation.vector += movement.translation;
+            character_pos.translation.vector
+        };
+
+        // Climb up, faster than the climb speed, trying to move sideways.
+        let mut pos = Vector::zeros();
+        for _ in 0..60 {
+            pos = move_character(&controller, (Vector::x() + Vector::y()) * 0.05);
+            assert!((pos.x - 1.0).abs() < 1.0e-5);
+        }
+        assert!((pos.y - 1.61).abs() < 1.0e-3);
+
+        // Climb down until reaching the ground.
+        for _ in 0..120 {
+            pos = move_character(&controller, -Vector::y() * 0.05);
+        }
+        assert!((pos.y - 0.61).abs() < 1.0e-3);
+
+        // Walk away from the ladder.
+        controller.exit_ladder();
+        for _ in 0..10 {
+            pos = move_character(&controller, Vector::x() * 0.05);
+        }
+        assert!((pos.x - 1.5).abs() < 1.0e-3);
+        assert!((pos.y - 0.61).abs() < 1.0e-3);
+    }
+}
@@ -101,12 +101,15 @@ impl CollisionPipeline {
         narrow_phase.register_pairs(None, colliders, bodies, &self.broad_phase_events, events);
         narrow_phase.compute_contacts(
             prediction_distance,
+            // There is no constraints solver to speed up here, so keep all the contacts.
+            usize::MAX,
             bodies,
             colliders,
             modified_colliders,
             hooks,
             events,
         );
+        narrow_phase.update_contact_ages();
         narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
     }
 
@@ -0,0 +1,465 @@
+//! Backend-agnostic rendering of wireframes for debugging physics scenes.
+
+use crate::dynamics::{ImpulseJointSet, MultibodyJointSet, RigidBodySet};
+use crate::geometry::{ColliderSet, NarrowPhase, AABB};
+use crate::math::{Isometry, Point, Real};
+use parry::shape::{Cuboid, Shape, TypedShape};
+
+/// A color, as red, green, blue and alpha components in `[0, 1]`.
+pub type DebugColor = [f32; 4];
+
+/// Trait implemented by the renderers receiving the geometry emitted by the `DebugRenderPipeline`.
+pub trait DebugRenderBackend {
+    /// Draws a line segment between two world-space points.
+    fn emit_line(&mut self, from: Point<Real>, to: Point<Real>, color: DebugColor);
+    /// Draws a world-space point.
+    fn emit_point(&mut self, pos: Point<Real>, color: DebugColor);
+}
+
+bitflags::bitflags! {
+    #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+    /// Flags selecting the elements rendered by the `DebugRenderPipeline`.
+    pub struct DebugRenderMode: u32 {
+        /// If set, the wireframe of the shape of each collider is rendered.
+        const COLLIDER_SHAPES = 0b0001;
+        /// If set, the AABB of each collider is rendered.
+        const COLLIDER_AABBS = 0b0010;
+        /// If set, the anchors of each impulse joint and multibody joint are rendered.
+        const JOINTS = 0b0100;
+        /// If set, the solver contacts and their normals are rendered.
+        const CONTACTS = 0b1000;
+    }
+}
+
+impl Default for DebugRenderMode {
+    fn default() -> Self {
+        DebugRenderMode::COLLIDER_SHAPES | DebugRenderMode::JOINTS
+    }
+}
+
+/// The colors and level of detail used by the `DebugRenderPipeline`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct DebugRenderStyle {
+    /// The color of the colliders attached to dynamic rigid-bodies.
+    pub dynamic_collider_color: DebugColor,
+    /// The color of the colliders attached to non-dynamic rigid-bodies, or to no rigid-body.
+    pub fixed_collider_color: DebugColor,
+    /// The color of the colliders attached to sleeping rigid-bodies.
+    pub sleeping_collider_color: DebugColor,
+    /// The color of the colliders AABBs.
+    pub aabb_color: DebugColor,
+    /// The color of the joint anchors, and of the lines attaching them to their rigid-bodies.
+    pub joint_anchor_color: DebugColor,
+    /// The color of the line between the two anchors of a joint.
+    pub joint_separation_color: DebugColor,
+    /// The color of the contact points and normals.
+    pub contact_color: DebugColor,
+    /// The length of the rendered contact normals.
+    pub contact_normal_length: Real,
+    /// The number of subdivisions used to render curved shapes.
+    pub subdivisions: u32,
+}
+
+impl Default for DebugRenderStyle {
+    fn default() -> Self {
+        Self {
+            dynamic_collider_color: [0.9, 0.6, 0.1, 1.0],
+            fixed_collider_color: [0.5, 0.5, 0.5, 1.0],
+            sleeping_collider_color: [0.1, 0.4, 0.9, 1.0],
+            aabb_color: [0.9, 0.9, 0.2, 1.0],
+            joint_anchor_color: [0.2, 0.2, 0.9, 1.0],
+            joint_separation_color: [0.9, 0.1, 0.1, 1.0],
+            contact_color: [0.1, 0.9, 0.1, 1.0],
+            contact_normal_length: 0.3,
+            subdivisions: 16,
+        }
+    }
+}
+
+/// Pipeline emitting the wireframes of colliders, joints and contacts to a `DebugRenderBackend`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct DebugRenderPipeline {
+    /// The colors and level of detail of the rendering.
+    pub style: DebugRenderStyle,
+    /// The elements to render.
+    pub mode: DebugRenderMode,
+}
+
+impl DebugRenderPipeline {
+    /// Creates a new debug render pipeline with the given style and mode.
+    pub fn new(style: DebugRenderStyle, mode: DebugRenderMode) -> Self {
+        Self { style, mode }
+    }
+
+    /// Emits to `backend` the geometry of all the elements selected by `self.mode`.
+    pub fn render(
+        &mut self,
+        backend: &mut impl DebugRenderBackend,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        narrow_phase: &NarrowPhase,
+    ) {
+        if self
+            .mode
+            .intersects(DebugRenderMode::COLLIDER_SHAPES | DebugRenderMode::COLLIDER_AABBS)
+        {
+            self.render_colliders(backend, bodies, colliders);
+        }
+
+        if self.mode.contains(DebugRenderMode::JOINTS) {
+            self.render_joints(backend, bodies, impulse_joints, multibody_joints);
+        }
+
+        if self.mode.contains(DebugRenderMode::CONTACTS) {
+            self.render_contacts(backend, narrow_phase);
+        }
+    }
+
+    /// Emits the shapes and AABBs of all the colliders.
+    pub fn render_colliders(
+        &mut self,
+        backend: &mut impl DebugRenderBackend,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        for (_, collider) in colliders.iter() {
+            if self.mode.contains(DebugRenderMode::COLLIDER_SHAPES) {
+                let color = match collider.parent().and_then(|h| bodies.get(h)) {
+                    Some(body) if body.is_sleeping() => self.style.sleeping_collider_color,
+                    Some(body) if body.is_dynamic() => self.style.dynamic_collider_color,
+                    _ => self.style.fixed_collider_color,
+                };
+                self.render_shape(backend, collider.shape(), collider.position(), color);
+            }
+
+            if self.mode.contains(DebugRenderMode::COLLIDER_AABBS) {
+                self.render_aabb(backend, &collider.compute_aabb(), self.style.aabb_color);
+            }
+        }
+    }
+
+    /// Emits the anchors of all the impulse joints and multibody joints.
+    pub fn render_joints(
+        &mut self,
+        backend: &mut impl DebugRenderBackend,
+        bodies: &RigidBodySet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+    ) {
+        for (_, joint) in impulse_joints.iter() {
+            if let (Some(rb1), Some(rb2)) = (bodies.get(joint.body1), bodies.get(joint.body2)) {
+                let frame1 = rb1.position() * joint.data.local_frame1;
+                let frame2 = rb2.position() * joint.data.local_frame2;
+                self.render_joint(backend, rb1.position(), &frame1, rb2.position(), &frame2);
+            }
+        }
+
+        for multibody in multibody_joints.multibodies() {
+            for link in multibody.links() {
+                let parent = link
+                    .parent_id()
+                    .and_then(|id| multibody.link(id))
+                    .and_then(|parent| bodies.get(parent.rigid_body_handle()));
+
+                if let (Some(rb1), Some(rb2)) = (parent, bodies.get(link.rigid_body_handle())) {
+                    let data = &link.joint().data;
+                    let frame1 = rb1.position() * data.local_frame1;
+                    let frame2 = rb2.position() * data.local_frame2;
+                    self.render_joint(backend, rb1.position(), &frame1, rb2.position(), &frame2);
+                }
+            }
+        }
+    }
+
+    /// Emits the solver contacts of all the contact pairs, with their normals.
+    pub fn render_contacts(
+        &mut self,
+        backend: &mut impl DebugRenderBackend,
+        narrow_phase: &NarrowPhase,
+    ) {
+        let color = self.style.contact_color;
+
+        for pair in narrow_phase.contact_pairs() {
+            for manifold in &pair.manifolds {
+                for contact in &manifold.data.solver_contacts {
+                    let tip =
+                        contact.point + manifold.data.normal * self.style.contact_normal_length;
+                    backend.emit_point(contact.point, color);
+                    backend.emit_line(contact.point, tip, color);
+                }
+            }
+        }
+    }
+
+    fn render_joint(
+        &self,
+        backend: &mut impl DebugRenderBackend,
+        pos1: &Isometry<Real>,
+        frame1: &Isometry<Real>,
+        pos2: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+    ) {
+        let anchor1 = Point::from(frame1.translation.vector);
+        let anchor2 = Point::from(frame2.translation.vector);
+        let color = self.style.joint_anchor_color;
+
+        backend.emit_point(anchor1, color);
+        backend.emit_point(anchor2, color);
+        backend.emit_line(Point::from(pos1.translation.vector), anchor1, color);
+        backend.emit_line(Point::from(pos2.translation.vector), anchor2, color);
+        backend.emit_line(anchor1, anchor2, self.style.joint_separation_color);
+    }
+
+    fn render_aabb(&self, backend: &mut impl DebugRenderBackend, aabb: &AABB, color: DebugColor) {
+        let pos = Isometry::from(aabb.center().coords);
+        self.render_shape(backend, &Cuboid::new(aabb.half_extents()), &pos, color);
+    }
+
+    fn render_shape(
+        &self,
+        backend: &mut impl DebugRenderBackend,
+        shape: &dyn Shape,
+        pos: &Isometry<Real>,
+        color: DebugColor,
+    ) {
+        let subdivs = self.style.subdivisions;
+
+        match shape.as_typed_shape() {
+            TypedShape::Segment(s) => backend.emit_line(pos * s.a, pos * s.b, color),
+            TypedShape::Triangle(t) => emit_polyline(backend, pos, &[t.a, t.b, t.c], true, color),
+            TypedShape::TriMesh(mesh) => {
+                for t in mesh.triangles() {
+                    emit_polyline(backend, pos, &[t.a, t.b, t.c], true, color);
+                }
+            }
+            TypedShape::Polyline(polyline) => {
+                for s in polyline.segments() {
+                    backend.emit_line(pos * s.a, pos * s.b, color);
+                }
+            }
+            TypedShape::HalfSpace(halfspace) => {
+                // Half-spaces are infinite: only their normal is rendered.
+                let origin = Point::from(pos.translation.vector);
+                let tip = origin + pos * *halfspace.normal * self.style.contact_normal_length;
+                backend.emit_line(origin, tip, color);
+            }
+            TypedShape::Compound(compound) => {
+                for (sub_pos, sub_shape) in compound.shapes() {
+                    self.render_shape(backend, &**sub_shape, &(pos * sub_pos), color);
+                }
+            }
+            TypedShape::RoundCuboid(s) => self.render_shape(backend, &s.base_shape, pos, color),
+            TypedShape::RoundTriangle(s) => self.render_shape(backend, &s.base_shape, pos, color),
+            #[cfg(feature = "dim2")]
+            TypedShape::Ball(ball) => {
+                emit_polyline(backend, pos, &ball.to_polyline(subdivs), true, color)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::Cuboid(cuboid) => {
+                emit_polyline(backend, pos, &cuboid.to_polyline(), true, color)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::Capsule(capsule) => {
+                emit_polyline(backend, pos, &capsule.to_polyline(subdivs), true, color)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::ConvexPolygon(poly) => {
+                emit_polyline(backend, pos, poly.points(), true, color)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::HeightField(heightfield) => {
+                for s in heightfield.segments() {
+                    backend.emit_line(pos * s.a, pos * s.b, color);
+                }
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundConvexPolygon(s) => {
+                self.render_shape(backend, &s.base_shape, pos, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Ball(ball) => {
+                let (vtx, idx) = ball.to_trimesh(subdivs, subdivs / 2);
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Cuboid(cuboid) => {
+                let (vtx, idx) = cuboid.to_trimesh();
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Capsule(capsule) => {
+                let (vtx, idx) = capsule.to_trimesh(subdivs, subdivs / 2);
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Cylinder(cylinder) => {
+                let (vtx, idx) = cylinder.to_trimesh(subdivs);
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Cone(cone) => {
+                let (vtx, idx) = cone.to_trimesh(subdivs);
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::ConvexPolyhedron(poly) => {
+                let (vtx, idx) = poly.to_trimesh();
+                emit_trimesh(backend, pos, &vtx, &idx, color)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::HeightField(heightfield) => {
+                for t in heightfield.triangles() {
+                    emit_polyline(backend, pos, &[t.a, t.b, t.c], true, color);
+                }
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCylinder(s) => self.render_shape(backend, &s.base_shape, pos, color),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCone(s) => self.render_shape(backend, &s.base_shape, pos, color),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundConvexPolyhedron(s) => {
+                self.render_shape(backend, &s.base_shape, pos, color)
+            }
+            TypedShape::Custom(_) => {
+                // Shapes unknown to Rapier are rendered as their AABB.
+                self.render_aabb(backend, &shape.compute_aabb(pos), color)
+            }
+        }
+    }
+}
+
+fn emit_polyline(
+    backend: &mut impl DebugRenderBackend,
+    pos: &Isometry<Real>,
+    points: &[Point<Real>],
+    closed: bool,
+    color: DebugColor,
+) {
+    for pts in points.windows(2) {
+        backend.emit_line(pos * pts[0], pos * pts[1], color);
+    }
+
+    if closed && points.len() > 2 {
+        backend.emit_line(pos * points[points.len() - 1], pos * points[0], color);
+    }
+}
+
+#[cfg(feature = "dim3")]
+fn emit_trimesh(
+    backend: &mut impl DebugRenderBackend,
+    pos: &Isometry<Real>,
+    vertices: &[Point<Real>],
+    indices: &[[u32; 3]],
+    color: DebugColor,
+) {
+    for idx in indices {
+        let triangle = [
+            vertices[idx[0] as usize],
+            vertices[idx[1] as usize],
+            vertices[idx[2] as usize],
+        ];
+        emit_polyline(backend, pos, &triangle, true, color);
+    }
+}
+
+// The parallel solver doesn't support impulse joints yet.
+#[cfg(all(test, not(feature = "parallel")))]
+mod test {
+    use super::{
+        DebugColor, DebugRenderBackend, DebugRenderMode, DebugRenderPipeline, DebugRenderStyle,
+    };
+    use crate::dynamics::{FixedJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[derive(Default)]
+    struct Counter {
+        lines: Vec<DebugColor>,
+        points: Vec<DebugColor>,
+    }
+
+    impl DebugRenderBackend for Counter {
+        fn emit_line(&mut self, _: Point<Real>, _: Point<Real>, color: DebugColor) {
+            self.lines.push(color);
+        }
+        fn emit_point(&mut self, _: Point<Real>, color: DebugColor) {
+            self.points.push(color);
+        }
+    }
+
+    #[test]
+    fn debug_render_pipeline() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        #[cfg(feature = "dim2")]
+        world
+            .colliders
+            .insert(ColliderBuilder::cuboid(10.0, 0.5).build());
+        #[cfg(feature = "dim3")]
+        world
+            .colliders
+            .insert(ColliderBuilder::cuboid(10.0, 0.5, 10.0).build());
+        // The first ball rests on the ground, the two others are attached by a joint.
+        let handles: Vec<_> = [1.0, 5.0, 7.0]
+            .iter()
+            .map(|y| {
+                let body = RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * *y)
+                    .build();
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5).build(),
+                    handle,
+                    &mut world.bodies,
+                );
+                handle
+            })
+            .collect();
+        world
+            .impulse_joints
+            .insert(handles[1], handles[2], FixedJoint::new());
+        world.step();
+
+        let render = |mode| {
+            let mut debug_render = DebugRenderPipeline::new(DebugRenderStyle::default(), mode);
+            let mut counter = Counter::default();
+            debug_render.render(
+                &mut counter,
+                &world.bodies,
+                &world.colliders,
+                &world.impulse_joints,
+                &world.multibody_joints,
+                &world.narrow_phase,
+            );
+            (debug_render.style, counter)
+        };
+
+        // One box per AABB: 4 edges in 2D, and 12 triangles in 3D.
+        let (style, aabbs) = render(DebugRenderMode::COLLIDER_AABBS);
+        #[cfg(feature = "dim2")]
+        assert_eq!(aabbs.lines.len(), 4 * 4);
+        #[cfg(feature = "dim3")]
+        assert_eq!(aabbs.lines.len(), 4 * 12 * 3);
+        assert!(aabbs.lines.iter().all(|c| *c == style.aabb_color));
+        assert!(aabbs.points.is_empty());
+
+        let (style, shapes) = render(DebugRenderMode::COLLIDER_SHAPES);
+        assert!(shapes.lines.contains(&style.fixed_collider_color));
+        assert!(shapes.lines.contains(&style.dynamic_collider_color));
+
+        // Two anchors, the two lines to the bodies, and the line between the anchors.
+        let (style, joints) = render(DebugRenderMode::JOINTS);
+        assert_eq!(joints.points, [style.joint_anchor_color; 2]);
+        assert_eq!(joints.lines.len(), 3);
+
+        let (style, contacts) = render(DebugRenderMode::CONTACTS);
+        assert!(!contacts.points.is_empty());
+        assert_eq!(contacts.points.len(), contacts.lines.len());
+        assert!(contacts.points.iter().all(|c| *c == style.contact_color));
+    }
+}
@@ -1,5 +1,11 @@
-use crate::geometry::{ContactEvent, ContactPair, IntersectionEvent};
+use crate::dynamics::{IslandEvent, SleepEvent};
+use crate::geometry::{
+    ColliderHandle, ContactEvent, ContactImpulse, ContactPair, IntersectionEvent,
+};
 use crossbeam::channel::Sender;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -9,6 +15,9 @@ bitflags::bitflags! {
         const INTERSECTION_EVENTS = 0b0001;
         /// If set, Rapier will call `PhysicsHooks::handle_contact_event` whenever relevant for this collider.
         const CONTACT_EVENTS = 0b0010;
+        /// If set, Rapier will call `EventHandler::handle_contact_impulses` after each timestep for the
+        /// contact pairs involving this collider.
+        const CONTACT_IMPULSE_EVENTS = 0b0100;
     }
 }
 
@@ -31,6 +40,22 @@ pub trait EventHandler: Send + Sync {
     /// A contact event is emitted when two collider start or stop touching, independently from the
     /// number of contact points involved.
     fn handle_contact_event(&self, event: ContactEvent, contact_pair: &ContactPair);
+    /// Handle the impulses applied by the constraints solver to a contact pair.
+    ///
+    /// This is called after the constraints solver ran, for each contact pair with active
+    /// contacts solved during this timestep and involving a collider with the
+    /// `ActiveEvents::CONTACT_IMPULSE_EVENTS` flag. The `impulses` contain one element per
+    /// contact seen by the constraints solver.
+    fn handle_contact_impulses(&self, _contact_pair: &ContactPair, _impulses: &[ContactImpulse]) {}
+    /// Handle a change of connectivity of the simulation islands.
+    ///
+    /// An island event is emitted when rigid-bodies become connected, or disconnected, through
+    /// contacts or joints.
+    fn handle_island_event(&self, _event: IslandEvent) {}
+    /// Handle a sleep event.
+    ///
+    /// A sleep event is emitted when a dynamic rigid-body falls asleep or wakes up.
+    fn handle_sleep_event(&self, _event: SleepEvent) {}
 }
 
 impl EventHandler for () {
@@ -66,3 +91,445 @@ impl EventHandler for ChannelEventCollector {
         let _ = self.contact_event_sender.send(event);
     }
 }
+
+/// A physics event handler that collects events into bounded ring buffers.
+///
+/// Unlike `ChannelEventCollector`, the memory used by this buffer doesn't grow if the events
+/// aren't drained often enough: once `capacity` events are buffered, each new event replaces
+/// the oldest one and the overflow flag is set. The overflow flag can be checked after each
+/// step to detect that events were lost, e.g., to increase the capacity or to drain the
+/// events more often.
+pub struct ContactEventBuffer {
+    capacity: usize,
+    overflow: AtomicBool,
+    intersection_events: Mutex<VecDeque<IntersectionEvent>>,
+    contact_events: Mutex<VecDeque<ContactEvent>>,
+}
+
+impl ContactEventBuffer {
+    /// Creates a new event buffer storing up to `capacity` contact events, and up to `capacity`
+    /// intersection events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            overflow: AtomicBool::new(false),
+            intersection_events: Mutex::new(VecDeque::with_capacity(capacity)),
+            contact_events: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// The maximum number of events of each kind stored by this buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of events of each kind stored by this buffer.
+    ///
+    /// If the new capacity is smaller than the number of events currently buffered, the
+    /// oldest events are discarded and the overflow flag is set.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let overflow = Self::truncate(self.intersection_events.get_mut().unwrap(), capacity)
+            | Self::truncate(self.contact_events.get_mut().unwrap(), capacity);
+
+        if overflow {
+            *self.overflow.get_mut() = true;
+        }
+    }
+
+    /// Returns `true` if some events were discarded because this buffer was full.
+    ///
+    /// This flag remains set until `Self::clear_overflow` is called.
+    pub fn overflow(&self) -> bool {
+        self.overflow.load(Ordering::Relaxed)
+    }
+
+    /// Resets the overflow flag.
+    pub fn clear_overflow(&self) {
+        self.overflow.store(false, Ordering::Relaxed)
+    }
+
+    /// Removes and returns all the buffered intersection events, from the oldest to the newest.
+    pub fn drain_intersection_events(&self) -> Vec<IntersectionEvent> {
+        self.intersection_events.lock().unwrap().drain(..).collect()
+    }
+
+    /// Removes and returns all the buffered contact events, from the oldest to the newest.
+    pub fn drain_contact_events(&self) -> Vec<ContactEvent> {
+        self.contact_events.lock().unwrap().drain(..).collect()
+    }
+
+    fn push<E>(&self, events: &Mutex<VecDeque<E>>, event: E) {
+        let mut events = events.lock().unwrap();
+        events.push_back(event);
+
+        if Self::truncate(&mut events, self.capacity) {
+            self.overflow.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // Discards the oldest events until there are at most `capacity` of them. Returns `true` if
+    // any event was discarded.
+    fn truncate<E>(events: &mut VecDeque<E>, capacity: usize) -> bool {
+        let excess = events.len().saturating_sub(capacity);
+        events.drain(..excess);
+        excess > 0
+    }
+}
+
+impl EventHandler for ContactEventBuffer {
+    fn handle_intersection_event(&self, event: IntersectionEvent) {
+        self.push(&self.intersection_events, event);
+    }
+
+    fn handle_contact_event(&self, event: ContactEvent, _: &ContactPair) {
+        self.push(&self.contact_events, event);
+    }
+}
+
+/// An event handler that buffers the events emitted during several substeps and forwards
+/// them, deduplicated, to another event handler once all the substeps are complete.
+pub(crate) struct SubstepEventCollector<'a> {
+    events: &'a dyn EventHandler,
+    intersection_events: Mutex<Vec<IntersectionEvent>>,
+    contact_events: Mutex<Vec<(ContactEvent, ContactPair)>>,
+}
+
+impl<'a> SubstepEventCollector<'a> {
+    pub fn new(events: &'a dyn EventHandler) -> Self {
+        Self {
+            events,
+            intersection_events: Mutex::new(Vec::new()),
+            contact_events: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Forwards the buffered events to the wrapped event handler.
+    ///
+    /// For each pair of colliders, only the first and last events are taken into account:
+    /// - If both are "started" (resp. "stopped") events, a single "started" (resp. "stopped")
+    ///   event is emitted.
+    /// - If the first event is "started" and the last is "stopped", both are emitted.
+    /// - If the first event is "stopped" and the last is "started", no event is emitted since
+    ///   the colliders were in contact at the beginning and at the end of the step.
+    pub fn flush(self) {
+        let intersection_events = self.intersection_events.into_inner().unwrap();
+        for (first, last) in
+            Self::first_and_last(&intersection_events, |e| (e.collider1, e.collider2))
+        {
+            let first = intersection_events[first];
+            let last = intersection_events[last];
+
+            match (first.intersecting, last.intersecting) {
+                (true, true) | (false, false) => self.events.handle_intersection_event(first),
+                (true, false) => {
+                    self.events.handle_intersection_event(first);
+                    self.events.handle_intersection_event(last);
+                }
+                (false, true) => {}
+            }
+        }
+
+        let contact_events = self.contact_events.into_inner().unwrap();
+        for (first, last) in Self::first_and_last(&contact_events, |(e, _)| match e {
+            ContactEvent::Started(h1, h2) | ContactEvent::Stopped(h1, h2) => (*h1, *h2),
+        }) {
+            let (first_event, first_pair) = &contact_events[first];
+            let (last_event, last_pair) = &contact_events[last];
+
+            match (first_event, last_event) {
+                (ContactEvent::Started(..), ContactEvent::Started(..)) => {
+                    self.events.handle_contact_event(*first_event, last_pair)
+                }
+                (ContactEvent::Stopped(..), ContactEvent::Stopped(..)) => {
+                    self.events.handle_contact_event(*first_event, first_pair)
+                }
+                (ContactEvent::Started(..), ContactEvent::Stopped(..)) => {
+                    self.events.handle_contact_event(*first_event, first_pair);
+                    self.events.handle_contact_event(*last_event, last_pair);
+                }
+                (ContactEvent::Stopped(..), ContactEvent::Started(..)) => {}
+            }
+        }
+    }
+
+    /// Finds the indices of the first and last events of each pair of colliders,
+    /// sorted by order of the first event.
+    fn first_and_last<E>(
+        events: &[E],
+        key: impl Fn(&E) -> (ColliderHandle, ColliderHandle),
+    ) -> Vec<(usize, usize)> {
+        let mut pair_ids = HashMap::new();
+        let mut result: Vec<(usize, usize)> = Vec::new();
+
+        for (i, event) in events.iter().enumerate() {
+            let pair_id = *pair_ids.entry(key(event)).or_insert_with(|| {
+                result.push((i, i));
+                result.len() - 1
+            });
+            result[pair_id].1 = i;
+        }
+
+        result
+    }
+}
+
+impl<'a> EventHandler for SubstepEventCollector<'a> {
+    fn handle_intersection_event(&self, event: IntersectionEvent) {
+        self.intersection_events.lock().unwrap().push(event);
+    }
+
+    fn handle_contact_event(&self, event: ContactEvent, contact_pair: &ContactPair) {
+        self.contact_events
+            .lock()
+            .unwrap()
+            .push((event, contact_pair.clone()));
+    }
+
+    fn handle_contact_impulses(&self, contact_pair: &ContactPair, impulses: &[ContactImpulse]) {
+        self.events.handle_contact_impulses(contact_pair, impulses);
+    }
+
+    fn handle_island_event(&self, event: IslandEvent) {
+        self.events.handle_island_event(event);
+    }
+
+    fn handle_sleep_event(&self, event: SleepEvent) {
+        self.events.handle_sleep_event(event);
+    }
+}
+
+/// An event handler that collects the events into vectors.
+#[derive(Default)]
+pub(crate) struct VecEventCollector {
+    contact_events: Mutex<Vec<ContactEvent>>,
+    intersection_events: Mutex<Vec<IntersectionEvent>>,
+}
+
+impl VecEventCollector {
+    /// The collected contact and intersection events, in the order they were emitted.
+    pub fn into_events(self) -> (Vec<ContactEvent>, Vec<IntersectionEvent>) {
+        (
+            self.contact_events.into_inner().unwrap(),
+            self.intersection_events.into_inner().unwrap(),
+        )
+    }
+}
+
+impl EventHandler for VecEventCollector {
+    fn handle_intersection_event(&self, event: IntersectionEvent) {
+        self.intersection_events.lock().unwrap().push(event);
+    }
+
+    fn handle_contact_event(&self, event: ContactEvent, _: &ContactPair) {
+        self.contact_events.lock().unwrap().push(event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ActiveEvents, ContactEventBuffer, EventHandler};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{
+        ColliderBuilder, ContactEvent, ContactImpulse, ContactPair, IntersectionEvent,
+    };
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use std::sync::Mutex;
+
+    #[test]
+    fn contact_impulse_events_report_resting_contact() {
+        #[derive(Default)]
+        struct ImpulseCollector(Mutex<Vec<Real>>);
+
+        impl EventHandler for ImpulseCollector {
+            fn handle_intersection_event(&self, _: IntersectionEvent) {}
+            fn handle_contact_event(&self, _: ContactEvent, _: &ContactPair) {}
+            fn handle_contact_impulses(&self, _: &ContactPair, impulses: &[ContactImpulse]) {
+                let total = impulses.iter().map(|i| i.normal_impulse).sum();
+                self.0.lock().unwrap().push(total);
+            }
+        }
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.0)
+                .can_sleep(false)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ground,
+            &mut world.bodies,
+        );
+        let collider = ColliderBuilder::ball(0.5)
+            .active_events(ActiveEvents::CONTACT_IMPULSE_EVENTS)
+            .build();
+        world
+            .colliders
+            .insert_with_parent(collider, ball, &mut world.bodies);
+
+        let events = ImpulseCollector::default();
+        for _ in 0..100 {
+            world.step_with(&(), &events);
+        }
+
+        // Once the ball rests on the ground, the contact compensates the gravity.
+        let impulses = events.0.into_inner().unwrap();
+        let expected = world.bodies[ball].mass() * 9.81 * world.integration_parameters.dt;
+        assert_eq!(impulses.len(), 100);
+        assert!((impulses.last().unwrap() - expected).abs() < expected * 0.1);
+    }
+
+    #[test]
+    fn contact_event_buffer_overflow() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // Three separate balls start touching the ground during the first step.
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(5.0).build(),
+            ground,
+            &mut world.bodies,
+        );
+        for x in [-0.6 as Real, 0.0, 0.6] {
+            let dir = Vector::x() * x + Vector::y() * (1.0 - x * x).sqrt();
+            let ball = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(dir * 5.4)
+                    .build(),
+            );
+            let collider = ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::CONTACT_EVENTS)
+                .build();
+            world
+                .colliders
+                .insert_with_parent(collider, ball, &mut world.bodies);
+        }
+
+        let mut events = ContactEventBuffer::new(2);
+        world.step_with(&(), &events);
+
+        // Only the last two events are kept.
+        assert!(events.overflow());
+        let contact_events = events.drain_contact_events();
+        assert_eq!(contact_events.len(), 2);
+        assert!(contact_events
+            .iter()
+            .all(|e| matches!(e, ContactEvent::Started(..))));
+        assert!(events.drain_contact_events().is_empty());
+
+        events.clear_overflow();
+        assert!(!events.overflow());
+
+        // Shrinking the capacity below the number of buffered events discards the oldest ones.
+        for event in contact_events {
+            let (h1, h2) = match event {
+                ContactEvent::Started(h1, h2) | ContactEvent::Stopped(h1, h2) => (h1, h2),
+            };
+            events.handle_intersection_event(IntersectionEvent::new(h1, h2, true));
+        }
+        assert!(!events.overflow());
+        events.set_capacity(1);
+        assert!(events.overflow());
+        assert_eq!(events.capacity(), 1);
+        assert_eq!(events.drain_intersection_events().len(), 1);
+    }
+
+    #[test]
+    fn substeps_report_contacts_started_and_stopped_during_the_step() {
+        let mut world = TestWorld::new(Vector::zeros());
+        world.integration_parameters.dt = 0.1;
+
+        // A ball bouncing off a wall during the first half of the step.
+        let wall = world.bodies.insert(
+            RigidBodyBuilder::new_static()
+                .translation(Vector::x() * 1.3)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).restitution(1.0).build(),
+            wall,
+            &mut world.bodies,
+        );
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .linvel(Vector::x() * 10.0)
+                .build(),
+        );
+        let collider = ColliderBuilder::ball(0.5)
+            .restitution(1.0)
+            .active_events(ActiveEvents::CONTACT_EVENTS)
+            .build();
+        world
+            .colliders
+            .insert_with_parent(collider, ball, &mut world.bodies);
+
+        let events = ContactEventBuffer::new(16);
+        world.step_with_substeps(10, &events);
+
+        let contact_events = events.drain_contact_events();
+        assert_eq!(contact_events.len(), 2);
+        assert!(matches!(contact_events[0], ContactEvent::Started(..)));
+        assert!(matches!(contact_events[1], ContactEvent::Stopped(..)));
+        assert!(world.bodies[ball].linvel().x < 0.0);
+    }
+
+    #[test]
+    fn substeps_dont_report_contacts_stopped_and_restarted_during_the_step() {
+        // A ball resting on the ground, then jumping and landing again within 0.1 seconds.
+        let jumping_ball = || {
+            let mut world = TestWorld::new(Vector::y() * -100.0);
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            let ground = world
+                .colliders
+                .insert(ground.translation(Vector::y() * -0.5).build());
+            let ball = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * 0.5)
+                    .build(),
+            );
+            let collider = ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::CONTACT_EVENTS)
+                .build();
+            let collider = world
+                .colliders
+                .insert_with_parent(collider, ball, &mut world.bodies);
+
+            for _ in 0..10 {
+                world.step();
+            }
+            let pair = world.narrow_phase.contact_pair(ground, collider).unwrap();
+            assert!(pair.has_any_active_contact);
+
+            world.bodies[ball].set_linvel(Vector::y() * 2.0, true);
+            world.integration_parameters.dt = 0.1;
+            (world, ground, collider)
+        };
+
+        // With separate timesteps, the contact stops then starts again.
+        let (mut world, _, _) = jumping_ball();
+        world.integration_parameters.dt = 0.01;
+        let events = ContactEventBuffer::new(16);
+        for _ in 0..10 {
+            world.step_with(&(), &events);
+        }
+        let contact_events = events.drain_contact_events();
+        assert_eq!(contact_events.len(), 2);
+        assert!(matches!(contact_events[0], ContactEvent::Stopped(..)));
+        assert!(matches!(contact_events[1], ContactEvent::Started(..)));
+
+        // With substeps, the ball touches the ground at the beginning and the end of the step.
+        let (mut world, ground, collider) = jumping_ball();
+        let events = ContactEventBuffer::new(16);
+        world.step_with_substeps(10, &events);
+        assert!(events.drain_contact_events().is_empty());
+        let pair = world.narrow_phase.contact_pair(ground, collider).unwrap();
+        assert!(pair.has_any_active_contact);
+    }
+}
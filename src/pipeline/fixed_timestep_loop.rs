@@ -0,0 +1,114 @@
+use crate::math::Real;
+
+/// Helper running the physics simulation with a fixed timestep, independently from the
+/// frame rate of the application.
+///
+/// The elapsed real time is accumulated, and the simulation is stepped as many times as
+/// needed to catch up with it. The remaining time, smaller than one timestep, is kept for
+/// the next update and can be used to interpolate the positions rendered between the last
+/// two simulation steps.
+///
+/// If the simulation can't keep up with the real time, at most `max_steps_per_update` steps
+/// are performed per update, and the time left behind is dropped instead of accumulating
+/// without bound.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct FixedTimestepLoop {
+    /// The timestep length of each simulation step, typically equal to
+    /// `IntegrationParameters::dt`.
+    pub fixed_dt: Real,
+    /// The real time elapsed and not simulated yet.
+    pub accumulator: Real,
+    /// The maximum number of simulation steps performed by a single update.
+    pub max_steps_per_update: usize,
+}
+
+impl FixedTimestepLoop {
+    /// Creates a new loop stepping the simulation with the given timestep length.
+    ///
+    /// Panics if `fixed_dt` isn't strictly positive.
+    pub fn new(fixed_dt: Real) -> Self {
+        assert!(fixed_dt > 0.0, "The fixed timestep must be positive.");
+        Self {
+            fixed_dt,
+            accumulator: 0.0,
+            max_steps_per_update: 8,
+        }
+    }
+
+    /// Sets the maximum number of simulation steps performed by a single update.
+    pub fn with_max_steps_per_update(mut self, max_steps_per_update: usize) -> Self {
+        self.max_steps_per_update = max_steps_per_update;
+        self
+    }
+
+    /// Accumulates `real_dt` seconds of real time, and calls `step` once for each timestep needed
+    /// to catch up with the accumulated time, up to `max_steps_per_update` times.
+    ///
+    /// Returns the interpolation factor, in `[0, 1)`, between the positions before and after the
+    /// last simulation step, that matches the current real time.
+    pub fn update(&mut self, real_dt: Real, mut step: impl FnMut()) -> Real {
+        assert!(self.fixed_dt > 0.0, "The fixed timestep must be positive.");
+        self.accumulator += real_dt;
+
+        let mut num_steps = 0;
+        while self.accumulator >= self.fixed_dt {
+            if num_steps == self.max_steps_per_update {
+                // Drop the whole timesteps we can't catch up with.
+                self.accumulator %= self.fixed_dt;
+                break;
+            }
+
+            step();
+            self.accumulator -= self.fixed_dt;
+            num_steps += 1;
+        }
+
+        self.accumulator / self.fixed_dt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedTimestepLoop;
+
+    #[test]
+    fn fixed_timestep_loop() {
+        let mut fixed_loop = FixedTimestepLoop::new(0.25);
+        let mut num_steps = 0;
+
+        // Not enough time elapsed for a single step.
+        let alpha = fixed_loop.update(0.125, || num_steps += 1);
+        assert_eq!(num_steps, 0);
+        assert_eq!(alpha, 0.5);
+
+        // Catch up with the accumulated time.
+        let alpha = fixed_loop.update(0.75, || num_steps += 1);
+        assert_eq!(num_steps, 3);
+        assert_eq!(alpha, 0.5);
+        assert_eq!(fixed_loop.accumulator, 0.125);
+    }
+
+    #[test]
+    fn fixed_timestep_loop_max_steps_per_update() {
+        let mut fixed_loop = FixedTimestepLoop::new(0.25).with_max_steps_per_update(2);
+        let mut num_steps = 0;
+
+        // Only two of the five timesteps are simulated, the others are dropped.
+        let alpha = fixed_loop.update(1.375, || num_steps += 1);
+        assert_eq!(num_steps, 2);
+        assert_eq!(alpha, 0.5);
+        assert_eq!(fixed_loop.accumulator, 0.125);
+
+        // The next update doesn't have any backlog to catch up with.
+        let alpha = fixed_loop.update(0.25, || num_steps += 1);
+        assert_eq!(num_steps, 3);
+        assert_eq!(alpha, 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fixed_timestep_loop_zero_dt() {
+        let _ = FixedTimestepLoop::new(0.0);
+    }
+}
@@ -1,14 +1,49 @@
 //! Structure for combining the various physics components to perform an actual simulation.
 
+#[cfg(feature = "default-sets")]
+pub use character_controller::{
+    CharacterLadder, EffectiveCharacterMovement, KinematicCharacterController,
+};
 pub use collision_pipeline::CollisionPipeline;
-pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
-pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
+#[cfg(feature = "default-sets")]
+pub use debug_render_pipeline::{
+    DebugColor, DebugRenderBackend, DebugRenderMode, DebugRenderPipeline, DebugRenderStyle,
+};
+pub use event_handler::{ActiveEvents, ChannelEventCollector, ContactEventBuffer, EventHandler};
+pub use fixed_timestep_loop::FixedTimestepLoop;
+pub use physics_hooks::{
+    ActiveHooks, ContactModificationContext, ContactModifier, PairFilterContext, PhysicsHooks,
+};
 pub use physics_pipeline::PhysicsPipeline;
-pub use query_pipeline::{QueryPipeline, QueryPipelineMode};
+#[cfg(feature = "default-sets")]
+pub use physics_recorder::{PhysicsRecorder, PhysicsReplayer, RecordedEvent, StepRecord};
+#[cfg(all(feature = "default-sets", feature = "binary-serde"))]
+pub use physics_scene::{DeserializeError, PHYSICS_SCENE_MAGIC_NUMBER};
+#[cfg(all(feature = "default-sets", feature = "serde-serialize"))]
+pub use physics_scene::{PhysicsScene, PHYSICS_SCENE_FORMAT_VERSION};
+#[cfg(feature = "dim3")]
+pub use query_pipeline::RayIntersectionExt;
+pub use query_pipeline::{
+    ExplosionFalloff, QueryPipeline, QueryPipelineMode, RayCastOptions, ShapeCast,
+};
 
+pub(crate) use physics_hooks::update_oneway_solver_contacts;
+
+#[cfg(feature = "default-sets")]
+mod character_controller;
 mod collision_pipeline;
+#[cfg(feature = "default-sets")]
+mod debug_render_pipeline;
 mod event_handler;
+mod fixed_timestep_loop;
 mod physics_hooks;
 mod physics_pipeline;
+#[cfg(feature = "default-sets")]
+mod physics_recorder;
+#[cfg(all(feature = "default-sets", feature = "serde-serialize"))]
+mod physics_scene;
 mod query_pipeline;
+#[cfg(all(test, feature = "default-sets"))]
+pub(crate) mod test_world;
+pub mod trajectory;
 mod user_changes;
@@ -61,57 +61,79 @@ impl<'a, Bodies, Colliders> ContactModificationContext<'a, Bodies, Colliders> {
         allowed_local_n1: &Vector<Real>,
         allowed_angle: Real,
     ) {
-        const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
-        const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
-        const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
+        update_oneway_solver_contacts(
+            &self.manifold.local_n1,
+            allowed_local_n1,
+            allowed_angle,
+            self.solver_contacts,
+            self.user_data,
+        )
+    }
+}
+
+/// Removes the solver contacts of a manifold involving a one-way platform if its contact normal
+/// is not close enough to the allowed normal.
+///
+/// The `local_n` is the local-space contact normal pointing outward from the platform. The
+/// `state` must be persistent across timesteps (as long as the manifold exists) and initialized
+/// to zero.
+pub(crate) fn update_oneway_solver_contacts(
+    local_n: &Vector<Real>,
+    allowed_local_n: &Vector<Real>,
+    allowed_angle: Real,
+    solver_contacts: &mut Vec<SolverContact>,
+    state: &mut u32,
+) {
+    const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
+    const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
+    const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
 
-        let cang = ComplexField::cos(allowed_angle);
+    let cang = ComplexField::cos(allowed_angle);
 
-        // Test the allowed normal with the local-space contact normal that
-        // points towards the exterior of context.collider1.
-        let contact_is_ok = self.manifold.local_n1.dot(&allowed_local_n1) >= cang;
+    // Test the allowed normal with the local-space contact normal that
+    // points towards the exterior of the platform.
+    let contact_is_ok = local_n.dot(allowed_local_n) >= cang;
 
-        match *self.user_data {
-            CONTACT_CONFIGURATION_UNKNOWN => {
-                if contact_is_ok {
-                    // The contact is close enough to the allowed normal.
-                    *self.user_data = CONTACT_CURRENTLY_ALLOWED;
-                } else {
-                    // The contact normal isn't close enough to the allowed
-                    // normal, so remove all the contacts and mark further contacts
-                    // as forbidden.
-                    self.solver_contacts.clear();
+    match *state {
+        CONTACT_CONFIGURATION_UNKNOWN => {
+            if contact_is_ok {
+                // The contact is close enough to the allowed normal.
+                *state = CONTACT_CURRENTLY_ALLOWED;
+            } else {
+                // The contact normal isn't close enough to the allowed
+                // normal, so remove all the contacts and mark further contacts
+                // as forbidden.
+                solver_contacts.clear();
 
-                    // NOTE: in some very rare cases `local_n1` will be
-                    // zero if the objects are exactly touching at one point.
-                    // So in this case we can't really conclude.
-                    // If the norm is non-zero, then we can tell we need to forbid
-                    // further contacts. Otherwise we have to wait for the next frame.
-                    if self.manifold.local_n1.norm_squared() > 0.1 {
-                        *self.user_data = CONTACT_CURRENTLY_FORBIDDEN;
-                    }
+                // NOTE: in some very rare cases `local_n` will be
+                // zero if the objects are exactly touching at one point.
+                // So in this case we can't really conclude.
+                // If the norm is non-zero, then we can tell we need to forbid
+                // further contacts. Otherwise we have to wait for the next frame.
+                if local_n.norm_squared() > 0.1 {
+                    *state = CONTACT_CURRENTLY_FORBIDDEN;
                 }
             }
-            CONTACT_CURRENTLY_FORBIDDEN => {
-                // Contacts are forbidden so we need to continue forbidding contacts
-                // until all the contacts are non-penetrating again. In that case, if
-                // the contacts are OK wrt. the contact normal, then we can mark them as allowed.
-                if contact_is_ok && self.solver_contacts.iter().all(|c| c.dist > 0.0) {
-                    *self.user_data = CONTACT_CURRENTLY_ALLOWED;
-                } else {
-                    // Discard all the contacts.
-                    self.solver_contacts.clear();
-                }
+        }
+        CONTACT_CURRENTLY_FORBIDDEN => {
+            // Contacts are forbidden so we need to continue forbidding contacts
+            // until all the contacts are non-penetrating again. In that case, if
+            // the contacts are OK wrt. the contact normal, then we can mark them as allowed.
+            if contact_is_ok && solver_contacts.iter().all(|c| c.dist > 0.0) {
+                *state = CONTACT_CURRENTLY_ALLOWED;
+            } else {
+                // Discard all the contacts.
+                solver_contacts.clear();
             }
-            CONTACT_CURRENTLY_ALLOWED => {
-                // We allow all the contacts right now. The configuration becomes
-                // uncertain again when the contact manifold no longer contains any contact.
-                if self.solver_contacts.is_empty() {
-                    *self.user_data = CONTACT_CONFIGURATION_UNKNOWN;
-                }
+        }
+        CONTACT_CURRENTLY_ALLOWED => {
+            // We allow all the contacts right now. The configuration becomes
+            // uncertain again when the contact manifold no longer contains any contact.
+            if solver_contacts.is_empty() {
+                *state = CONTACT_CONFIGURATION_UNKNOWN;
             }
-            _ => unreachable!(),
         }
+        _ => unreachable!(),
     }
 }
 
@@ -259,3 +281,98 @@ impl<Bodies, Colliders> PhysicsHooks<Bodies, Colliders> for () {
 
     fn modify_solver_contacts(&self, _: &mut ContactModificationContext<Bodies, Colliders>) {}
 }
+
+/// Physics hooks calling a closure to modify the contacts seen by the constraints solver.
+///
+/// The closure is called after the contact generation and before the constraints are built,
+/// for each contact manifold involving at least one collider with the
+/// `ActiveHooks::MODIFY_SOLVER_CONTACTS` flag. Through the `ContactModificationContext`, it can
+/// remove solver contacts, flip the contact normal, or override the friction of each contact.
+/// All the pairs are accepted by the other hooks, like with `()`.
+pub struct ContactModifier<F>(pub F);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<Bodies, Colliders, F> PhysicsHooks<Bodies, Colliders> for ContactModifier<F>
+where
+    F: Fn(&mut ContactModificationContext<Bodies, Colliders>) + Send + Sync,
+{
+    fn filter_contact_pair(
+        &self,
+        _context: &PairFilterContext<Bodies, Colliders>,
+    ) -> Option<SolverFlags> {
+        Some(SolverFlags::default())
+    }
+
+    fn filter_intersection_pair(&self, _: &PairFilterContext<Bodies, Colliders>) -> bool {
+        true
+    }
+
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext<Bodies, Colliders>) {
+        (self.0)(context)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl<Bodies, Colliders, F> PhysicsHooks<Bodies, Colliders> for ContactModifier<F>
+where
+    F: Fn(&mut ContactModificationContext<Bodies, Colliders>),
+{
+    fn filter_contact_pair(
+        &self,
+        _context: &PairFilterContext<Bodies, Colliders>,
+    ) -> Option<SolverFlags> {
+        Some(SolverFlags::default())
+    }
+
+    fn filter_intersection_pair(&self, _: &PairFilterContext<Bodies, Colliders>) -> bool {
+        true
+    }
+
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext<Bodies, Colliders>) {
+        (self.0)(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ActiveHooks, ContactModificationContext, ContactModifier};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn contact_modifier_removes_contacts() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.2)
+                .build(),
+        );
+        let collider = ColliderBuilder::ball(0.5)
+            .active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS)
+            .build();
+        world
+            .colliders
+            .insert_with_parent(collider, ground, &mut world.bodies);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ball,
+            &mut world.bodies,
+        );
+
+        let hooks = ContactModifier(
+            |context: &mut ContactModificationContext<RigidBodySet, ColliderSet>| {
+                context.solver_contacts.clear()
+            },
+        );
+
+        for _ in 0..60 {
+            world.step_with(&hooks, &());
+        }
+
+        // Without any solver contact, the ball falls through the ground.
+        assert!(world.bodies[ball].translation().y < -1.0);
+    }
+}
@@ -5,23 +5,28 @@ use crate::data::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
 #[cfg(not(feature = "parallel"))]
 use crate::dynamics::IslandSolver;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders, RigidBodyDamping,
-    RigidBodyDominance, RigidBodyForces, RigidBodyHandle, RigidBodyIds, RigidBodyMassProps,
-    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    BuoyancyVolume, CCDSolver, CcdMode, ForceField, ImpulseJointSet, IntegrationParameters,
+    IslandManager, IslandMetrics, MultibodyJointSet, RigidBodyActivation, RigidBodyCcd,
+    RigidBodyChanges, RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces,
+    RigidBodyHandle, RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity, SolverResidual,
 };
 #[cfg(feature = "parallel")]
 use crate::dynamics::{JointGraphEdge, ParallelIslandSolver as IslandSolver};
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
     ColliderHandle, ColliderMaterial, ColliderPair, ColliderParent, ColliderPosition,
-    ColliderShape, ColliderType, ContactManifoldIndex, NarrowPhase,
+    ColliderShape, ColliderType, ContactManifoldIndex, NarrowPhase, AABB,
 };
-use crate::math::{Real, Vector};
+use crate::math::{Point, Real, Vector};
 use crate::pipeline::{EventHandler, PhysicsHooks};
 
 #[cfg(feature = "default-sets")]
-use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
+use {
+    crate::dynamics::RigidBodySet,
+    crate::geometry::{ColliderSet, ContactEvent, IntersectionEvent},
+    crate::pipeline::event_handler::{SubstepEventCollector, VecEventCollector},
+};
 
 /// The physics pipeline, responsible for stepping the whole physics simulation.
 ///
@@ -42,6 +47,17 @@ pub struct PhysicsPipeline {
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    last_step_residual: SolverResidual,
+    record_prev_positions: bool,
+    /// The force fields applied to the awake dynamic rigid-bodies at each timestep.
+    pub force_fields: Vec<Box<dyn ForceField>>,
+    /// The region where the force fields are applied.
+    ///
+    /// Only the rigid-bodies with a center-of-mass inside this AABB are affected by the
+    /// force fields. Unbounded by default.
+    pub force_fields_region: AABB,
+    /// The fluid volumes applying buoyancy forces to the awake dynamic rigid-bodies at each timestep.
+    pub buoyancy_volumes: Vec<BuoyancyVolume>,
 }
 
 impl Default for PhysicsPipeline {
@@ -62,13 +78,30 @@ impl PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(false),
             solvers: Vec::new(),
+            last_step_residual: SolverResidual::default(),
             manifold_indices: Vec::new(),
             joint_constraint_indices: Vec::new(),
             broadphase_collider_pairs: Vec::new(),
+            record_prev_positions: true,
             broad_phase_events: Vec::new(),
+            force_fields: Vec::new(),
+            force_fields_region: AABB::new(
+                Point::from(Vector::repeat(-Real::MAX)),
+                Point::from(Vector::repeat(Real::MAX)),
+            ),
+            buoyancy_volumes: Vec::new(),
         }
     }
 
+    /// The convergence information of the constraints solver, measured during the last
+    /// timestep (or its last substep).
+    ///
+    /// Each field is the maximum over all the simulation islands. With the `parallel`
+    /// feature enabled, the velocity error isn't measured and is always zero.
+    pub fn last_step_residual(&self) -> SolverResidual {
+        self.last_step_residual
+    }
+
     fn clear_modified_colliders(
         &mut self,
         colliders: &mut impl ComponentSetMut<ColliderChanges>,
@@ -79,6 +112,46 @@ impl PhysicsPipeline {
         }
     }
 
+    /// Sets the speculative margins of the colliders attached to the active rigid-bodies, i.e.,
+    /// the distance they may travel during a timestep of length `dt` if they have speculative
+    /// CCD enabled, and zero otherwise.
+    fn update_speculative_margins<Bodies, Colliders>(
+        &self,
+        dt: Real,
+        islands: &IslandManager,
+        bodies: &Bodies,
+        colliders: &mut Colliders,
+    ) where
+        Bodies: ComponentSet<RigidBodyCcd>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyColliders>,
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>,
+    {
+        for handle in islands.active_dynamic_bodies() {
+            let (rb_ccd, rb_vels, rb_colliders): (
+                &RigidBodyCcd,
+                &RigidBodyVelocity,
+                &RigidBodyColliders,
+            ) = bodies.index_bundle(handle.0);
+
+            let margin = if rb_ccd.ccd_enabled && rb_ccd.ccd_mode == CcdMode::Speculative {
+                rb_ccd.max_point_velocity(rb_vels) * dt
+            } else {
+                0.0
+            };
+
+            for collider in &rb_colliders.0 {
+                let co_bf_data: &ColliderBroadPhaseData = colliders.index(collider.0);
+
+                if co_bf_data.speculative_margin != margin {
+                    colliders.map_mut_internal(collider.0, |co_bf_data| {
+                        co_bf_data.speculative_margin = margin
+                    });
+                }
+            }
+        }
+    }
+
     fn detect_collisions<Bodies, Colliders>(
         &mut self,
         integration_parameters: &IntegrationParameters,
@@ -96,7 +169,10 @@ impl PhysicsPipeline {
         Bodies: ComponentSetMut<RigidBodyActivation>
             + ComponentSet<RigidBodyType>
             + ComponentSetMut<RigidBodyIds>
-            + ComponentSet<RigidBodyDominance>,
+            + ComponentSet<RigidBodyDominance>
+            + ComponentSet<RigidBodyCcd>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyColliders>,
         Colliders: ComponentSetMut<ColliderBroadPhaseData>
             + ComponentSet<ColliderChanges>
             + ComponentSet<ColliderPosition>
@@ -109,6 +185,8 @@ impl PhysicsPipeline {
         self.counters.stages.collision_detection_time.resume();
         self.counters.cd.broad_phase_time.resume();
 
+        self.update_speculative_margins(integration_parameters.dt, islands, bodies, colliders);
+
         // Update broad-phase.
         self.broad_phase_events.clear();
         self.broadphase_collider_pairs.clear();
@@ -143,6 +221,7 @@ impl PhysicsPipeline {
         );
         narrow_phase.compute_contacts(
             integration_parameters.prediction_distance,
+            integration_parameters.max_manifold_contacts,
             bodies,
             colliders,
             modified_colliders,
@@ -160,11 +239,13 @@ impl PhysicsPipeline {
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
+        broad_phase: &BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut Bodies,
         colliders: &mut Colliders,
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
+        events: &dyn EventHandler,
     ) where
         Bodies: ComponentSetMut<RigidBodyPosition>
             + ComponentSetMut<RigidBodyVelocity>
@@ -175,17 +256,27 @@ impl PhysicsPipeline {
             + ComponentSet<RigidBodyDamping>
             + ComponentSet<RigidBodyColliders>
             + ComponentSet<RigidBodyType>,
-        Colliders: ComponentSetOption<ColliderParent>,
+        Colliders: ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderType>,
     {
         self.counters.stages.island_construction_time.resume();
         islands.update_active_set_with_contacts(
             bodies,
             colliders,
+            broad_phase,
             narrow_phase,
             impulse_joints,
             multibody_joints,
             integration_parameters.min_island_size,
+            integration_parameters.wake_up_radius,
+            events,
         );
+
+        if integration_parameters.deterministic_ordering {
+            islands.sort_active_islands(bodies);
+        }
         self.counters.stages.island_construction_time.pause();
 
         if self.manifold_indices.len() < islands.num_islands() {
@@ -204,12 +295,15 @@ impl PhysicsPipeline {
             bodies,
             &mut manifolds,
             &mut self.manifold_indices,
+            integration_parameters.deterministic_ordering,
         );
         impulse_joints.select_active_interactions(
             islands,
             bodies,
             &mut self.joint_constraint_indices,
+            integration_parameters.deterministic_ordering,
         );
+        impulse_joints.reset_impulses();
 
         self.counters.stages.update_time.resume();
         for handle in islands.active_dynamic_bodies() {
@@ -225,6 +319,99 @@ impl PhysicsPipeline {
             bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
                 forces.add_gravity_acceleration(&gravity, effective_inv_mass)
             });
+
+            // Flush the impulses queued since the last step, before the constraints are solved.
+            let forces: RigidBodyForces = *bodies.index(handle.0);
+            if !forces.deferred_impulses().is_empty() {
+                let mprops: RigidBodyMassProps = *bodies.index(handle.0);
+                bodies.map_mut_internal(handle.0, |vels: &mut RigidBodyVelocity| {
+                    for (impulse, point) in forces.deferred_impulses() {
+                        match point {
+                            Some(point) => vels.apply_impulse_at_point(&mprops, *impulse, *point),
+                            None => vels.apply_impulse(&mprops, *impulse),
+                        }
+                    }
+                });
+                bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
+                    forces.clear_deferred_impulses()
+                });
+            }
+
+            // NOTE: the multibodies already take the gyroscopic forces of their links into account.
+            #[cfg(feature = "dim3")]
+            if multibody_joints.rigid_body_link(*handle).is_none() {
+                let (mprops, vels): (&RigidBodyMassProps, &RigidBodyVelocity) =
+                    bodies.index_bundle(handle.0);
+                let gyroscopic_torque = mprops.gyroscopic_torque(&position.rotation, &vels.angvel);
+                bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
+                    forces.torque += gyroscopic_torque
+                });
+            }
+
+            if !self.force_fields.is_empty() {
+                let (mprops, vels): (&RigidBodyMassProps, &RigidBodyVelocity) =
+                    bodies.index_bundle(handle.0);
+
+                if self
+                    .force_fields_region
+                    .contains_local_point(&mprops.world_com)
+                {
+                    let mut acceleration = Vector::zeros();
+                    for field in &self.force_fields {
+                        acceleration += field.acceleration_at(&mprops.world_com, &vels.linvel);
+                    }
+
+                    bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
+                        forces.force += acceleration * effective_inv_mass
+                    });
+                }
+            }
+
+            for volume in &self.buoyancy_volumes {
+                let (rb_colliders, mprops, vels): (
+                    &RigidBodyColliders,
+                    &RigidBodyMassProps,
+                    &RigidBodyVelocity,
+                ) = bodies.index_bundle(handle.0);
+
+                let mut total_volume = 0.0;
+                let mut submerged_volume = 0.0;
+                let mut submerged_centroid = Point::origin();
+
+                for co_handle in &rb_colliders.0 {
+                    let (co_type, co_shape, co_pos): (
+                        &ColliderType,
+                        &ColliderShape,
+                        &ColliderPosition,
+                    ) = colliders.index_bundle(co_handle.0);
+
+                    if !co_type.is_sensor() {
+                        let (co_submerged_volume, co_volume, co_centroid) =
+                            volume.submerged_volume(&**co_shape, co_pos);
+                        total_volume += co_volume;
+                        submerged_volume += co_submerged_volume;
+                        submerged_centroid += co_centroid.coords * co_submerged_volume;
+                    }
+                }
+
+                if submerged_volume > 0.0 {
+                    submerged_centroid /= submerged_volume;
+                    let submerged_fraction = submerged_volume / total_volume;
+                    let (mprops, vels) = (*mprops, *vels);
+
+                    bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
+                        volume.apply_forces(
+                            gravity,
+                            submerged_volume,
+                            submerged_centroid,
+                            submerged_fraction,
+                            forces,
+                            &vels,
+                            &mprops,
+                        )
+                    });
+                }
+            }
         }
 
         for multibody in &mut multibody_joints.multibodies {
@@ -310,6 +497,30 @@ impl PhysicsPipeline {
             });
         }
         self.counters.stages.solver_time.pause();
+
+        self.last_step_residual = SolverResidual::default();
+        islands.island_metrics.clear();
+
+        for (island_id, solver) in self.solvers[..islands.num_islands()].iter().enumerate() {
+            self.last_step_residual.merge(solver.residual());
+
+            let contact_count = self.manifold_indices[island_id]
+                .iter()
+                .map(|i| manifolds[*i].data.solver_contacts.len())
+                .sum::<usize>();
+            islands.island_metrics.push(IslandMetrics {
+                body_count: islands.active_island(island_id).len() as u32,
+                joint_count: self.joint_constraint_indices[island_id].len() as u32,
+                contact_count: contact_count as u32,
+                last_solve_time_ns: solver.solve_time_ns(),
+            });
+        }
+
+        impulse_joints.remove_broken_joints(
+            islands,
+            bodies,
+            &self.joint_constraint_indices[..islands.num_islands()],
+        );
     }
 
     fn run_ccd_motion_clamping<Bodies, Colliders>(
@@ -428,9 +639,11 @@ impl PhysicsPipeline {
                         &rb_pos.position,
                         // NOTE: we don't use the `world_com` here because it is not
                         //       really updated for kinematic bodies.
-                        &(rb_pos.position * rb_mprops.local_mprops.local_com),
+                        &rb_mprops.local_mprops.local_com,
                     );
-                    bodies.set_internal(handle.0, RigidBodyPosition::from(new_pos));
+                    let mut new_poss = RigidBodyPosition::from(new_pos);
+                    new_poss.prev_position = rb_pos.prev_position;
+                    bodies.set_internal(handle.0, new_poss);
                 }
                 _ => {}
             }
@@ -480,6 +693,118 @@ impl PhysicsPipeline {
         );
     }
 
+    /// Executes one timestep of the physics simulation, and returns the contact and
+    /// intersection events it emitted.
+    ///
+    /// This is the same as `self.step`, except that the events are collected into vectors
+    /// instead of being sent to an `EventHandler`.
+    #[cfg(feature = "default-sets")]
+    pub fn step_with_events(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+    ) -> (Vec<ContactEvent>, Vec<IntersectionEvent>) {
+        let events = VecEventCollector::default();
+
+        self.step(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            hooks,
+            &events,
+        );
+
+        events.into_events()
+    }
+
+    /// Executes one timestep of the physics simulation, split into `substeps` substeps.
+    ///
+    /// Each substep runs the whole pipeline with a timestep length equal to
+    /// `integration_parameters.dt / substeps`. Position-based kinematic bodies reach the
+    /// target set with `RigidBody::set_next_kinematic_position` at the end of the last substep.
+    /// The positions used by `RigidBody::interpolated_position` are the ones before the first substep.
+    ///
+    /// The events emitted during all the substeps are forwarded to `events` once the last substep
+    /// completes. They are deduplicated so that at most one "started" and one "stopped" event is
+    /// emitted per pair of colliders for the whole step. No event is emitted for pairs that stopped,
+    /// and then started again, touching during the step.
+    #[cfg(feature = "default-sets")]
+    pub fn step_with_substeps(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        substeps: u32,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
+    ) {
+        let substeps = substeps.max(1);
+        let mut substep_params = *integration_parameters;
+        substep_params.dt /= substeps as Real;
+
+        let kinematic_targets: Vec<_> = bodies
+            .iter()
+            .filter(|(_, rb)| rb.rb_type == RigidBodyType::KinematicPositionBased)
+            .map(|(handle, rb)| (handle, rb.rb_pos.next_position))
+            .collect();
+
+        let substep_events = SubstepEventCollector::new(events);
+
+        for i in 0..substeps {
+            // Only record the positions used for interpolation at the first substep.
+            self.record_prev_positions = i == 0;
+
+            // Move the kinematic bodies by an equal fraction of their remaining motion.
+            let fraction = 1.0 / (substeps - i) as Real;
+            for (handle, target) in &kinematic_targets {
+                if let Some(rb) = bodies.get_mut_internal(*handle) {
+                    rb.rb_pos.next_position = rb.rb_pos.position.lerp_slerp(target, fraction);
+                }
+            }
+
+            self.step(
+                gravity,
+                &substep_params,
+                islands,
+                broad_phase,
+                narrow_phase,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+                ccd_solver,
+                hooks,
+                &substep_events,
+            );
+        }
+
+        self.record_prev_positions = true;
+        substep_events.flush();
+    }
+
     /// Executes one timestep of the physics simulation.
     pub fn step_generic<Bodies, Colliders>(
         &mut self,
@@ -536,6 +861,15 @@ impl PhysicsPipeline {
             modified_colliders,
         );
 
+        if self.record_prev_positions {
+            // Record the positions at the beginning of the timestep for interpolation.
+            for handle in islands.iter_active_bodies() {
+                bodies.map_mut_internal(handle.0, |poss: &mut RigidBodyPosition| {
+                    poss.prev_position = poss.position
+                });
+            }
+        }
+
         // TODO: do this only on user-change.
         // TODO: do we want some kind of automatic inverse kinematics?
         for multibody in &mut multibody_joints.multibodies {
@@ -637,13 +971,23 @@ impl PhysicsPipeline {
                 gravity,
                 &integration_parameters,
                 islands,
+                broad_phase,
                 narrow_phase,
                 bodies,
                 colliders,
                 impulse_joints,
                 multibody_joints,
+                events,
             );
 
+            for field in &mut self.force_fields {
+                field.advance_time(integration_parameters.dt);
+            }
+
+            if remaining_substeps == 0 {
+                narrow_phase.emit_contact_impulse_events(bodies, colliders, events);
+            }
+
             // If CCD is enabled, execute the CCD motion clamping.
             if ccd_is_enabled {
                 // NOTE: don't the forces into account when updating the CCD active flags because
@@ -693,6 +1037,7 @@ impl PhysicsPipeline {
             self.clear_modified_colliders(colliders, modified_colliders);
         }
 
+        narrow_phase.update_contact_ages();
         self.counters.step_completed();
     }
 }
@@ -704,7 +1049,7 @@ mod test {
         RigidBodySet,
     };
     use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
-    use crate::math::Vector;
+    use crate::math::{Real, Vector};
     use crate::pipeline::PhysicsPipeline;
     use crate::prelude::MultibodyJointSet;
 
@@ -745,6 +1090,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn velocity_based_kinematic_bodies_rotate_around_their_center_of_mass() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhase::new();
+        let mut nf = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut islands = IslandManager::new();
+
+        // A body with its center-of-mass one unit away from its origin.
+        let rb = RigidBodyBuilder::new_kinematic_velocity_based()
+            .translation(Vector::x() * 2.0)
+            .build();
+        let handle = bodies.insert(rb);
+        let co = ColliderBuilder::ball(0.5).translation(Vector::x()).build();
+        colliders.insert_with_parent(co, handle, &mut bodies);
+        let local_com = bodies[handle].mass_properties().local_com;
+        assert!((local_com.coords - Vector::x()).norm() < 1.0e-6);
+
+        for _ in 0..10 {
+            #[cfg(feature = "dim2")]
+            bodies[handle].set_angvel(1.0, true);
+            #[cfg(feature = "dim3")]
+            bodies[handle].set_angvel(Vector::z(), true);
+
+            pipeline.step(
+                &Vector::zeros(),
+                &IntegrationParameters::default(),
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                &(),
+                &(),
+            );
+        }
+
+        // The body rotated, but its center-of-mass didn't move.
+        let world_com = bodies[handle].position() * local_com;
+        assert!(bodies[handle].rotation().angle() > 0.1);
+        assert!((world_com.coords - Vector::x() * 3.0).norm() < 1.0e-4);
+    }
+
     #[test]
     fn rigid_body_removal_before_step() {
         let mut colliders = ColliderSet::new();
@@ -897,4 +1291,741 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn physics_snapshot_diff_and_patch() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut islands = IslandManager::new();
+        let mut broad_phase = BroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut ccd_solver = CCDSolver::new();
+
+        let falling = RigidBodyBuilder::new_dynamic().build();
+        let resting = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 10.0)
+            .gravity_scale(0.0)
+            .build();
+        let mut handles = vec![bodies.insert(falling), bodies.insert(resting)];
+        handles.push(bodies.insert(RigidBodyBuilder::new_dynamic().build()));
+        for handle in &handles {
+            colliders.insert_with_parent(ColliderBuilder::ball(0.5).build(), *handle, &mut bodies);
+        }
+        let removed = handles[2];
+        let snapshot1 = bodies.snapshot(&narrow_phase, &impulse_joints);
+
+        bodies.remove(
+            removed,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+        );
+        pipeline.step(
+            &(Vector::y() * -9.81),
+            &IntegrationParameters::default(),
+            &mut islands,
+            &mut broad_phase,
+            &mut narrow_phase,
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut ccd_solver,
+            &(),
+            &(),
+        );
+        let snapshot2 = bodies.snapshot(&narrow_phase, &impulse_joints);
+
+        let patch = snapshot1.diff(&snapshot2, 1.0e-3);
+        assert_eq!(patch.len(), 1);
+        assert_eq!(patch.removed(), &[removed]);
+
+        let mut patched = snapshot1.clone();
+        patched.apply_patch(&patch);
+        assert!(patched.diff(&snapshot2, 0.0).is_empty());
+
+        // With a large threshold, only the removal remains.
+        let patch = snapshot1.diff(&snapshot2, 1.0);
+        assert_eq!(patch.len(), 0);
+        assert!(!patch.is_empty());
+        assert!(snapshot2.diff(&snapshot2, 0.0).is_empty());
+    }
+
+    #[test]
+    fn step_with_events_collects_contact_events() {
+        use crate::geometry::ContactEvent;
+        use crate::pipeline::test_world::TestWorld;
+        use crate::pipeline::ActiveEvents;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.2)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            ground,
+            &mut world.bodies,
+        );
+        let collider = ColliderBuilder::ball(0.5)
+            .active_events(ActiveEvents::CONTACT_EVENTS)
+            .build();
+        world
+            .colliders
+            .insert_with_parent(collider, ball, &mut world.bodies);
+
+        let mut contact_events = Vec::new();
+        for _ in 0..30 {
+            let (contacts, intersections) = world.pipeline.step_with_events(
+                &world.gravity,
+                &world.integration_parameters,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+                &(),
+            );
+            assert!(intersections.is_empty());
+            contact_events.extend(contacts);
+        }
+
+        // The ball may bounce a few times before resting on the ground.
+        assert!(!contact_events.is_empty());
+        for (i, event) in contact_events.iter().enumerate() {
+            if i % 2 == 0 {
+                assert!(matches!(event, ContactEvent::Started(..)));
+            } else {
+                assert!(matches!(event, ContactEvent::Stopped(..)));
+            }
+        }
+    }
+
+    #[test]
+    fn substeps_move_kinematic_bodies_gradually() {
+        use crate::pipeline::test_world::TestWorld;
+        use crate::pipeline::{ActiveEvents, ContactEventBuffer};
+
+        let mut world = TestWorld::new(Vector::zeros());
+        world.integration_parameters.dt = 0.1;
+
+        // A kinematic sensor crossing a ball on its way to its target.
+        let ball = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 5.0)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.6).build(),
+            ball,
+            &mut world.bodies,
+        );
+        let kinematic = world
+            .bodies
+            .insert(RigidBodyBuilder::new_kinematic_position_based().build());
+        let sensor = ColliderBuilder::ball(0.5)
+            .sensor(true)
+            .active_events(ActiveEvents::INTERSECTION_EVENTS)
+            .build();
+        world
+            .colliders
+            .insert_with_parent(sensor, kinematic, &mut world.bodies);
+
+        let mut target = *world.bodies[kinematic].position();
+        target.translation.vector = Vector::x() * 10.0;
+        world.bodies[kinematic].set_next_kinematic_position(target);
+
+        let events = ContactEventBuffer::new(16);
+        world.step_with_substeps(10, &events);
+
+        // The sensor went through the ball instead of jumping to its target at once.
+        let intersections = events.drain_intersection_events();
+        assert_eq!(intersections.len(), 2);
+        assert!(intersections[0].intersecting);
+        assert!(!intersections[1].intersecting);
+
+        let kinematic_pos = world.bodies[kinematic].translation();
+        assert!((kinematic_pos - Vector::x() * 10.0).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn rolling_friction_stops_rolling_balls() {
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(50.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(50.0, 0.5, 50.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // Two balls rolling without slipping, one of them with rolling friction.
+        let mut balls = vec![];
+        for (i, rolling_friction) in [0.0, 0.1].into_iter().enumerate() {
+            #[cfg(feature = "dim2")]
+            let angvel = -4.0;
+            #[cfg(feature = "dim3")]
+            let angvel = Vector::z() * -4.0;
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() + Vector::x() * (i as Real * 20.0 - 20.0))
+                .linvel(Vector::x() * 2.0)
+                .angvel(angvel)
+                .build();
+            let handle = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5)
+                .rolling_friction(rolling_friction)
+                .build();
+            world
+                .colliders
+                .insert_with_parent(collider, handle, &mut world.bodies);
+            balls.push(handle);
+        }
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        let (free, braked) = (&world.bodies[balls[0]], &world.bodies[balls[1]]);
+        assert!(free.linvel().x > 1.5);
+        assert!(braked.linvel().norm() < 1.0e-3);
+        #[cfg(feature = "dim2")]
+        assert!(braked.angvel().abs() < 1.0e-3);
+        #[cfg(feature = "dim3")]
+        assert!(braked.angvel().norm() < 1.0e-3);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn spinning_friction_stops_spinning_balls() {
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(50.0, 0.5, 50.0).build(),
+            ground,
+            &mut world.bodies,
+        );
+
+        // Two balls spinning around the contact normal, one of them with spinning friction.
+        let mut balls = vec![];
+        for (i, spinning_friction) in [0.0, 0.1].into_iter().enumerate() {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() + Vector::x() * (i as Real * 5.0))
+                .angvel(Vector::y() * 5.0)
+                .build();
+            let handle = world.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5)
+                .spinning_friction(spinning_friction)
+                .build();
+            world
+                .colliders
+                .insert_with_parent(collider, handle, &mut world.bodies);
+            balls.push(handle);
+        }
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        let (free, braked) = (&world.bodies[balls[0]], &world.bodies[balls[1]]);
+        assert!(free.angvel().y > 4.5);
+        assert!(braked.angvel().norm() < 1.0e-3);
+        assert!(braked.linvel().norm() < 1.0e-3);
+    }
+
+    // The parallel solver doesn't support impulse joints yet.
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn prismatic_position_motors_are_timestep_independent() {
+        use crate::dynamics::{PrismaticJoint, PrismaticMotor};
+        use crate::pipeline::test_world::TestWorld;
+
+        // Returns the positions of a motorized slider after each tenth of a second.
+        let run = |substeps: usize| {
+            let mut world = TestWorld::new(Vector::zeros());
+            world.integration_parameters.dt = 0.1 / substeps as Real;
+
+            let anchor = world.bodies.insert(RigidBodyBuilder::new_static().build());
+            let slider = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                slider,
+                &mut world.bodies,
+            );
+            let mass = world.bodies[slider].mass();
+            let stiffness = 50.0 * mass;
+            let motor = PrismaticMotor::Position {
+                target: 1.0,
+                stiffness,
+                damping: 2.0 * (stiffness * mass).sqrt(),
+            };
+            let joint = PrismaticJoint::new(Vector::x_axis()).motor(motor);
+            world.impulse_joints.insert(anchor, slider, joint);
+
+            let mut positions = vec![];
+            for _ in 0..20 {
+                for _ in 0..substeps {
+                    world.step();
+                }
+                positions.push(world.bodies[slider].translation().x);
+            }
+            positions
+        };
+
+        let coarse = run(2);
+        let fine = run(8);
+
+        // The critically-damped slider reaches the target at the same rate with both timesteps.
+        for (coarse, fine) in coarse.iter().zip(fine.iter()) {
+            assert!((coarse - fine).abs() < 0.05);
+        }
+        assert!(coarse[3] > 0.3 && coarse[3] < 0.9);
+        assert!((coarse[19] - 1.0).abs() < 1.0e-2);
+        assert!((fine[19] - 1.0).abs() < 1.0e-2);
+    }
+
+    // The parallel solver doesn't support impulse joints yet.
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn overloaded_weld_joints_break_once() {
+        use crate::dynamics::FixedJoint;
+        use crate::math::Point;
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world.integration_parameters.max_ccd_substeps = 4;
+
+        // Two boxes hanging from weld joints, only one of them heavy enough to break its joint.
+        let mut joints = vec![];
+        for (i, density) in [1.0, 1000.0].into_iter().enumerate() {
+            let origin = Vector::x() * (i as Real * 5.0) + Vector::y() * 10.0;
+            let anchor = world
+                .bodies
+                .insert(RigidBodyBuilder::new_static().translation(origin).build());
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(origin - Vector::y())
+                    .build(),
+            );
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).density(density).build(),
+                body,
+                &mut world.bodies,
+            );
+            let joint = FixedJoint::new()
+                .local_anchor1(Point::from(-Vector::y()))
+                .break_threshold(10.0, 1000.0);
+            joints.push(world.impulse_joints.insert(anchor, body, joint));
+        }
+
+        // A fast CCD-enabled ball hitting a wall, to split the first timestep into CCD substeps.
+        let wall = world.bodies.insert(
+            RigidBodyBuilder::new_static()
+                .translation(Vector::x() * 20.0)
+                .build(),
+        );
+        #[cfg(feature = "dim2")]
+        let wall_shape = ColliderBuilder::cuboid(0.1, 5.0);
+        #[cfg(feature = "dim3")]
+        let wall_shape = ColliderBuilder::cuboid(0.1, 5.0, 5.0);
+        world
+            .colliders
+            .insert_with_parent(wall_shape.build(), wall, &mut world.bodies);
+        let bullet = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 18.0)
+                .linvel(Vector::x() * 200.0)
+                .ccd_enabled(true)
+                .build(),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.1).build(),
+            bullet,
+            &mut world.bodies,
+        );
+
+        world.step();
+        assert!(world.pipeline.counters.ccd.num_substeps > 1);
+
+        let events: Vec<_> = world.impulse_joints.drain_break_events().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].joint, joints[1]);
+        assert!(events[0].impulse.linear.norm() > 10.0);
+        assert!(world.impulse_joints.get(joints[0]).is_some());
+        assert!(world.impulse_joints.get(joints[1]).is_none());
+
+        for _ in 0..10 {
+            world.step();
+        }
+
+        assert_eq!(world.impulse_joints.drain_break_events().count(), 0);
+        assert!(world.impulse_joints.get(joints[0]).is_some());
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn anisotropic_friction_depends_on_the_sliding_direction() {
+        use crate::math::Rotation;
+        use crate::pipeline::test_world::TestWorld;
+        use na::RealField;
+
+        // The ground is a frictionless rail along its local x axis, also when it is rotated.
+        for rotated in [false, true] {
+            let mut world = TestWorld::new(Vector::y() * -9.81);
+            let angle = if rotated { Real::frac_pi_2() } else { 0.0 };
+            let ground = world.bodies.insert(
+                RigidBodyBuilder::new_static()
+                    .rotation(Vector::y() * angle)
+                    .build(),
+            );
+            let ground_shape = ColliderBuilder::cuboid(50.0, 0.5, 50.0)
+                .anisotropic_friction(Vector::x_axis(), [0.0, 1.0])
+                .build();
+            world
+                .colliders
+                .insert_with_parent(ground_shape, ground, &mut world.bodies);
+
+            let mut boxes = vec![];
+            for (i, dir) in [Vector::x(), Vector::z()].into_iter().enumerate() {
+                let body = RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() + Vector::x() * (i as Real * 20.0))
+                    .linvel(dir * 3.0)
+                    .build();
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::cuboid(0.5, 0.5, 0.5).friction(0.0).build(),
+                    handle,
+                    &mut world.bodies,
+                );
+                boxes.push(handle);
+            }
+
+            for _ in 0..120 {
+                world.step();
+            }
+
+            // The body sliding along the rail keeps its velocity, the other one stops.
+            let rail = Rotation::new(Vector::y() * angle) * Vector::x();
+            let (along, across) = if rotated { (1, 0) } else { (0, 1) };
+            let along_vel = world.bodies[boxes[along]].linvel();
+            let across_vel = world.bodies[boxes[across]].linvel();
+            assert!(along_vel.iter().all(|x| x.is_finite()));
+            assert!((along_vel.dot(&rail).abs() - 3.0).abs() < 1.0e-2);
+            assert!(across_vel.norm() < 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn per_axis_damping_follows_the_body_orientation() {
+        use crate::pipeline::test_world::TestWorld;
+        use na::RealField;
+
+        let mut world = TestWorld::new(Vector::zeros());
+        // The local x axis of the rigid-body is aligned with the world y axis.
+        #[cfg(feature = "dim2")]
+        let rotation = Real::frac_pi_2();
+        #[cfg(feature = "dim3")]
+        let rotation = Vector::z() * Real::frac_pi_2();
+        let body = RigidBodyBuilder::new_dynamic()
+            .rotation(rotation)
+            .linvel(Vector::x() + Vector::y())
+            .linear_damping_axes(Vector::x() * 5.0)
+            .build();
+        let handle = world.bodies.insert(body);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            handle,
+            &mut world.bodies,
+        );
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        let linvel = world.bodies[handle].linvel();
+        assert!((linvel.x - 1.0).abs() < 1.0e-5);
+        assert!(linvel.y > 0.0 && linvel.y < 0.01);
+    }
+
+    #[test]
+    fn velocity_caps_limit_falling_and_spinning_bodies() {
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let mut bodies = vec![];
+        for (i, (max_linvel, max_angvel)) in
+            [(Real::MAX, Real::MAX), (5.0, 2.0)].into_iter().enumerate()
+        {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * (i as Real * 5.0))
+                .max_linear_velocity(max_linvel)
+                .max_angular_velocity(max_angvel)
+                .build();
+            let handle = world.bodies.insert(body);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                handle,
+                &mut world.bodies,
+            );
+            bodies.push(handle);
+        }
+
+        for _ in 0..120 {
+            for handle in &bodies {
+                #[cfg(feature = "dim2")]
+                world.bodies[*handle].apply_torque(1.0, true);
+                #[cfg(feature = "dim3")]
+                world.bodies[*handle].apply_torque(Vector::z(), true);
+            }
+            world.step();
+
+            let capped = &world.bodies[bodies[1]];
+            assert!(capped.linvel().norm() <= 5.0 + 1.0e-4);
+            #[cfg(feature = "dim2")]
+            assert!(capped.angvel().abs() <= 2.0 + 1.0e-4);
+            #[cfg(feature = "dim3")]
+            assert!(capped.angvel().norm() <= 2.0 + 1.0e-4);
+        }
+
+        // The capped body falls straight down at the maximum velocity.
+        let (free, capped) = (&world.bodies[bodies[0]], &world.bodies[bodies[1]]);
+        assert!(free.linvel().norm() > 15.0);
+        assert!((capped.linvel() + Vector::y() * 5.0).norm() < 1.0e-4);
+    }
+
+    #[test]
+    fn force_fields_only_affect_bodies_inside_their_region() {
+        use crate::dynamics::WindField;
+        use crate::math::Point;
+        use crate::pipeline::test_world::TestWorld;
+        use parry::bounding_volume::AABB;
+
+        let mut world = TestWorld::new(Vector::zeros());
+        world
+            .pipeline
+            .force_fields
+            .push(Box::new(WindField::new(Vector::x(), 4.0, 0.0)));
+        world.pipeline.force_fields_region =
+            AABB::from_half_extents(Point::origin(), Vector::repeat(100.0));
+
+        let mut bodies = vec![];
+        for y in [0.0, 200.0] {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * y)
+                .build();
+            let handle = world.bodies.insert(body);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                handle,
+                &mut world.bodies,
+            );
+            bodies.push(handle);
+        }
+
+        for _ in 0..600 {
+            world.step();
+        }
+
+        // Only the body inside of the region reached the wind velocity.
+        assert!((world.bodies[bodies[0]].linvel() - Vector::x() * 4.0).norm() < 1.0e-2);
+        assert_eq!(*world.bodies[bodies[1]].linvel(), Vector::zeros());
+    }
+
+    #[test]
+    fn explosion_impulses_decrease_with_the_distance() {
+        use crate::math::Point;
+        use crate::pipeline::test_world::TestWorld;
+        use crate::pipeline::{ExplosionFalloff, QueryPipeline};
+
+        for occlusion in [false, true] {
+            let mut world = TestWorld::new(Vector::zeros());
+
+            // The balls are at distances 2, 4, 7.5, and 4 (behind a wall) from the explosion.
+            let mut balls = vec![];
+            for translation in [
+                Vector::x() * 2.5,
+                Vector::x() * -4.5,
+                Vector::y() * 8.0,
+                Vector::y() * -4.5,
+            ] {
+                let body = RigidBodyBuilder::new_dynamic()
+                    .translation(translation)
+                    .build();
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5).build(),
+                    handle,
+                    &mut world.bodies,
+                );
+                balls.push(handle);
+            }
+            let wall = world.bodies.insert(
+                RigidBodyBuilder::new_static()
+                    .translation(Vector::y() * -2.0)
+                    .build(),
+            );
+            #[cfg(feature = "dim2")]
+            let wall_shape = ColliderBuilder::cuboid(2.0, 0.1);
+            #[cfg(feature = "dim3")]
+            let wall_shape = ColliderBuilder::cuboid(2.0, 0.1, 2.0);
+            world
+                .colliders
+                .insert_with_parent(wall_shape.build(), wall, &mut world.bodies);
+
+            let mut query_pipeline = QueryPipeline::new();
+            query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+            query_pipeline.apply_explosion_impulse(
+                &mut world.bodies,
+                &world.colliders,
+                Point::origin(),
+                5.0,
+                10.0,
+                ExplosionFalloff::Linear,
+                occlusion,
+            );
+
+            // The linear falloff gives impulses of 6 and 2 to the balls at distances 2 and 4.
+            let mass = world.bodies[balls[0]].mass();
+            let linvel = |i: usize| *world.bodies[balls[i]].linvel();
+            assert!((linvel(0) - Vector::x() * (6.0 / mass)).norm() < 1.0e-4);
+            assert!((linvel(1) + Vector::x() * (2.0 / mass)).norm() < 1.0e-4);
+            assert_eq!(linvel(2), Vector::zeros());
+            if occlusion {
+                assert_eq!(linvel(3), Vector::zeros());
+            } else {
+                assert!((linvel(3) + Vector::y() * (2.0 / mass)).norm() < 1.0e-4);
+            }
+            assert_eq!(*world.bodies[wall].linvel(), Vector::zeros());
+        }
+
+        assert_eq!(ExplosionFalloff::Constant.factor(4.0, 5.0), 1.0);
+        assert_eq!(ExplosionFalloff::Linear.factor(6.0, 5.0), 0.0);
+        assert_eq!(ExplosionFalloff::InverseSquare.factor(2.0, 5.0), 0.2);
+    }
+
+    #[test]
+    fn contact_ages_count_the_touching_frames() {
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new(Vector::zeros());
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let ground_collider =
+            world
+                .colliders
+                .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        // The ball starts slightly above the ground and falls on it at constant speed.
+        let body = world.bodies.insert(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * 1.05)
+                .linvel(Vector::y() * -1.0)
+                .build(),
+        );
+        let ball = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            body,
+            &mut world.bodies,
+        );
+        let age = |world: &TestWorld| {
+            world
+                .narrow_phase
+                .contact_pair(ground_collider, ball)
+                .map(|pair| pair.contact_age_frames)
+                .unwrap_or(0)
+        };
+
+        let mut steps_before_contact = 0;
+        while age(&world) == 0 {
+            world.step();
+            steps_before_contact += 1;
+            assert!(steps_before_contact < 10);
+        }
+
+        // The age is incremented once per timestep while touching.
+        assert_eq!(age(&world), 1);
+        for expected in 2..10 {
+            world.step();
+            assert_eq!(age(&world), expected);
+        }
+
+        // And reset once the colliders are separated. Next to the ground's corner, their
+        // AABBs still overlap so the contact pair isn't removed.
+        let mut corner = Vector::y() * 0.9;
+        corner.x = 10.4;
+        world.bodies[body].set_translation(corner, true);
+        world.bodies[body].set_linvel(Vector::zeros(), true);
+        world.step();
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground_collider, ball)
+            .is_some());
+        assert_eq!(age(&world), 0);
+    }
+
+    // The parallel solver doesn't measure the velocity error yet.
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn solver_residual_decreases_with_the_iterations() {
+        use crate::pipeline::test_world::TestWorld;
+
+        // The velocity error left after the last step on a resting stack of boxes.
+        let residual = |iterations: usize| {
+            let mut world = TestWorld::new(Vector::y() * -9.81);
+            world.integration_parameters.max_velocity_iterations = iterations;
+            let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+            #[cfg(feature = "dim2")]
+            let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world
+                .colliders
+                .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+            for i in 0..5 {
+                let body = world.bodies.insert(
+                    RigidBodyBuilder::new_dynamic()
+                        .translation(Vector::y() * (1.0 + i as Real))
+                        .build(),
+                );
+                #[cfg(feature = "dim2")]
+                let shape = ColliderBuilder::cuboid(0.5, 0.5);
+                #[cfg(feature = "dim3")]
+                let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+                world
+                    .colliders
+                    .insert_with_parent(shape.build(), body, &mut world.bodies);
+            }
+
+            for _ in 0..60 {
+                world.step();
+            }
+
+            let residual = world.pipeline.last_step_residual();
+            assert_eq!(residual.iterations_run, iterations as u32);
+            residual.max_velocity_error
+        };
+
+        let errors: Vec<_> = [1, 2, 4, 8, 16].iter().map(|i| residual(*i)).collect();
+        assert!(errors.iter().all(|error| *error > 0.0));
+        for pair in errors.windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+    }
 }
@@ -0,0 +1,344 @@
+//! Recording and replay of physics timesteps.
+
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandEvent, IslandManager,
+    MultibodyJointSet, PhysicsSnapshot, RigidBodySet, SleepEvent,
+};
+use crate::geometry::{
+    BroadPhase, ColliderSet, ContactEvent, ContactImpulse, ContactPair, IntersectionEvent,
+    NarrowPhase,
+};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline};
+use std::sync::Mutex;
+
+/// An event emitted during a recorded timestep.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum RecordedEvent {
+    /// An intersection event.
+    Intersection(IntersectionEvent),
+    /// A contact event.
+    Contact(ContactEvent),
+}
+
+/// The inputs and outputs of a single recorded timestep.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct StepRecord {
+    /// The gravity used for this timestep.
+    pub gravity: Vector<Real>,
+    /// The integration parameters used for this timestep.
+    pub integration_parameters: IntegrationParameters,
+    /// The state of the rigid-bodies, contacts, and joint impulses at the beginning of this
+    /// timestep, including the forces applied by the user.
+    pub initial_state: PhysicsSnapshot,
+    /// The events emitted during this timestep, in order.
+    pub events: Vec<RecordedEvent>,
+    /// The `RigidBodySet::state_hash` at the end of this timestep.
+    pub final_state_hash: u64,
+}
+
+/// An event handler recording the events before forwarding them to another event handler.
+struct EventRecorder<'a> {
+    events: &'a dyn EventHandler,
+    recorded: Mutex<Vec<RecordedEvent>>,
+}
+
+impl<'a> EventRecorder<'a> {
+    fn new(events: &'a dyn EventHandler) -> Self {
+        Self {
+            events,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn into_recorded(self) -> Vec<RecordedEvent> {
+        self.recorded.into_inner().unwrap()
+    }
+}
+
+impl<'a> EventHandler for EventRecorder<'a> {
+    fn handle_intersection_event(&self, event: IntersectionEvent) {
+        self.recorded
+            .lock()
+            .unwrap()
+            .push(RecordedEvent::Intersection(event));
+        self.events.handle_intersection_event(event);
+    }
+
+    fn handle_contact_event(&self, event: ContactEvent, contact_pair: &ContactPair) {
+        self.recorded
+            .lock()
+            .unwrap()
+            .push(RecordedEvent::Contact(event));
+        self.events.handle_contact_event(event, contact_pair);
+    }
+
+    fn handle_contact_impulses(&self, contact_pair: &ContactPair, impulses: &[ContactImpulse]) {
+        self.events.handle_contact_impulses(contact_pair, impulses);
+    }
+
+    fn handle_island_event(&self, event: IslandEvent) {
+        self.events.handle_island_event(event);
+    }
+
+    fn handle_sleep_event(&self, event: SleepEvent) {
+        self.events.handle_sleep_event(event);
+    }
+}
+
+/// A physics pipeline recording every timestep it performs.
+///
+/// The records can be serialized, e.g., to be attached to a bug report, and replayed with a
+/// `PhysicsReplayer` starting from a copy of the physics structures as they were when the
+/// recording started.
+#[derive(Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PhysicsRecorder {
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pipeline: PhysicsPipeline,
+    records: Vec<StepRecord>,
+}
+
+impl PhysicsRecorder {
+    /// Initializes a recorder performing its timesteps with the given physics pipeline.
+    pub fn new(pipeline: PhysicsPipeline) -> Self {
+        Self {
+            pipeline,
+            records: Vec::new(),
+        }
+    }
+
+    /// The physics pipeline used to perform the timesteps.
+    pub fn pipeline(&self) -> &PhysicsPipeline {
+        &self.pipeline
+    }
+
+    /// Mutable reference to the physics pipeline used to perform the timesteps.
+    pub fn pipeline_mut(&mut self) -> &mut PhysicsPipeline {
+        &mut self.pipeline
+    }
+
+    /// The timesteps recorded so far.
+    pub fn records(&self) -> &[StepRecord] {
+        &self.records
+    }
+
+    /// Removes all the timesteps recorded so far.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Executes one timestep of the physics simulation, like `PhysicsPipeline::step`, and records it.
+    pub fn step(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
+    ) {
+        let initial_state = bodies.snapshot(narrow_phase, impulse_joints);
+        let recorder = EventRecorder::new(events);
+
+        self.pipeline.step(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            hooks,
+            &recorder,
+        );
+
+        self.records.push(StepRecord {
+            gravity: *gravity,
+            integration_parameters: *integration_parameters,
+            initial_state,
+            events: recorder.into_recorded(),
+            final_state_hash: bodies.state_hash(),
+        });
+    }
+}
+
+/// Replays the timesteps recorded by a `PhysicsRecorder`.
+#[derive(Default)]
+pub struct PhysicsReplayer {
+    pipeline: PhysicsPipeline,
+}
+
+impl PhysicsReplayer {
+    /// Initializes a replayer performing its timesteps with the given physics pipeline.
+    pub fn new(pipeline: PhysicsPipeline) -> Self {
+        Self { pipeline }
+    }
+
+    /// Replays a recorded timestep.
+    ///
+    /// The rigid-bodies, contacts, and joint impulses are first reset to the initial state of
+    /// the record. The physics structures must otherwise be identical to the ones of the
+    /// recorded simulation at the beginning of this timestep, which is the case if all the
+    /// previous records were replayed in order from a copy of the structures taken when the
+    /// recording started.
+    ///
+    /// Returns `true` if the replayed timestep emitted the recorded events and resulted in the
+    /// recorded state hash.
+    pub fn play_step(
+        &mut self,
+        record: &StepRecord,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
+    ) -> bool {
+        bodies.restore(
+            &record.initial_state,
+            colliders,
+            narrow_phase,
+            impulse_joints,
+        );
+        let recorder = EventRecorder::new(events);
+
+        self.pipeline.step(
+            &record.gravity,
+            &record.integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            hooks,
+            &recorder,
+        );
+
+        recorder.into_recorded() == record.events && bodies.state_hash() == record.final_state_hash
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PhysicsRecorder, PhysicsReplayer, RecordedEvent};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, ContactEvent};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::{ActiveEvents, PhysicsPipeline};
+
+    #[test]
+    fn replayed_steps_match_their_records() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world
+            .colliders
+            .insert_with_parent(ground_shape.build(), ground, &mut world.bodies);
+
+        for i in 0..3 {
+            let body = world.bodies.insert(
+                RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::y() * (1.0 + i as Real * 1.5))
+                    .build(),
+            );
+            let collider = ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::CONTACT_EVENTS)
+                .build();
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+        }
+
+        // The replay starts from a copy of the structures taken before the recording.
+        let mut islands = world.islands.clone();
+        let mut broad_phase = world.broad_phase.clone();
+        let mut narrow_phase = world.narrow_phase.clone();
+        let mut bodies = world.bodies.clone();
+        let mut colliders = world.colliders.clone();
+        let mut impulse_joints = world.impulse_joints.clone();
+        let mut multibody_joints = world.multibody_joints.clone();
+        let mut ccd_solver = world.ccd_solver.clone();
+
+        let mut recorder = PhysicsRecorder::new(PhysicsPipeline::new());
+        for _ in 0..100 {
+            recorder.step(
+                &world.gravity,
+                &world.integration_parameters,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+                &(),
+                &(),
+            );
+        }
+
+        let records = recorder.records();
+        assert_eq!(records.len(), 100);
+        assert!(records.iter().any(|record| record
+            .events
+            .iter()
+            .any(|event| matches!(event, RecordedEvent::Contact(ContactEvent::Started(..))))));
+
+        let mut replayer = PhysicsReplayer::new(PhysicsPipeline::new());
+        for record in records {
+            assert!(replayer.play_step(
+                record,
+                &mut islands,
+                &mut broad_phase,
+                &mut narrow_phase,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut ccd_solver,
+                &(),
+                &(),
+            ));
+        }
+
+        // A replay with different inputs doesn't match its record.
+        let mut record = records[50].clone();
+        record.gravity = Vector::zeros();
+        assert!(!replayer.play_step(
+            &record,
+            &mut islands,
+            &mut broad_phase,
+            &mut narrow_phase,
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut ccd_solver,
+            &(),
+            &(),
+        ));
+    }
+}
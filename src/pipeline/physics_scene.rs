@@ -0,0 +1,290 @@
+//! Versioned serialization of whole physics scenes.
+
+use crate::dynamics::{CCDSolver, ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodySet};
+use crate::geometry::{BroadPhase, ColliderSet, NarrowPhase};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The version of the serialization format of `PhysicsScene`.
+///
+/// This is incremented whenever the format changes in a way that prevents scenes serialized by
+/// a previous version from being deserialized.
+pub const PHYSICS_SCENE_FORMAT_VERSION: u32 = 1;
+
+/// All the structures needed to save and load a physics simulation, e.g., for save games or
+/// level editors.
+///
+/// Deserializing a `PhysicsScene` fails if it was serialized with a different
+/// `PHYSICS_SCENE_FORMAT_VERSION`. A deserialized scene gives the same simulation results as
+/// the original one when stepped with the same parameters.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PhysicsScene {
+    #[serde(deserialize_with = "deserialize_format_version")]
+    format_version: u32,
+    /// The island manager of the scene.
+    pub islands: IslandManager,
+    /// The broad-phase of the scene.
+    pub broad_phase: BroadPhase,
+    /// The narrow-phase of the scene.
+    pub narrow_phase: NarrowPhase,
+    /// The rigid-bodies of the scene.
+    pub bodies: RigidBodySet,
+    /// The colliders of the scene.
+    pub colliders: ColliderSet,
+    /// The impulse joints of the scene.
+    pub impulse_joints: ImpulseJointSet,
+    /// The multibody joints of the scene.
+    pub multibody_joints: MultibodyJointSet,
+    /// The CCD solver of the scene.
+    pub ccd_solver: CCDSolver,
+}
+
+impl PhysicsScene {
+    /// Groups the given structures into a scene that can be serialized.
+    pub fn new(
+        islands: IslandManager,
+        broad_phase: BroadPhase,
+        narrow_phase: NarrowPhase,
+        bodies: RigidBodySet,
+        colliders: ColliderSet,
+        impulse_joints: ImpulseJointSet,
+        multibody_joints: MultibodyJointSet,
+        ccd_solver: CCDSolver,
+    ) -> Self {
+        Self {
+            format_version: PHYSICS_SCENE_FORMAT_VERSION,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        }
+    }
+}
+
+fn deserialize_format_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+
+    if version != PHYSICS_SCENE_FORMAT_VERSION {
+        Err(serde::de::Error::custom(format!(
+            "unsupported physics scene format version {} (expected {})",
+            version, PHYSICS_SCENE_FORMAT_VERSION
+        )))
+    } else {
+        Ok(version)
+    }
+}
+
+/// The magic number at the beginning of the binary serialization of a `PhysicsScene`.
+#[cfg(feature = "binary-serde")]
+pub const PHYSICS_SCENE_MAGIC_NUMBER: [u8; 4] = *b"RPSC";
+
+/// Error returned when a `PhysicsScene` can't be read from bytes.
+#[cfg(feature = "binary-serde")]
+#[derive(Debug)]
+pub enum DeserializeError {
+    /// The bytes don't start with `PHYSICS_SCENE_MAGIC_NUMBER`.
+    InvalidMagicNumber,
+    /// The bytes were written with an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The bytes following the header could not be decoded.
+    Decode(postcard::Error),
+}
+
+#[cfg(feature = "binary-serde")]
+impl std::fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeserializeError::InvalidMagicNumber => {
+                write!(f, "the data is not a binary physics scene")
+            }
+            DeserializeError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported physics scene format version {} (expected {})",
+                version, PHYSICS_SCENE_FORMAT_VERSION
+            ),
+            DeserializeError::Decode(e) => write!(f, "invalid physics scene data: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "binary-serde")]
+impl std::error::Error for DeserializeError {}
+
+#[cfg(feature = "binary-serde")]
+impl PhysicsScene {
+    /// Serializes this scene into a compact binary format.
+    ///
+    /// The bytes start with `PHYSICS_SCENE_MAGIC_NUMBER` followed by one byte containing
+    /// `PHYSICS_SCENE_FORMAT_VERSION`, so stale saves can be detected by `Self::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = PHYSICS_SCENE_MAGIC_NUMBER.to_vec();
+        bytes.push(PHYSICS_SCENE_FORMAT_VERSION as u8);
+        postcard::to_extend(self, bytes)
+            .expect("Serializing a physics scene into memory should not fail.")
+    }
+
+    /// Deserializes a scene from bytes produced by `Self::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let header_len = PHYSICS_SCENE_MAGIC_NUMBER.len();
+
+        if bytes.len() <= header_len || bytes[..header_len] != PHYSICS_SCENE_MAGIC_NUMBER {
+            return Err(DeserializeError::InvalidMagicNumber);
+        }
+
+        let version = bytes[header_len];
+        if version as u32 != PHYSICS_SCENE_FORMAT_VERSION {
+            return Err(DeserializeError::UnsupportedVersion(version));
+        }
+
+        postcard::from_bytes(&bytes[header_len + 1..]).map_err(DeserializeError::Decode)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PhysicsScene;
+    use crate::dynamics::{PrismaticJoint, RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::PhysicsPipeline;
+
+    fn into_scene(world: TestWorld) -> PhysicsScene {
+        PhysicsScene::new(
+            world.islands,
+            world.broad_phase,
+            world.narrow_phase,
+            world.bodies,
+            world.colliders,
+            world.impulse_joints,
+            world.multibody_joints,
+            world.ccd_solver,
+        )
+    }
+
+    #[test]
+    fn physics_scene_serialization_round_trip() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let gravity = world.gravity;
+        let integration_parameters = world.integration_parameters;
+
+        let ground = RigidBodyBuilder::new_static()
+            .translation(Vector::y() * -10.0)
+            .build();
+        let ground = world.bodies.insert(ground);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(10.0).build(),
+            ground,
+            &mut world.bodies,
+        );
+
+        let mut handles: Vec<RigidBodyHandle> = Vec::new();
+        for i in 0..6 {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(
+                    Vector::x() * (i as Real * 0.3) + Vector::y() * (2.0 + i as Real * 1.1),
+                )
+                .build();
+            let handle = world.bodies.insert(body);
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                handle,
+                &mut world.bodies,
+            );
+            handles.push(handle);
+        }
+
+        let prismatic = PrismaticJoint::new(Vector::x_axis());
+        world
+            .impulse_joints
+            .insert(handles[0], handles[1], prismatic);
+        // NOTE: multibody free joints are not implemented in 2D yet.
+        #[cfg(feature = "dim3")]
+        {
+            use crate::dynamics::RevoluteJoint;
+            let revolute = RevoluteJoint::new(Vector::z_axis());
+            world
+                .multibody_joints
+                .insert(handles[2], handles[3], revolute);
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut scene = into_scene(world);
+
+        let step = |pipeline: &mut PhysicsPipeline, scene: &mut PhysicsScene| {
+            pipeline.step(
+                &gravity,
+                &integration_parameters,
+                &mut scene.islands,
+                &mut scene.broad_phase,
+                &mut scene.narrow_phase,
+                &mut scene.bodies,
+                &mut scene.colliders,
+                &mut scene.impulse_joints,
+                &mut scene.multibody_joints,
+                &mut scene.ccd_solver,
+                &(),
+                &(),
+            )
+        };
+
+        for _ in 0..20 {
+            step(&mut pipeline, &mut scene);
+        }
+
+        let serialized = bincode::serialize(&scene).unwrap();
+        let mut scene2: PhysicsScene = bincode::deserialize(&serialized).unwrap();
+        let mut pipeline2 = PhysicsPipeline::new();
+
+        for _ in 0..100 {
+            step(&mut pipeline, &mut scene);
+            step(&mut pipeline2, &mut scene2);
+
+            for handle in &handles {
+                assert_eq!(
+                    scene.bodies[*handle].position(),
+                    scene2.bodies[*handle].position()
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "binary-serde")]
+    #[test]
+    fn physics_scene_binary_round_trip() {
+        use super::DeserializeError;
+
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 3.0)
+            .build();
+        let handle = world.bodies.insert(body);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).build(),
+            handle,
+            &mut world.bodies,
+        );
+        let scene = into_scene(world);
+
+        let bytes = scene.to_bytes();
+        let scene2 = PhysicsScene::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            scene.bodies[handle].position(),
+            scene2.bodies[handle].position()
+        );
+
+        let mut stale = bytes.clone();
+        stale[4] += 1;
+        assert!(matches!(
+            PhysicsScene::from_bytes(&stale),
+            Err(DeserializeError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            PhysicsScene::from_bytes(&bytes[1..]),
+            Err(DeserializeError::InvalidMagicNumber)
+        ));
+    }
+}
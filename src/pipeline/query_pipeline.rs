@@ -7,8 +7,10 @@ use crate::geometry::{
     ColliderFlags, ColliderHandle, ColliderParent, ColliderPosition, ColliderShape,
     InteractionGroups, PointProjection, Ray, RayIntersection, AABB, QBVH,
 };
-use crate::math::{Isometry, Point, Real, Vector};
-use parry::partitioning::QBVHDataGenerator;
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+use parry::bounding_volume::{BoundingVolume, SimdAABB};
+use parry::math::{SimdBool, SimdReal, SIMD_WIDTH};
+use parry::partitioning::{QBVHDataGenerator, SimdVisitStatus};
 use parry::query::details::{
     IntersectionCompositeShapeShapeBestFirstVisitor,
     NonlinearTOICompositeShapeShapeBestFirstVisitor, PointCompositeShapeProjBestFirstVisitor,
@@ -20,7 +22,10 @@ use parry::query::visitors::{
     BoundingVolumeIntersectionsVisitor, PointIntersectionsVisitor, RayIntersectionsVisitor,
 };
 use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, TOI};
-use parry::shape::{FeatureId, Shape, TypedSimdCompositeShape};
+use parry::shape::{FeatureId, Shape, TypedShape, TypedSimdCompositeShape};
+#[cfg(feature = "dim3")]
+use parry::shape::{HeightField, Triangle};
+use simba::simd::{SimdBool as _, SimdPartialOrd, SimdValue};
 use std::sync::Arc;
 
 #[cfg(feature = "default-sets")]
@@ -63,6 +68,119 @@ pub enum QueryPipelineMode {
     },
 }
 
+/// How the impulse of an explosion decreases with the distance to its center.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum ExplosionFalloff {
+    /// The impulse is the same everywhere inside of the explosion radius.
+    Constant,
+    /// The impulse decreases linearly, down to zero at the explosion radius.
+    Linear,
+    /// The impulse is divided by `1 + distance²`.
+    InverseSquare,
+}
+
+impl ExplosionFalloff {
+    /// The factor multiplying the explosion strength at the given distance from its center.
+    pub fn factor(self, distance: Real, radius: Real) -> Real {
+        match self {
+            ExplosionFalloff::Constant => 1.0,
+            ExplosionFalloff::Linear => (1.0 - distance / radius).max(0.0),
+            ExplosionFalloff::InverseSquare => 1.0 / (1.0 + distance * distance),
+        }
+    }
+}
+
+/// Options controlling a ray-cast.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RayCastOptions {
+    /// If `true`, the hits on back faces, where the ray direction points in the same direction as
+    /// the outward normal of the surface hit (i.e. `ray.dir.dot(normal) > 0.0`), are ignored and
+    /// the ray keeps going in search for a front-facing hit. This includes the exit point of a
+    /// ray starting inside of a convex shape.
+    ///
+    /// Default: `false`.
+    pub backface_culling: bool,
+    /// If `true`, a ray starting inside of a shape hits it at time 0.0. If `false`, the ray
+    /// hits the boundary of the shape even if it starts inside of it.
+    ///
+    /// Default: `true`.
+    pub solid: bool,
+    /// The maximum time-of-impact that can be reported by the cast. This effectively limits the
+    /// length of the ray to `ray.dir.norm() * max_toi`.
+    ///
+    /// Default: `Real::MAX`.
+    pub max_toi: Real,
+}
+
+impl Default for RayCastOptions {
+    fn default() -> Self {
+        Self {
+            backface_culling: false,
+            solid: true,
+            max_toi: Real::MAX,
+        }
+    }
+}
+
+/// A shape cast performed by `QueryPipeline::batch_cast_shapes`.
+#[derive(Copy, Clone)]
+pub struct ShapeCast<'a> {
+    /// The shape to cast.
+    pub shape: &'a dyn Shape,
+    /// The initial position of the shape to cast.
+    pub shape_pos: Isometry<Real>,
+    /// The constant velocity of the shape to cast (i.e. the cast direction).
+    pub shape_vel: Vector<Real>,
+    /// The maximum time-of-impact that can be reported by this cast. This effectively limits the
+    /// distance traveled by the shape to `shape_vel.norm() * max_toi`.
+    pub max_toi: Real,
+}
+
+impl<'a> ShapeCast<'a> {
+    /// Creates a new shape cast.
+    pub fn new(
+        shape: &'a dyn Shape,
+        shape_pos: Isometry<Real>,
+        shape_vel: Vector<Real>,
+        max_toi: Real,
+    ) -> Self {
+        Self {
+            shape,
+            shape_pos,
+            shape_vel,
+            max_toi,
+        }
+    }
+}
+
+/// A collider that may be hit by a shape cast, with a lower bound of its time of impact.
+type ShapeCastCandidate<'a> = (Real, ColliderHandle, &'a Isometry<Real>, &'a dyn Shape);
+
+/// The result of a ray-cast, including the triangle hit by the ray and the barycentric
+/// coordinates of the hit point on this triangle.
+#[cfg(feature = "dim3")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RayIntersectionExt {
+    /// The time of impact of the ray with the collider.
+    pub toi: Real,
+    /// The normal of the collider at the hit point, in world-space.
+    pub normal: Vector<Real>,
+    /// The index of the triangle hit by the ray.
+    ///
+    /// For a `TriMesh`, this is the index of the triangle in `TriMesh::indices`. For a
+    /// `HeightField`, this is `i + j * nrows` for the first triangle of the cell at the
+    /// `i`-th row and `j`-th column, and this plus `nrows * ncols` for the second triangle.
+    /// This is `u32::MAX` for all the other shapes.
+    pub triangle_index: u32,
+    /// The barycentric coordinates of the hit point on the triangle hit by the ray.
+    ///
+    /// This is `[1.0, 0.0, 0.0]` if the collider hit is not a `TriMesh` nor a `HeightField`.
+    pub barycentric: [Real; 3],
+}
+
 impl<'a, Colliders> TypedSimdCompositeShape for QueryPipelineAsCompositeShape<'a, Colliders>
 where
     // TODO ECS: make everything optional but the shape?
@@ -434,6 +552,71 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Find the closest intersection between a ray and a set of collider, including the
+    /// triangle hit by the ray and the barycentric coordinates of the hit point.
+    ///
+    /// The triangle and barycentric coordinates are only meaningful for triangle meshes and
+    /// heightfields, and can be used to look up per-triangle or per-vertex attributes.
+    ///
+    /// # Parameters
+    /// - `colliders`: the set of colliders taking part in this pipeline.
+    /// - `ray`: the ray to cast.
+    /// - `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
+    ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
+    /// - `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it.
+    /// - `query_groups`: the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// - `filter`: a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    #[cfg(feature = "dim3")]
+    pub fn cast_ray_and_get_barycentric<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<(ColliderHandle, RayIntersectionExt)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let (handle, inter) =
+            self.cast_ray_and_get_normal(colliders, ray, max_toi, solid, query_groups, filter)?;
+        let co_pos: &ColliderPosition = colliders.index(handle.0);
+        let co_shape: &ColliderShape = colliders.index(handle.0);
+        let local_hit = co_pos.inverse_transform_point(&ray.point_at(inter.toi));
+
+        let (triangle_index, barycentric) = match (co_shape.as_typed_shape(), inter.feature) {
+            // Back-face hits are identified by a face ID shifted by the number of triangles.
+            (TypedShape::TriMesh(trimesh), FeatureId::Face(fid)) => {
+                let tid = fid % trimesh.num_triangles() as u32;
+                let tri = trimesh.triangle(tid);
+                (tid, triangle_barycentric_coordinates(&tri, &local_hit))
+            }
+            (TypedShape::HeightField(heightfield), _) => {
+                heightfield_triangle_at(heightfield, &local_hit)
+                    .unwrap_or((u32::MAX, [1.0, 0.0, 0.0]))
+            }
+            _ => (u32::MAX, [1.0, 0.0, 0.0]),
+        };
+
+        Some((
+            handle,
+            RayIntersectionExt {
+                toi: inter.toi,
+                normal: inter.normal,
+                triangle_index,
+                barycentric,
+            },
+        ))
+    }
+
     /// Find the all intersections between a ray and a set of collider and passes them to a callback.
     ///
     /// # Parameters
@@ -488,6 +671,173 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 
+    /// Checks if a ray intersects any collider.
+    ///
+    /// This is faster than `cast_ray` because the traversal of the QBVH stops as soon as one
+    /// collider is hit, instead of searching for the closest one. A ray starting inside of a
+    /// shape intersects it.
+    ///
+    /// # Parameters
+    /// - `colliders`: the set of colliders taking part in this pipeline.
+    /// - `ray`: the ray to cast.
+    /// - `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
+    ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
+    /// - `query_groups`: the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// - `filter`: a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn any_intersection<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        max_toi: Real,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> bool
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
+        let mut found = false;
+        let mut leaf_callback = |handle: &ColliderHandle| {
+            pipeline_shape.map_typed_part_at(*handle, |co_pos, co_shape| {
+                let co_pos = co_pos.copied().unwrap_or_else(Isometry::identity);
+                found = co_shape.intersects_ray(&co_pos, ray, max_toi);
+            });
+
+            // Stop the traversal at the first hit.
+            !found
+        };
+
+        let mut visitor = RayIntersectionsVisitor::new(ray, max_toi, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        found
+    }
+
+    /// Find the closest intersection between a ray and a set of collider, with additional
+    /// options like backface culling.
+    ///
+    /// # Parameters
+    /// - `colliders`: the set of colliders taking part in this pipeline.
+    /// - `ray`: the ray to cast.
+    /// - `options`: the options controlling this ray-cast.
+    /// - `query_groups`: the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// - `filter`: a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn cast_ray_with_options<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        options: RayCastOptions,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<(ColliderHandle, RayIntersection)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        if !options.backface_culling {
+            return self.cast_ray_and_get_normal(
+                colliders,
+                ray,
+                options.max_toi,
+                options.solid,
+                query_groups,
+                filter,
+            );
+        }
+
+        let mut best: Option<(ColliderHandle, RayIntersection)> = None;
+        self.intersections_with_ray_with_options(
+            colliders,
+            ray,
+            options,
+            query_groups,
+            filter,
+            |handle, hit| {
+                if best.map(|(_, best)| hit.toi < best.toi).unwrap_or(true) {
+                    best = Some((handle, hit));
+                }
+                true
+            },
+        );
+
+        best
+    }
+
+    /// Find the all intersections between a ray and a set of collider and passes them to a
+    /// callback, with additional options like backface culling.
+    ///
+    /// # Parameters
+    /// - `colliders`: the set of colliders taking part in this pipeline.
+    /// - `ray`: the ray to cast.
+    /// - `options`: the options controlling this ray-cast.
+    /// - `query_groups`: the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// - `filter`: a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    /// - `callback`: function executed on each collider for which a ray intersection has been found.
+    ///   There is no guarantees on the order the results will be yielded. If this callback returns `false`,
+    ///   this method will exit early, ignore any further raycast.
+    pub fn intersections_with_ray_with_options<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        options: RayCastOptions,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+        mut callback: impl FnMut(ColliderHandle, RayIntersection) -> bool,
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        if !options.backface_culling {
+            return self.intersections_with_ray(
+                colliders,
+                ray,
+                options.max_toi,
+                options.solid,
+                query_groups,
+                filter,
+                callback,
+            );
+        }
+
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
+            if let Some(co_shape) = co_shape {
+                let (co_flags, co_pos): (&ColliderFlags, &ColliderPosition) =
+                    colliders.index_bundle(handle.0);
+                if co_flags.collision_groups.test(query_groups)
+                    && filter.map(|f| f(*handle)).unwrap_or(true)
+                {
+                    if let Some(hit) = cast_ray_with_backface_culling(
+                        &**co_shape,
+                        co_pos,
+                        ray,
+                        options.max_toi,
+                        options.solid,
+                    ) {
+                        return callback(*handle, hit);
+                    }
+                }
+            }
+
+            true
+        };
+
+        let mut visitor = RayIntersectionsVisitor::new(ray, options.max_toi, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+    }
+
     /// Gets the handle of up to one collider intersecting the given shape.
     ///
     /// # Parameters
@@ -562,6 +912,99 @@ impl QueryPipeline {
             .map(|h| (h.1 .1, h.1 .0))
     }
 
+    /// Find the projections of many points on their closest colliders.
+    ///
+    /// This gives the same results as calling `project_point` for each element of `points`, but
+    /// the QBVH is traversed only once for all the points. This is faster when a lot of points
+    /// are projected at the same time.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `points` - The points to project.
+    /// * `solid` - If this is set to `true` then the collider shapes are considered to
+    ///   be plain (if the point is located inside of a plain shape, its projection is the point
+    ///   itself). If it is set to `false` the collider shapes are considered to be hollow
+    ///   (if the point is located inside of an hollow shape, it is projected on the shape's
+    ///   boundary).
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn batch_project_points<Colliders>(
+        &self,
+        colliders: &Colliders,
+        points: &[Point<Real>],
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Vec<Option<(ColliderHandle, PointProjection)>>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let simd_points: Vec<Point<SimdReal>> = points.iter().map(|pt| Point::splat(*pt)).collect();
+        let mut best_dists = vec![Real::MAX; points.len()];
+        let mut results = vec![None; points.len()];
+        let mut point_masks = vec![0; points.len()];
+
+        // Each node is visited if it may contain a collider closer to at least one of the points
+        // than their closest collider found so far.
+        let mut visitor = |bv: &SimdAABB, data: Option<[Option<&ColliderHandle>; SIMD_WIDTH]>| {
+            let mut mask = SimdBool::splat(false);
+
+            for i in 0..points.len() {
+                let dist = bv.distance_to_local_point(&simd_points[i]);
+                let point_mask = dist.simd_lt(SimdReal::splat(best_dists[i]));
+                point_masks[i] = point_mask.bitmask();
+                mask = mask | point_mask;
+            }
+
+            if let Some(data) = data {
+                for (ii, handle) in data.iter().enumerate() {
+                    let handle = match handle {
+                        Some(handle) if (mask.bitmask() & (1 << ii)) != 0 => **handle,
+                        _ => continue,
+                    };
+                    let co_flags: Option<&ColliderFlags> = colliders.get(handle.0);
+
+                    if let Some(co_flags) = co_flags {
+                        if !co_flags.collision_groups.test(query_groups)
+                            || !filter.map(|f| f(handle)).unwrap_or(true)
+                        {
+                            continue;
+                        }
+
+                        let (co_pos, co_shape): (&ColliderPosition, &ColliderShape) =
+                            colliders.index_bundle(handle.0);
+
+                        for i in 0..points.len() {
+                            if (point_masks[i] & (1 << ii)) != 0 {
+                                let proj = co_shape.project_point(co_pos, &points[i], solid);
+                                // A point inside of a solid shape can't get any closer.
+                                let dist = if solid && proj.is_inside {
+                                    0.0
+                                } else {
+                                    na::distance(&points[i], &proj.point)
+                                };
+
+                                if dist < best_dists[i] {
+                                    best_dists[i] = dist;
+                                    results[i] = Some((handle, proj));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            SimdVisitStatus::MaybeContinue(mask)
+        };
+        self.qbvh.traverse_depth_first(&mut visitor);
+        results
+    }
+
     /// Find all the colliders containing the given point.
     ///
     /// # Parameters
@@ -646,6 +1089,68 @@ impl QueryPipeline {
             .map(|h| (h.1 .1 .0, h.1 .0, h.1 .1 .1))
     }
 
+    /// Find the signed distance between a point and the closest collider, as well as the
+    /// closest point on its boundary.
+    ///
+    /// Returns the handle of the collider, the signed distance (negative if the point is inside
+    /// of the collider), the projection of the point on the boundary of the collider, and the
+    /// outward normal of the collider boundary at this projection. The normal is zero if it
+    /// cannot be determined, e.g., if the point lies exactly on a bare edge of a triangle mesh.
+    ///
+    /// Convex shapes rely on GJK/EPA to project points located inside of them. Compound shapes
+    /// and meshes are handled part by part, based on their own notion of inside and outside.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `point` - The point to project.
+    /// * `solid` - If this is set to `true`, a collider containing the point is always selected
+    ///   over a collider not containing it, even if the boundary of the latter is closer. If it
+    ///   is set to `false`, the collider with the closest boundary is selected.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn project_point_signed<Colliders>(
+        &self,
+        colliders: &Colliders,
+        point: &Point<Real>,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<(ColliderHandle, Real, Point<Real>, Vector<Real>)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let (handle, _) = self.project_point(colliders, point, solid, query_groups, filter)?;
+        let co_pos: &ColliderPosition = colliders.index(handle.0);
+        let co_shape: &ColliderShape = colliders.index(handle.0);
+
+        // Project again on the boundary, in case the point was inside of a solid shape.
+        let local_point = co_pos.inverse_transform_point(point);
+        let (proj, feature) = co_shape.project_local_point_and_get_feature(&local_point);
+        let dpt = local_point - proj.point;
+        let dist = dpt.norm();
+
+        let local_normal = match dpt.try_normalize(crate::math::DEFAULT_EPSILON) {
+            Some(dir) if proj.is_inside => -dir,
+            Some(dir) => dir,
+            None => co_shape
+                .feature_normal_at_point(feature, &proj.point)
+                .map(|n| n.into_inner())
+                .unwrap_or_else(Vector::zeros),
+        };
+
+        Some((
+            handle,
+            if proj.is_inside { -dist } else { dist },
+            co_pos.0 * proj.point,
+            co_pos.0 * local_normal,
+        ))
+    }
+
     /// Finds all handles of all the colliders with an AABB intersecting the given AABB.
     pub fn colliders_with_aabb_intersecting_aabb(
         &self,
@@ -656,6 +1161,55 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 
+    /// Finds the handles of all the colliders with an AABB intersecting the volume swept by an
+    /// AABB moving linearly from `aabb_start` to `aabb_end`.
+    ///
+    /// This only relies on AABBs, so it is much faster, but less precise, than `cast_shape`.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `aabb_start` - The AABB at the beginning of its motion.
+    /// * `aabb_end` - The AABB at the end of its motion.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn aabb_sweep<Colliders>(
+        &self,
+        colliders: &Colliders,
+        aabb_start: &AABB,
+        aabb_end: &AABB,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Vec<ColliderHandle>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
+        let swept_aabb = aabb_start.merged(aabb_end);
+        let mut result = vec![];
+
+        // The QBVH gives the colliders intersecting the union of both AABBs,
+        // then we only keep the ones actually reached by the moving AABB.
+        let mut callback = |handle: &ColliderHandle| {
+            pipeline_shape.map_typed_part_at(*handle, |co_pos, co_shape| {
+                let aabb = co_shape.compute_aabb(co_pos.unwrap_or(&Isometry::identity()));
+
+                if moving_aabb_toi(aabb_start, aabb_end, &aabb).is_some() {
+                    result.push(*handle);
+                }
+            });
+            true
+        };
+
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&swept_aabb, &mut callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        result
+    }
+
     /// Casts a shape at a constant linear velocity and retrieve the first collider it hits.
     ///
     /// This is similar to ray-casting except that we are casting a whole shape instead of just a
@@ -701,15 +1255,333 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
-    /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
+    /// Checks if a shape moving at a constant linear velocity hits any collider.
     ///
-    /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
-    /// space.
+    /// This is faster than `cast_shape` because the traversal of the QBVH stops as soon as one
+    /// collider is hit, instead of searching for the first one.
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
-    /// * `shape_motion` - The motion of the shape.
-    /// * `shape` - The shape to cast.
+    /// * `shape_pos` - The initial position of the shape to cast.
+    /// * `shape_vel` - The constant velocity of the shape to cast (i.e. the cast direction).
+    /// * `shape` - The shape to cast.
+    /// * `max_toi` - The maximum time-of-impact that can be reported by this cast. This effectively
+    ///   limits the distance traveled by the shape to `shapeVel.norm() * maxToi`.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn any_shape_intersection<Colliders>(
+        &self,
+        colliders: &Colliders,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        max_toi: Real,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> bool
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
+        let aabb_start = shape.compute_aabb(shape_pos);
+        let mut shape_end = *shape_pos;
+        shape_end.translation.vector += shape_vel * max_toi;
+        let aabb_end = shape.compute_aabb(&shape_end);
+
+        let mut found = false;
+        let mut callback = |handle: &ColliderHandle| {
+            pipeline_shape.map_untyped_part_at(*handle, |co_pos, co_shape| {
+                let co_pos = co_pos.copied().unwrap_or_else(Isometry::identity);
+
+                // Skip the exact time-of-impact computation if even the AABBs don't meet.
+                if moving_aabb_toi(&aabb_start, &aabb_end, &co_shape.compute_aabb(&co_pos))
+                    .is_some()
+                {
+                    found = self
+                        .query_dispatcher
+                        .time_of_impact(
+                            &co_pos.inv_mul(shape_pos),
+                            &co_pos.inverse_transform_vector(shape_vel),
+                            co_shape,
+                            shape,
+                            max_toi,
+                        )
+                        .unwrap_or(None)
+                        .is_some();
+                }
+            });
+
+            // Stop the traversal at the first hit.
+            !found
+        };
+
+        let swept_aabb = aabb_start.merged(&aabb_end);
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&swept_aabb, &mut callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        found
+    }
+
+    /// Casts a shape at a constant linear velocity and retrieve all the colliders it hits, sorted
+    /// by increasing time of impact.
+    ///
+    /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
+    /// space.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_pos` - The initial position of the shape to cast.
+    /// * `shape_vel` - The constant velocity of the shape to cast (i.e. the cast direction).
+    /// * `shape` - The shape to cast.
+    /// * `max_toi` - The maximum time-of-impact that can be reported by this cast. This effectively
+    ///   limits the distance traveled by the shape to `shapeVel.norm() * maxToi`.
+    /// * `max_hits` - The maximum number of hits to report. Only the `max_hits` first hits
+    ///   are computed.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn cast_shape_all<Colliders>(
+        &self,
+        colliders: &Colliders,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        max_toi: Real,
+        max_hits: usize,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Vec<(ColliderHandle, TOI)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        if max_hits == 0 {
+            return vec![];
+        }
+
+        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
+        let aabb_start = shape.compute_aabb(shape_pos);
+        let mut shape_end = *shape_pos;
+        shape_end.translation.vector += shape_vel * max_toi;
+        let aabb_end = shape.compute_aabb(&shape_end);
+
+        // Collect all the candidates from a single traversal of the QBVH, along with the
+        // time the AABB of the cast shape reaches their AABB. That time is a lower bound
+        // of their time of impact.
+        let mut candidates = vec![];
+        let mut callback = |handle: &ColliderHandle| {
+            pipeline_shape.map_typed_part_at(*handle, |co_pos, co_shape| {
+                let aabb = co_shape.compute_aabb(co_pos.unwrap_or(&Isometry::identity()));
+
+                if let Some(t) = moving_aabb_toi(&aabb_start, &aabb_end, &aabb) {
+                    candidates.push((t * max_toi, *handle));
+                }
+            });
+            true
+        };
+
+        let swept_aabb = aabb_start.merged(&aabb_end);
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&swept_aabb, &mut callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut hits: Vec<(ColliderHandle, TOI)> = vec![];
+
+        for (toi_lower_bound, handle) in candidates {
+            // The remaining candidates can't be hit before the last of the `max_hits` first hits.
+            if hits.len() >= max_hits && toi_lower_bound > hits[max_hits - 1].1.toi {
+                break;
+            }
+
+            pipeline_shape.map_untyped_part_at(handle, |co_pos, co_shape| {
+                let co_pos = co_pos.copied().unwrap_or_else(Isometry::identity);
+                let toi = self
+                    .query_dispatcher
+                    .time_of_impact(
+                        &co_pos.inv_mul(shape_pos),
+                        &co_pos.inverse_transform_vector(shape_vel),
+                        co_shape,
+                        shape,
+                        max_toi,
+                    )
+                    .unwrap_or(None);
+
+                if let Some(toi) = toi {
+                    let i = hits.partition_point(|hit| hit.1.toi <= toi.toi);
+                    hits.insert(i, (handle, toi.transform1_by(&co_pos)));
+                    hits.truncate(max_hits);
+                }
+            });
+        }
+
+        hits
+    }
+
+    /// Casts many shapes at constant linear velocities and retrieve the first collider hit by
+    /// each of them.
+    ///
+    /// This gives the same results as calling `cast_shape` for each element of `casts`, but the
+    /// QBVH is traversed only once for all the casts, and the time-of-impact computations of the
+    /// different casts run in parallel if the `parallel` feature is enabled. This is faster when
+    /// a lot of shapes are cast at the same time.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `casts` - The shapes to cast, with their initial positions, velocities, and maximum
+    ///   time-of-impact. See `cast_shape` for details.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///   its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///   is either `None` or returns `true`.
+    pub fn batch_cast_shapes<Colliders>(
+        &self,
+        colliders: &Colliders,
+        casts: &[ShapeCast],
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Vec<Option<(ColliderHandle, TOI)>>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let swept_aabbs: Vec<_> = casts
+            .iter()
+            .map(|cast| {
+                let mut shape_end = cast.shape_pos;
+                shape_end.translation.vector += cast.shape_vel * cast.max_toi;
+                (
+                    cast.shape.compute_aabb(&cast.shape_pos),
+                    cast.shape.compute_aabb(&shape_end),
+                )
+            })
+            .collect();
+        let simd_swept_aabbs: Vec<_> = swept_aabbs
+            .iter()
+            .map(|(start, end)| SimdAABB::splat(start.merged(end)))
+            .collect();
+
+        // Collect the candidates of all the casts from a single traversal of the QBVH, along
+        // with the time the AABB of the cast shape reaches their AABB.
+        let mut candidates: Vec<Vec<ShapeCastCandidate>> = vec![vec![]; casts.len()];
+        let mut cast_masks = vec![0; casts.len()];
+        let mut visitor = |bv: &SimdAABB, data: Option<[Option<&ColliderHandle>; SIMD_WIDTH]>| {
+            let mut mask = SimdBool::splat(false);
+
+            for (cast_mask, swept_aabb) in cast_masks.iter_mut().zip(simd_swept_aabbs.iter()) {
+                let cast_intersections = bv.intersects(swept_aabb);
+                *cast_mask = cast_intersections.bitmask();
+                mask = mask | cast_intersections;
+            }
+
+            if let Some(data) = data {
+                for (ii, handle) in data.iter().enumerate() {
+                    let handle = match handle {
+                        Some(handle) if (mask.bitmask() & (1 << ii)) != 0 => **handle,
+                        _ => continue,
+                    };
+                    let co_flags: Option<&ColliderFlags> = colliders.get(handle.0);
+
+                    if let Some(co_flags) = co_flags {
+                        if !co_flags.collision_groups.test(query_groups)
+                            || !filter.map(|f| f(handle)).unwrap_or(true)
+                        {
+                            continue;
+                        }
+
+                        let (co_pos, co_shape): (&ColliderPosition, &ColliderShape) =
+                            colliders.index_bundle(handle.0);
+                        let aabb = co_shape.compute_aabb(co_pos);
+
+                        for (i, cast_mask) in cast_masks.iter().enumerate() {
+                            if (cast_mask & (1 << ii)) != 0 {
+                                let (start, end) = &swept_aabbs[i];
+
+                                if let Some(t) = moving_aabb_toi(start, end, &aabb) {
+                                    candidates[i].push((
+                                        t * casts[i].max_toi,
+                                        handle,
+                                        co_pos,
+                                        &**co_shape,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            SimdVisitStatus::MaybeContinue(mask)
+        };
+        self.qbvh.traverse_depth_first(&mut visitor);
+
+        let first_hit = |(mut candidates, cast): (Vec<ShapeCastCandidate>, &ShapeCast)| {
+            candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            let mut hit: Option<(ColliderHandle, TOI)> = None;
+
+            for (toi_lower_bound, handle, co_pos, co_shape) in candidates {
+                // The remaining candidates can't be hit before the current hit.
+                if hit.map(|hit| toi_lower_bound > hit.1.toi).unwrap_or(false) {
+                    break;
+                }
+
+                let max_toi = hit.map(|hit| hit.1.toi).unwrap_or(cast.max_toi);
+                let toi = self
+                    .query_dispatcher
+                    .time_of_impact(
+                        &co_pos.inv_mul(&cast.shape_pos),
+                        &co_pos.inverse_transform_vector(&cast.shape_vel),
+                        co_shape,
+                        cast.shape,
+                        max_toi,
+                    )
+                    .unwrap_or(None);
+
+                if let Some(toi) = toi {
+                    if hit.map(|hit| toi.toi < hit.1.toi).unwrap_or(true) {
+                        hit = Some((handle, toi.transform1_by(co_pos)));
+                    }
+                }
+            }
+
+            hit
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        return candidates
+            .into_iter()
+            .zip(casts.iter())
+            .map(first_hit)
+            .collect();
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            candidates
+                .into_par_iter()
+                .zip(casts.par_iter())
+                .map(first_hit)
+                .collect()
+        }
+    }
+
+    /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
+    ///
+    /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
+    /// space.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_motion` - The motion of the shape.
+    /// * `shape` - The shape to cast.
     /// * `start_time` - The starting time of the interval where the motion takes place.
     /// * `end_time` - The end time of the interval where the motion takes place.
     /// * `stop_at_penetration` - If the casted shape starts in a penetration state with any
@@ -810,4 +1682,721 @@ impl QueryPipeline {
 
         self.qbvh.traverse_depth_first(&mut visitor);
     }
+
+    /// Applies the radial impulse of an explosion to every awake dynamic rigid-body closer
+    /// than `radius` to `center`.
+    ///
+    /// The impulse applied to each rigid-body has a magnitude of `strength` multiplied by
+    /// the `falloff` factor at the distance between `center` and its closest collider. It is
+    /// applied at the closest point of that collider.
+    ///
+    /// If `occlusion` is `true`, the rigid-bodies hidden from `center` by a collider that isn't
+    /// attached to a dynamic or kinematic rigid-body aren't affected.
+    #[cfg(feature = "default-sets")]
+    pub fn apply_explosion_impulse(
+        &self,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        center: Point<Real>,
+        radius: Real,
+        strength: Real,
+        falloff: ExplosionFalloff,
+        occlusion: bool,
+    ) {
+        let mut closest_points = std::collections::HashMap::new();
+        let aabb = AABB::from_half_extents(center, Vector::repeat(radius));
+
+        self.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+            if let Some(co) = colliders.get(*handle) {
+                if let Some(body_handle) = co.parent() {
+                    let body = &bodies[body_handle];
+
+                    if body.is_dynamic() && !body.is_sleeping() {
+                        let proj = co.shape().project_point(co.position(), &center, true);
+                        let dist = na::distance(&proj.point, &center);
+                        let closest = closest_points.entry(body_handle).or_insert((dist, proj));
+
+                        if dist < closest.0 {
+                            *closest = (dist, proj);
+                        }
+                    }
+                }
+            }
+
+            true
+        });
+
+        let is_obstacle = |handle: ColliderHandle| {
+            colliders[handle]
+                .parent()
+                .map(|parent| bodies[parent].is_static())
+                .unwrap_or(true)
+        };
+
+        let mut impulses = vec![];
+
+        for (body_handle, (dist, proj)) in closest_points {
+            if dist > radius {
+                continue;
+            }
+
+            let body = &bodies[body_handle];
+            let dir = if proj.is_inside || dist <= crate::math::DEFAULT_EPSILON {
+                body.position() * body.mass_properties().local_com - center
+            } else {
+                proj.point - center
+            };
+
+            if occlusion && !proj.is_inside {
+                let ray = Ray::new(center, dir);
+                let max_toi = 1.0 - crate::math::DEFAULT_EPSILON;
+
+                if self
+                    .cast_ray(
+                        colliders,
+                        &ray,
+                        max_toi,
+                        true,
+                        InteractionGroups::all(),
+                        Some(&is_obstacle),
+                    )
+                    .is_some()
+                {
+                    continue;
+                }
+            }
+
+            if let Some(dir) = dir.try_normalize(crate::math::DEFAULT_EPSILON) {
+                let impulse = dir * (strength * falloff.factor(dist, radius));
+                impulses.push((body_handle, impulse, proj.point));
+            }
+        }
+
+        for (body_handle, impulse, point) in impulses {
+            bodies[body_handle].apply_impulse_at_point(impulse, point, true);
+        }
+    }
+}
+
+/// The first time in `[0, 1]` when an AABB moving linearly from `start` to `end` intersects `aabb`.
+fn moving_aabb_toi(start: &AABB, end: &AABB, aabb: &AABB) -> Option<Real> {
+    let mut tmin: Real = 0.0;
+    let mut tmax: Real = 1.0;
+
+    // Restricts [tmin, tmax] to the times `t` satisfying `a + b * t <= c`.
+    let mut clip = |a: Real, b: Real, c: Real| {
+        if b.abs() <= crate::math::DEFAULT_EPSILON {
+            if a > c {
+                tmax = -1.0;
+            }
+        } else if b > 0.0 {
+            tmax = tmax.min((c - a) / b);
+        } else {
+            tmin = tmin.max((c - a) / b);
+        }
+    };
+
+    for i in 0..DIM {
+        // The moving AABB overlaps `aabb` along this axis if `mins(t) <= aabb.maxs`
+        // and `maxs(t) >= aabb.mins`.
+        clip(start.mins[i], end.mins[i] - start.mins[i], aabb.maxs[i]);
+        clip(-start.maxs[i], start.maxs[i] - end.maxs[i], -aabb.mins[i]);
+    }
+
+    if tmin <= tmax {
+        Some(tmin)
+    } else {
+        None
+    }
+}
+
+/// The barycentric coordinates of the projection of `pt` on the plane of `tri`.
+#[cfg(feature = "dim3")]
+fn triangle_barycentric_coordinates(tri: &Triangle, pt: &Point<Real>) -> [Real; 3] {
+    let ab = tri.b - tri.a;
+    let ac = tri.c - tri.a;
+    let ap = pt - tri.a;
+    let (d00, d01, d11) = (ab.dot(&ab), ab.dot(&ac), ac.dot(&ac));
+    let (d20, d21) = (ap.dot(&ab), ap.dot(&ac));
+    let denom = d00 * d11 - d01 * d01;
+
+    if denom.abs() <= crate::math::DEFAULT_EPSILON {
+        // Degenerate triangle.
+        return [1.0, 0.0, 0.0];
+    }
+
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    [1.0 - v - w, v, w]
+}
+
+/// The index of the heightfield triangle containing `pt`, and the barycentric coordinates
+/// of `pt` on this triangle.
+#[cfg(feature = "dim3")]
+fn heightfield_triangle_at(
+    heightfield: &HeightField,
+    pt: &Point<Real>,
+) -> Option<(u32, [Real; 3])> {
+    let (i, j) = heightfield.cell_at_point(pt)?;
+    let (tri1, tri2) = heightfield.triangles_at(i, j);
+    let cell_id = (i + j * heightfield.nrows()) as u32;
+    let num_cells = (heightfield.nrows() * heightfield.ncols()) as u32;
+    let candidates = [(tri1, cell_id), (tri2, cell_id + num_cells)];
+
+    // Select the triangle with the hit point the deepest inside of it, to be robust
+    // against hits on the diagonal of the cell.
+    candidates
+        .iter()
+        .filter_map(|(tri, tid)| tri.map(|tri| (*tid, triangle_barycentric_coordinates(&tri, pt))))
+        .max_by(|a, b| {
+            let min_a = a.1[0].min(a.1[1]).min(a.1[2]);
+            let min_b = b.1[0].min(b.1[1]).min(b.1[2]);
+            min_a
+                .partial_cmp(&min_b)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Casts a ray on a shape, ignoring all the hits on back faces.
+fn cast_ray_with_backface_culling(
+    shape: &dyn Shape,
+    pos: &Isometry<Real>,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+) -> Option<RayIntersection> {
+    // The small distance the ray travels past a back face before being cast again.
+    const SKIN: Real = 1.0e-4;
+    const MAX_BACKFACES: usize = 32;
+
+    let local_ray = ray.inverse_transform_by(pos);
+    let skip = SKIN / local_ray.dir.norm();
+    let mut start = 0.0;
+
+    for _ in 0..MAX_BACKFACES {
+        let curr_ray = Ray::new(local_ray.point_at(start), local_ray.dir);
+        let mut hit = shape.cast_local_ray_and_get_normal(&curr_ray, max_toi - start, solid)?;
+
+        if !is_backface_hit(shape, &hit, &curr_ray) {
+            hit.toi += start;
+            hit.normal = pos * hit.normal;
+            return Some(hit);
+        }
+
+        // Once past a back face, the ray starts outside of the shape.
+        start += hit.toi + skip;
+
+        if start > max_toi {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Is the hit of the given ray, expressed in the local-space of `shape`, on a back face?
+fn is_backface_hit(shape: &dyn Shape, hit: &RayIntersection, local_ray: &Ray) -> bool {
+    // Meshes and one-sided primitives flip the normal so it faces the ray, and identify
+    // their back faces with the feature ID instead.
+    let backface_feature = match (shape.as_typed_shape(), hit.feature) {
+        (TypedShape::TriMesh(trimesh), feature) => trimesh.is_backface(feature),
+        #[cfg(feature = "dim2")]
+        (TypedShape::HeightField(heightfield), FeatureId::Face(fid)) => {
+            fid as usize >= heightfield.num_cells()
+        }
+        #[cfg(feature = "dim3")]
+        (TypedShape::HeightField(heightfield), FeatureId::Face(fid)) => {
+            fid as usize >= heightfield.nrows() * heightfield.ncols() * 2
+        }
+        #[cfg(feature = "dim2")]
+        (TypedShape::Polyline(_), FeatureId::Face(1)) => true,
+        (TypedShape::Triangle(_), FeatureId::Face(1))
+        | (TypedShape::Segment(_), FeatureId::Face(1)) => true,
+        _ => false,
+    };
+
+    // The normal is also flipped to face the ray when it exits a convex shape.
+    let exits_convex =
+        hit.toi > 0.0 && shape.is_convex() && shape.contains_local_point(&local_ray.origin);
+
+    backface_feature || exits_convex || local_ray.dir.dot(&hit.normal) > 0.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::{QueryPipeline, ShapeCast};
+    use crate::geometry::{Ball, ColliderBuilder, ColliderHandle, InteractionGroups, Ray, AABB};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn batch_shape_casts_match_individual_casts() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        let mut handles = vec![];
+        for i in 0..8 {
+            for j in 0..8 {
+                let collider = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * i as Real * 3.0 + Vector::y() * j as Real * 3.0)
+                    .build();
+                handles.push(world.colliders.insert(collider));
+            }
+        }
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let shape = Ball::new(0.2);
+        let casts: Vec<_> = (0..40)
+            .map(|k| {
+                let angle = k as Real * 0.37;
+                let mut shape_pos = Isometry::identity();
+                shape_pos.translation.vector =
+                    Vector::x() * (k % 7) as Real * 3.0 + Vector::y() * 1.5;
+                let mut shape_vel = Vector::zeros();
+                shape_vel.x = angle.cos();
+                shape_vel.y = angle.sin();
+                ShapeCast::new(&shape, shape_pos, shape_vel, 10.0)
+            })
+            .collect();
+
+        let excluded = handles[9];
+        let filter = |handle: ColliderHandle| handle != excluded;
+        let batch = query_pipeline.batch_cast_shapes(
+            &world.colliders,
+            &casts,
+            InteractionGroups::all(),
+            Some(&filter),
+        );
+
+        assert_eq!(batch.len(), casts.len());
+        assert!(batch.iter().filter(|hit| hit.is_some()).count() > 10);
+
+        for (hit, cast) in batch.iter().zip(casts.iter()) {
+            let expected = query_pipeline.cast_shape(
+                &world.colliders,
+                &cast.shape_pos,
+                &cast.shape_vel,
+                cast.shape,
+                cast.max_toi,
+                InteractionGroups::all(),
+                Some(&filter),
+            );
+
+            assert_eq!(hit.map(|hit| hit.0), expected.map(|hit| hit.0));
+            assert_ne!(hit.map(|hit| hit.0), Some(excluded));
+            if let (Some(hit), Some(expected)) = (hit, expected) {
+                assert!((hit.1.toi - expected.1.toi).abs() < 1.0e-4);
+                assert!((hit.1.witness1 - expected.1.witness1).norm() < 1.0e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn batch_point_projections_match_individual_projections() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        let mut handles = vec![];
+        for i in 0..8 {
+            for j in 0..8 {
+                let collider = ColliderBuilder::ball(0.5 + (i + j) as Real * 0.05)
+                    .translation(Vector::x() * i as Real * 3.0 + Vector::y() * j as Real * 3.0)
+                    .build();
+                handles.push(world.colliders.insert(collider));
+            }
+        }
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let points: Vec<_> = (0..50)
+            .map(|k| {
+                let mut point = Point::origin();
+                point.x = (k * 7 % 30) as Real * 0.83 - 2.0;
+                point.y = (k * 11 % 30) as Real * 0.79 - 2.0;
+                point
+            })
+            .collect();
+
+        let excluded = handles[9];
+        let filter = |handle: ColliderHandle| handle != excluded;
+
+        for solid in [true, false] {
+            let batch = query_pipeline.batch_project_points(
+                &world.colliders,
+                &points,
+                solid,
+                InteractionGroups::all(),
+                Some(&filter),
+            );
+            assert_eq!(batch.len(), points.len());
+
+            for (proj, point) in batch.iter().zip(points.iter()) {
+                let expected = query_pipeline
+                    .project_point(
+                        &world.colliders,
+                        point,
+                        solid,
+                        InteractionGroups::all(),
+                        Some(&filter),
+                    )
+                    .unwrap();
+                let proj = proj.unwrap();
+
+                assert_ne!(proj.0, excluded);
+                assert_eq!(proj.1.is_inside, expected.1.is_inside);
+                assert!((proj.1.point - expected.1.point).norm() < 1.0e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn any_intersection_queries() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        // A wall of balls along `y`, at `x = 5`.
+        let handles: Vec<_> = (0..10)
+            .map(|j| {
+                let collider = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * 5.0 + Vector::y() * j as Real)
+                    .build();
+                world.colliders.insert(collider)
+            })
+            .collect();
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let groups = InteractionGroups::all();
+        let ray = Ray::new(Point::origin(), Vector::x());
+        assert!(query_pipeline.any_intersection(&world.colliders, &ray, 10.0, groups, None));
+        assert!(!query_pipeline.any_intersection(&world.colliders, &ray, 4.0, groups, None));
+        let missing_ray = Ray::new(Point::origin(), -Vector::x());
+        assert!(!query_pipeline.any_intersection(
+            &world.colliders,
+            &missing_ray,
+            10.0,
+            groups,
+            None
+        ));
+        let ignore_first = |handle: ColliderHandle| handle != handles[0];
+        assert!(!query_pipeline.any_intersection(
+            &world.colliders,
+            &ray,
+            10.0,
+            groups,
+            Some(&ignore_first)
+        ));
+
+        let shape = Ball::new(0.2);
+        let shape_pos = Isometry::identity();
+        let shape_vel = Vector::x();
+        assert!(query_pipeline.any_shape_intersection(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            10.0,
+            groups,
+            None
+        ));
+        assert!(!query_pipeline.any_shape_intersection(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            4.0,
+            groups,
+            None
+        ));
+        // The ball is large enough to hit the collider next to the filtered one.
+        assert!(query_pipeline.any_shape_intersection(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &Ball::new(0.6),
+            10.0,
+            groups,
+            Some(&ignore_first)
+        ));
+        assert!(!query_pipeline.any_shape_intersection(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            10.0,
+            groups,
+            Some(&ignore_first)
+        ));
+    }
+
+    #[test]
+    fn aabb_sweep_only_reports_the_colliders_on_the_path() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        // Two balls along the diagonal, two off the diagonal but inside the AABB of the whole
+        // motion, and one away from it.
+        let handles: Vec<_> = [
+            (3.0, 3.0),
+            (7.0, 7.0),
+            (10.0, 0.0),
+            (0.0, 10.0),
+            (-5.0, 0.0),
+        ]
+        .iter()
+        .map(|(x, y)| {
+            let collider = ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * *x + Vector::y() * *y)
+                .build();
+            world.colliders.insert(collider)
+        })
+        .collect();
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let aabb_start = AABB::from_half_extents(Point::origin(), Vector::repeat(0.5));
+        let aabb_end = AABB::from_half_extents(
+            Point::from(Vector::x() * 10.0 + Vector::y() * 10.0),
+            Vector::repeat(0.5),
+        );
+        let groups = InteractionGroups::all();
+
+        let mut hits =
+            query_pipeline.aabb_sweep(&world.colliders, &aabb_start, &aabb_end, groups, None);
+        hits.sort_by_key(|handle| handle.into_raw_parts());
+        assert_eq!(hits, handles[..2]);
+
+        let ignore_first = |handle: ColliderHandle| handle != handles[0];
+        let hits = query_pipeline.aabb_sweep(
+            &world.colliders,
+            &aabb_start,
+            &aabb_end,
+            groups,
+            Some(&ignore_first),
+        );
+        assert_eq!(hits, [handles[1]]);
+    }
+
+    #[test]
+    fn cast_shape_all_sorts_the_hits_by_toi() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        // Balls along `x`, inserted out of order.
+        let handles: Vec<_> = [9.0, 3.0, 12.0, 6.0]
+            .iter()
+            .map(|x| {
+                let collider = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * *x)
+                    .build();
+                world.colliders.insert(collider)
+            })
+            .collect();
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let shape = Ball::new(0.2);
+        let shape_pos = Isometry::identity();
+        let shape_vel = Vector::x();
+        let groups = InteractionGroups::all();
+
+        let hits = query_pipeline.cast_shape_all(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            100.0,
+            usize::MAX,
+            groups,
+            None,
+        );
+        let hit_handles: Vec<_> = hits.iter().map(|hit| hit.0).collect();
+        assert_eq!(
+            hit_handles,
+            [handles[1], handles[3], handles[0], handles[2]]
+        );
+        for (hit, expected_toi) in hits.iter().zip([2.3, 5.3, 8.3, 11.3].iter()) {
+            assert!((hit.1.toi - expected_toi).abs() < 1.0e-4);
+        }
+
+        // The ball at `x = 12` can't be reached before `max_toi`.
+        let hits = query_pipeline.cast_shape_all(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            10.0,
+            usize::MAX,
+            groups,
+            None,
+        );
+        let hit_handles: Vec<_> = hits.iter().map(|hit| hit.0).collect();
+        assert_eq!(hit_handles, [handles[1], handles[3], handles[0]]);
+
+        let hits = query_pipeline.cast_shape_all(
+            &world.colliders,
+            &shape_pos,
+            &shape_vel,
+            &shape,
+            10.0,
+            2,
+            groups,
+            None,
+        );
+        let hit_handles: Vec<_> = hits.iter().map(|hit| hit.0).collect();
+        assert_eq!(hit_handles, [handles[1], handles[3]]);
+    }
+
+    #[test]
+    fn project_point_signed_inside_and_outside() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::cuboid(2.0, 1.0);
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cuboid(2.0, 1.0, 1.0);
+        let handle = world
+            .colliders
+            .insert(collider.translation(Vector::x() * 5.0).build());
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let groups = InteractionGroups::all();
+
+        // Inside, closest to the top face.
+        let point = Point::from(Vector::x() * 5.5 + Vector::y() * 0.2);
+        let (hit, dist, proj, normal) = query_pipeline
+            .project_point_signed(&world.colliders, &point, true, groups, None)
+            .unwrap();
+        assert_eq!(hit, handle);
+        assert!((dist + 0.8).abs() < 1.0e-5);
+        assert!((proj - Point::from(Vector::x() * 5.5 + Vector::y())).norm() < 1.0e-5);
+        assert!((normal - Vector::y()).norm() < 1.0e-5);
+
+        // Outside, facing the right face.
+        let point = Point::from(Vector::x() * 9.0);
+        let (hit, dist, proj, normal) = query_pipeline
+            .project_point_signed(&world.colliders, &point, true, groups, None)
+            .unwrap();
+        assert_eq!(hit, handle);
+        assert!((dist - 2.0).abs() < 1.0e-5);
+        assert!((proj - Point::from(Vector::x() * 7.0)).norm() < 1.0e-5);
+        assert!((normal - Vector::x()).norm() < 1.0e-5);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn cast_ray_and_get_barycentric_on_a_trimesh() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        // A square made of two triangles, one unit above the ground.
+        let vertices = vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, 4.0),
+            Point::new(4.0, 0.0, 4.0),
+        ];
+        let indices = vec![[0, 1, 2], [1, 3, 2]];
+        let collider = ColliderBuilder::trimesh(vertices, indices)
+            .translation(Vector::y())
+            .build();
+        let handle = world.colliders.insert(collider);
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let groups = InteractionGroups::all();
+        let rays = [
+            (
+                Ray::new(Point::new(1.0, 5.0, 1.0), -Vector::y()),
+                0,
+                [0.5, 0.25, 0.25],
+            ),
+            (
+                Ray::new(Point::new(3.0, 5.0, 3.0), -Vector::y()),
+                1,
+                [0.25, 0.5, 0.25],
+            ),
+            // From below, through the back face of the first triangle.
+            (
+                Ray::new(Point::new(1.0, -3.0, 1.0), Vector::y()),
+                0,
+                [0.5, 0.25, 0.25],
+            ),
+        ];
+
+        for (ray, triangle_index, barycentric) in rays.iter() {
+            let (hit, inter) = query_pipeline
+                .cast_ray_and_get_barycentric(&world.colliders, ray, 10.0, true, groups, None)
+                .unwrap();
+            assert_eq!(hit, handle);
+            assert!((inter.toi - 4.0).abs() < 1.0e-5);
+            assert!((inter.normal.dot(&ray.dir) + 1.0).abs() < 1.0e-5);
+            assert_eq!(inter.triangle_index, *triangle_index);
+            for (coord, expected) in inter.barycentric.iter().zip(barycentric.iter()) {
+                assert!((coord - expected).abs() < 1.0e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn backface_culling_from_inside_of_a_mesh() {
+        use super::RayCastOptions;
+        use crate::geometry::Cuboid;
+
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+
+        // A closed mesh around the origin, and a ball further along `x`.
+        #[cfg(feature = "dim2")]
+        let mesh = {
+            let vertices = Cuboid::new(Vector::repeat(1.0)).to_polyline();
+            let indices = (0..vertices.len() as u32)
+                .map(|i| [i, (i + 1) % vertices.len() as u32])
+                .collect();
+            ColliderBuilder::polyline(vertices, Some(indices))
+        };
+        #[cfg(feature = "dim3")]
+        let mesh = {
+            let (vertices, indices) = Cuboid::new(Vector::repeat(1.0)).to_trimesh();
+            ColliderBuilder::trimesh(vertices, indices)
+        };
+        let mesh = world.colliders.insert(mesh.build());
+        let ball = world.colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * 5.0)
+                .build(),
+        );
+        query_pipeline.update(&world.islands, &world.bodies, &world.colliders);
+
+        let groups = InteractionGroups::all();
+        let ray = Ray::new(Point::origin(), Vector::x());
+
+        // The ray exits the mesh through one of its back faces.
+        let options = RayCastOptions::default();
+        let (hit, inter) = query_pipeline
+            .cast_ray_with_options(&world.colliders, &ray, options, groups, None)
+            .unwrap();
+        assert_eq!(hit, mesh);
+        assert!((inter.toi - 1.0).abs() < 1.0e-5);
+
+        let options = RayCastOptions {
+            backface_culling: true,
+            ..RayCastOptions::default()
+        };
+        let (hit, inter) = query_pipeline
+            .cast_ray_with_options(&world.colliders, &ray, options, groups, None)
+            .unwrap();
+        assert_eq!(hit, ball);
+        assert!((inter.toi - 4.5).abs() < 1.0e-5);
+
+        let mut hits = vec![];
+        query_pipeline.intersections_with_ray_with_options(
+            &world.colliders,
+            &ray,
+            options,
+            groups,
+            None,
+            |handle, _| {
+                hits.push(handle);
+                true
+            },
+        );
+        assert_eq!(hits, [ball]);
+    }
 }
@@ -0,0 +1,89 @@
+//! A physics world shared by the tests of the various physics features.
+
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+    RigidBodySet,
+};
+use crate::geometry::{BroadPhase, ColliderSet, NarrowPhase};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline};
+
+/// All the structures needed to run a physics simulation.
+pub(crate) struct TestWorld {
+    pub gravity: Vector<Real>,
+    pub integration_parameters: IntegrationParameters,
+    pub pipeline: PhysicsPipeline,
+    pub islands: IslandManager,
+    pub broad_phase: BroadPhase,
+    pub narrow_phase: NarrowPhase,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub ccd_solver: CCDSolver,
+}
+
+impl TestWorld {
+    /// An empty world with the given gravity and the default integration parameters.
+    pub fn new(gravity: Vector<Real>) -> Self {
+        Self {
+            gravity,
+            integration_parameters: IntegrationParameters::default(),
+            pipeline: PhysicsPipeline::new(),
+            islands: IslandManager::new(),
+            broad_phase: BroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+        }
+    }
+
+    /// Runs one timestep, without hooks nor event handler.
+    pub fn step(&mut self) {
+        self.step_with(&(), &())
+    }
+
+    /// Runs one timestep with the given hooks and event handler.
+    pub fn step_with(
+        &mut self,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
+    ) {
+        self.pipeline.step(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            hooks,
+            events,
+        );
+    }
+
+    /// Runs one timestep split into `substeps` substeps, with the given event handler.
+    pub fn step_with_substeps(&mut self, substeps: u32, events: &dyn EventHandler) {
+        self.pipeline.step_with_substeps(
+            &self.gravity,
+            &self.integration_parameters,
+            substeps,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            &(),
+            events,
+        );
+    }
+}
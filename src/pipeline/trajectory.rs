@@ -0,0 +1,147 @@
+//! Prediction of the trajectories of projectiles.
+
+use crate::data::ComponentSet;
+use crate::geometry::{
+    ColliderFlags, ColliderHandle, ColliderPosition, ColliderShape, InteractionGroups, Ray,
+};
+use crate::math::{Point, Real, Vector};
+use crate::pipeline::QueryPipeline;
+
+/// The result of the prediction of the trajectory of a projectile.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrajectoryResult {
+    /// The collider hit by the projectile, if any.
+    pub collider: Option<ColliderHandle>,
+    /// The position of the projectile when it hits the collider, or at the end of the
+    /// prediction if nothing was hit.
+    pub position: Point<Real>,
+    /// The velocity of the projectile at `position`.
+    pub velocity: Vector<Real>,
+    /// The normal of the collider surface at the hit point, if a collider was hit.
+    pub normal: Option<Vector<Real>>,
+    /// The time elapsed since the launch when the projectile reaches `position`.
+    pub time: Real,
+    /// The number of integration steps performed.
+    pub num_steps: u32,
+}
+
+/// Predicts the trajectory of a point-like projectile, until it hits a collider.
+///
+/// The projectile starts at `launch` with the given `velocity`, and is accelerated by `gravity`
+/// and slowed down by a linear `drag`, the same way as a rigid-body with a `linear_damping`
+/// equal to `drag`. Its motion is integrated with timesteps of length `dt`, at most `max_steps`
+/// times, and each segment travelled during a timestep is cast against the colliders of the
+/// `queries` pipeline. Smaller timesteps result in more accurate, but more expensive, predictions.
+///
+/// The `query_groups` and the `filter` select the colliders that may be hit, like for
+/// `QueryPipeline::cast_ray`. They should exclude the collider launching the projectile, if any.
+pub fn predict<Colliders>(
+    launch: Point<Real>,
+    velocity: Vector<Real>,
+    gravity: Vector<Real>,
+    drag: Real,
+    dt: Real,
+    max_steps: u32,
+    queries: &QueryPipeline,
+    colliders: &Colliders,
+    query_groups: InteractionGroups,
+    filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+) -> TrajectoryResult
+where
+    Colliders:
+        ComponentSet<ColliderFlags> + ComponentSet<ColliderPosition> + ComponentSet<ColliderShape>,
+{
+    let mut result = TrajectoryResult {
+        collider: None,
+        position: launch,
+        velocity,
+        normal: None,
+        time: 0.0,
+        num_steps: 0,
+    };
+
+    while result.num_steps < max_steps {
+        let velocity = (result.velocity + gravity * dt) / (1.0 + drag * dt);
+        let ray = Ray::new(result.position, velocity * dt);
+        result.num_steps += 1;
+
+        if let Some((handle, hit)) =
+            queries.cast_ray_and_get_normal(colliders, &ray, 1.0, true, query_groups, filter)
+        {
+            result.collider = Some(handle);
+            result.position = ray.point_at(hit.toi);
+            result.velocity = velocity;
+            result.normal = Some(hit.normal);
+            result.time += hit.toi * dt;
+            break;
+        }
+
+        result.position += velocity * dt;
+        result.velocity = velocity;
+        result.time += dt;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IslandManager, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet, InteractionGroups};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::QueryPipeline;
+
+    #[test]
+    fn trajectory_predictions_hit_the_ground() {
+        let islands = IslandManager::new();
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(50.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(50.0, 0.5, 50.0);
+        let ground = colliders.insert(ground.translation(Vector::y() * -0.5).build());
+        query_pipeline.update(&islands, &bodies, &colliders);
+
+        let dt = 1.0 / 120.0;
+        let gravity = Vector::y() * -9.81;
+        let predict = |drag, max_steps| {
+            super::predict(
+                Point::from(Vector::y()),
+                Vector::x() * 10.0,
+                gravity,
+                drag,
+                dt,
+                max_steps,
+                &query_pipeline,
+                &colliders,
+                InteractionGroups::all(),
+                None,
+            )
+        };
+
+        // Without drag, the landing point matches the analytic parabola.
+        let result = predict(0.0, 1000);
+        let fall_time = (2.0 / 9.81 as Real).sqrt();
+        assert_eq!(result.collider, Some(ground));
+        assert!((result.time - fall_time).abs() < 0.01);
+        assert!((result.position.x - 10.0 * fall_time).abs() < 0.1);
+        assert!(result.position.y.abs() < 1.0e-4);
+        assert!((result.normal.unwrap() - Vector::y()).norm() < 1.0e-4);
+        assert_eq!(result.num_steps, (result.time / dt).ceil() as u32);
+
+        // The drag shortens the trajectory.
+        let dragged = predict(0.5, 1000);
+        assert_eq!(dragged.collider, Some(ground));
+        assert!(dragged.position.x < 10.0 * fall_time - 0.2);
+
+        // The prediction stops after `max_steps`.
+        let interrupted = predict(0.0, 10);
+        assert_eq!(interrupted.collider, None);
+        assert_eq!(interrupted.num_steps, 10);
+        assert!((interrupted.time - 10.0 * dt).abs() < 1.0e-5);
+        assert!(interrupted.position.y > 0.0);
+    }
+}